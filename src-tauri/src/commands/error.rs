@@ -0,0 +1,88 @@
+//! 命令错误类型
+//!
+//! 序列化为 `{ "kind": "...", "message": "..." }`，前端可按 `kind` 区分错误
+
+use crate::storage::profiles::ProfileError;
+use crate::vision::{EvalError, FaceDetectorError};
+use serde::Serialize;
+
+/// 命令错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum CommandError {
+    /// 窗口不存在（参数为窗口标签）
+    WindowNotFound(String),
+    /// 窗口操作失败
+    Window(String),
+    /// 参数无效
+    InvalidArgument(String),
+    /// 数据库错误或数据库不可用
+    Database(String),
+    /// 文件读写错误
+    Io(String),
+    /// 摄像头被其他应用占用
+    CameraBusy(String),
+    /// 视觉检测启动或运行失败
+    Vision(String),
+    /// 操作超时（可以重试）
+    Timeout(String),
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::WindowNotFound(label) => write!(f, "Window not found: {}", label),
+            CommandError::Window(msg) => write!(f, "Window error: {}", msg),
+            CommandError::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
+            CommandError::Database(msg) => write!(f, "Database error: {}", msg),
+            CommandError::Io(msg) => write!(f, "IO error: {}", msg),
+            CommandError::CameraBusy(msg) => write!(f, "Camera is in use by another application: {}", msg),
+            CommandError::Vision(msg) => write!(f, "Vision error: {}", msg),
+            CommandError::Timeout(msg) => write!(f, "Timed out: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<rusqlite::Error> for CommandError {
+    fn from(err: rusqlite::Error) -> Self {
+        CommandError::Database(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(err: std::io::Error) -> Self {
+        CommandError::Io(err.to_string())
+    }
+}
+
+impl From<ProfileError> for CommandError {
+    fn from(err: ProfileError) -> Self {
+        match err {
+            ProfileError::Io(e) => CommandError::Io(e.to_string()),
+            other => CommandError::InvalidArgument(other.to_string()),
+        }
+    }
+}
+
+impl From<FaceDetectorError> for CommandError {
+    fn from(err: FaceDetectorError) -> Self {
+        CommandError::Vision(err.to_string())
+    }
+}
+
+impl From<EvalError> for CommandError {
+    fn from(err: EvalError) -> Self {
+        match err {
+            EvalError::Io(e) => CommandError::Io(e.to_string()),
+            EvalError::Detector(e) => e.into(),
+        }
+    }
+}
+
+impl From<tauri::Error> for CommandError {
+    fn from(err: tauri::Error) -> Self {
+        CommandError::Window(err.to_string())
+    }
+}
@@ -3,9 +3,52 @@
 //! 定义前端可调用的 Tauri 命令
 //! 包括宠物状态管理、视觉检测控制等功能
 
-use crate::state::{FocusStats, GestureType, PetMood, PetStateMachine, PetStateConfig};
-use crate::vision::{FocusState, VisionProcessor, VisionProcessorConfig, CapturedFrame};
+mod error;
+
+pub use error::CommandError;
+
+use crate::config::apply::{ApplyMode, ApplyReport};
+use crate::config::{schema, AppConfig};
+use crate::i18n::{self, Locale};
+use crate::state::fatigue::{self, BreakSuggestion};
+use crate::state::frame_rate::FrameRateMonitor;
+use crate::state::accessible::AccessibleNarrator;
+use crate::state::auto_hide::{AutoHide, WindowAction};
+use crate::state::buffers::{self, BufferStats};
+use crate::state::challenge::{Challenge, ChallengeProgress, ChallengeUpdate, MAX_CHALLENGE_MINUTES};
+use crate::state::distraction::DistractionTracker;
+use crate::state::goal_progress::{GoalProgressEvent, GoalProgressTracker};
+use crate::state::notification::{Notification, NotificationKind, NotificationPrefs};
+use crate::state::phone::{PhoneDistraction, PhoneGlanceConfig, PhoneGlanceDetector};
+use crate::state::sparkline::FocusSparkline;
+use crate::state::suspend::{ResumeReport, SuspendDetector, SUSPEND_CHECK_INTERVAL_MS};
+use crate::state::trend::{FocusTrend, FocusTrendWindow};
+use crate::state::{FocusLevel, FocusMilestones, FocusStats, GestureReaction, GestureType, Milestone, MoodSnapshot, Personality, PersonalitySettings, PetMood, PetStateMachine, PetStateConfig, ScoreClassification, SessionPeak, SessionRecap, SessionTracker};
+use crate::storage::focus_log::{self, FocusLogConfig, FocusLogger};
+use crate::storage::challenges::ChallengeRecord;
+use crate::storage::coach::Tip;
+use crate::storage::compare::DayComparison;
+use crate::storage::day_notes::{DayDigest, MAX_DAY_NOTE_CHARS};
+use crate::storage::diagnostics::{self, DiagnosticsBundle, HealthCheck};
+use crate::storage::distractions::DistractionEvent;
+use crate::storage::events::{Event, EventKind};
+use crate::storage::hour_profile::BucketGranularity;
+use crate::storage::lifetime::LifetimeStats;
+use crate::storage::pace::GoalOnTrack;
+use crate::storage::paths::DataPaths;
+use crate::storage::profiles::{self, ProfileStore};
+use crate::storage::recordings::{RecordingInfo, RecordingStore, RECORDING_INTERVAL_MS};
+use crate::storage::samples::{FocusConsistency, FocusSample};
+use crate::storage::streak::{Streak, StreakFreezeStatus};
+use crate::storage::timeline::FocusBlock;
+use crate::storage::{self, Database, FocusSession, GoalProgress};
+use crate::vision::autotune::{self, RecommendedCameraSettings};
+use crate::vision::jitter::{self, MAX_JITTER_SECS};
+use crate::vision::{BlazeFaceDetector, CalculatorBenchmark, DetectionDump, DumpSummary, MAX_DUMP_DURATION_MS, dump_path, CameraCapture, EvalResult, FocusCalculator, JitterMetrics, FocusProfile, FocusSource, FocusState, MockDetectionConfig, ModelInfo, ModelIo, PreviewBlur, RawModelOutput, ResourceReport, VisionProcessor, VisionProcessorConfig, CapturedFrame, YawZone, calibrated_face_size};
+use chrono::Timelike;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tauri::{State, Manager, Emitter};
 use parking_lot::Mutex;
@@ -24,6 +67,76 @@ pub struct AppState {
     pub focus_state_rx: Mutex<Option<watch::Receiver<FocusState>>>,
     /// 是否正在运行视觉检测
     pub vision_running: Mutex<bool>,
+    /// 视觉检测是否正在停止（`vision_running` 已清除但来源尚未停止完），期间拒绝重新启动
+    pub vision_stopping: Mutex<bool>,
+    /// 数据库（在应用 setup 阶段打开）
+    pub database: Mutex<Option<Database>>,
+    /// 应用配置
+    pub config: Mutex<AppConfig>,
+    /// 应用数据目录（在应用 setup 阶段确定）
+    pub data_dir: Mutex<Option<PathBuf>>,
+    /// 专注状态采样日志（仅在用户开启时存在）
+    pub focus_log: Mutex<Option<FocusLogger>>,
+    /// 当前专注会话（视觉检测运行期间存在）
+    pub session: Mutex<Option<SessionTracker>>,
+    /// 今日已写入数据库的专注时长（毫秒）
+    pub flushed_focus_ms: Mutex<u64>,
+    /// 当前统计日 (YYYY-MM-DD)，实时累计的数据属于这一天
+    pub current_day: Mutex<String>,
+    /// 日期切换锁：比较 `current_day` 和执行切换在持有期间完成，避免同一次跨天被切换两次
+    pub rollover_lock: Mutex<()>,
+    /// 运行中设置的摄像头帧率（下次启动视觉检测时沿用）
+    pub camera_fps: Mutex<Option<u32>>,
+    /// 自动调优选定的采集分辨率（下次启动视觉检测时沿用）
+    pub camera_resolution: Mutex<Option<(u32, u32)>>,
+    /// 运行中设置的 NMS IoU 阈值（下次启动视觉检测时沿用）
+    pub nms_threshold: Mutex<Option<f32>>,
+    /// 等待批量写入数据库的活动事件
+    pub pending_events: Mutex<Vec<Event>>,
+    /// 等待批量写入数据库的专注分数采样
+    pub pending_samples: Mutex<Vec<FocusSample>>,
+    /// 上一次记录采样的时间戳（毫秒）
+    pub last_sample_ms: Mutex<i64>,
+    /// 已记录目标完成事件的日期（每天只记录一次）
+    pub goal_completed_day: Mutex<Option<String>>,
+    /// 每日目标进度档位（用于 `goal_progress` 事件）
+    pub goal_progress: Mutex<GoalProgressTracker>,
+    /// 分心跟踪（视觉检测运行期间）
+    pub distraction: Mutex<DistractionTracker>,
+    /// 进行中的专注挑战
+    pub challenge: Mutex<Option<Challenge>>,
+    /// 最近的专注状态（用于诊断包，从旧到新）
+    pub recent_focus_states: Mutex<VecDeque<FocusState>>,
+    /// 当前使用中的档案名称
+    pub active_profile: Mutex<String>,
+    /// 最近几秒的专注分数（用于趋势判断）
+    pub focus_trend: Mutex<FocusTrendWindow>,
+    /// 上一次主动提醒休息的时间戳（毫秒）
+    pub last_break_reminder_ms: Mutex<i64>,
+    /// 最近 60 分钟的每分钟平均专注分数
+    pub focus_sparkline: Mutex<FocusSparkline>,
+    /// 当前选择的专注来源
+    pub focus_source: Mutex<FocusSource>,
+    /// 外部来源的推送通道（外部来源活动时）
+    pub external_focus_tx: Mutex<Option<watch::Sender<FocusState>>>,
+    /// 当前会话的录制（开启会话录制时，每秒最多一条）
+    pub session_recording: Mutex<Vec<FocusState>>,
+    /// 看手机检测（开启 `focus.phone_detection_enabled` 时）
+    pub phone_glance: Mutex<PhoneGlanceDetector>,
+    /// 等待发送的会话回顾（`session_recap` 事件），按会话结束顺序排列
+    pub pending_recaps: Mutex<VecDeque<SessionRecap>>,
+    /// 定期推送统计更新的任务（前端开启时存在）
+    pub stats_stream: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    /// 专注时自动隐藏宠物窗口（开启 `pet.auto_hide_on_focus` 时）
+    pub auto_hide: Mutex<AutoHide>,
+    /// 摄像头实际帧率监测
+    pub frame_rate: Mutex<FrameRateMonitor>,
+    /// 应用启动时间
+    pub started_at: std::time::Instant,
+    /// 本次运行中成功启动视觉检测的次数
+    pub vision_sessions: Mutex<u32>,
+    /// 无障碍状态播报（开启 `ui.accessibility_mode` 时）
+    pub accessible: Mutex<AccessibleNarrator>,
 }
 
 impl Default for AppState {
@@ -39,346 +152,4185 @@ impl Default for AppState {
             vision_processor: Mutex::new(None),
             focus_state_rx: Mutex::new(None),
             vision_running: Mutex::new(false),
+            vision_stopping: Mutex::new(false),
+            database: Mutex::new(None),
+            config: Mutex::new(AppConfig::default()),
+            data_dir: Mutex::new(None),
+            focus_log: Mutex::new(None),
+            session: Mutex::new(None),
+            flushed_focus_ms: Mutex::new(0),
+            current_day: Mutex::new(storage::today_key()),
+            rollover_lock: Mutex::new(()),
+            camera_fps: Mutex::new(None),
+            camera_resolution: Mutex::new(None),
+            nms_threshold: Mutex::new(None),
+            pending_events: Mutex::new(Vec::new()),
+            pending_samples: Mutex::new(Vec::new()),
+            last_sample_ms: Mutex::new(0),
+            goal_completed_day: Mutex::new(None),
+            goal_progress: Mutex::new(GoalProgressTracker::default()),
+            distraction: Mutex::new(DistractionTracker::default()),
+            challenge: Mutex::new(None),
+            recent_focus_states: Mutex::new(VecDeque::with_capacity(diagnostics::RECENT_FOCUS_STATES)),
+            active_profile: Mutex::new(profiles::DEFAULT_PROFILE.to_string()),
+            focus_trend: Mutex::new(FocusTrendWindow::default()),
+            last_break_reminder_ms: Mutex::new(0),
+            focus_sparkline: Mutex::new(FocusSparkline::default()),
+            focus_source: Mutex::new(FocusSource::default()),
+            external_focus_tx: Mutex::new(None),
+            session_recording: Mutex::new(Vec::new()),
+            phone_glance: Mutex::new(PhoneGlanceDetector::default()),
+            pending_recaps: Mutex::new(VecDeque::new()),
+            stats_stream: Mutex::new(None),
+            auto_hide: Mutex::new(AutoHide::default()),
+            frame_rate: Mutex::new(FrameRateMonitor::default()),
+            started_at: std::time::Instant::now(),
+            vision_sessions: Mutex::new(0),
+            accessible: Mutex::new(AccessibleNarrator::default()),
         }
     }
 }
 
-/// 获取当前宠物状态
-#[tauri::command]
-pub fn get_pet_state(state: State<'_, Arc<AppState>>) -> PetStateResponse {
-    let machine = state.pet_state_machine.lock();
-    let stats = state.focus_stats.lock().clone();
-    let vision_running = *state.vision_running.lock();
+impl AppState {
+    /// 当前档案的数据文件路径（数据目录未确定或档案目录已不存在时返回 None）
+    pub fn data_paths(&self) -> Option<DataPaths> {
+        let dir = self.data_dir.lock().clone()?;
+        let active = self.active_profile.lock().clone();
+        let dir = ProfileStore::new(&dir).data_dir(&active).ok()?;
+        Some(DataPaths::new(&dir))
+    }
 
-    // 如果视觉检测正在运行，尝试获取最新的专注状态
-    let (focus_score, face_detected) = if vision_running {
-        if let Some(ref rx) = *state.focus_state_rx.lock() {
-            let focus_state = rx.borrow().clone();
-            (focus_state.focus_score, focus_state.face_present)
-        } else {
-            (stats.focus_score, false)
-        }
-    } else {
-        (stats.focus_score, false)
-    };
+    /// 采样日志配置（数据目录未确定时返回 None）
+    fn focus_log_config(&self) -> Option<FocusLogConfig> {
+        let paths = self.data_paths()?;
+        Some(FocusLogConfig {
+            dir: paths.log_path,
+            max_file_bytes: self.config.lock().privacy.focus_log_max_bytes,
+            max_files: 5,
+        })
+    }
 
-    PetStateResponse {
-        mood: machine.mood,
-        focus_score,
-        total_focus_minutes: stats.total_focus_ms as f32 / 60000.0,
-        is_vision_active: vision_running,
-        face_detected,
+    /// 数据目录下的档案存储
+    fn profile_store(&self) -> Result<ProfileStore, CommandError> {
+        let dir = self
+            .data_dir
+            .lock()
+            .clone()
+            .ok_or_else(|| CommandError::Io("Data directory is not available".to_string()))?;
+        Ok(ProfileStore::new(&dir))
     }
-}
 
-/// 宠物状态响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PetStateResponse {
-    /// 当前情绪
-    pub mood: PetMood,
-    /// 当前专注分数
-    pub focus_score: f32,
-    /// 今日累计专注时间（分钟）
-    pub total_focus_minutes: f32,
-    /// 视觉检测是否活跃
-    pub is_vision_active: bool,
-    /// 是否检测到人脸
-    pub face_detected: bool,
-}
+    /// 切换到已保存的档案：写入待写入的缓冲后，打开该档案的数据库、加载其配置并按配置重建状态机，
+    /// 返回加载的配置
+    ///
+    /// 专注检测运行中时拒绝切换（会话属于切换前的档案）
+    pub fn switch_profile(&self, name: &str) -> Result<AppConfig, CommandError> {
+        if *self.vision_running.lock() || self.external_focus_tx.lock().is_some() {
+            return Err(CommandError::InvalidArgument(
+                "Stop focus detection before switching profiles".to_string(),
+            ));
+        }
+        let paths = DataPaths::new(&self.profile_store()?.data_dir(name)?);
+        if *self.active_profile.lock() == name {
+            return Ok(self.config.lock().clone());
+        }
 
-/// 启动视觉检测
-#[tauri::command]
-pub async fn start_vision(
-    state: State<'_, Arc<AppState>>,
-    app_handle: tauri::AppHandle,
-) -> Result<(), String> {
-    {
-        let mut running = state.vision_running.lock();
-        if *running {
-            return Err("Vision is already running".to_string());
+        self.flush_events();
+        self.flush_samples();
+        let database = Database::open(&paths.database_path)?;
+        let config = AppConfig::load_or_default(&paths.config_path);
+        {
+            let mut current = self.config.lock();
+            *self.pet_state_machine.lock() = PetStateMachine::new(PetStateConfig::from_app_config(&config));
+            *current = config.clone();
         }
-        *running = true;
+        *self.database.lock() = Some(database);
+        *self.active_profile.lock() = name.to_string();
+        self.reset_live_stats();
+
+        tracing::info!("Switched to profile {}", name);
+        Ok(config)
     }
 
-    tracing::info!("Starting vision detection...");
+    /// 设置 Demo 模式的宠物情绪，返回设置后的情绪；情绪锁定时返回错误，不假装设置成功
+    pub fn set_demo_mood(&self, mood: PetMood) -> Result<PetMood, String> {
+        let mut machine = self.pet_state_machine.lock();
+        if let Some(locked) = machine.mood_lock() {
+            return Err(format!("Mood is locked to {:?}; unlock it first", locked));
+        }
+        machine.set_mood(mood);
 
-    // 使用闭包来处理启动逻辑，失败时自动重置 vision_running
-    let result = (|| -> Result<(), String> {
-        // 获取资源目录路径
-        let resource_path = app_handle
-            .path()
-            .resource_dir()
-            .map_err(|e| format!("Failed to get resource dir: {}", e))?;
-
-        let model_path = resource_path
-            .join("models")
-            .join("blazeface.onnx")
-            .to_string_lossy()
-            .to_string();
-
-        let anchors_path = resource_path
-            .join("models")
-            .join("anchors.npy")
-            .to_string_lossy()
-            .to_string();
-
-        // 创建视觉处理器配置
-        let config = VisionProcessorConfig {
-            model_path,
-            anchors_path: Some(anchors_path),
-            detect_every_frame: false, // 隔帧检测以降低 CPU
-            ..Default::default()
+        tracing::info!("Demo mood set to: {:?}", machine.mood);
+        Ok(machine.mood)
+    }
+
+    /// 按最近检测到人脸的偏航角校准副屏区间、开启副屏判断并保存，区间限制在配置允许的范围内
+    pub fn calibrate_secondary_screen(&self) -> Result<YawZone, CommandError> {
+        let yaws: Vec<f32> = self
+            .recent_focus_states
+            .lock()
+            .iter()
+            .filter(|s| s.face_present)
+            .map(|s| s.yaw)
+            .collect();
+        let zone = YawZone::calibrate(&yaws, SECONDARY_SCREEN_HALF_WIDTH).ok_or_else(|| {
+            CommandError::InvalidArgument(
+                "Look at the secondary screen with your face visible to the camera, then try again".to_string(),
+            )
+        })?;
+        let zone = YawZone {
+            min_yaw: schema::clamp_to_range("focus.secondary_screen_yaw_min", zone.min_yaw),
+            max_yaw: schema::clamp_to_range("focus.secondary_screen_yaw_max", zone.max_yaw),
         };
 
-        // 创建视觉处理器
-        let processor = Arc::new(VisionProcessor::new(config));
-        let focus_rx = processor.subscribe();
-        let frame_rx = processor.subscribe_frames();
+        {
+            let mut config = self.config.lock();
+            config.focus.secondary_screen_enabled = true;
+            config.focus.secondary_screen_yaw_min = zone.min_yaw;
+            config.focus.secondary_screen_yaw_max = zone.max_yaw;
+            if let Some(ref processor) = *self.vision_processor.lock() {
+                processor.set_focus_config(config.focus.calculator_config());
+            }
+        }
 
-        // 启动处理器
-        processor.start()?;
+        if let Err(e) = self.save_config() {
+            tracing::warn!("{}", e);
+        }
 
-        // 保存处理器和接收器
-        {
-            *state.vision_processor.lock() = Some(processor.clone());
-            *state.focus_state_rx.lock() = Some(focus_rx.clone());
+        tracing::info!("Secondary screen yaw zone: {:.1}..{:.1}", zone.min_yaw, zone.max_yaw);
+        Ok(zone)
+    }
+
+    /// 按最近检测到人脸的俯仰角校准低头阈值并保存，结果限制在配置允许的范围内
+    pub fn calibrate_lower_screen(&self) -> Result<f32, CommandError> {
+        let pitches: Vec<f32> = self
+            .recent_focus_states
+            .lock()
+            .iter()
+            .filter(|s| s.face_present)
+            .map(|s| s.pitch)
+            .collect();
+        let threshold = PhoneGlanceConfig::calibrate_threshold(&pitches).ok_or_else(|| {
+            CommandError::InvalidArgument(
+                "Look at the lower screen with your face visible to the camera, then try again".to_string(),
+            )
+        })?;
+        let threshold = schema::clamp_to_range("focus.phone_pitch_threshold", threshold);
+
+        self.config.lock().focus.phone_pitch_threshold = threshold;
+        if let Err(e) = self.save_config() {
+            tracing::warn!("{}", e);
         }
 
-        // 启动状态更新任务
-        let state_clone = Arc::clone(&state);
-        let app_handle_clone = app_handle.clone();
+        tracing::info!("Phone pitch threshold calibrated: {:.1}", threshold);
+        Ok(threshold)
+    }
 
-        tokio::spawn(async move {
-            let mut rx = focus_rx;
+    /// 数据目录下的会话录制存储
+    fn recording_store(&self) -> Result<RecordingStore, CommandError> {
+        let dir = self
+            .data_dir
+            .lock()
+            .clone()
+            .ok_or_else(|| CommandError::Io("Data directory is not available".to_string()))?;
+        let max_recordings = self.config.lock().privacy.max_recordings;
+        Ok(RecordingStore::new(&dir, max_recordings as usize))
+    }
 
-            while rx.changed().await.is_ok() {
-                let focus_state = rx.borrow().clone();
+    /// 保存配置到数据目录
+    fn save_config(&self) -> Result<(), String> {
+        let paths = self
+            .data_paths()
+            .ok_or_else(|| "Data directory is not available".to_string())?;
+        self.config
+            .lock()
+            .save(paths.config_path)
+            .map_err(|e| format!("Failed to save config: {}", e))
+    }
 
-                // 更新宠物状态机
-                {
-                    let mut machine = state_clone.pet_state_machine.lock();
-                    let new_mood = machine.update(focus_state.focus_score, focus_state.face_present);
+    /// 结束当前会话，写入会话记录和今日统计
+    ///
+    /// 开启会话回顾（配置 `pet.session_recap`）时生成回顾，等待事件任务发送
+    fn flush_session(&self) {
+        let Some(tracker) = self.session.lock().take() else {
+            return;
+        };
+        let recap = self.session_recap(&tracker);
+        self.save_session(tracker);
+        if let Some(recap) = recap {
+            let mut pending = self.pending_recaps.lock();
+            pending.push_back(recap);
+            buffers::evict_oldest(&mut pending, MAX_PENDING_RECAPS);
+        }
+    }
 
-                    // 如果状态改变，发送事件到前端
-                    if let Some(mood) = new_mood {
-                        let _ = app_handle_clone.emit("pet_mood_changed", mood);
-                    }
+    /// 按会话当前的累计数据生成回顾（写入数据库之前调用，以便和之前的会话比较）
+    fn session_recap(&self, tracker: &SessionTracker) -> Option<SessionRecap> {
+        if !self.config.lock().pet.session_recap {
+            return None;
+        }
+        let total_focus_ms = self.pet_state_machine.lock().total_focus_ms;
+        let session = tracker.snapshot(chrono::Local::now().timestamp_millis(), total_focus_ms);
+        let previous_best_ms = match with_database(self, |db| db.longest_session_ms(tracker.saved_id())) {
+            Ok(best) => best,
+            Err(e) => {
+                tracing::warn!("Failed to read session history: {}", e);
+                None
+            }
+        };
+        Some(tracker.recap(&session, previous_best_ms))
+    }
 
-                    // 更新统计
-                    let mut stats = state_clone.focus_stats.lock();
-                    stats.focus_score = focus_state.focus_score;
-                    stats.current_mood = machine.mood;
-                    stats.focus_level = machine.focus_level;
-                    stats.total_focus_ms = machine.total_focus_ms;
-                }
+    /// 取出全部等待发送的会话回顾（每个会话只取出一次），按会话结束顺序排列
+    pub fn take_session_recaps(&self) -> Vec<SessionRecap> {
+        self.pending_recaps.lock().drain(..).collect()
+    }
 
-                // 发送专注状态事件
-                let _ = app_handle_clone.emit("focus_state", &focus_state);
+    /// 停止当前的专注来源：停止视觉处理器或关闭外部推送通道（状态更新任务随之结束），
+    /// 并写入本次会话和进行中的分心
+    ///
+    /// 不修改 `vision_running`，由调用方负责
+    fn stop_focus_source(&self) {
+        if let Some(processor) = self.vision_processor.lock().take() {
+            processor.stop();
+            // 进行中的检测结果转储随之结束
+            match processor.stop_detection_dump() {
+                Some(Ok(summary)) => tracing::info!("Detection dump finished: {} ({} frames)", summary.path, summary.frames),
+                Some(Err(e)) => tracing::warn!("Failed to finish detection dump: {}", e),
+                None => {}
             }
+        }
+        *self.external_focus_tx.lock() = None;
+        *self.focus_state_rx.lock() = None;
+        self.frame_rate.lock().reset();
 
-            tracing::info!("Vision state update task ended");
-        });
+        self.flush_session();
+        let distraction = std::mem::take(&mut *self.distraction.lock()).finish(chrono::Local::now().timestamp_millis());
+        if let Some(ref event) = distraction {
+            self.save_distraction(event);
+        }
+        self.flush_events();
+        self.flush_samples();
+    }
 
-        // 启动预览帧推送任务
-        let app_handle_preview = app_handle.clone();
-        tokio::spawn(async move {
-            tracing::info!("Vision preview task started, waiting for frames...");
-            let mut rx = frame_rx;
-            let mut frame_count = 0u64;
+    /// 标记视觉检测开始启动，返回启动守卫
+    ///
+    /// 已在运行或其他专注来源活动时拒绝。守卫在 `commit` 之前被丢弃（启动中途出错或 panic）时，
+    /// 停止已创建的处理器、清空接收端并重置 `vision_running`，之后可以重新启动
+    fn begin_vision_start(&self) -> Result<VisionStartGuard<'_>, CommandError> {
+        let mut running = self.vision_running.lock();
+        if *running {
+            return Err(CommandError::Vision("Vision is already running".to_string()));
+        }
+        if *self.vision_stopping.lock() {
+            return Err(CommandError::Vision("Vision is still stopping; try again shortly".to_string()));
+        }
+        if *self.focus_source.lock() != FocusSource::Camera {
+            return Err(CommandError::Vision(
+                "Another focus source is active; switch to the camera source first".to_string(),
+            ));
+        }
+        *running = true;
+        Ok(VisionStartGuard {
+            state: self,
+            committed: false,
+        })
+    }
 
-            while rx.changed().await.is_ok() {
-                let frame = rx.borrow().clone();
+    /// 停止视觉检测，未运行时返回错误
+    ///
+    /// 先清除 `vision_running` 并释放锁再停止来源：写入会话时会锁定状态机，
+    /// 持有 `vision_running` 时不能再锁定状态机，否则与先锁状态机的读取方形成死锁。
+    /// 清除的同时标记正在停止，来源停止完之前的启动请求被拒绝，不会在这段间隙里装上新的处理器
+    pub fn stop_vision(&self) -> Result<(), String> {
+        {
+            let mut running = self.vision_running.lock();
+            if !*running {
+                return Err("Vision is not running".to_string());
+            }
+            *running = false;
+            *self.vision_stopping.lock() = true;
+        }
 
-                if frame.is_empty() {
-                    continue;
-                }
+        tracing::info!("Stopping vision detection...");
 
-                frame_count += 1;
-                if frame_count == 1 {
-                    tracing::info!("First frame received for preview: {}x{}", frame.width, frame.height);
-                }
+        // 停止处理器，写入本次会话和进行中的分心
+        self.stop_focus_source();
+        *self.vision_stopping.lock() = false;
 
-                // 将 RGB 帧编码为 JPEG 并转为 base64
-                if let Some(preview) = encode_frame_to_base64(&frame) {
-                    if frame_count % 30 == 1 {
-                        tracing::debug!("Emitting preview frame #{}", frame_count);
-                    }
-                    let _ = app_handle_preview.emit("vision_preview", preview);
-                } else {
-                    tracing::warn!("Failed to encode frame #{} to base64", frame_count);
-                }
+        tracing::info!("Vision detection stopped");
+        Ok(())
+    }
+
+    /// 系统睡眠唤醒后的处理
+    ///
+    /// 睡眠前运行的视觉检测已不可靠：先停止并写入会话，重置状态机的计时，再重新检查摄像头。
+    /// 返回的 `restart` 表示是否应重新启动视觉检测（睡眠前在运行、配置允许且摄像头可用）
+    pub fn handle_resume(&self, slept_ms: i64) -> ResumeReport {
+        let was_running = *self.vision_running.lock() && *self.focus_source.lock() == FocusSource::Camera;
+        if was_running {
+            if let Err(e) = self.stop_vision() {
+                tracing::warn!("Failed to stop vision after resume: {}", e);
             }
+        }
+        self.pet_state_machine.lock().reset_after_suspend();
 
-            tracing::info!("Vision preview task ended after {} frames", frame_count);
-        });
+        let (device_index, fps, restart_after_resume) = {
+            let config = self.config.lock();
+            (config.camera.device_index, config.camera.fps, config.camera.restart_after_resume)
+        };
+        let camera_error = if was_running {
+            let camera = crate::vision::CameraConfig {
+                device_index,
+                target_fps: crate::vision::clamp_fps(fps),
+                ..Default::default()
+            };
+            CameraCapture::probe(&camera).err()
+        } else {
+            None
+        };
+        if let Some(ref e) = camera_error {
+            tracing::warn!("Camera unavailable after resume: {}", e);
+        }
 
-        Ok(())
-    })();
+        ResumeReport {
+            slept_ms,
+            was_running,
+            restart: was_running && restart_after_resume && camera_error.is_none(),
+            camera_error,
+        }
+    }
+
+    /// 视觉检测状态，包括本次运行的时长和视觉检测次数
+    pub fn vision_status(&self) -> VisionStatusResponse {
+        let focus_state = self.vision_focus_state();
 
-    // 如果启动失败，重置 vision_running 状态
-    if result.is_err() {
-        *state.vision_running.lock() = false;
+        VisionStatusResponse {
+            is_running: focus_state.is_some(),
+            is_stale: focus_state.as_ref().is_some_and(focus_state_is_stale),
+            focus_state,
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            vision_sessions: *self.vision_sessions.lock(),
+        }
     }
 
-    result.map(|_| {
-        tracing::info!("Vision detection started successfully");
-    })
-}
+    /// 视觉检测完全启动后的最新专注状态，未运行或仍在启动中时为 None
+    ///
+    /// `vision_running` 在启动开始时就置为 true（防止重复启动），而接收端要等处理器创建后才保存；
+    /// 两者都就绪才视为运行中，避免在这段间隙里把“尚无数据”报告成“没有人脸”
+    fn vision_focus_state(&self) -> Option<FocusState> {
+        if !*self.vision_running.lock() {
+            return None;
+        }
+        self.focus_state_rx.lock().as_ref().map(|rx| rx.borrow().clone())
+    }
 
-/// 停止视觉检测
-#[tauri::command]
-pub fn stop_vision(state: State<'_, Arc<AppState>>) -> Result<(), String> {
-    let mut running = state.vision_running.lock();
-    if !*running {
-        return Err("Vision is not running".to_string());
+    /// 当前专注来源的最新专注状态：摄像头来源见 [`Self::vision_focus_state`]，外部来源为最近推送的状态
+    fn latest_focus_state(&self) -> Option<FocusState> {
+        match *self.focus_source.lock() {
+            FocusSource::Camera => self.vision_focus_state(),
+            FocusSource::External => self.focus_state_rx.lock().as_ref().map(|rx| rx.borrow().clone()),
+        }
     }
 
-    tracing::info!("Stopping vision detection...");
+    /// 当前宠物状态
+    fn pet_state(&self) -> PetStateResponse {
+        // 先读取视觉状态再锁定状态机，不在持有状态机锁时锁定 `vision_running`
+        let is_camera = *self.focus_source.lock() == FocusSource::Camera;
+        let focus_state = self.latest_focus_state();
+        let machine = self.pet_state_machine.lock();
+        let stats = self.focus_stats.lock().clone();
 
-    // 停止处理器
-    if let Some(ref processor) = *state.vision_processor.lock() {
-        processor.stop();
+        // 专注来源活动时使用最新的专注状态，按其检测时间判断是否过时（外部来源停止推送时同样过时）
+        let (focus_score, face_detected, is_stale) = match focus_state {
+            Some(ref focus_state) => (focus_state.focus_score, focus_state.face_present, focus_state_is_stale(focus_state)),
+            None => (stats.focus_score, false, false),
+        };
+
+        PetStateResponse {
+            mood: machine.mood,
+            intensity: machine.intensity(),
+            focus_score,
+            total_focus_minutes: stats.total_focus_ms as f32 / 60000.0,
+            is_vision_active: is_camera && focus_state.is_some(),
+            face_detected,
+            on_break: machine.is_on_manual_break(),
+            looking_for_user: machine.is_looking_for_user(),
+            is_stale,
+            deep_focus: machine.is_deep_focus(),
+        }
     }
 
-    // 清理状态
-    *state.vision_processor.lock() = None;
-    *state.focus_state_rx.lock() = None;
-    *running = false;
+    /// 切换到外部来源：停止摄像头（如果在运行），返回外部推送的专注状态接收端
+    ///
+    /// 配置 `focus.external_source_enabled` 未开启时拒绝切换
+    pub fn start_external_source(&self) -> Result<watch::Receiver<FocusState>, CommandError> {
+        if !self.config.lock().focus.external_source_enabled {
+            return Err(CommandError::InvalidArgument(
+                "No external focus source is configured".to_string(),
+            ));
+        }
 
-    tracing::info!("Vision detection stopped");
-    Ok(())
-}
+        {
+            let mut running = self.vision_running.lock();
+            if *running {
+                tracing::info!("Stopping vision detection to switch to the external source");
+            }
+            *running = false;
+            *self.vision_stopping.lock() = true;
+        }
+        self.stop_focus_source();
 
-/// 触发手势事件（用于测试/Demo模式）
-#[tauri::command]
-pub fn trigger_gesture(gesture: String, state: State<'_, Arc<AppState>>) -> Result<PetMood, String> {
-    let gesture_type = match gesture.to_lowercase().as_str() {
-        "wave" => GestureType::Wave,
-        "heart" => GestureType::Heart,
-        "ok" => GestureType::Ok,
-        "thumbsup" | "thumbs_up" => GestureType::ThumbsUp,
-        _ => return Err(format!("Unknown gesture: {}", gesture)),
-    };
+        let (tx, rx) = watch::channel(FocusState::default());
+        *self.external_focus_tx.lock() = Some(tx);
+        *self.focus_state_rx.lock() = Some(rx.clone());
+        *self.focus_source.lock() = FocusSource::External;
+        *self.vision_stopping.lock() = false;
+        self.start_session();
 
-    tracing::info!("Gesture triggered: {:?}", gesture_type);
+        tracing::info!("External focus source started");
+        Ok(rx)
+    }
 
-    // 更新宠物状态为互动模式
-    let mut machine = state.pet_state_machine.lock();
-    let new_mood = machine.on_gesture(gesture_type);
+    /// 停止外部来源（如果在运行），并切换回摄像头来源（不启动摄像头）
+    pub fn stop_external_source(&self) {
+        if self.external_focus_tx.lock().is_some() {
+            self.stop_focus_source();
+            tracing::info!("External focus source stopped");
+        }
+        *self.focus_source.lock() = FocusSource::Camera;
+    }
 
-    Ok(new_mood)
-}
+    /// 推送一次外部来源的专注状态（缺少时间戳时使用当前时间）
+    pub fn push_external_focus_state(&self, mut focus_state: FocusState) -> Result<(), CommandError> {
+        let tx = self.external_focus_tx.lock();
+        let tx = tx
+            .as_ref()
+            .ok_or_else(|| CommandError::InvalidArgument("External focus source is not active".to_string()))?;
+        if focus_state.timestamp_ms == 0 {
+            focus_state.timestamp_ms = chrono::Local::now().timestamp_millis() as u64;
+        }
+        tx.send_replace(focus_state);
+        Ok(())
+    }
 
-/// 设置 Demo 模式的宠物状态（用于录屏展示）
-#[tauri::command]
-pub fn set_demo_mood(mood: String, state: State<'_, Arc<AppState>>) -> Result<PetMood, String> {
-    let new_mood = match mood.to_lowercase().as_str() {
-        "idle" => PetMood::Idle,
-        "happy" => PetMood::Happy,
-        "excited" => PetMood::Excited,
-        "sad" => PetMood::Sad,
-        "sleepy" => PetMood::Sleepy,
-        "interact" => PetMood::Interact,
-        _ => return Err(format!("Unknown mood: {}", mood)),
-    };
+    /// 在 `duration` 内采集检测到的人脸大小，用中位数校准理想人脸大小并保存到配置
+    ///
+    /// 只采集新的检测结果（跳过复用上一次结果的状态），立即应用到运行中的视觉处理器，返回校准后的值
+    pub async fn calibrate_face_size(&self, duration: std::time::Duration) -> Result<f32, CommandError> {
+        let mut rx = self
+            .focus_state_rx
+            .lock()
+            .clone()
+            .ok_or_else(|| CommandError::InvalidArgument("Vision is not running".to_string()))?;
 
-    let mut machine = state.pet_state_machine.lock();
-    machine.mood = new_mood;
+        let deadline = tokio::time::Instant::now() + duration;
+        let mut sizes = Vec::new();
+        while let Ok(Ok(())) = tokio::time::timeout_at(deadline, rx.changed()).await {
+            let size = {
+                let focus_state = rx.borrow_and_update();
+                (focus_state.face_present && !focus_state.is_reused()).then_some(focus_state.face_size)
+            };
+            sizes.extend(size);
+        }
 
-    tracing::info!("Demo mood set to: {:?}", new_mood);
+        let ideal_face_size = calibrated_face_size(&sizes).ok_or_else(|| {
+            CommandError::InvalidArgument(
+                "Sit normally with your face visible to the camera, then try again".to_string(),
+            )
+        })?;
 
-    Ok(new_mood)
-}
+        {
+            let mut config = self.config.lock();
+            config.focus.ideal_face_size = ideal_face_size;
+            if let Some(ref processor) = *self.vision_processor.lock() {
+                processor.set_focus_config(config.focus.calculator_config());
+            }
+        }
 
-/// 获取今日专注统计
-#[tauri::command]
-pub fn get_focus_stats(state: State<'_, Arc<AppState>>) -> FocusStats {
-    state.focus_stats.lock().clone()
-}
+        if let Err(e) = self.save_config() {
+            tracing::warn!("{}", e);
+        }
 
-/// 重置今日统计
-#[tauri::command]
-pub fn reset_stats(state: State<'_, Arc<AppState>>) {
-    let mut stats = state.focus_stats.lock();
-    stats.total_focus_ms = 0;
+        tracing::info!("Ideal face size calibrated: {:.3} ({} samples)", ideal_face_size, sizes.len());
+        Ok(ideal_face_size)
+    }
 
-    let mut machine = state.pet_state_machine.lock();
-    machine.reset_daily_stats();
+    /// 开始新的专注会话
+    fn start_session(&self) {
+        let total_focus_ms = self.pet_state_machine.lock().total_focus_ms;
+        *self.session.lock() = Some(SessionTracker::start(
+            chrono::Local::now().timestamp_millis(),
+            total_focus_ms,
+        ));
+        self.session_recording.lock().clear();
+        self.log_event(EventKind::SessionStarted, serde_json::json!({}));
+    }
 
-    tracing::info!("Focus stats reset");
-}
+    /// 写入会话记录，并累加到当前统计日
+    fn save_session(&self, mut tracker: SessionTracker) {
+        let now_ms = chrono::Local::now().timestamp_millis();
+        let total_focus_ms = self.pet_state_machine.lock().total_focus_ms;
+        let session = tracker.snapshot(now_ms, total_focus_ms);
 
-/// 获取视觉检测状态（详细信息）
-#[tauri::command]
-pub fn get_vision_status(state: State<'_, Arc<AppState>>) -> VisionStatusResponse {
-    let running = *state.vision_running.lock();
+        self.log_event(
+            EventKind::SessionEnded,
+            serde_json::json!({
+                "focus_ms": session.focus_duration_ms,
+                "distracted_ms": session.distracted_duration_ms,
+                "avg_focus_score": session.avg_focus_score,
+            }),
+        );
 
-    let focus_state = if running {
-        state.focus_state_rx.lock().as_ref().map(|rx| rx.borrow().clone())
-    } else {
-        None
-    };
+        match self.write_session(&mut tracker, now_ms, total_focus_ms) {
+            Ok(_) => tracing::info!(
+                "Session saved: focus {}ms, avg score {:.2}",
+                session.focus_duration_ms,
+                session.avg_focus_score
+            ),
+            Err(e) => tracing::warn!("Failed to save session: {}", e),
+        }
 
-    VisionStatusResponse {
-        is_running: running,
-        focus_state,
+        self.save_recording();
     }
-}
 
-/// 视觉检测状态响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VisionStatusResponse {
-    /// 是否正在运行
-    pub is_running: bool,
-    /// 当前专注状态
-    pub focus_state: Option<FocusState>,
-}
+    /// 会话进行中且开启了会话录制时，把专注状态加入当前会话的录制
+    fn record_session_state(&self, focus_state: &FocusState) {
+        let enabled = self.config.lock().privacy.max_recordings > 0;
+        let in_session = self.session.lock().is_some();
+        if !enabled || !in_session {
+            return;
+        }
 
-/// 预览帧数据（发送到前端）
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PreviewFrame {
-    /// Base64 编码的 JPEG 图像
-    pub data: String,
-    /// 图像宽度
-    pub width: u32,
-    /// 图像高度
-    pub height: u32,
-    /// 时间戳
-    pub timestamp_ms: u64,
-}
+        let mut recording = self.session_recording.lock();
+        if recording
+            .last()
+            .is_some_and(|last| focus_state.timestamp_ms < last.timestamp_ms + RECORDING_INTERVAL_MS)
+        {
+            return;
+        }
+        recording.push(focus_state.clone());
+    }
 
-/// 将摄像头帧编码为 base64 JPEG
-fn encode_frame_to_base64(frame: &CapturedFrame) -> Option<PreviewFrame> {
-    use image::{RgbImage, ImageEncoder, codecs::jpeg::JpegEncoder};
-    use std::io::Cursor;
+    /// 保存当前会话的录制，超出保留数量时删除最旧的录制
+    fn save_recording(&self) {
+        let states = std::mem::take(&mut *self.session_recording.lock());
+        if states.is_empty() || self.config.lock().privacy.max_recordings == 0 {
+            return;
+        }
 
-    // 从原始 RGB 数据创建图像
-    let img = RgbImage::from_raw(frame.width, frame.height, frame.data.clone())?;
+        match self.recording_store().and_then(|store| Ok(store.save(&states)?)) {
+            Ok(Some(info)) => tracing::info!("Session recording saved: {} ({} states)", info.id, info.samples),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to save session recording: {}", e),
+        }
+    }
 
-    // 缩小图像以减少传输大小（目标宽度 160px）
-    let scale = 160.0 / frame.width as f32;
-    let new_width = 160;
-    let new_height = (frame.height as f32 * scale) as u32;
-    let resized = image::imageops::resize(&img, new_width, new_height, image::imageops::FilterType::Triangle);
+    /// 把会话写入数据库，并把上一次写入后新增的时长累加到当前统计日
+    ///
+    /// 第一次写入时插入会话记录并计入会话数，之后（检查点或会话结束）只更新同一条记录，
+    /// 因此多次写入不会重复计算专注时长
+    fn write_session(
+        &self,
+        tracker: &mut SessionTracker,
+        now_ms: i64,
+        total_focus_ms: u64,
+    ) -> Result<CheckpointInfo, CommandError> {
+        let mut session = tracker.snapshot(now_ms, total_focus_ms);
+        let (focus_ms, distracted_ms) = tracker.unsaved_durations(&session);
+        let date = self.current_day.lock().clone();
 
-    // 编码为 JPEG
-    let mut buffer = Cursor::new(Vec::new());
-    let encoder = JpegEncoder::new_with_quality(&mut buffer, 60);
-    encoder.write_image(
-        resized.as_raw(),
-        new_width,
-        new_height,
-        image::ExtendedColorType::Rgb8,
-    ).ok()?;
+        session.id = with_database(self, |db| db.save_session_progress(&session, &date, focus_ms, distracted_ms))?;
+        tracker.mark_saved(&session);
+        *self.flushed_focus_ms.lock() = total_focus_ms;
 
-    // 转为 base64
-    let jpeg_data = buffer.into_inner();
-    let base64_data = BASE64.encode(&jpeg_data);
+        Ok(CheckpointInfo {
+            date,
+            session: Some(session),
+            focus_ms,
+            distracted_ms,
+            checkpointed_at_ms: now_ms,
+        })
+    }
 
-    Some(PreviewFrame {
-        data: format!("data:image/jpeg;base64,{}", base64_data),
-        width: new_width,
-        height: new_height,
-        timestamp_ms: frame.timestamp_ms,
-    })
+    /// 保存检查点：把进行中的会话和今日统计写入数据库，但不结束会话
+    ///
+    /// 自上次写入后没有新增时长时不会重复累加
+    pub fn checkpoint_session(&self) -> Result<CheckpointInfo, CommandError> {
+        let now_ms = chrono::Local::now().timestamp_millis();
+        // 先读取状态机再锁会话，与状态任务的加锁顺序（状态机 → 会话）一致
+        let total_focus_ms = self.pet_state_machine.lock().total_focus_ms;
+
+        if let Some(tracker) = self.session.lock().as_mut() {
+            return self.write_session(tracker, now_ms, total_focus_ms);
+        }
+
+        // 没有进行中的会话：只写入尚未保存的专注时长
+        let date = self.current_day.lock().clone();
+        let focus_ms = total_focus_ms.saturating_sub(*self.flushed_focus_ms.lock()) as i64;
+        if focus_ms > 0 {
+            with_database(self, |db| db.add_daily_stats(&date, focus_ms, 0, 0, 0))?;
+        }
+        *self.flushed_focus_ms.lock() = total_focus_ms;
+
+        Ok(CheckpointInfo {
+            date,
+            session: None,
+            focus_ms,
+            distracted_ms: 0,
+            checkpointed_at_ms: now_ms,
+        })
+    }
+
+    /// 手动补录一条已结束的会话，累加到会话开始当天的统计，返回会话 ID
+    ///
+    /// 要求 `end_ms > start_ms`、不晚于 `now_ms`，且专注与分心时长之和不超过会话时长
+    pub fn add_manual_session(
+        &self,
+        start_ms: i64,
+        end_ms: i64,
+        focus_ms: i64,
+        distracted_ms: i64,
+        label: Option<&str>,
+        now_ms: i64,
+    ) -> Result<i64, CommandError> {
+        if end_ms <= start_ms {
+            return Err(CommandError::InvalidArgument("Session must end after it starts".to_string()));
+        }
+        if end_ms > now_ms {
+            return Err(CommandError::InvalidArgument("Session cannot end in the future".to_string()));
+        }
+        // 极端的时间戳和时长相加减可能溢出，溢出同样视为不合法
+        let fits = match (focus_ms.checked_add(distracted_ms), end_ms.checked_sub(start_ms)) {
+            (Some(total), Some(span)) => focus_ms >= 0 && distracted_ms >= 0 && total <= span,
+            _ => false,
+        };
+        if !fits {
+            return Err(CommandError::InvalidArgument(
+                "Focus and distracted durations must fit within the session".to_string(),
+            ));
+        }
+        if label.is_some_and(|label| label.chars().count() > MAX_SESSION_LABEL_CHARS) {
+            return Err(CommandError::InvalidArgument(format!(
+                "Label must be at most {} characters",
+                MAX_SESSION_LABEL_CHARS
+            )));
+        }
+
+        let session = FocusSession {
+            id: 0,
+            start_time: start_ms,
+            end_time: end_ms,
+            focus_duration_ms: focus_ms,
+            distracted_duration_ms: distracted_ms,
+            avg_focus_score: 0.0,
+            min_focus_score: 0.0,
+            max_focus_score: 0.0,
+        };
+        let label = label.map(str::trim).filter(|label| !label.is_empty());
+
+        with_database(self, |db| db.record_session(&session, true, label))
+    }
+
+    /// 当前的统计更新：实时统计和今日数据库记录合并
+    pub fn stats_update(&self) -> Result<StatsUpdate, CommandError> {
+        let today = self.current_day.lock().clone();
+        let live = self.focus_stats.lock().clone();
+        let unflushed = self.unflushed_focus_ms();
+
+        let stored = with_database(self, |db| db.stats_or_empty(&today))?;
+        Ok(StatsUpdate::merge(live, stored, unflushed))
+    }
+
+    /// 按配置的上限裁剪所有内存缓冲区，丢弃最旧的条目，返回丢弃的总条数
+    pub fn enforce_buffer_limits(&self) -> usize {
+        let limits = self.config.lock().buffers.clone();
+        buffers::evict_oldest(&mut self.recent_focus_states.lock(), limits.focus_history)
+            + self.focus_sparkline.lock().evict_oldest(limits.sparkline)
+            + self.focus_trend.lock().evict_oldest(limits.trend)
+            + buffers::truncate_oldest(&mut self.pending_samples.lock(), limits.pending_samples)
+            + buffers::truncate_oldest(&mut self.pending_events.lock(), limits.pending_events)
+            + buffers::truncate_oldest(&mut self.session_recording.lock(), limits.session_recording)
+    }
+
+    /// 内存缓冲区的当前大小和估算的内存占用
+    pub fn buffer_stats(&self) -> BufferStats {
+        let limits = self.config.lock().buffers.clone();
+        let focus_history_len = self.recent_focus_states.lock().len();
+        let (sparkline_len, sparkline_bytes) = {
+            let sparkline = self.focus_sparkline.lock();
+            (sparkline.len(), sparkline.estimated_bytes())
+        };
+        let (trend_len, trend_bytes) = {
+            let trend = self.focus_trend.lock();
+            (trend.len(), trend.estimated_bytes())
+        };
+        let pending_samples_len = self.pending_samples.lock().len();
+        let pending_events_len = self.pending_events.lock().len();
+        let session_recording_len = self.session_recording.lock().len();
+
+        BufferStats {
+            focus_history_len,
+            sparkline_len,
+            trend_len,
+            pending_samples_len,
+            pending_events_len,
+            session_recording_len,
+            est_bytes: buffers::bytes_of::<FocusState>(focus_history_len + session_recording_len)
+                + sparkline_bytes
+                + trend_bytes
+                + buffers::bytes_of::<FocusSample>(pending_samples_len)
+                + buffers::bytes_of::<Event>(pending_events_len),
+            limits,
+        }
+    }
+
+    /// 尚未写入数据库的专注时长（毫秒）
+    fn unflushed_focus_ms(&self) -> u64 {
+        let total = self.pet_state_machine.lock().total_focus_ms;
+        total.saturating_sub(*self.flushed_focus_ms.lock())
+    }
+
+    /// 日期切换：把当前统计日的数据写入数据库，重置实时统计并开始新的一天
+    ///
+    /// 进行中的会话会在切换点结束并重新开始，保证数据落在正确的日期
+    ///
+    /// # Returns
+    /// 被结束的统计日
+    pub fn rollover_day(&self) -> String {
+        let _guard = self.rollover_lock.lock();
+        self.rollover_to(storage::today_key())
+    }
+
+    /// 检查是否跨天，跨天时执行日期切换
+    ///
+    /// # Returns
+    /// 发生切换时返回被结束的统计日；多个任务同时检测到跨天时只有一个执行切换
+    pub fn check_day_rollover(&self, now: chrono::DateTime<chrono::Local>) -> Option<String> {
+        let day = storage::day_key(now);
+        let _guard = self.rollover_lock.lock();
+        if *self.current_day.lock() == day {
+            return None;
+        }
+        Some(self.rollover_to(day))
+    }
+
+    /// 结束当前统计日并切换到 `new_day`，调用方需持有 `rollover_lock`
+    fn rollover_to(&self, new_day: String) -> String {
+        let previous_day = self.current_day.lock().clone();
+
+        let tracker = self.session.lock().take();
+        let restart_session = tracker.is_some();
+        match tracker {
+            Some(tracker) => self.save_session(tracker),
+            None => {
+                let unflushed = self.unflushed_focus_ms() as i64;
+                if unflushed > 0 {
+                    if let Err(e) = with_database(self, |db| db.add_daily_stats(&previous_day, unflushed, 0, 0, 0)) {
+                        tracing::warn!("Failed to flush daily stats: {}", e);
+                    }
+                }
+            }
+        }
+
+        // 重置实时统计
+        self.pet_state_machine.lock().reset_daily_stats();
+        self.focus_stats.lock().total_focus_ms = 0;
+        *self.flushed_focus_ms.lock() = 0;
+        *self.current_day.lock() = new_day;
+
+        if restart_session {
+            self.start_session();
+        }
+
+        tracing::info!("Day rollover: {} finished", previous_day);
+        previous_day
+    }
+
+    /// 清空当前统计日的数据（实时计数和数据库），之前的日期不受影响
+    pub fn reset_today(&self) -> Result<(), CommandError> {
+        self.reset_live_stats();
+
+        let date = self.current_day.lock().clone();
+        if self.database.lock().is_some() {
+            with_database(self, |db| db.clear_day(&date))?;
+        }
+
+        Ok(())
+    }
+
+    /// 清空全部数据（实时计数、待写入的缓冲和数据库记录，包括示例数据），目标设置保留
+    pub fn reset_all_data(&self) -> Result<(), CommandError> {
+        self.reset_live_stats();
+        self.pending_events.lock().clear();
+        self.pending_samples.lock().clear();
+
+        with_database(self, |db| db.reset_all_data())
+    }
+
+    /// 重置实时计数，进行中的会话从重置点重新开始
+    fn reset_live_stats(&self) {
+        self.focus_stats.lock().total_focus_ms = 0;
+        self.pet_state_machine.lock().reset_daily_stats();
+        *self.flushed_focus_ms.lock() = 0;
+
+        if let Some(ref mut session) = *self.session.lock() {
+            *session = SessionTracker::start(chrono::Local::now().timestamp_millis(), 0);
+        }
+    }
+
+    /// 记录活动事件（先放入缓冲区，由后台任务批量写入）
+    pub fn log_event(&self, kind: EventKind, payload: serde_json::Value) {
+        self.pending_events.lock().push(Event::now(kind, payload));
+    }
+
+    /// 把缓冲的事件写入数据库
+    ///
+    /// 数据库不可用时保留事件，稍后重试
+    pub fn flush_events(&self) {
+        let events = std::mem::take(&mut *self.pending_events.lock());
+        if events.is_empty() {
+            return;
+        }
+
+        if let Err(e) = with_database(self, |db| db.insert_events(&events)) {
+            tracing::warn!("Failed to write {} events: {}", events.len(), e);
+            // 放回队列等待下次写入，数据库持续不可用时按上限丢弃最旧的事件
+            let limit = self.config.lock().buffers.pending_events;
+            let mut pending = self.pending_events.lock();
+            let newer = std::mem::replace(&mut *pending, events);
+            pending.extend(newer);
+            let dropped = buffers::truncate_oldest(&mut pending, limit);
+            if dropped > 0 {
+                tracing::warn!("Dropped {} oldest pending events", dropped);
+            }
+        }
+    }
+
+    /// 更新配置并应用到运行中的状态机和视觉处理器，返回按字段分类的应用结果（不负责重启视觉检测）
+    ///
+    /// 状态机按新配置整体重建，报告为立即生效的字段都已生效
+    pub fn apply_config_update(&self, config: AppConfig, mode: ApplyMode) -> Result<ApplyReport, CommandError> {
+        let report = {
+            let mut current = self.config.lock();
+            let report = current
+                .apply_update(config, mode)
+                .map_err(|e| CommandError::InvalidArgument(e.to_string()))?;
+
+            self.pet_state_machine
+                .lock()
+                .apply_config(PetStateConfig::from_app_config(&current));
+            if report.live.iter().any(|path| path == "camera.fps") {
+                *self.camera_fps.lock() = Some(crate::vision::clamp_fps(current.camera.fps));
+            }
+            if let Some(ref processor) = *self.vision_processor.lock() {
+                processor.set_focus_config(current.focus.calculator_config());
+                processor.set_focus_thresholds(current.focus.enter_threshold, current.focus.exit_threshold);
+                if let Some(fps) = *self.camera_fps.lock() {
+                    processor.set_camera_fps(fps);
+                }
+            }
+            report
+        };
+
+        if let Err(e) = self.save_config() {
+            tracing::warn!("{}", e);
+        }
+        Ok(report)
+    }
+
+    /// 应用手势：宠物进入互动模式，反应取决于互动前的情绪，情绪变化和手势都记录为活动事件
+    pub fn apply_gesture(&self, gesture_type: GestureType) -> GestureReaction {
+        let mut machine = self.pet_state_machine.lock();
+        let previous_mood = machine.mood;
+        let reaction = machine.on_gesture(gesture_type);
+        if machine.mood != previous_mood {
+            self.log_event(
+                EventKind::MoodChanged,
+                serde_json::json!({ "from": previous_mood, "to": machine.mood }),
+            );
+        }
+        drop(machine);
+
+        self.log_event(
+            EventKind::Gesture,
+            serde_json::json!({ "gesture": gesture_type, "mood": reaction.mood, "cue": reaction.cue }),
+        );
+        reaction
+    }
+
+    /// 写入一条分心记录
+    fn save_distraction(&self, event: &DistractionEvent) {
+        if let Err(e) = with_database(self, |db| db.insert_distraction(event)) {
+            tracing::warn!("Failed to save distraction: {}", e);
+        }
+    }
+
+    /// 开始一次专注挑战，已有进行中的挑战时返回错误
+    pub fn start_challenge(&self, now_ms: i64, target_ms: i64) -> Result<ChallengeProgress, CommandError> {
+        let mut challenge = self.challenge.lock();
+        if challenge.is_some() {
+            return Err(CommandError::InvalidArgument("A challenge is already running".to_string()));
+        }
+        let started = Challenge::new(now_ms, target_ms);
+        let progress = started.progress();
+        *challenge = Some(started);
+        Ok(progress)
+    }
+
+    /// 放弃进行中的挑战，返回放弃时的进度
+    pub fn abandon_challenge(&self) -> Option<ChallengeProgress> {
+        self.challenge.lock().take().map(|challenge| challenge.progress())
+    }
+
+    /// 把专注等级计入进行中的挑战；达到目标时结束挑战并写入记录
+    fn observe_challenge(&self, now_ms: i64, level: FocusLevel) -> Option<ChallengeUpdate> {
+        let mut challenge = self.challenge.lock();
+        let update = challenge.as_mut()?.observe(now_ms, level);
+        if let Some(ChallengeUpdate::Complete(ref progress)) = update {
+            *challenge = None;
+            let record = ChallengeRecord {
+                id: 0,
+                started_at_ms: progress.started_at_ms,
+                completed_at_ms: now_ms,
+                target_ms: progress.target_ms,
+            };
+            if let Err(e) = with_database(self, |db| db.insert_challenge(&record)) {
+                tracing::warn!("Failed to save challenge: {}", e);
+            }
+        }
+        update
+    }
+
+    /// 数据目录中缓存的锚框文件路径（数据目录未确定时返回 None）
+    fn anchors_cache_path(&self) -> Option<PathBuf> {
+        Some(self.data_dir.lock().as_ref()?.join("models").join(ANCHORS_FILE))
+    }
+
+    /// 选择可用的锚框文件：打包的文件缺失或损坏时改用数据目录中的缓存（必要时重新生成）
+    fn usable_anchors_path(&self, bundled: String) -> String {
+        if BlazeFaceDetector::anchors_file_valid(&bundled) {
+            return bundled;
+        }
+        let Some(cache) = self.anchors_cache_path() else {
+            return bundled;
+        };
+        match BlazeFaceDetector::ensure_anchors_file(&cache) {
+            Ok(_) => {
+                tracing::warn!("Bundled anchors {} unusable, using {}", bundled, cache.display());
+                cache.to_string_lossy().into_owned()
+            }
+            Err(e) => {
+                tracing::warn!("Failed to write anchors cache {}: {}", cache.display(), e);
+                bundled
+            }
+        }
+    }
+
+    /// 记录专注分数采样（只记录检测到人脸时的分数，按固定间隔降采样）
+    pub fn record_focus_sample(&self, timestamp_ms: i64, focus_score: f32, face_present: bool) {
+        if !face_present {
+            return;
+        }
+
+        let mut last = self.last_sample_ms.lock();
+        if timestamp_ms - *last < FOCUS_SAMPLE_INTERVAL_MS {
+            return;
+        }
+        *last = timestamp_ms;
+
+        self.pending_samples.lock().push(FocusSample { timestamp_ms, focus_score });
+    }
+
+    /// 把缓冲的专注分数采样写入数据库
+    ///
+    /// 数据库不可用时保留采样，稍后重试
+    pub fn flush_samples(&self) {
+        let samples = std::mem::take(&mut *self.pending_samples.lock());
+        if samples.is_empty() {
+            return;
+        }
+
+        if let Err(e) = with_database(self, |db| db.insert_focus_samples(&samples)) {
+            tracing::warn!("Failed to write {} focus samples: {}", samples.len(), e);
+            let mut pending = self.pending_samples.lock();
+            let newer = std::mem::replace(&mut *pending, samples);
+            pending.extend(newer);
+        }
+    }
+
+    /// 检查今日目标进度：跨过新的进度档位时返回 `goal_progress` 事件，首次完成时记录活动事件
+    ///
+    /// 两次检查之间跨过多个档位时只返回最高的一个，见 [`GoalProgressTracker::observe`]
+    fn check_goal_progress(&self) -> Option<GoalProgressEvent> {
+        let today = self.current_day.lock().clone();
+        let progress = with_database(self, |db| db.get_goal_progress(&today)).ok()?;
+        let progress = progress.with_extra_focus(self.unflushed_focus_ms() as i64);
+
+        let completed_today = self.goal_completed_day.lock().as_deref() == Some(today.as_str());
+        if !completed_today && progress.target_minutes > 0 && progress.percent >= 100.0 {
+            self.log_event(
+                EventKind::GoalCompleted,
+                serde_json::json!({ "date": today, "target_minutes": progress.target_minutes }),
+            );
+            *self.goal_completed_day.lock() = Some(today.clone());
+        }
+
+        let step = self.config.lock().ui.goal_progress_step;
+        let mut tracker = self.goal_progress.lock();
+        tracker.set_step(step);
+        tracker.observe(&today, progress.achieved_ms, progress.target_minutes * 60_000)
+    }
+
+    /// 最近 `FATIGUE_LOOKBACK_MS` 内的专注分数采样（包括尚未写入数据库的采样），按时间升序
+    fn recent_focus_samples(&self, now_ms: i64) -> Result<Vec<FocusSample>, CommandError> {
+        let mut samples =
+            with_database(self, |db| db.get_focus_samples(now_ms - fatigue::FATIGUE_LOOKBACK_MS, now_ms + 1))?;
+        samples.extend(self.pending_samples.lock().iter().copied());
+        samples.sort_by_key(|s| s.timestamp_ms);
+        Ok(samples)
+    }
+
+    /// 开启看手机检测时观察一次专注状态，持续低头达到设定时长时返回信号
+    fn observe_phone_glance(&self, focus_state: &FocusState) -> Option<PhoneDistraction> {
+        let (enabled, config) = {
+            let config = self.config.lock();
+            (config.focus.phone_detection_enabled, config.focus.phone_glance_config())
+        };
+        if !enabled {
+            return None;
+        }
+        let mut detector = self.phone_glance.lock();
+        detector.set_config(config);
+        detector.observe(focus_state.timestamp_ms as i64, focus_state.pitch, focus_state.face_present)
+    }
+
+    /// 开启休息提醒（配置 `pet.break_reminders`）时检查是否需要提醒休息
+    ///
+    /// 两次提醒之间至少间隔 `BREAK_REMINDER_INTERVAL_MS`
+    fn check_break_reminder(&self, now_ms: i64) -> Option<BreakSuggestion> {
+        if !self.config.lock().pet.break_reminders {
+            return None;
+        }
+        let mut last = self.last_break_reminder_ms.lock();
+        if now_ms - *last < BREAK_REMINDER_INTERVAL_MS {
+            return None;
+        }
+        let suggestion = fatigue::suggest_break(&self.recent_focus_samples(now_ms).ok()?)?;
+        *last = now_ms;
+        Some(suggestion)
+    }
+}
+
+/// 事件写入间隔（秒）
+const EVENT_FLUSH_SECS: u64 = 5;
+
+/// 专注分数采样间隔（毫秒）
+const FOCUS_SAMPLE_INTERVAL_MS: i64 = 5_000;
+
+/// 两次主动休息提醒之间的最短间隔（毫秒）
+const BREAK_REMINDER_INTERVAL_MS: i64 = 30 * 60_000;
+
+/// 最多保留的待发送会话回顾，超出时丢弃最旧的
+const MAX_PENDING_RECAPS: usize = 8;
+
+/// 启动事件和专注分数采样的批量写入任务（同时检查今日目标进度，跨过新档位时发送 `goal_progress` 事件；
+/// 开启休息提醒时，专注回落发送 `break_reminder` 事件；会话结束后发送 `session_recap` 事件）
+pub fn spawn_event_flush(state: Arc<AppState>, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(EVENT_FLUSH_SECS));
+        loop {
+            interval.tick().await;
+            if let Some(progress) = state.check_goal_progress() {
+                tracing::info!("Daily goal progress reached {}%", progress.percent);
+                let _ = app_handle.emit("goal_progress", &progress);
+                let kind = if progress.percent >= 100 {
+                    NotificationKind::GoalReached
+                } else {
+                    NotificationKind::GoalProgress
+                };
+                notify(&state, &app_handle, kind);
+            }
+            if let Some(suggestion) = state.check_break_reminder(chrono::Local::now().timestamp_millis()) {
+                tracing::info!("Break reminder: {} minutes", suggestion.recommended_minutes);
+                let _ = app_handle.emit("break_reminder", &suggestion);
+                notify(&state, &app_handle, NotificationKind::BreakReminder);
+            }
+            for recap in state.take_session_recaps() {
+                let _ = app_handle.emit("session_recap", &recap);
+            }
+            state.flush_events();
+            state.flush_samples();
+        }
+    });
+}
+
+/// 定期推送的统计更新（`stats_update` 事件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsUpdate {
+    /// 实时统计
+    pub live: FocusStats,
+    /// 今日统计：数据库中的记录加上尚未写入的专注时间
+    pub today: storage::DailyStats,
+}
+
+impl StatsUpdate {
+    /// 合并实时统计和今日数据库记录
+    ///
+    /// 实时累计的专注时间中已写入数据库的部分已包含在 `persisted` 中，只补上 `unflushed_focus_ms`
+    fn merge(live: FocusStats, mut persisted: storage::DailyStats, unflushed_focus_ms: u64) -> Self {
+        persisted.total_focus_ms += unflushed_focus_ms as i64;
+        Self { live, today: persisted }
+    }
+}
+
+/// 开始定期发送 `stats_update` 事件，已有发送任务时先停止旧任务
+///
+/// 间隔每次发送后按配置 `stats.stream_interval_secs` 重新读取，修改配置立即生效
+pub fn start_stats_stream(state: Arc<AppState>, app_handle: tauri::AppHandle) {
+    let task_state = state.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        loop {
+            match task_state.stats_update() {
+                Ok(update) => {
+                    let _ = app_handle.emit("stats_update", &update);
+                }
+                Err(e) => tracing::warn!("Failed to build stats update: {}", e),
+            }
+            let interval_secs = task_state.config.lock().stats.stream_interval_secs.max(1);
+            tokio::time::sleep(std::time::Duration::from_secs(interval_secs as u64)).await;
+        }
+    });
+    if let Some(previous) = state.stats_stream.lock().replace(handle) {
+        previous.abort();
+    }
+}
+
+/// 停止发送 `stats_update` 事件，返回之前是否在发送
+pub fn stop_stats_stream(state: &AppState) -> bool {
+    match state.stats_stream.lock().take() {
+        Some(handle) => {
+            handle.abort();
+            true
+        }
+        None => false,
+    }
+}
+
+/// 日期检查间隔（秒）
+const DAY_ROLLOVER_CHECK_SECS: u64 = 30;
+
+/// 检查是否跨天，跨天时通知前端
+fn check_day_rollover(state: &AppState, app_handle: &tauri::AppHandle) {
+    if let Some(previous_day) = state.check_day_rollover(chrono::Local::now()) {
+        let _ = app_handle.emit("day_rollover", &previous_day);
+        notify(state, app_handle, NotificationKind::DayRollover);
+    }
+}
+
+/// 按通知偏好发送 `notification` 事件，类型关闭或处于免打扰时段时不发送
+fn notify(state: &AppState, app_handle: &tauri::AppHandle, kind: NotificationKind) {
+    let notification = {
+        let config = state.config.lock();
+        Notification::build(&config.notifications, config.ui.locale, kind, chrono::Local::now().hour(), false)
+    };
+    if let Some(notification) = notification {
+        let _ = app_handle.emit("notification", &notification);
+    }
+}
+
+/// 启动跨天检测任务
+///
+/// 与视觉检测无关，用户午夜时不在电脑前（或未开启摄像头）也能按时切换
+pub fn spawn_day_rollover_watch(state: Arc<AppState>, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(DAY_ROLLOVER_CHECK_SECS));
+        loop {
+            interval.tick().await;
+            check_day_rollover(&state, &app_handle);
+        }
+    });
+}
+
+/// 锁屏状态检查间隔（秒）
+const SCREEN_LOCK_CHECK_SECS: u64 = 2;
+
+/// 启动系统锁屏检测任务（配置 `focus.pause_when_locked` 开启时生效）
+///
+/// 锁屏时宠物立即进入离开状态，解锁后等待重新检测到人脸
+pub fn spawn_screen_lock_watch(state: Arc<AppState>, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(SCREEN_LOCK_CHECK_SECS));
+        loop {
+            interval.tick().await;
+
+            let locked = if state.config.lock().focus.pause_when_locked {
+                crate::state::screen_lock::is_screen_locked().unwrap_or(false)
+            } else {
+                false
+            };
+
+            let mut machine = state.pet_state_machine.lock();
+            if machine.set_screen_locked(locked).is_some() {
+                emit_mood_changed(&app_handle, &machine);
+            }
+        }
+    });
+}
+
+/// 启动系统睡眠唤醒检测任务
+///
+/// 唤醒后停止睡眠前的视觉检测、重置宠物计时并发送 `system_resumed`，
+/// 睡眠前在运行且开启 `camera.restart_after_resume` 时重新启动视觉检测
+pub fn spawn_suspend_watch(state: Arc<AppState>, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut detector = SuspendDetector::default();
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_millis(SUSPEND_CHECK_INTERVAL_MS as u64));
+        loop {
+            interval.tick().await;
+            let Some(slept_ms) = detector.observe(chrono::Local::now().timestamp_millis()) else {
+                continue;
+            };
+            tracing::info!("System resumed after about {} s", slept_ms / 1000);
+
+            // 摄像头探测可能阻塞
+            let report = {
+                let state = Arc::clone(&state);
+                match tokio::task::spawn_blocking(move || state.handle_resume(slept_ms)).await {
+                    Ok(report) => report,
+                    Err(e) => {
+                        tracing::warn!("Resume handling failed: {}", e);
+                        continue;
+                    }
+                }
+            };
+            emit_mood_changed(&app_handle, &state.pet_state_machine.lock());
+            let _ = app_handle.emit("system_resumed", &report);
+
+            if report.restart {
+                if let Err(e) = start_vision(app_handle.state::<Arc<AppState>>(), app_handle.clone()).await {
+                    tracing::warn!("Failed to restart vision after resume: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// 宠物窗口标签
+pub const PET_WINDOW_LABEL: &str = "pet";
+
+/// 按标签查找窗口，找不到时返回 `WindowNotFound`
+fn find_window<W>(label: &str, lookup: impl FnOnce(&str) -> Option<W>) -> Result<W, CommandError> {
+    lookup(label).ok_or_else(|| CommandError::WindowNotFound(label.to_string()))
+}
+
+/// 获取宠物窗口
+///
+/// 所有需要操作宠物窗口的地方都应通过此函数获取，窗口不存在时返回明确的错误
+pub fn pet_window(app: &tauri::AppHandle) -> Result<tauri::WebviewWindow, CommandError> {
+    find_window(PET_WINDOW_LABEL, |label| app.get_webview_window(label))
+}
+
+/// 在数据库上执行操作，数据库不可用时返回错误
+///
+/// `f` 可以返回数据库错误，也可以直接返回命令错误（需要校验参数时）
+fn with_database<T, E: Into<CommandError>>(
+    state: &AppState,
+    f: impl FnOnce(&Database) -> Result<T, E>,
+) -> Result<T, CommandError> {
+    let db = state.database.lock();
+    let db = db
+        .as_ref()
+        .ok_or_else(|| CommandError::Database("Database is not available".to_string()))?;
+    f(db).map_err(Into::into)
+}
+
+/// 显示或隐藏宠物窗口
+fn apply_window_action(app_handle: &tauri::AppHandle, action: WindowAction) -> Result<(), CommandError> {
+    let window = pet_window(app_handle)?;
+    match action {
+        WindowAction::Hide => window.hide(),
+        WindowAction::Show => window.show(),
+    }
+    .map_err(|e| CommandError::Window(e.to_string()))
+}
+
+/// 手动显示或隐藏宠物窗口
+///
+/// 开启专注时自动隐藏时，手动设置会保持到专注状态下一次变化
+#[tauri::command]
+pub fn set_pet_visible(
+    visible: bool,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), CommandError> {
+    apply_window_action(&app_handle, if visible { WindowAction::Show } else { WindowAction::Hide })?;
+    let machine = state.pet_state_machine.lock();
+    state.auto_hide.lock().set_manual(visible, machine.mood, machine.is_deep_focus());
+    Ok(())
+}
+
+/// 获取当前宠物状态
+#[tauri::command]
+pub fn get_pet_state(state: State<'_, Arc<AppState>>) -> PetStateResponse {
+    state.pet_state()
+}
+
+/// 专注状态距离产生它的检测超过该时长（毫秒）视为过时
+const STALE_FOCUS_STATE_MS: u64 = 2_000;
+
+/// 专注状态是否已过时（处理卡顿、持续跳帧或外部来源停止推送时，界面据此显示“过时”提示）
+fn focus_state_is_stale(focus_state: &FocusState) -> bool {
+    let now_ms = chrono::Local::now().timestamp_millis() as u64;
+    focus_state.is_stale_at(now_ms, STALE_FOCUS_STATE_MS)
+}
+
+/// 情绪变化事件（`pet_mood_changed`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoodChangedPayload {
+    /// 新的情绪
+    pub mood: PetMood,
+    /// 情绪强度 (0.0 - 1.0)
+    pub intensity: f32,
+}
+
+/// 发送情绪变化事件
+fn emit_mood_changed(app_handle: &tauri::AppHandle, machine: &PetStateMachine) {
+    let payload = MoodChangedPayload {
+        mood: machine.mood,
+        intensity: machine.intensity(),
+    };
+    let _ = app_handle.emit("pet_mood_changed", payload);
+}
+
+/// 宠物状态响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PetStateResponse {
+    /// 当前情绪
+    pub mood: PetMood,
+    /// 情绪强度 (0.0 - 1.0)
+    pub intensity: f32,
+    /// 当前专注分数
+    pub focus_score: f32,
+    /// 今日累计专注时间（分钟）
+    pub total_focus_minutes: f32,
+    /// 视觉检测是否活跃
+    pub is_vision_active: bool,
+    /// 是否检测到人脸
+    pub face_detected: bool,
+    /// 是否处于手动休息
+    pub on_break: bool,
+    /// 人脸短暂丢失，宠物正在寻找用户
+    pub looking_for_user: bool,
+    /// 专注分数已过时（视觉检测或外部来源活动中但一段时间没有新的结果）
+    pub is_stale: bool,
+    /// 连续专注已达到深度专注时间
+    pub deep_focus: bool,
+}
+
+/// 启动状态更新任务：把专注来源推送的专注状态应用到宠物状态机、会话和统计，并转发给前端
+///
+/// 来源关闭通道（摄像头停止或切换来源）时任务结束
+fn spawn_focus_state_task(state: Arc<AppState>, app_handle: tauri::AppHandle, focus_rx: watch::Receiver<FocusState>) {
+    tokio::spawn(async move {
+        let mut rx = focus_rx;
+        let mut last_update = std::time::SystemTime::now();
+
+        while rx.changed().await.is_ok() {
+            let focus_state = rx.borrow().clone();
+
+            // 跨天时先结算前一天，避免数据计入错误的日期
+            check_day_rollover(&state, &app_handle);
+
+            // 摄像头实际帧率持续过低时提醒，并按实际帧间隔累计专注时间
+            let (auto_hide_enabled, low_fps_compensation, target_fps, accessibility) = {
+                let config = state.config.lock();
+                let target_fps = state.camera_fps.lock().unwrap_or(config.camera.fps);
+                let accessibility = config
+                    .ui
+                    .accessibility_mode
+                    .then_some((config.ui.locale, config.ui.spoken_descriptions));
+                (config.pet.auto_hide_on_focus, config.camera.low_fps_compensation, target_fps, accessibility)
+            };
+            let is_camera = *state.focus_source.lock() == FocusSource::Camera;
+            let (low_fps_warning, low_fps) = if is_camera {
+                let mut monitor = state.frame_rate.lock();
+                (monitor.observe(focus_state.timestamp_ms as i64, target_fps), monitor.is_low())
+            } else {
+                (None, false)
+            };
+            if let Some(ref warning) = low_fps_warning {
+                tracing::warn!(
+                    "Camera is delivering {:.1} fps (target {} fps)",
+                    warning.measured_fps,
+                    warning.target_fps
+                );
+                let _ = app_handle.emit("low_fps_warning", warning);
+            }
+
+            // 更新宠物状态机
+            let distraction;
+            let challenge;
+            let window_action;
+            {
+                let mut machine = state.pet_state_machine.lock();
+                let previous_mood = machine.mood;
+                machine.set_real_time_accrual(low_fps_compensation && low_fps);
+
+                // 系统睡眠唤醒后墙钟跳变：睡眠时段视为离开
+                // 只检查摄像头来源：外部来源的推送间隔本身可能很长，睡眠唤醒由唤醒检测任务处理
+                let now = std::time::SystemTime::now();
+                let elapsed = now.duration_since(last_update).unwrap_or_default();
+                last_update = now;
+                if is_camera && machine.handle_time_jump(elapsed) {
+                    emit_mood_changed(&app_handle, &machine);
+                }
+
+                // 运动唤醒：在人脸锁定前提前离开睡觉状态
+                if focus_state.motion_wake && machine.on_motion().is_some() {
+                    emit_mood_changed(&app_handle, &machine);
+                }
+
+                let confidence = focus_state.face_present.then_some(focus_state.face_confidence);
+                let new_mood =
+                    machine.update_with_confidence(focus_state.focus_score, focus_state.face_present, confidence);
+
+                // 如果状态改变，发送事件到前端
+                if new_mood.is_some() {
+                    emit_mood_changed(&app_handle, &machine);
+                }
+
+                // 无障碍模式：状态描述变化时发送文字描述
+                if let Some((locale, speak)) = accessibility {
+                    let focus_minutes = (machine.current_focus_secs() / 60.0) as u64;
+                    let mut narrator = state.accessible.lock();
+                    if let Some(accessible) =
+                        narrator.observe(locale, speak, machine.mood, machine.focus_level, focus_minutes)
+                    {
+                        let _ = app_handle.emit("accessible_state", &accessible);
+                    }
+                }
+
+                // 更新统计
+                let mut stats = state.focus_stats.lock();
+                stats.focus_score = focus_state.focus_score;
+                stats.current_mood = machine.mood;
+                stats.focus_level = machine.focus_level;
+                stats.total_focus_ms = machine.total_focus_ms;
+
+                // 累计会话数据
+                if let Some(ref mut session) = *state.session.lock() {
+                    session.record(focus_state.timestamp_ms as i64, focus_state.focus_score, machine.focus_level);
+                }
+                state.record_focus_sample(
+                    focus_state.timestamp_ms as i64,
+                    focus_state.focus_score,
+                    focus_state.face_present,
+                );
+                distraction = state.distraction.lock().observe(
+                    focus_state.timestamp_ms as i64,
+                    machine.focus_level,
+                    focus_state.face_present,
+                );
+                challenge = state.observe_challenge(focus_state.timestamp_ms as i64, machine.focus_level);
+
+                if machine.mood != previous_mood {
+                    state.log_event(
+                        EventKind::MoodChanged,
+                        serde_json::json!({ "from": previous_mood, "to": machine.mood }),
+                    );
+                }
+
+                window_action = state.auto_hide.lock().observe(
+                    focus_state.timestamp_ms as i64,
+                    auto_hide_enabled,
+                    machine.mood,
+                    machine.focus_level,
+                    machine.is_deep_focus(),
+                );
+            }
+
+            // 专注时自动隐藏宠物，离开专注时重新显示
+            if let Some(action) = window_action {
+                if let Err(e) = apply_window_action(&app_handle, action) {
+                    tracing::warn!("Failed to auto {:?} pet window: {}", action, e);
+                }
+            }
+
+            // 一次分心结束，写入记录
+            if let Some(ref event) = distraction {
+                state.save_distraction(event);
+            }
+
+            // 专注挑战的倒计时和完成
+            match challenge {
+                Some(ChallengeUpdate::Progress(progress)) => {
+                    let _ = app_handle.emit("challenge_progress", &progress);
+                }
+                Some(ChallengeUpdate::Complete(progress)) => {
+                    tracing::info!("Challenge completed ({}ms)", progress.target_ms);
+                    let _ = app_handle.emit("challenge_complete", &progress);
+                    notify(&state, &app_handle, NotificationKind::ChallengeComplete);
+                }
+                None => {}
+            }
+
+            // 保留最近的专注状态（用于诊断包）
+            state.recent_focus_states.lock().push_back(focus_state.clone());
+            state.record_session_state(&focus_state);
+
+            // 持续低头看手机时让宠物提醒
+            if let Some(signal) = state.observe_phone_glance(&focus_state) {
+                tracing::info!("Phone distraction detected ({}ms)", signal.duration_ms);
+                let _ = app_handle.emit("phone_distraction", &signal);
+                notify(&state, &app_handle, NotificationKind::PhoneDistraction);
+            }
+            state
+                .focus_trend
+                .lock()
+                .push(focus_state.timestamp_ms as i64, focus_state.focus_score);
+            state
+                .focus_sparkline
+                .lock()
+                .push(focus_state.timestamp_ms as i64, focus_state.focus_score);
+            state.enforce_buffer_limits();
+
+            // 写入采样日志（不阻塞）
+            if let Some(ref logger) = *state.focus_log.lock() {
+                logger.log(&focus_state);
+            }
+
+            // 发送专注状态事件
+            let _ = app_handle.emit("focus_state", &focus_state);
+        }
+
+        tracing::info!("Focus state update task ended");
+    });
+}
+
+/// 视觉检测启动守卫，见 [`AppState::begin_vision_start`]
+struct VisionStartGuard<'a> {
+    state: &'a AppState,
+    committed: bool,
+}
+
+impl VisionStartGuard<'_> {
+    /// 启动成功，保留运行状态并计入本次运行的视觉检测次数
+    fn commit(mut self) {
+        self.committed = true;
+        *self.state.vision_sessions.lock() += 1;
+    }
+}
+
+impl Drop for VisionStartGuard<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.state.stop_focus_source();
+            *self.state.vision_running.lock() = false;
+            tracing::warn!("Vision start failed, state rolled back");
+        }
+    }
+}
+
+/// 启动视觉检测的时间上限（解析模型路径、探测摄像头）
+const VISION_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// 解析模型路径并按当前设置构建视觉处理器配置，然后试探摄像头是否可用
+fn prepare_vision_config(state: &AppState, app_handle: &tauri::AppHandle) -> Result<VisionProcessorConfig, CommandError> {
+    let (model_dir, debug_raw_output) = {
+        let config = state.config.lock();
+        (config.model.dir.clone(), config.model.debug_raw_output)
+    };
+    let (model_path, anchors_path) =
+        resolve_model_paths(app_handle, model_dir.as_deref()).map_err(CommandError::Vision)?;
+    let anchors_path = state.usable_anchors_path(anchors_path);
+
+    // 创建视觉处理器配置（按当前档位选择计算器参数）
+    let (calculator, cadence, heat, passivity, turn_grace, mock_seed, frame_timeout_secs, preview_fps) = {
+        let config = state.config.lock();
+        (
+            config.focus.calculator_config(),
+            config.camera.detection_cadence(&config.focus),
+            config.focus.heat_config(),
+            config.focus.passivity_config(),
+            config.focus.turn_grace_config(),
+            config.camera.mock_seed,
+            config.camera.frame_timeout_secs,
+            config.camera.preview_fps,
+        )
+    };
+    let mut config = VisionProcessorConfig {
+        model_path,
+        anchors_path: Some(anchors_path),
+        detect_every_frame: false, // 按专注分数自适应检测间隔以降低 CPU
+        cadence,
+        focus: calculator,
+        heat,
+        passivity,
+        turn_grace,
+        frame_timeout_ms: frame_timeout_secs as u64 * 1000,
+        preview_fps,
+        mock_detection: MockDetectionConfig {
+            seed: mock_seed,
+            ..Default::default()
+        },
+        debug_raw_output,
+        ..Default::default()
+    };
+    if let Some(fps) = *state.camera_fps.lock() {
+        config.camera.target_fps = fps;
+    }
+    if let Some((width, height)) = *state.camera_resolution.lock() {
+        config.camera.width = width;
+        config.camera.height = height;
+    }
+    if let Some(threshold) = *state.nms_threshold.lock() {
+        config.nms_threshold = threshold;
+    }
+
+    // 先试探摄像头，设备被其他应用占用时给出明确的错误
+    CameraCapture::probe(&config.camera).map_err(camera_probe_error)?;
+
+    Ok(config)
+}
+
+/// 启动视觉检测
+#[tauri::command]
+pub async fn start_vision(
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), CommandError> {
+    let guard = state.begin_vision_start()?;
+
+    tracing::info!("Starting vision detection...");
+
+    // 资源目录和摄像头探测在文件系统或驱动异常时可能卡住，放到阻塞线程并限制时间，超时时前端可以重试
+    let config = {
+        let state = Arc::clone(&state);
+        let app_handle = app_handle.clone();
+        blocking_with_timeout("start_vision", VISION_START_TIMEOUT, move || {
+            prepare_vision_config(&state, &app_handle)
+        })
+        .await?
+    };
+
+    // 启动逻辑中的任何错误都会在返回前丢弃守卫，回滚 vision_running 和已创建的处理器
+    let result = (|| -> Result<(), CommandError> {
+        // 创建视觉处理器
+        let processor = Arc::new(VisionProcessor::new(config));
+        let focus_rx = processor.subscribe();
+        let frame_rx = processor.subscribe_frames();
+        let overlay_rx = processor.subscribe_overlay();
+        let mut recovered_rx = processor.subscribe_recoveries();
+
+        // 启动处理器
+        processor.start().map_err(CommandError::Vision)?;
+
+        // 保存处理器和接收器
+        {
+            *state.vision_processor.lock() = Some(processor.clone());
+            *state.focus_state_rx.lock() = Some(focus_rx.clone());
+        }
+
+        // 开始新的专注会话
+        state.start_session();
+
+        // 用户开启采样日志时启动写入线程
+        if state.config.lock().privacy.focus_log_enabled {
+            let mut focus_log = state.focus_log.lock();
+            if focus_log.is_none() {
+                if let Some(log_config) = state.focus_log_config() {
+                    match FocusLogger::spawn(log_config) {
+                        Ok(logger) => *focus_log = Some(logger),
+                        Err(e) => tracing::warn!("Failed to start focus log: {}", e),
+                    }
+                }
+            }
+        }
+
+        // 启动状态更新任务
+        spawn_focus_state_task(Arc::clone(&state), app_handle.clone(), focus_rx);
+
+        // 看门狗重启采集后恢复出帧时通知前端
+        let app_handle_recovered = app_handle.clone();
+        tokio::spawn(async move {
+            while recovered_rx.changed().await.is_ok() {
+                let recoveries = *recovered_rx.borrow_and_update();
+                tracing::info!("Vision recovered (total {})", recoveries);
+                let _ = app_handle_recovered.emit("vision_recovered", recoveries);
+            }
+        });
+
+        // 启动预览帧推送任务
+        let app_handle_preview = app_handle.clone();
+        let state_preview = Arc::clone(&state);
+        tokio::spawn(async move {
+            tracing::info!("Vision preview task started, waiting for frames...");
+            let mut rx = frame_rx;
+            let mut frame_count = 0u64;
+
+            while rx.changed().await.is_ok() {
+                let frame = rx.borrow().clone();
+
+                if frame.is_empty() {
+                    continue;
+                }
+
+                frame_count += 1;
+                if frame_count == 1 {
+                    tracing::info!("First frame received for preview: {}x{}", frame.width, frame.height);
+                }
+
+                // 将 RGB 帧编码为 JPEG 并转为 base64（按隐私设置模糊，原始帧不受影响）
+                let (blur, strength) = {
+                    let config = state_preview.config.lock();
+                    (config.privacy.preview_blur, config.privacy.preview_blur_strength)
+                };
+                if let Some(preview) = encode_frame_to_base64(&frame, blur, strength) {
+                    if frame_count % 30 == 1 {
+                        tracing::debug!("Emitting preview frame #{}", frame_count);
+                    }
+                    // 同一帧的检测结果，坐标按预览图尺寸换算
+                    let overlay = overlay_rx.borrow().clone();
+                    if overlay.frame_id == frame.frame_id {
+                        let _ = app_handle_preview.emit("overlay_data", overlay.to_pixels(preview.width, preview.height));
+                    }
+                    let _ = app_handle_preview.emit("vision_preview", preview);
+                } else {
+                    tracing::warn!("Failed to encode frame #{} to base64", frame_count);
+                }
+            }
+
+            tracing::info!("Vision preview task ended after {} frames", frame_count);
+        });
+
+        Ok(())
+    })();
+
+    result?;
+    guard.commit();
+    tracing::info!("Vision detection started successfully");
+    Ok(())
+}
+
+/// 限制异步命令主体的执行时间，超时返回 `CommandError::Timeout`，避免前端的调用一直等待
+async fn with_timeout<T>(
+    label: &str,
+    duration: std::time::Duration,
+    future: impl std::future::Future<Output = Result<T, CommandError>>,
+) -> Result<T, CommandError> {
+    tokio::time::timeout(duration, future).await.unwrap_or_else(|_| {
+        tracing::error!("{} timed out after {:?}", label, duration);
+        Err(CommandError::Timeout(format!("{} did not finish within {}s", label, duration.as_secs_f32())))
+    })
+}
+
+/// 在阻塞线程中执行可能卡住的同步操作（文件读写、摄像头探测），并限制等待时间
+///
+/// 超时后命令立即返回，阻塞线程中的操作无法取消，会在后台继续执行到结束
+async fn blocking_with_timeout<T: Send + 'static>(
+    label: &str,
+    duration: std::time::Duration,
+    operation: impl FnOnce() -> Result<T, CommandError> + Send + 'static,
+) -> Result<T, CommandError> {
+    with_timeout(label, duration, async {
+        tokio::task::spawn_blocking(operation)
+            .await
+            .map_err(|e| CommandError::Vision(e.to_string()))?
+    })
+    .await
+}
+
+/// 摄像头探测失败时转换为命令错误，设备被占用时返回 `CameraBusy`
+fn camera_probe_error(message: String) -> CommandError {
+    if crate::vision::is_camera_busy_error(&message) {
+        CommandError::CameraBusy(message)
+    } else {
+        CommandError::Vision(message)
+    }
+}
+
+/// 模型文件名
+const MODEL_FILE: &str = "blazeface.onnx";
+
+/// 锚框文件名
+const ANCHORS_FILE: &str = "anchors.npy";
+
+/// 依次检查候选资源目录，返回第一个包含模型文件的目录
+fn find_model_root(candidates: &[(&'static str, PathBuf)]) -> Option<(&'static str, PathBuf)> {
+    candidates
+        .iter()
+        .find(|(_, dir)| dir.join("models").join(MODEL_FILE).is_file())
+        .cloned()
+}
+
+/// 解析模型和锚框文件路径
+///
+/// 按顺序尝试：应用资源目录、程序所在目录、配置中指定的目录，
+/// 开发环境和打包后的资源位置不同，使用第一个包含模型的目录
+fn resolve_model_paths(app_handle: &tauri::AppHandle, model_dir: Option<&str>) -> Result<(String, String), String> {
+    let mut candidates = Vec::new();
+
+    match app_handle.path().resource_dir() {
+        Ok(dir) => candidates.push(("resource dir", dir)),
+        Err(e) => tracing::warn!("Failed to get resource dir: {}", e),
+    }
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        candidates.push(("executable dir", exe_dir.join("resources")));
+        candidates.push(("executable dir", exe_dir));
+    }
+    if let Some(dir) = model_dir {
+        candidates.push(("config override", PathBuf::from(dir)));
+    }
+
+    let (source, resource_path) = find_model_root(&candidates).ok_or_else(|| {
+        let tried: Vec<String> = candidates.iter().map(|(_, dir)| dir.display().to_string()).collect();
+        format!("Model {} not found in: {}", MODEL_FILE, tried.join(", "))
+    })?;
+    tracing::info!("Using models from {} ({})", resource_path.display(), source);
+
+    let model_path = resource_path
+        .join("models")
+        .join(MODEL_FILE)
+        .to_string_lossy()
+        .to_string();
+
+    let anchors_path = resource_path
+        .join("models")
+        .join(ANCHORS_FILE)
+        .to_string_lossy()
+        .to_string();
+
+    Ok((model_path, anchors_path))
+}
+
+/// 调整摄像头目标帧率（1-60），运行中立即生效，降低帧率可减少 CPU 占用
+///
+/// 返回限制到允许范围后的实际帧率
+#[tauri::command]
+pub fn set_camera_fps(fps: u32, state: State<'_, Arc<AppState>>) -> u32 {
+    let fps = crate::vision::clamp_fps(fps);
+    *state.camera_fps.lock() = Some(fps);
+
+    if let Some(ref processor) = *state.vision_processor.lock() {
+        processor.set_camera_fps(fps);
+    }
+
+    tracing::info!("Camera fps set to {}", fps);
+    fps
+}
+
+/// 自动调优摄像头：在几组帧率 / 分辨率下测量检测耗时和置信度，推荐设置
+///
+/// 需要独占摄像头，视觉检测运行中时返回错误；测量结束后摄像头即释放。
+/// `apply` 为 true 时保存推荐的帧率和分辨率，帧率立即生效，分辨率在下次启动视觉检测时生效
+#[tauri::command]
+pub async fn auto_tune_camera(
+    apply: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<RecommendedCameraSettings, CommandError> {
+    if *state.vision_running.lock() {
+        return Err(CommandError::Vision("Stop vision before auto-tuning the camera".to_string()));
+    }
+
+    let (model_dir, device_index) = {
+        let config = state.config.lock();
+        (config.model.dir.clone(), config.camera.device_index)
+    };
+    let (model_path, anchors_path) =
+        resolve_model_paths(&app_handle, model_dir.as_deref()).map_err(CommandError::Vision)?;
+    let anchors_path = state.usable_anchors_path(anchors_path);
+    let mut detector = BlazeFaceDetector::new(&model_path, Some(&anchors_path))
+        .map_err(|e| CommandError::Vision(e.to_string()))?;
+
+    let results = tokio::task::spawn_blocking(move || autotune::run_benchmarks(device_index, &mut detector))
+        .await
+        .map_err(|e| CommandError::Vision(e.to_string()))?
+        .map_err(camera_probe_error)?;
+
+    let mut recommended = autotune::recommend(&results)
+        .ok_or_else(|| CommandError::Vision("No benchmark results".to_string()))?;
+
+    if apply.unwrap_or(false) {
+        *state.camera_fps.lock() = Some(recommended.fps);
+        *state.camera_resolution.lock() = Some((recommended.width, recommended.height));
+        if let Some(ref processor) = *state.vision_processor.lock() {
+            processor.set_camera_fps(recommended.fps);
+        }
+        recommended.applied = true;
+    }
+
+    tracing::info!(
+        "Camera auto-tune recommends {} fps at {}x{} ({:.1} ms/frame, applied: {})",
+        recommended.fps,
+        recommended.width,
+        recommended.height,
+        recommended.avg_latency_ms,
+        recommended.applied
+    );
+    Ok(recommended)
+}
+
+/// 测量检测稳定性：用户保持不动时连续检测 `duration_secs` 秒，返回人脸框和置信度的波动以及漏检比例
+///
+/// 波动大通常说明光线不足或摄像头画质差。需要独占摄像头，视觉检测运行中时返回错误；测量结束后摄像头即释放
+#[tauri::command]
+pub async fn measure_jitter(
+    duration_secs: u32,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<JitterMetrics, CommandError> {
+    if duration_secs == 0 || duration_secs > MAX_JITTER_SECS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Measurement duration must be between 1 and {} seconds",
+            MAX_JITTER_SECS
+        )));
+    }
+    if *state.vision_running.lock() {
+        return Err(CommandError::Vision("Stop vision before measuring detection jitter".to_string()));
+    }
+
+    let (model_dir, device_index) = {
+        let config = state.config.lock();
+        (config.model.dir.clone(), config.camera.device_index)
+    };
+    let (model_path, anchors_path) =
+        resolve_model_paths(&app_handle, model_dir.as_deref()).map_err(CommandError::Vision)?;
+    let anchors_path = state.usable_anchors_path(anchors_path);
+    let mut detector = BlazeFaceDetector::new(&model_path, Some(&anchors_path))
+        .map_err(|e| CommandError::Vision(e.to_string()))?;
+
+    let metrics = tokio::task::spawn_blocking(move || jitter::measure_jitter(device_index, duration_secs, &mut detector))
+        .await
+        .map_err(|e| CommandError::Vision(e.to_string()))?
+        .map_err(camera_probe_error)?;
+
+    tracing::info!(
+        "Detection jitter: center std {:.4}, confidence std {:.4}, drop rate {:.2} ({} frames)",
+        metrics.bbox_center_std,
+        metrics.confidence_std,
+        metrics.drop_rate,
+        metrics.frames
+    );
+    Ok(metrics)
+}
+
+/// 开始转储检测结果：视觉检测运行期间把每次检测的主人脸和专注状态逐行写入数据目录下 `dumps/<name>`（JSONL），
+/// 返回文件的完整路径
+///
+/// `name` 只能是文件名，文件已存在时拒绝。超过最长时长（5 分钟）后停止写入，需要调用 `stop_detection_dump` 结束
+#[tauri::command]
+pub fn start_detection_dump(name: String, state: State<'_, Arc<AppState>>) -> Result<String, CommandError> {
+    let processor = state.vision_processor.lock().clone();
+    let processor = processor.ok_or_else(|| CommandError::InvalidArgument("Vision is not running".to_string()))?;
+    let paths = state
+        .data_paths()
+        .ok_or_else(|| CommandError::Io("Data directory is not available".to_string()))?;
+    let path = dump_path(&paths.data_dir, &name)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Invalid dump file name: {:?}", name)))?;
+    let dump = DetectionDump::create(&path, MAX_DUMP_DURATION_MS)?;
+    processor.start_detection_dump(dump).map_err(CommandError::InvalidArgument)?;
+
+    let path = path.display().to_string();
+    tracing::info!("Detection dump started: {}", path);
+    Ok(path)
+}
+
+/// 结束检测结果转储，返回写入的帧数和时长
+#[tauri::command]
+pub fn stop_detection_dump(state: State<'_, Arc<AppState>>) -> Result<DumpSummary, CommandError> {
+    let processor = state.vision_processor.lock().clone();
+    let summary = processor
+        .and_then(|processor| processor.stop_detection_dump())
+        .ok_or_else(|| CommandError::InvalidArgument("No detection dump is running".to_string()))??;
+
+    tracing::info!("Detection dump finished: {} ({} frames)", summary.path, summary.frames);
+    Ok(summary)
+}
+
+/// 校验 NMS IoU 阈值：拒绝非有限值，其余限制到 0-1
+fn nms_threshold_arg(value: f32) -> Result<f32, CommandError> {
+    if !value.is_finite() {
+        return Err(CommandError::InvalidArgument(format!("NMS threshold must be a finite number, got {}", value)));
+    }
+    Ok(value.clamp(0.0, 1.0))
+}
+
+/// 设置人脸检测的 NMS IoU 阈值（0-1），运行中立即生效
+///
+/// 返回限制到允许范围后的实际阈值
+#[tauri::command]
+pub fn set_nms_threshold(value: f32, state: State<'_, Arc<AppState>>) -> Result<f32, CommandError> {
+    let value = nms_threshold_arg(value)?;
+    *state.nms_threshold.lock() = Some(value);
+
+    if let Some(ref processor) = *state.vision_processor.lock() {
+        processor.set_nms_threshold(value);
+    }
+
+    tracing::info!("NMS threshold set to {}", value);
+    Ok(value)
+}
+
+/// 专注评分基准测试的最大计算次数
+const MAX_BENCHMARK_ITERATIONS: u32 = 10_000_000;
+
+/// 用当前的专注检测设置对固定人脸重复计算专注分数，测量本机的评分耗时（不含摄像头和推理）
+#[tauri::command]
+pub async fn benchmark_focus_calc(iterations: u32, state: State<'_, Arc<AppState>>) -> Result<CalculatorBenchmark, CommandError> {
+    if iterations == 0 || iterations > MAX_BENCHMARK_ITERATIONS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Iterations must be between 1 and {}",
+            MAX_BENCHMARK_ITERATIONS
+        )));
+    }
+    let calculator = FocusCalculator::new(state.config.lock().focus.calculator_config());
+    let result = tokio::task::spawn_blocking(move || calculator.benchmark(iterations))
+        .await
+        .map_err(|e| CommandError::Vision(e.to_string()))?;
+
+    tracing::info!("Focus calculator benchmark: {:.0} ns/call ({} iterations)", result.ns_per_call, iterations);
+    Ok(result)
+}
+
+/// 获取当前的 NMS IoU 阈值
+#[tauri::command]
+pub fn get_nms_threshold(state: State<'_, Arc<AppState>>) -> f32 {
+    if let Some(ref processor) = *state.vision_processor.lock() {
+        return processor.nms_threshold();
+    }
+    state.nms_threshold.lock().unwrap_or(crate::vision::DEFAULT_NMS_THRESHOLD)
+}
+
+/// 获取模型信息（路径、校验和、锚框数量等）
+///
+/// 视觉检测运行时返回已加载模型的信息，否则只检查模型文件
+#[tauri::command]
+pub fn get_model_info(
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<ModelInfo, String> {
+    if let Some(info) = state.vision_processor.lock().as_ref().and_then(|p| p.model_info()) {
+        return Ok(info);
+    }
+
+    let model_dir = state.config.lock().model.dir.clone();
+    let (model_path, _) = resolve_model_paths(&app_handle, model_dir.as_deref())?;
+    Ok(ModelInfo::inspect(&model_path))
+}
+
+/// 获取模型的输入输出签名，并与解码逻辑预期的形状对比（排查替换模型后的问题）
+///
+/// 加载一次模型读取签名，不影响正在运行的视觉检测；模拟模式下返回预期的签名
+#[tauri::command]
+pub async fn get_model_io(
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<ModelIo, CommandError> {
+    let model_dir = state.config.lock().model.dir.clone();
+    let (model_path, anchors_path) =
+        resolve_model_paths(&app_handle, model_dir.as_deref()).map_err(CommandError::Vision)?;
+    let anchors_path = state.usable_anchors_path(anchors_path);
+
+    tokio::task::spawn_blocking(move || {
+        BlazeFaceDetector::new(&model_path, Some(&anchors_path))
+            .map(|detector| detector.model_io())
+            .map_err(|e| CommandError::Vision(e.to_string()))
+    })
+    .await
+    .map_err(|e| CommandError::Vision(format!("Model inspection task failed: {}", e)))?
+}
+
+/// 最近一次推理的原始输出摘要
+///
+/// 需要开启 `model.debug_raw_output` 并运行视觉检测；模拟模式下没有原始输出，返回 None
+#[tauri::command]
+pub fn get_last_raw_output(state: State<'_, Arc<AppState>>) -> Option<RawModelOutput> {
+    state.vision_processor.lock().as_ref().and_then(|p| p.last_raw_output())
+}
+
+/// 校验随应用发布的资源（模型、锚框）是否完整，逐个报告状态
+///
+/// 找不到模型时检查应用资源目录，缺失的资源在报告中标记为 `missing`
+#[tauri::command]
+pub fn verify_resources(
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<ResourceReport, CommandError> {
+    let model_dir = state.config.lock().model.dir.clone();
+    let root = match resolve_model_paths(&app_handle, model_dir.as_deref()) {
+        // 模型位于 <资源目录>/models/ 下
+        Ok((model_path, _)) => Path::new(&model_path).parent().and_then(Path::parent).map(Path::to_path_buf),
+        Err(_) => app_handle.path().resource_dir().ok(),
+    }
+    .ok_or_else(|| CommandError::Io("Resource directory is not available".to_string()))?;
+
+    Ok(crate::vision::verify_resources(&root))
+}
+
+/// 停止视觉检测
+#[tauri::command]
+pub fn stop_vision(state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    state.stop_vision()
+}
+
+/// 获取当前选择的专注来源
+#[tauri::command]
+pub fn get_focus_source(state: State<'_, Arc<AppState>>) -> FocusSource {
+    *state.focus_source.lock()
+}
+
+/// 切换专注来源（camera / external）
+///
+/// 先停止当前来源并写入会话，再启动所选来源；同一时间只有一个来源处于活动状态。
+/// 配置中未开启外部来源时拒绝切换到 `external`
+#[tauri::command]
+pub async fn set_focus_source(
+    source: String,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<FocusSource, CommandError> {
+    let source = FocusSource::parse(&source)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown focus source: {}", source)))?;
+
+    match source {
+        FocusSource::Camera => {
+            state.stop_external_source();
+            if !*state.vision_running.lock() {
+                start_vision(state.clone(), app_handle).await?;
+            }
+        }
+        FocusSource::External => {
+            let focus_rx = state.start_external_source()?;
+            spawn_focus_state_task(Arc::clone(&state), app_handle, focus_rx);
+        }
+    }
+
+    tracing::info!("Focus source set to: {:?}", source);
+    Ok(source)
+}
+
+/// 外部来源推送一次专注状态（外部来源未活动时返回错误）
+#[tauri::command]
+pub fn push_focus_state(focus_state: FocusState, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    state.push_external_focus_state(focus_state)
+}
+
+/// 触发手势事件（用于测试/Demo模式）
+#[tauri::command]
+pub fn trigger_gesture(gesture: String, state: State<'_, Arc<AppState>>) -> Result<GestureReaction, String> {
+    let gesture_type = match gesture.to_lowercase().as_str() {
+        "wave" => GestureType::Wave,
+        "heart" => GestureType::Heart,
+        "ok" => GestureType::Ok,
+        "thumbsup" | "thumbs_up" => GestureType::ThumbsUp,
+        _ => return Err(format!("Unknown gesture: {}", gesture)),
+    };
+
+    tracing::info!("Gesture triggered: {:?}", gesture_type);
+
+    Ok(state.apply_gesture(gesture_type))
+}
+
+/// 设置 Demo 模式的宠物状态（用于录屏展示）
+#[tauri::command]
+pub fn set_demo_mood(mood: String, state: State<'_, Arc<AppState>>) -> Result<PetMood, String> {
+    let new_mood = PetMood::parse(&mood).ok_or_else(|| format!("Unknown mood: {}", mood))?;
+    state.set_demo_mood(new_mood)
+}
+
+/// 调整兴奋和深度专注的连续专注时间（分钟）并保存到配置，从下一次连续专注开始生效
+///
+/// 深度专注时间传入 None 表示不判断深度专注；需要长于兴奋时间
+#[tauri::command]
+pub fn set_focus_milestones(
+    excited_minutes: f32,
+    deep_focus_minutes: Option<f32>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<FocusMilestones, CommandError> {
+    let milestones = FocusMilestones {
+        excited_minutes,
+        deep_focus_minutes,
+    };
+    {
+        let mut config = state.config.lock();
+        let mut candidate = config.clone();
+        candidate.pet.excited_focus_minutes = excited_minutes;
+        candidate.pet.deep_focus_minutes = deep_focus_minutes;
+        candidate
+            .validate()
+            .map_err(|e| CommandError::InvalidArgument(e.to_string()))?;
+        *config = candidate;
+    }
+    state.pet_state_machine.lock().set_focus_milestones(milestones);
+
+    if let Err(e) = state.save_config() {
+        tracing::warn!("{}", e);
+    }
+    tracing::info!("Focus milestones set: excited {} min, deep focus {:?} min", excited_minutes, deep_focus_minutes);
+    Ok(milestones)
+}
+
+/// 开启或关闭专注分数平滑并保存到配置（关闭后宠物立即反应，用于演示和测试）
+#[tauri::command]
+pub fn set_smoothing_enabled(enabled: bool, state: State<'_, Arc<AppState>>) {
+    state.config.lock().focus.smoothing_enabled = enabled;
+    state.pet_state_machine.lock().set_smoothing_enabled(enabled);
+
+    if let Err(e) = state.save_config() {
+        tracing::warn!("{}", e);
+    }
+    tracing::info!("Focus smoothing {}", if enabled { "enabled" } else { "disabled" });
+}
+
+/// 锁定宠物情绪（演示、共享屏幕时使用），传入 None 解除锁定，返回当前锁定的情绪
+///
+/// 锁定期间检测结果不会改变情绪，专注时长照常累计
+#[tauri::command]
+pub fn set_mood_lock(
+    mood: Option<String>,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<PetMood>, CommandError> {
+    let mood = mood
+        .map(|name| PetMood::parse(&name).ok_or_else(|| CommandError::InvalidArgument(format!("Unknown mood: {}", name))))
+        .transpose()?;
+
+    let mut machine = state.pet_state_machine.lock();
+    let previous = machine.mood;
+    machine.set_mood_lock(mood);
+    if machine.mood != previous {
+        emit_mood_changed(&app_handle, &machine);
+    }
+    Ok(machine.mood_lock())
+}
+
+/// 获取情绪快照（前端可通过 change_seq 检测遗漏的情绪变化事件）
+#[tauri::command]
+pub fn get_mood_snapshot(state: State<'_, Arc<AppState>>) -> MoodSnapshot {
+    state.pet_state_machine.lock().mood_snapshot()
+}
+
+/// 获取下一个情绪里程碑（如“还有 3 分钟进入兴奋”），未处于专注状态时返回 None
+#[tauri::command]
+pub fn get_next_milestone(state: State<'_, Arc<AppState>>) -> Option<Milestone> {
+    state.pet_state_machine.lock().next_milestone()
+}
+
+/// 开始手动休息（暂停专注累计，视觉检测继续运行）
+#[tauri::command]
+pub fn start_manual_break(state: State<'_, Arc<AppState>>, app_handle: tauri::AppHandle) -> PetMood {
+    let mut machine = state.pet_state_machine.lock();
+    let mood = machine.start_manual_break();
+    emit_mood_changed(&app_handle, &machine);
+    mood
+}
+
+/// 结束手动休息
+#[tauri::command]
+pub fn end_manual_break(state: State<'_, Arc<AppState>>) {
+    state.pet_state_machine.lock().end_manual_break();
+}
+
+/// 临时最长离开超时（秒）
+const MAX_TEMPORARY_AWAY_TIMEOUT_SECS: f32 = 3600.0;
+
+/// 临时离开超时最长持续时间（秒）
+const MAX_TEMPORARY_AWAY_DURATION_SECS: f32 = 4.0 * 3600.0;
+
+/// 在接下来的 `duration_secs` 秒内把离开超时临时改为 `secs` 秒，到期后自动恢复
+///
+/// 重复调用会延长失效时间而不是叠加，返回临时超时的剩余秒数
+#[tauri::command]
+pub fn set_temporary_away_timeout(secs: f32, duration_secs: f32, state: State<'_, Arc<AppState>>) -> Result<f32, CommandError> {
+    if !(0.5..=MAX_TEMPORARY_AWAY_TIMEOUT_SECS).contains(&secs) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Away timeout must be between 0.5 and {} seconds",
+            MAX_TEMPORARY_AWAY_TIMEOUT_SECS
+        )));
+    }
+    if !(duration_secs > 0.0 && duration_secs <= MAX_TEMPORARY_AWAY_DURATION_SECS) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Duration must be between 0 and {} seconds",
+            MAX_TEMPORARY_AWAY_DURATION_SECS
+        )));
+    }
+
+    let now = std::time::Instant::now();
+    let until = state.pet_state_machine.lock().set_temporary_away_timeout_at(
+        now,
+        secs,
+        std::time::Duration::from_secs_f32(duration_secs),
+    );
+    Ok(until.duration_since(now).as_secs_f32())
+}
+
+/// 切换专注评分档位（strict / normal / relaxed / custom）
+///
+/// 预设档位立即应用到状态机和运行中的视觉处理器，`custom` 保留当前参数
+#[tauri::command]
+pub fn set_focus_profile(profile: String, state: State<'_, Arc<AppState>>) -> Result<FocusProfile, String> {
+    let profile = FocusProfile::parse(&profile).ok_or_else(|| format!("Unknown focus profile: {}", profile))?;
+
+    {
+        let mut config = state.config.lock();
+        config.focus.profile = profile;
+
+        if let Some(preset) = profile.preset() {
+            config.focus.enter_threshold = preset.enter_threshold;
+            config.focus.exit_threshold = preset.exit_threshold;
+
+            state
+                .pet_state_machine
+                .lock()
+                .set_focus_thresholds(preset.enter_threshold, preset.exit_threshold);
+            if let Some(ref processor) = *state.vision_processor.lock() {
+                processor.set_focus_config(config.focus.calculator_config());
+                processor.set_focus_thresholds(preset.enter_threshold, preset.exit_threshold);
+            }
+        }
+    }
+
+    if let Err(e) = state.save_config() {
+        tracing::warn!("{}", e);
+    }
+
+    tracing::info!("Focus profile set to: {:?}", profile);
+    Ok(profile)
+}
+
+/// 切换宠物性格（companion / accountability / custom）
+///
+/// 预设性格立即应用到状态机，`custom` 保留当前设置，返回切换后生效的设置
+#[tauri::command]
+pub fn set_personality(kind: String, state: State<'_, Arc<AppState>>) -> Result<PersonalitySettings, CommandError> {
+    let personality = Personality::parse(&kind)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown personality: {}", kind)))?;
+
+    let settings = {
+        let mut config = state.config.lock();
+        config.pet.apply_personality(personality);
+
+        let mut machine = state.pet_state_machine.lock();
+        machine.set_reaction_style(config.pet.reaction_style);
+        machine.set_sad_on_distraction(config.pet.sad_on_distraction);
+
+        PersonalitySettings {
+            reaction_style: config.pet.reaction_style,
+            sad_on_distraction: config.pet.sad_on_distraction,
+            break_reminders: config.pet.break_reminders,
+        }
+    };
+
+    if let Err(e) = state.save_config() {
+        tracing::warn!("{}", e);
+    }
+
+    tracing::info!("Personality set to: {:?}", personality);
+    Ok(settings)
+}
+
+/// 设置通知偏好（哪些事件通知、免打扰时段、提示音）并保存
+#[tauri::command]
+pub fn set_notification_prefs(prefs: NotificationPrefs, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    {
+        let mut config = state.config.lock();
+        let mut updated = config.clone();
+        updated.notifications = prefs;
+        updated
+            .validate()
+            .map_err(|e| CommandError::InvalidArgument(e.to_string()))?;
+        *config = updated;
+    }
+
+    if let Err(e) = state.save_config() {
+        tracing::warn!("{}", e);
+    }
+    Ok(())
+}
+
+/// 立即发送一条示例通知，用于检查通知权限和样式（不受开关和免打扰时段限制）
+#[tauri::command]
+pub fn preview_notification(
+    kind: String,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<Notification, CommandError> {
+    let kind = NotificationKind::parse(&kind)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown notification kind: {}", kind)))?;
+    let notification = {
+        let config = state.config.lock();
+        Notification::build(&config.notifications, config.ui.locale, kind, chrono::Local::now().hour(), true)
+    }
+    .ok_or_else(|| CommandError::InvalidArgument(format!("No notification for {:?}", kind)))?;
+
+    let _ = app_handle.emit("notification", &notification);
+    Ok(notification)
+}
+
+/// 预览按当前设置持续收到 `score` 时宠物的专注等级和情绪（不影响正在运行的状态机）
+#[tauri::command]
+pub fn classify_score(score: f32, face_present: bool, state: State<'_, Arc<AppState>>) -> ScoreClassification {
+    let config = PetStateConfig::from_app_config(&state.config.lock());
+    PetStateMachine::classify_score(config, score.clamp(0.0, 1.0), face_present)
+}
+
+/// 导出当前的专注和宠物设置为可分享的预设字符串
+#[tauri::command]
+pub fn export_preset(state: State<'_, Arc<AppState>>) -> String {
+    state.config.lock().export_preset()
+}
+
+/// 导入预设字符串，替换专注和宠物设置并立即应用到状态机和运行中的视觉处理器
+///
+/// 预设无法解码或取值超出范围时返回错误，当前配置保持不变
+#[tauri::command]
+pub fn import_preset(preset: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    {
+        let mut config = state.config.lock();
+        config
+            .import_preset(&preset)
+            .map_err(|e| CommandError::InvalidArgument(e.to_string()))?;
+
+        {
+            let mut machine = state.pet_state_machine.lock();
+            machine.set_focus_thresholds(config.focus.enter_threshold, config.focus.exit_threshold);
+            machine.set_reaction_style(config.pet.reaction_style);
+            machine.set_sad_on_distraction(config.pet.sad_on_distraction);
+            machine.set_smoothing_enabled(config.focus.smoothing_enabled);
+        }
+        if let Some(ref processor) = *state.vision_processor.lock() {
+            processor.set_focus_config(config.focus.calculator_config());
+            processor.set_focus_thresholds(config.focus.enter_threshold, config.focus.exit_threshold);
+        }
+    }
+
+    if let Err(e) = state.save_config() {
+        tracing::warn!("{}", e);
+    }
+
+    tracing::info!("Config preset imported");
+    Ok(())
+}
+
+/// 更新配置：可以立即生效的字段马上应用到状态机和运行中的视觉处理器
+///
+/// 摄像头设备、模型目录等字段需要重启视觉检测：`apply_and_restart`（默认）时保存并在视觉检测运行中时重启，
+/// `apply_live_only` 时这些字段保持原值。返回哪些字段已生效、哪些需要重启
+#[tauri::command]
+pub async fn update_config(
+    config: AppConfig,
+    mode: Option<ApplyMode>,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<ApplyReport, CommandError> {
+    let mode = mode.unwrap_or_default();
+    let mut report = state.apply_config_update(config, mode)?;
+
+    let restart = mode == ApplyMode::ApplyAndRestart && !report.needs_restart.is_empty() && *state.vision_running.lock();
+    if restart {
+        tracing::info!("Restarting vision to apply: {}", report.needs_restart.join(", "));
+        // 期间可能已被其他调用停止，此时直接重新启动
+        if let Err(e) = state.stop_vision() {
+            tracing::warn!("{}", e);
+        }
+        start_vision(state, app_handle).await?;
+        report.restarted = true;
+    }
+
+    tracing::info!(
+        "Config updated (live: {}, needs restart: {})",
+        report.live.len(),
+        report.needs_restart.len()
+    );
+    Ok(report)
+}
+
+/// 获取界面文案（情绪、建议和通知）
+///
+/// `locale` 为空时使用配置中的界面语言，无法识别的语言使用英文；当前语言缺少的条目也使用英文
+#[tauri::command]
+pub fn get_localized_strings(locale: Option<String>, state: State<'_, Arc<AppState>>) -> HashMap<String, String> {
+    let locale = match locale {
+        Some(name) => Locale::parse(&name).unwrap_or(Locale::En),
+        None => state.config.lock().ui.locale,
+    };
+    i18n::strings(locale)
+}
+
+/// 校准副屏区间时，以平均偏航角为中心向两侧扩展的角度（度）
+const SECONDARY_SCREEN_HALF_WIDTH: f32 = 10.0;
+
+/// 校准副屏区间：用户看向副屏时调用，用最近检测到人脸的偏航角确定区间并开启副屏模式
+///
+/// 立即应用到运行中的视觉处理器，返回校准后的区间
+#[tauri::command]
+pub fn calibrate_secondary_screen(state: State<'_, Arc<AppState>>) -> Result<YawZone, CommandError> {
+    state.calibrate_secondary_screen()
+}
+
+/// 校准时间的上限（秒）
+const MAX_FACE_SIZE_CALIBRATION_SECS: u32 = 60;
+
+/// 校准理想人脸大小：用户保持正常坐姿时调用，采集 `duration_secs` 秒内检测到的人脸大小，取中位数写入配置
+#[tauri::command]
+pub async fn calibrate_face_size(duration_secs: u32, state: State<'_, Arc<AppState>>) -> Result<f32, CommandError> {
+    if duration_secs == 0 || duration_secs > MAX_FACE_SIZE_CALIBRATION_SECS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Calibration duration must be between 1 and {} seconds",
+            MAX_FACE_SIZE_CALIBRATION_SECS
+        )));
+    }
+    state
+        .calibrate_face_size(std::time::Duration::from_secs(duration_secs as u64))
+        .await
+}
+
+/// 校准看手机检测的低头阈值：用户看向下方副屏时调用，用最近检测到人脸的俯仰角加上余量作为阈值
+///
+/// 返回校准后的阈值（度）
+#[tauri::command]
+pub fn calibrate_lower_screen(state: State<'_, Arc<AppState>>) -> Result<f32, CommandError> {
+    state.calibrate_lower_screen()
+}
+
+/// 获取当前会话的最高专注分数及其时间，没有进行中的会话或还没有采样时返回 None
+#[tauri::command]
+pub fn get_session_peak(state: State<'_, Arc<AppState>>) -> Option<SessionPeak> {
+    state.session.lock().as_ref().and_then(SessionTracker::peak)
+}
+
+/// 重置当前会话的最高专注分数（新会话开始时自动重置）
+#[tauri::command]
+pub fn reset_session_peak(state: State<'_, Arc<AppState>>) {
+    if let Some(ref mut session) = *state.session.lock() {
+        session.reset_peak();
+    }
+}
+
+/// 获取今日专注统计
+#[tauri::command]
+pub fn get_focus_stats(state: State<'_, Arc<AppState>>) -> FocusStats {
+    state.focus_stats.lock().clone()
+}
+
+/// 开始定期推送统计更新（`stats_update` 事件，包含实时统计和今日数据库记录的合并结果），
+/// 前端不再需要轮询 `get_focus_stats`
+#[tauri::command]
+pub fn subscribe_stats_updates(state: State<'_, Arc<AppState>>, app_handle: tauri::AppHandle) {
+    start_stats_stream(state.inner().clone(), app_handle);
+}
+
+/// 停止推送统计更新，返回之前是否在推送
+#[tauri::command]
+pub fn unsubscribe_stats_updates(state: State<'_, Arc<AppState>>) -> bool {
+    stop_stats_stream(&state)
+}
+
+/// 重置今日统计（包括数据库中今天的记录）
+#[tauri::command]
+pub fn reset_stats(state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    state.reset_today()?;
+
+    tracing::info!("Focus stats reset");
+    Ok(())
+}
+
+/// 清空全部数据（包括示例数据），目标设置保留
+#[tauri::command]
+pub fn reset_all_data(state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    state.reset_all_data()?;
+
+    tracing::info!("All data reset");
+    Ok(())
+}
+
+/// 写入最近一周的示例数据（新用户引导用），返回写入的天数
+///
+/// 已有记录的日子不会被覆盖；示例数据用 `reset_all_data` 清除
+#[tauri::command]
+pub fn seed_demo_data(state: State<'_, Arc<AppState>>) -> Result<u32, CommandError> {
+    with_database(&state, |db| db.seed_demo_data(chrono::Local::now().date_naive()))
+}
+
+/// 按会话记录重建每日统计（统计与会话不一致时修复用），返回重建的天数
+#[tauri::command]
+pub fn rebuild_daily_stats(state: State<'_, Arc<AppState>>) -> Result<usize, CommandError> {
+    with_database(&state, |db| db.rebuild_daily_stats())
+}
+
+/// 检查点写入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    /// 写入的统计日 (YYYY-MM-DD)
+    pub date: String,
+    /// 写入的会话记录（没有进行中的会话时为 None）
+    pub session: Option<FocusSession>,
+    /// 本次新写入今日统计的专注时长（毫秒）
+    pub focus_ms: i64,
+    /// 本次新写入今日统计的分心时长（毫秒）
+    pub distracted_ms: i64,
+    /// 写入时间 (Unix 时间戳，毫秒)
+    pub checkpointed_at_ms: i64,
+}
+
+/// 手动补录会话的备注最大长度（字符）
+const MAX_SESSION_LABEL_CHARS: usize = 100;
+
+/// 手动补录一条历史会话（从其他工具迁移或离线专注），返回会话 ID
+///
+/// 时长计入会话开始当天的统计，记录中标记为手动
+#[tauri::command]
+pub fn add_manual_session(
+    start_ms: i64,
+    end_ms: i64,
+    focus_ms: i64,
+    distracted_ms: i64,
+    label: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<i64, CommandError> {
+    let id = state.add_manual_session(
+        start_ms,
+        end_ms,
+        focus_ms,
+        distracted_ms,
+        label.as_deref(),
+        chrono::Local::now().timestamp_millis(),
+    )?;
+
+    tracing::info!("Manual session {} added: {}ms focus", id, focus_ms);
+    Ok(id)
+}
+
+/// 立即保存进行中的会话和今日统计（不结束会话）
+#[tauri::command]
+pub fn checkpoint_session(state: State<'_, Arc<AppState>>) -> Result<CheckpointInfo, CommandError> {
+    let info = state.checkpoint_session()?;
+
+    tracing::info!(
+        "Session checkpoint: +{}ms focus, +{}ms distracted",
+        info.focus_ms,
+        info.distracted_ms
+    );
+    Ok(info)
+}
+
+/// 手动触发日期切换（用于测试和跨时区修正）
+///
+/// 返回被结束的统计日
+#[tauri::command]
+pub fn rollover_day(state: State<'_, Arc<AppState>>, app_handle: tauri::AppHandle) -> String {
+    let previous_day = state.rollover_day();
+    let _ = app_handle.emit("day_rollover", &previous_day);
+    previous_day
+}
+
+/// 为指定日期设置专注目标（分钟）
+#[tauri::command]
+pub fn set_goal(date: String, minutes: i64, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    if minutes < 0 {
+        return Err(CommandError::InvalidArgument(format!("Invalid goal: {} minutes", minutes)));
+    }
+    if storage::weekday_of(&date).is_none() {
+        return Err(CommandError::InvalidArgument(format!("Invalid date: {}", date)));
+    }
+
+    with_database(&state, |db| db.set_goal(&date, minutes))
+}
+
+/// 设置某个星期的默认专注目标（0 = 周一，6 = 周日）
+#[tauri::command]
+pub fn set_weekday_goal(weekday: usize, minutes: i64, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    if weekday > 6 {
+        return Err(CommandError::InvalidArgument(format!("Invalid weekday: {}", weekday)));
+    }
+    if minutes < 0 {
+        return Err(CommandError::InvalidArgument(format!("Invalid goal: {} minutes", minutes)));
+    }
+
+    with_database(&state, |db| db.set_weekday_goal(weekday, minutes))
+}
+
+/// 获取目标进度（默认当前统计日）
+#[tauri::command]
+pub fn get_goal_progress(
+    date: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<GoalProgress, CommandError> {
+    let today = state.current_day.lock().clone();
+    let date = date.unwrap_or_else(|| today.clone());
+    if storage::weekday_of(&date).is_none() {
+        return Err(CommandError::InvalidArgument(format!("Invalid date: {}", date)));
+    }
+
+    let progress = with_database(&state, |db| db.get_goal_progress(&date))?;
+
+    // 今天的进度需要叠加当前运行中尚未写入数据库的专注时长
+    if date == today {
+        Ok(progress.with_extra_focus(state.unflushed_focus_ms() as i64))
+    } else {
+        Ok(progress)
+    }
+}
+
+/// 按当前时刻预测今天的目标能否完成（参考前几天这个时刻之后通常还能专注多久），供宠物主动鼓励
+#[tauri::command]
+pub fn goal_on_track(state: State<'_, Arc<AppState>>) -> Result<GoalOnTrack, CommandError> {
+    let today = state.current_day.lock().clone();
+    let (day_start, _) = storage::day_bounds_ms(&today)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Invalid date: {}", today)))?;
+    let elapsed_ms = chrono::Local::now().timestamp_millis() - day_start;
+    let extra_focus_ms = state.unflushed_focus_ms() as i64;
+
+    with_database(&state, |db| db.goal_on_track(&today, elapsed_ms, extra_focus_ms))
+}
+
+/// 按小时统计的最长日期范围（天）
+const MAX_HOUR_PROFILE_DAYS: i64 = 366;
+
+/// `start` 到 `end`（含）之间每个小时的平均专注时长（分钟），供宠物建议把深度工作安排在最专注的时段
+///
+/// `granularity` 为 `quarter_hour` 时按 15 分钟统计（96 段），默认按小时
+#[tauri::command]
+pub fn get_focus_by_hour_profile(
+    start: String,
+    end: String,
+    granularity: Option<BucketGranularity>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<f64>, CommandError> {
+    let parse = |date: &str| {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| CommandError::InvalidArgument(format!("Invalid date: {}", date)))
+    };
+    let days = (parse(&end)? - parse(&start)?).num_days() + 1;
+    if !(1..=MAX_HOUR_PROFILE_DAYS).contains(&days) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Date range must cover 1 to {} days",
+            MAX_HOUR_PROFILE_DAYS
+        )));
+    }
+
+    with_database(&state, |db| db.focus_by_bucket_profile(&start, &end, granularity.unwrap_or_default()))
+}
+
+/// 指定日期（YYYY-MM-DD）每段的专注时长（毫秒），默认按小时，`quarter_hour` 时按 15 分钟
+#[tauri::command]
+pub fn get_day_focus_buckets(
+    date: String,
+    granularity: Option<BucketGranularity>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<i64>, CommandError> {
+    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid date: {}", date)))?;
+
+    with_database(&state, |db| db.focus_by_bucket_ms(&date, granularity.unwrap_or_default()))
+}
+
+/// 自安装以来的累计统计（用于总结卡片）
+#[tauri::command]
+pub fn get_lifetime_stats(state: State<'_, Arc<AppState>>) -> Result<LifetimeStats, CommandError> {
+    with_database(&state, |db| db.get_lifetime_stats())
+}
+
+/// 导出专注状态采样日志到指定路径
+///
+/// 返回导出的字节数
+#[tauri::command]
+pub fn export_focus_log(path: String, state: State<'_, Arc<AppState>>) -> Result<u64, String> {
+    // 先把写入线程中的缓冲刷到磁盘
+    if let Some(ref logger) = *state.focus_log.lock() {
+        logger.flush();
+    }
+
+    let log_config = state
+        .focus_log_config()
+        .ok_or_else(|| "Data directory is not available".to_string())?;
+
+    focus_log::export_focus_log(&log_config, std::path::Path::new(&path))
+        .map_err(|e| format!("Failed to export focus log: {}", e))
+}
+
+/// 收集诊断信息（配置、运行状态、模型信息、最近的专注状态和采样日志）并打包为 zip
+///
+/// 用于反馈问题，用户把生成的文件附到 issue 上
+#[tauri::command]
+pub fn collect_diagnostics(
+    path: String,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), CommandError> {
+    if let Some(ref logger) = *state.focus_log.lock() {
+        logger.flush();
+    }
+    let focus_log_lines = match state.focus_log_config() {
+        Some(log_config) => focus_log::tail_focus_log(&log_config, diagnostics::RECENT_LOG_LINES)?,
+        None => Vec::new(),
+    };
+
+    let config = state.config.lock().clone();
+    let model_info = match state.vision_processor.lock().as_ref().and_then(|p| p.model_info()) {
+        Some(info) => Some(info),
+        None => resolve_model_paths(&app_handle, config.model.dir.as_deref())
+            .ok()
+            .map(|(model_path, _)| ModelInfo::inspect(&model_path)),
+    };
+
+    let health = HealthCheck {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        mock_vision: cfg!(not(feature = "vision")),
+        vision_running: *state.vision_running.lock(),
+        database_available: state.database.lock().is_some(),
+        data_dir: state.data_dir.lock().as_ref().map(|dir| dir.display().to_string()),
+        camera_fps: *state.camera_fps.lock(),
+        pending_events: state.pending_events.lock().len(),
+        generated_at_ms: chrono::Local::now().timestamp_millis(),
+    };
+
+    let bundle = DiagnosticsBundle {
+        config,
+        health,
+        model_info,
+        focus_states: state.recent_focus_states.lock().iter().cloned().collect(),
+        focus_log_lines,
+    };
+    bundle.write_zip(Path::new(&path))?;
+
+    tracing::info!("Diagnostics written to {}", path);
+    Ok(())
+}
+
+/// 查询活动事件（`since_ms` 之后，按时间升序，最多 `limit` 条）
+#[tauri::command]
+pub fn get_events(since_ms: i64, limit: u32, state: State<'_, Arc<AppState>>) -> Result<Vec<Event>, CommandError> {
+    // 先写入缓冲中的事件，保证查询结果完整
+    state.flush_events();
+
+    with_database(&state, |db| db.get_events(since_ms, limit))
+}
+
+/// 获取指定日期的专注一致性（分数的平均值、标准差和变异系数），采样不足时返回 None
+#[tauri::command]
+pub fn get_focus_consistency(date: String, state: State<'_, Arc<AppState>>) -> Result<Option<FocusConsistency>, CommandError> {
+    state.flush_samples();
+
+    with_database(&state, |db| db.get_focus_consistency(&date))
+}
+
+/// 开始一次专注挑战：只累计专注中的时间，分心或离开时倒计时暂停
+///
+/// 进度每秒通过 `challenge_progress` 事件发送，达到目标时发送 `challenge_complete`
+#[tauri::command]
+pub fn start_challenge(
+    target_minutes: u32,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<ChallengeProgress, CommandError> {
+    if !(1..=MAX_CHALLENGE_MINUTES).contains(&target_minutes) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Challenge target must be between 1 and {} minutes",
+            MAX_CHALLENGE_MINUTES
+        )));
+    }
+    let now_ms = chrono::Local::now().timestamp_millis();
+    let progress = state.start_challenge(now_ms, target_minutes as i64 * 60_000)?;
+    let _ = app_handle.emit("challenge_progress", &progress);
+    Ok(progress)
+}
+
+/// 放弃进行中的专注挑战，发送 `challenge_failed` 事件；没有进行中的挑战时返回 None
+#[tauri::command]
+pub fn abandon_challenge(state: State<'_, Arc<AppState>>, app_handle: tauri::AppHandle) -> Option<ChallengeProgress> {
+    let progress = state.abandon_challenge()?;
+    let _ = app_handle.emit("challenge_failed", &progress);
+    Some(progress)
+}
+
+/// 获取进行中的专注挑战的进度
+#[tauri::command]
+pub fn get_challenge(state: State<'_, Arc<AppState>>) -> Option<ChallengeProgress> {
+    state.challenge.lock().as_ref().map(Challenge::progress)
+}
+
+/// 最近完成的专注挑战（按完成时间倒序，最多 `limit` 条）
+#[tauri::command]
+pub fn get_recent_challenges(limit: u32, state: State<'_, Arc<AppState>>) -> Result<Vec<ChallengeRecord>, CommandError> {
+    with_database(&state, |db| db.get_recent_challenges(limit))
+}
+
+/// 最近的分心记录（按时间倒序，最多 `limit` 条）
+#[tauri::command]
+pub fn get_recent_distractions(limit: u32, state: State<'_, Arc<AppState>>) -> Result<Vec<DistractionEvent>, CommandError> {
+    with_database(&state, |db| db.get_recent_distractions(limit))
+}
+
+/// 获取最近几秒专注分数的趋势（上升 / 下降 / 持平）
+#[tauri::command]
+pub fn get_focus_trend(state: State<'_, Arc<AppState>>) -> FocusTrend {
+    state.focus_trend.lock().trend(chrono::Local::now().timestamp_millis())
+}
+
+/// 获取最近 60 分钟的每分钟平均专注分数（最近的在最后，用于迷你折线图）
+#[tauri::command]
+pub fn get_focus_sparkline(state: State<'_, Arc<AppState>>) -> Vec<f32> {
+    state.focus_sparkline.lock().values()
+}
+
+/// 获取内存缓冲区的大小和估算的内存占用
+#[tauri::command]
+pub fn get_buffer_stats(state: State<'_, Arc<AppState>>) -> BufferStats {
+    state.buffer_stats()
+}
+
+/// 根据最近的专注分数判断是否建议休息（仅作提示），专注状态良好时返回 None
+#[tauri::command]
+pub fn suggest_break(state: State<'_, Arc<AppState>>) -> Result<Option<BreakSuggestion>, CommandError> {
+    let samples = state.recent_focus_samples(chrono::Local::now().timestamp_millis())?;
+    Ok(fatigue::suggest_break(&samples))
+}
+
+/// 获取数据目录以及配置、数据库、采样日志的路径
+///
+/// 不依赖视觉检测；应用启动时未能确定数据目录的情况下重新解析
+#[tauri::command]
+pub fn get_data_paths(state: State<'_, Arc<AppState>>, app_handle: tauri::AppHandle) -> Result<DataPaths, CommandError> {
+    if let Some(paths) = state.data_paths() {
+        return Ok(paths);
+    }
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::Io(format!("Failed to resolve data directory: {}", e)))?;
+    Ok(DataPaths::new(&dir))
+}
+
+/// 列出已保存的档案
+#[tauri::command]
+pub fn list_profiles(state: State<'_, Arc<AppState>>) -> Result<Vec<String>, CommandError> {
+    Ok(state.profile_store()?.list()?)
+}
+
+/// 使用中的档案名称
+#[tauri::command]
+pub fn get_active_profile(state: State<'_, Arc<AppState>>) -> String {
+    state.active_profile.lock().clone()
+}
+
+/// 创建空档案（不切换）
+#[tauri::command]
+pub fn create_profile(name: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    state.profile_store()?.create(&name)?;
+    tracing::info!("Profile created: {}", name);
+    Ok(())
+}
+
+/// 切换到档案（`default` 为默认档案），返回该档案的配置
+#[tauri::command]
+pub fn switch_profile(name: String, state: State<'_, Arc<AppState>>) -> Result<AppConfig, CommandError> {
+    state.switch_profile(&name)
+}
+
+/// 重命名档案（连同其中的配置和数据库），不能重命名使用中的档案或与已有档案重名
+#[tauri::command]
+pub fn rename_profile(old: String, new: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    let active = state.active_profile.lock().clone();
+    state.profile_store()?.rename(&old, &new, &active)?;
+    tracing::info!("Profile renamed: {} -> {}", old, new);
+    Ok(())
+}
+
+/// 删除档案（连同其中的配置和数据库），不能删除使用中的档案
+#[tauri::command]
+pub fn delete_profile(name: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    let active = state.active_profile.lock().clone();
+    state.profile_store()?.delete(&name, &active)?;
+    tracing::info!("Profile deleted: {}", name);
+    Ok(())
+}
+
+/// 已保存的会话录制（从新到旧）
+#[tauri::command]
+pub fn list_recordings(state: State<'_, Arc<AppState>>) -> Result<Vec<RecordingInfo>, CommandError> {
+    Ok(state.recording_store()?.list()?)
+}
+
+/// 读取会话录制中的专注状态（按时间升序），用于回看
+#[tauri::command]
+pub fn get_recording(id: String, state: State<'_, Arc<AppState>>) -> Result<Vec<FocusState>, CommandError> {
+    Ok(state.recording_store()?.load(&id)?)
+}
+
+/// 删除会话录制
+#[tauri::command]
+pub fn delete_recording(id: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    state.recording_store()?.delete(&id)?;
+    tracing::info!("Recording deleted: {}", id);
+    Ok(())
+}
+
+/// 连续专注冻结名额状态
+#[tauri::command]
+pub fn get_streak_freeze_status(state: State<'_, Arc<AppState>>) -> Result<StreakFreezeStatus, CommandError> {
+    let policy = state.config.lock().stats.streak_freeze_policy();
+    with_database(&state, |db| db.streak_freeze_status(chrono::Local::now().date_naive(), policy))
+}
+
+/// 全部历史连续专注（最近的在前），每天专注至少 `min_minutes` 分钟才算达标
+#[tauri::command]
+pub fn list_streaks(min_minutes: u32, state: State<'_, Arc<AppState>>) -> Result<Vec<Streak>, CommandError> {
+    with_database(&state, |db| db.list_streaks(min_minutes))
+}
+
+/// 消耗一个冻结名额，保护指定日期（YYYY-MM-DD）不打断连续专注
+///
+/// 只能冻结当前周期内、没有专注记录的日子；已冻结的日期不会重复消耗名额
+#[tauri::command]
+pub fn use_streak_freeze(date: String, state: State<'_, Arc<AppState>>) -> Result<StreakFreezeStatus, CommandError> {
+    let day = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid date: {}", date)))?;
+    let today = chrono::Local::now().date_naive();
+    let policy = state.config.lock().stats.streak_freeze_policy();
+    if day > today || day < policy.period_start(today) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Date {} is outside the current freeze period",
+            date
+        )));
+    }
+
+    with_database(&state, |db| -> Result<_, CommandError> {
+        if !db.is_day_frozen(&date)? {
+            if db.get_stats_by_date(&date)?.is_some_and(|stats| stats.total_focus_ms > 0) {
+                return Err(CommandError::InvalidArgument(format!("Date {} already has focus time", date)));
+            }
+            if db.streak_freeze_status(today, policy)?.available == 0 {
+                return Err(CommandError::InvalidArgument("No streak freezes left".to_string()));
+            }
+            db.insert_streak_freeze(&date, chrono::Local::now().timestamp_millis())?;
+        }
+
+        Ok(db.streak_freeze_status(today, policy)?)
+    })
+}
+
+/// 获取专注教练建议（基于最近的会话和每日统计）
+#[tauri::command]
+pub fn get_coaching_tips(state: State<'_, Arc<AppState>>) -> Result<Vec<Tip>, CommandError> {
+    with_database(&state, |db| db.generate_coaching_tips())
+}
+
+/// 对比两天的统计（差值为 date_a 减 date_b），今天未结算的专注时间也计入
+#[tauri::command]
+pub fn compare_days(date_a: String, date_b: String, state: State<'_, Arc<AppState>>) -> Result<DayComparison, CommandError> {
+    let thresholds = state.config.lock().stats.compare_thresholds();
+    let today = state.current_day.lock().clone();
+    let unflushed = state.unflushed_focus_ms() as i64;
+
+    with_database(&state, |db| -> rusqlite::Result<_> {
+        let load = |date: &str| -> rusqlite::Result<storage::DailyStats> {
+            let mut stats = db.stats_or_empty(date)?;
+            if date == today {
+                stats.total_focus_ms += unflushed;
+            }
+            Ok(stats)
+        };
+        let a = load(&date_a)?;
+        let b = load(&date_b)?;
+
+        Ok(DayComparison {
+            note_a: db.get_day_note(&date_a)?,
+            note_b: db.get_day_note(&date_b)?,
+            ..DayComparison::between(&a, &b, &thresholds)
+        })
+    })
+}
+
+/// 设置指定日期（YYYY-MM-DD）的备注，例如“生病”“出差”，备注为空时删除
+#[tauri::command]
+pub fn set_day_note(date: String, note: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid date: {}", date)))?;
+    if note.chars().count() > MAX_DAY_NOTE_CHARS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Note must be at most {} characters",
+            MAX_DAY_NOTE_CHARS
+        )));
+    }
+
+    with_database(&state, |db| db.set_day_note(&date, &note))
+}
+
+/// 获取指定日期的备注
+#[tauri::command]
+pub fn get_day_note(date: String, state: State<'_, Arc<AppState>>) -> Result<Option<String>, CommandError> {
+    with_database(&state, |db| db.get_day_note(&date))
+}
+
+/// 获取指定日期的摘要（统计、目标进度和备注），今天未结算的专注时间也计入
+#[tauri::command]
+pub fn get_day_digest(date: String, state: State<'_, Arc<AppState>>) -> Result<DayDigest, CommandError> {
+    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid date: {}", date)))?;
+    let unflushed = if *state.current_day.lock() == date {
+        state.unflushed_focus_ms() as i64
+    } else {
+        0
+    };
+
+    let mut digest = with_database(&state, |db| db.day_digest(&date))?;
+    digest.stats.total_focus_ms += unflushed;
+    digest.goal = digest.goal.with_extra_focus(unflushed);
+    Ok(digest)
+}
+
+/// 把指定日期的专注卡片保存为 PNG 图片
+#[tauri::command]
+pub fn save_focus_card(date: String, path: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid date: {}", date)))?;
+    let png = with_database(&state, |db| db.render_focus_card(&date))?;
+
+    std::fs::write(&path, png)?;
+
+    tracing::info!("Focus card for {} saved to {}", date, path);
+    Ok(())
+}
+
+/// 把指定日期的专注时间线色条保存为 PNG 图片
+#[tauri::command]
+pub fn save_timeline_image(date: String, path: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    let png = with_database(&state, |db| db.render_timeline_image(&date))?;
+
+    std::fs::write(&path, png)?;
+
+    tracing::info!("Timeline for {} saved to {}", date, path);
+    Ok(())
+}
+
+/// 指定日期最长的连续专注块（容忍短暂的分心或离开），没有专注时返回 None
+#[tauri::command]
+pub fn get_longest_focus_block(date: String, state: State<'_, Arc<AppState>>) -> Result<Option<FocusBlock>, CommandError> {
+    with_database(&state, |db| db.get_longest_focus_block(&date))
+}
+
+/// 导出匿名统计报告到指定路径
+///
+/// 报告只包含按天汇总的专注分布（不含日期和时间戳），仅在用户主动导出时写入，不会自动发送
+#[tauri::command]
+pub fn export_anonymized_report(path: String, state: State<'_, Arc<AppState>>) -> Result<(), CommandError> {
+    let report = with_database(&state, |db| db.build_anonymized_report())?;
+
+    let content = serde_json::to_string_pretty(&report).map_err(|e| CommandError::Io(e.to_string()))?;
+    std::fs::write(&path, content)?;
+
+    tracing::info!("Anonymized report exported to {}", path);
+    Ok(())
+}
+
+/// 模型评估的时间上限
+const MODEL_EVAL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// 用标注图片目录评估当前模型的检测精度（QA 回归测试用）
+///
+/// 加载模型和逐张推理耗时较长，放到阻塞线程执行，避免卡住界面
+#[tauri::command]
+pub async fn evaluate_model(
+    dir: String,
+    state: State<'_, Arc<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<EvalResult, CommandError> {
+    let model_dir = state.config.lock().model.dir.clone();
+    let result = {
+        let state = Arc::clone(&state);
+        let dir = dir.clone();
+        blocking_with_timeout("evaluate_model", MODEL_EVAL_TIMEOUT, move || {
+            let (model_path, anchors_path) =
+                resolve_model_paths(&app_handle, model_dir.as_deref()).map_err(CommandError::Io)?;
+            let anchors_path = state.usable_anchors_path(anchors_path);
+            let mut detector = BlazeFaceDetector::new(&model_path, Some(&anchors_path))?;
+
+            Ok(crate::vision::evaluate_dir(std::path::Path::new(&dir), |data, width, height| {
+                detector.detect(data, width, height)
+            })?)
+        })
+        .await?
+    };
+
+    tracing::info!(
+        "Model evaluation on {}: precision {:.3}, recall {:.3}, avg IoU {:.3} ({} images, {} skipped)",
+        dir,
+        result.precision,
+        result.recall,
+        result.avg_iou,
+        result.images,
+        result.skipped
+    );
+    Ok(result)
+}
+
+/// 重新生成锚框文件并缓存到数据目录，返回文件路径
+///
+/// 打包的锚框文件缺失或损坏时，启动视觉检测会自动改用这份缓存
+#[tauri::command]
+pub fn cache_anchors(state: State<'_, Arc<AppState>>) -> Result<String, CommandError> {
+    let path = state
+        .anchors_cache_path()
+        .ok_or_else(|| CommandError::Io("Data directory is not available".to_string()))?;
+    BlazeFaceDetector::write_anchors(&path)?;
+
+    tracing::info!("Anchors written to {}", path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// 获取视觉检测状态（详细信息）
+#[tauri::command]
+pub fn get_vision_status(state: State<'_, Arc<AppState>>) -> VisionStatusResponse {
+    state.vision_status()
+}
+
+/// 视觉检测状态响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisionStatusResponse {
+    /// 是否正在运行
+    pub is_running: bool,
+    /// 当前专注状态
+    pub focus_state: Option<FocusState>,
+    /// 当前专注状态是否已过时
+    pub is_stale: bool,
+    /// 应用已运行的时长（秒）
+    pub uptime_secs: u64,
+    /// 本次运行中启动视觉检测的次数
+    pub vision_sessions: u32,
+}
+
+/// 获取配置的 JSON Schema（供前端生成设置表单）
+#[tauri::command]
+pub fn get_config_schema() -> serde_json::Value {
+    crate::config::schema::config_schema()
+}
+
+/// 预览帧数据（发送到前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewFrame {
+    /// Base64 编码的 JPEG 图像
+    pub data: String,
+    /// 图像宽度
+    pub width: u32,
+    /// 图像高度
+    pub height: u32,
+    /// 时间戳
+    pub timestamp_ms: u64,
+    /// 帧编号（与 `overlay_data` 事件对应）
+    pub frame_id: u64,
+}
+
+/// 将摄像头帧编码为 base64 JPEG，缩小后按 `blur` 做匿名化处理
+fn encode_frame_to_base64(frame: &CapturedFrame, blur: PreviewBlur, blur_strength: f32) -> Option<PreviewFrame> {
+    use image::{RgbImage, ImageEncoder, codecs::jpeg::JpegEncoder};
+    use std::io::Cursor;
+
+    // 从原始 RGB 数据创建图像
+    let img = RgbImage::from_raw(frame.width, frame.height, frame.data.clone())?;
+
+    // 缩小图像以减少传输大小（目标宽度 160px）
+    let scale = 160.0 / frame.width as f32;
+    let new_width = 160;
+    let new_height = (frame.height as f32 * scale) as u32;
+    let resized = image::imageops::resize(&img, new_width, new_height, image::imageops::FilterType::Triangle);
+    let resized = blur.apply(resized, blur_strength);
+
+    // 编码为 JPEG
+    let mut buffer = Cursor::new(Vec::new());
+    let encoder = JpegEncoder::new_with_quality(&mut buffer, 60);
+    encoder.write_image(
+        resized.as_raw(),
+        new_width,
+        new_height,
+        image::ExtendedColorType::Rgb8,
+    ).ok()?;
+
+    // 转为 base64
+    let jpeg_data = buffer.into_inner();
+    let base64_data = BASE64.encode(&jpeg_data);
+
+    Some(PreviewFrame {
+        data: format!("data:image/jpeg;base64,{}", base64_data),
+        width: new_width,
+        height: new_height,
+        timestamp_ms: frame.timestamp_ms,
+        frame_id: frame.frame_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+
+    #[test]
+    fn test_find_window_missing() {
+        let result = find_window::<()>(PET_WINDOW_LABEL, |_| None);
+        assert_eq!(result, Err(CommandError::WindowNotFound("pet".to_string())));
+
+        let json = serde_json::to_value(result.unwrap_err()).unwrap();
+        assert_eq!(json["kind"], "window_not_found");
+        assert_eq!(json["message"], "pet");
+
+        assert_eq!(find_window(PET_WINDOW_LABEL, |label| Some(label.len())), Ok(3));
+    }
+
+    #[test]
+    fn test_camera_busy_errors() {
+        for message in [
+            "Failed to start camera stream: Could not start stream: Device or resource busy (os error 16)",
+            "Failed to open camera: MSMF error 0xC00D3704",
+            "Failed to open camera: The process cannot access the file because it is being used by another process. (0x80070020)",
+            "Failed to open camera: AVErrorDeviceInUseByAnotherApplication",
+        ] {
+            assert_eq!(camera_probe_error(message.to_string()), CommandError::CameraBusy(message.to_string()));
+        }
+
+        let message = "Failed to open camera: No such device".to_string();
+        assert_eq!(camera_probe_error(message.clone()), CommandError::Vision(message));
+    }
+
+    #[test]
+    fn test_find_model_root() {
+        let base = test_temp_path("models");
+        let candidates = vec![
+            ("resource dir", base.join("resources")),
+            ("executable dir", base.join("exe")),
+            ("config override", base.join("override")),
+        ];
+        for (_, dir) in &candidates {
+            std::fs::create_dir_all(dir.join("models")).unwrap();
+        }
+        assert!(find_model_root(&candidates).is_none());
+
+        // 后面的候选目录都有模型时，使用最靠前的一个
+        std::fs::write(base.join("override").join("models").join(MODEL_FILE), b"model").unwrap();
+        assert_eq!(find_model_root(&candidates).unwrap().0, "config override");
+        std::fs::write(base.join("exe").join("models").join(MODEL_FILE), b"model").unwrap();
+        assert_eq!(find_model_root(&candidates).unwrap(), ("executable dir", base.join("exe")));
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_event_log() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+
+        // 手势产生情绪变化和手势两条事件，写入前不落库
+        state.apply_gesture(GestureType::Wave);
+        assert!(with_database(&state, |db| db.get_events(0, 10)).unwrap().is_empty());
+
+        state.flush_events();
+        assert!(state.pending_events.lock().is_empty());
+
+        let events = with_database(&state, |db| db.get_events(0, 10)).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, EventKind::MoodChanged);
+        assert_eq!(events[0].payload["from"], "idle");
+        assert_eq!(events[0].payload["to"], "interact");
+        assert_eq!(events[1].kind, EventKind::Gesture);
+        assert_eq!(events[1].payload["gesture"], "wave");
+        assert!(events[0].id > 0);
+
+        // 已在互动中再次手势：情绪不变，只记录手势
+        state.apply_gesture(GestureType::Heart);
+        state.flush_events();
+        let events = with_database(&state, |db| db.get_events(0, 10)).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[2].kind, EventKind::Gesture);
+    }
+
+    #[test]
+    fn test_config_update_reaches_state_machine() {
+        let state = AppState::default();
+        let mut config = state.config.lock().clone();
+        config.focus.away_timeout = 12.0;
+        config.focus.away_grace_delay = 2.5;
+        config.focus.confirm_duration = 4.0;
+        config.focus.short_term_weight = 0.6;
+        config.focus.ema_alpha_min = 0.1;
+        config.focus.ema_alpha_max = 0.3;
+        config.pet.interact_duration = 5.0;
+        config.pet.min_mood_dwell_ms = 2000;
+        config.pet.recompute_after_interact = false;
+
+        let report = state.apply_config_update(config, ApplyMode::ApplyLiveOnly).unwrap();
+        assert!(report.needs_restart.is_empty());
+        for field in [
+            "focus.away_timeout",
+            "focus.away_grace_delay",
+            "focus.confirm_duration",
+            "focus.short_term_weight",
+            "focus.ema_alpha_min",
+            "focus.ema_alpha_max",
+            "pet.interact_duration",
+            "pet.min_mood_dwell_ms",
+            "pet.recompute_after_interact",
+        ] {
+            assert!(report.live.iter().any(|path| path == field), "{}", field);
+        }
+
+        // 报告为立即生效的字段都已进入运行中的状态机
+        let expected = PetStateConfig::from_app_config(&state.config.lock());
+        assert_eq!(*state.pet_state_machine.lock().config(), expected);
+        assert_eq!(expected.away_timeout, 12.0);
+        assert!(!expected.recompute_after_interact);
+    }
+
+    #[test]
+    fn test_failed_event_flush_is_capped() {
+        let state = AppState::default();
+        state.config.lock().buffers.pending_events = 5;
+        for _ in 0..8 {
+            state.log_event(EventKind::Gesture, serde_json::json!({}));
+        }
+
+        // 数据库不可用：事件放回队列，但不超过上限
+        state.flush_events();
+        assert_eq!(state.pending_events.lock().len(), 5);
+    }
+
+    #[test]
+    fn test_rollover_day() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        *state.current_day.lock() = "2026-10-15".to_string();
+        state.pet_state_machine.lock().total_focus_ms = 120_000;
+        state.focus_stats.lock().total_focus_ms = 120_000;
+
+        let previous_day = state.rollover_day();
+        assert_eq!(previous_day, "2026-10-15");
+
+        // 实时计数已重置
+        assert_eq!(state.pet_state_machine.lock().total_focus_ms, 0);
+        assert_eq!(state.focus_stats.lock().total_focus_ms, 0);
+        assert_eq!(*state.current_day.lock(), storage::today_key());
+
+        // 前一天的数据保留在数据库中
+        let stats = with_database(&state, |db| db.get_stats_by_date("2026-10-15"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.total_focus_ms, 120_000);
+    }
+
+    #[test]
+    fn test_switch_focus_source() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        assert_eq!(*state.focus_source.lock(), FocusSource::Camera);
+
+        // 没有配置外部来源时拒绝切换
+        assert!(matches!(state.start_external_source(), Err(CommandError::InvalidArgument(_))));
+        assert_eq!(*state.focus_source.lock(), FocusSource::Camera);
+
+        state.config.lock().focus.external_source_enabled = true;
+        let rx = state.start_external_source().unwrap();
+        assert_eq!(*state.focus_source.lock(), FocusSource::External);
+        assert!(state.session.lock().is_some());
+
+        let pushed = FocusState {
+            face_present: true,
+            focus_score: 0.9,
+            ..Default::default()
+        };
+        state.push_external_focus_state(pushed).unwrap();
+        assert_eq!(rx.borrow().focus_score, 0.9);
+        assert!(rx.borrow().timestamp_ms > 0);
+        let pet = state.pet_state();
+        assert_eq!(pet.focus_score, 0.9);
+        assert!(!pet.is_stale);
+        assert!(!pet.is_vision_active);
+
+        // 外部程序停止推送后按最新状态的时间判断为过时
+        let old = chrono::Local::now().timestamp_millis() as u64 - 10_000;
+        state
+            .push_external_focus_state(FocusState {
+                timestamp_ms: old,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(state.pet_state().is_stale);
+
+        // 切回摄像头：外部通道关闭，会话写入数据库
+        state.stop_external_source();
+        assert_eq!(*state.focus_source.lock(), FocusSource::Camera);
+        assert!(state.focus_state_rx.lock().is_none());
+        assert!(state.session.lock().is_none());
+        assert!(rx.has_changed().is_err());
+        assert!(state.push_external_focus_state(FocusState::default()).is_err());
+    }
+
+    #[test]
+    fn test_add_manual_session_to_yesterday() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+
+        let now = chrono::Local::now();
+        let yesterday = storage::day_key(now - chrono::Duration::days(1));
+        let (day_start, _) = storage::day_bounds_ms(&yesterday).unwrap();
+        let start_ms = day_start + 9 * 3_600_000;
+        let end_ms = start_ms + 3_600_000;
+        let now_ms = now.timestamp_millis();
+
+        let id = state
+            .add_manual_session(start_ms, end_ms, 50 * 60_000, 5 * 60_000, Some("离线写作"), now_ms)
+            .unwrap();
+        assert!(id > 0);
+
+        {
+            let db = state.database.lock();
+            let db = db.as_ref().unwrap();
+            let stats = db.get_stats_by_date(&yesterday).unwrap().unwrap();
+            assert_eq!(stats.total_focus_ms, 50 * 60_000);
+            assert_eq!(stats.total_distracted_ms, 5 * 60_000);
+            assert_eq!(stats.session_count, 1);
+            assert!(db.get_stats_by_date(&storage::day_key(now)).unwrap().is_none());
+        }
+
+        // 不合法的时间和时长
+        for (start, end, focus, distracted) in [
+            (end_ms, start_ms, 0, 0),
+            (start_ms, end_ms, 50 * 60_000, 20 * 60_000),
+            (start_ms, end_ms, -1, 0),
+            (now_ms, now_ms + 60_000, 0, 0),
+            // 溢出
+            (start_ms, end_ms, i64::MAX, i64::MAX),
+            (i64::MIN, end_ms, 0, 0),
+        ] {
+            assert!(matches!(
+                state.add_manual_session(start, end, focus, distracted, None, now_ms),
+                Err(CommandError::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_does_not_double_count() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        state.start_session();
+        state.pet_state_machine.lock().total_focus_ms = 60_000;
+
+        let first = state.checkpoint_session().unwrap();
+        assert_eq!(first.focus_ms, 60_000);
+        let session_id = first.session.unwrap().id;
+        assert!(session_id > 0);
+
+        // 没有新增时长：不重复累加，更新同一条会话记录
+        let second = state.checkpoint_session().unwrap();
+        assert_eq!(second.focus_ms, 0);
+        assert_eq!(second.session.unwrap().id, session_id);
+        let stats = with_database(&state, |db| db.get_today_stats()).unwrap().unwrap();
+        assert_eq!(stats.total_focus_ms, 60_000);
+        assert_eq!(stats.session_count, 1);
+        assert_eq!(state.unflushed_focus_ms(), 0);
+
+        // 会话继续，结束时只累加检查点之后的部分
+        state.pet_state_machine.lock().total_focus_ms = 90_000;
+        state.flush_session();
+        let stats = with_database(&state, |db| db.get_today_stats()).unwrap().unwrap();
+        assert_eq!(stats.total_focus_ms, 90_000);
+        assert_eq!(stats.session_count, 1);
+        // 最长专注按会话的完整时长计算，而不是最大的一次增量
+        assert_eq!(stats.longest_focus_ms, 90_000);
+        let sessions = with_database(&state, |db| db.get_recent_sessions(10)).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].focus_duration_ms, 90_000);
+    }
+
+    #[test]
+    fn test_stats_update_merges_without_double_counting() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        state.start_session();
+        state.pet_state_machine.lock().total_focus_ms = 60_000;
+        state.focus_stats.lock().total_focus_ms = 60_000;
+        state.checkpoint_session().unwrap();
+
+        // 检查点之后又累计了 30 秒：数据库中的 60 秒不重复计入
+        state.pet_state_machine.lock().total_focus_ms = 90_000;
+        state.focus_stats.lock().total_focus_ms = 90_000;
+        let update = state.stats_update().unwrap();
+        assert_eq!(update.live.total_focus_ms, 90_000);
+        assert_eq!(update.today.total_focus_ms, 90_000);
+        assert_eq!(update.today.session_count, 1);
+
+        // 之前的会话写入的数据照常计入
+        let today = state.current_day.lock().clone();
+        with_database(&state, |db| db.add_daily_stats(&today, 40_000, 0, 1, 40_000)).unwrap();
+        let update = state.stats_update().unwrap();
+        assert_eq!(update.today.total_focus_ms, 130_000);
+        assert_eq!(update.today.session_count, 2);
+    }
+
+    #[test]
+    fn test_buffer_limits_evict_oldest() {
+        let state = AppState::default();
+        state.config.lock().buffers.focus_history = 5;
+        state.config.lock().buffers.trend = 10;
+        state.config.lock().buffers.pending_samples = 100;
+
+        for i in 0..50u64 {
+            state.recent_focus_states.lock().push_back(FocusState {
+                timestamp_ms: i,
+                ..Default::default()
+            });
+            state.focus_trend.lock().push(i as i64, 0.5);
+        }
+        for i in 0..500 {
+            state.pending_samples.lock().push(FocusSample {
+                timestamp_ms: i,
+                focus_score: 0.5,
+            });
+        }
+        let unbounded = state.buffer_stats().est_bytes;
+
+        // 超出上限时丢弃最旧的条目
+        assert_eq!(state.enforce_buffer_limits(), 45 + 40 + 400);
+        let stats = state.buffer_stats();
+        assert_eq!(stats.focus_history_len, 5);
+        assert_eq!(stats.trend_len, 10);
+        assert_eq!(stats.pending_samples_len, 100);
+        assert_eq!(state.recent_focus_states.lock().front().unwrap().timestamp_ms, 45);
+        assert_eq!(state.pending_samples.lock()[0].timestamp_ms, 400);
+
+        // 估算的内存占用不超过上限对应的大小
+        assert!(stats.est_bytes < unbounded);
+        assert_eq!(
+            stats.est_bytes,
+            buffers::bytes_of::<FocusState>(5) + buffers::bytes_of::<(i64, f32)>(10) + buffers::bytes_of::<FocusSample>(100)
+        );
+        assert_eq!(state.enforce_buffer_limits(), 0);
+    }
+
+    #[test]
+    fn test_completed_challenge_is_persisted() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        state.start_challenge(0, 2_000).unwrap();
+        assert!(state.start_challenge(0, 2_000).is_err());
+
+        // 分心时不完成，专注累计到目标时完成并写入记录
+        assert!(matches!(state.observe_challenge(1_000, FocusLevel::Focused), Some(ChallengeUpdate::Progress(_))));
+        assert!(matches!(state.observe_challenge(3_000, FocusLevel::Distracted), Some(ChallengeUpdate::Progress(_))));
+        assert!(matches!(state.observe_challenge(4_000, FocusLevel::Focused), Some(ChallengeUpdate::Complete(_))));
+        assert!(state.challenge.lock().is_none());
+        let records = with_database(&state, |db| db.get_recent_challenges(10)).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].started_at_ms, records[0].completed_at_ms, records[0].target_ms), (0, 4_000, 2_000));
+
+        // 放弃的挑战不写入记录
+        state.start_challenge(5_000, 2_000).unwrap();
+        assert_eq!(state.abandon_challenge().unwrap().focused_ms, 0);
+        assert_eq!(state.observe_challenge(9_000, FocusLevel::Focused), None);
+        assert_eq!(with_database(&state, |db| db.get_recent_challenges(10)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_session_recap_once_per_session() {
+        use crate::state::FocusLevel;
+
+        let state = AppState::default();
+        let db = Database::in_memory().unwrap();
+        db.insert_session(&FocusSession {
+            id: 0,
+            start_time: 0,
+            end_time: 60_000,
+            focus_duration_ms: 60_000,
+            distracted_duration_ms: 0,
+            avg_focus_score: 0.8,
+            min_focus_score: 0.8,
+            max_focus_score: 0.8,
+        })
+        .unwrap();
+        *state.database.lock() = Some(db);
+
+        state.start_session();
+        if let Some(ref mut session) = *state.session.lock() {
+            for (i, (score, level)) in [
+                (0.9, FocusLevel::Focused),
+                (0.2, FocusLevel::Distracted),
+                (0.8, FocusLevel::Focused),
+                (0.1, FocusLevel::Distracted),
+                (0.1, FocusLevel::Distracted),
+                (0.7, FocusLevel::Focused),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                session.record(i as i64 * 100, score, level);
+            }
+        }
+        state.pet_state_machine.lock().total_focus_ms = 90_000;
+        assert_eq!(state.take_session_recaps(), vec![]);
+
+        state.flush_session();
+        let recap = state.take_session_recaps().pop().unwrap();
+        assert_eq!(recap.focus_ms, 90_000);
+        assert!((recap.avg_focus_score - 0.8).abs() < 1e-5);
+        assert_eq!(recap.peak_score, 0.9);
+        assert_eq!(recap.distraction_count, 2);
+        assert!(recap.personal_best);
+        assert!(recap.duration_ms >= 0);
+
+        // 每个会话只发送一次
+        assert_eq!(state.take_session_recaps(), vec![]);
+
+        // 事件任务取出之前结束的多个会话都保留，按结束顺序取出
+        state.start_session();
+        state.pet_state_machine.lock().total_focus_ms = 120_000;
+        state.flush_session();
+        state.start_session();
+        state.pet_state_machine.lock().total_focus_ms = 180_000;
+        state.flush_session();
+        let recaps = state.take_session_recaps();
+        assert_eq!(recaps.iter().map(|r| r.focus_ms).collect::<Vec<_>>(), vec![30_000, 60_000]);
+
+        // 关闭后不再生成
+        state.config.lock().pet.session_recap = false;
+        state.start_session();
+        state.flush_session();
+        assert_eq!(state.take_session_recaps(), vec![]);
+    }
+
+    #[test]
+    fn test_reset_today_keeps_previous_days() {
+        let state = AppState::default();
+        let db = Database::in_memory().unwrap();
+        let today = storage::today_key();
+        db.add_daily_stats("2026-01-01", 90_000, 0, 1, 90_000).unwrap();
+        db.add_daily_stats(&today, 60_000, 5_000, 1, 60_000).unwrap();
+        *state.database.lock() = Some(db);
+        state.pet_state_machine.lock().total_focus_ms = 30_000;
+        state.focus_stats.lock().total_focus_ms = 30_000;
+
+        state.reset_today().unwrap();
+
+        assert_eq!(state.pet_state_machine.lock().total_focus_ms, 0);
+        assert_eq!(state.focus_stats.lock().total_focus_ms, 0);
+        let today_stats = with_database(&state, |db| db.get_today_stats()).unwrap().unwrap();
+        assert_eq!(today_stats.total_focus_ms, 0);
+        assert_eq!(today_stats.session_count, 0);
+        let previous = with_database(&state, |db| db.get_stats_by_date("2026-01-01"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(previous.total_focus_ms, 90_000);
+    }
+
+    #[test]
+    fn test_auto_rollover_at_midnight() {
+        use chrono::TimeZone;
+
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        let before = chrono::Local.with_ymd_and_hms(2026, 10, 15, 23, 59, 50).unwrap();
+        let after = chrono::Local.with_ymd_and_hms(2026, 10, 16, 0, 0, 10).unwrap();
+        *state.current_day.lock() = storage::day_key(before);
+        state.pet_state_machine.lock().total_focus_ms = 60_000;
+
+        // 同一天内不切换
+        assert!(state.check_day_rollover(before).is_none());
+        assert_eq!(state.pet_state_machine.lock().total_focus_ms, 60_000);
+
+        // 跨过午夜：计数重置，前一天已写入
+        assert_eq!(state.check_day_rollover(after).as_deref(), Some("2026-10-15"));
+        assert_eq!(state.pet_state_machine.lock().total_focus_ms, 0);
+        assert_eq!(*state.current_day.lock(), "2026-10-16");
+        let stats = with_database(&state, |db| db.get_stats_by_date("2026-10-15"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.total_focus_ms, 60_000);
+        assert!(with_database(&state, |db| db.get_stats_by_date("2026-10-16")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_nms_threshold_arg_rejects_nan() {
+        assert!(matches!(nms_threshold_arg(f32::NAN), Err(CommandError::InvalidArgument(_))));
+        assert!(matches!(nms_threshold_arg(f32::NEG_INFINITY), Err(CommandError::InvalidArgument(_))));
+        assert_eq!(nms_threshold_arg(0.45), Ok(0.45));
+        assert_eq!(nms_threshold_arg(-1.0), Ok(0.0));
+    }
+
+    #[test]
+    fn test_concurrent_rollover_runs_once() {
+        use chrono::TimeZone;
+
+        let state = Arc::new(AppState::default());
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        let after = chrono::Local.with_ymd_and_hms(2026, 10, 16, 0, 0, 10).unwrap();
+        *state.current_day.lock() = "2026-10-15".to_string();
+        state.start_session();
+
+        // 状态任务和跨天任务同时检测到跨天，只有一个执行切换
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let state = state.clone();
+                std::thread::spawn(move || state.check_day_rollover(after))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results.iter().filter(|r| r.is_some()).count(), 1);
+        assert!(results.contains(&Some("2026-10-15".to_string())));
+
+        // 同一时刻再次检查不会结束刚重新开始的会话
+        assert!(state.check_day_rollover(after).is_none());
+        assert_eq!(*state.current_day.lock(), "2026-10-16");
+        assert!(state.session.lock().is_some());
+        let stats = with_database(&state, |db| db.get_stats_by_date("2026-10-15"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.session_count, 1);
+        assert!(with_database(&state, |db| db.get_stats_by_date("2026-10-16")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_preview_blur_keeps_detector_frame() {
+        let (width, height) = (320, 240);
+        let data: Vec<u8> = (0..width * height)
+            .flat_map(|i| {
+                let v = if (i % width / 8 + i / width / 8) % 2 == 0 { 255 } else { 0 };
+                [v, v, v]
+            })
+            .collect();
+        let frame = CapturedFrame { width, height, data: data.clone(), timestamp_ms: 0, frame_id: 1 };
+
+        let plain = encode_frame_to_base64(&frame, PreviewBlur::Off, 8.0).unwrap();
+        let blurred = encode_frame_to_base64(&frame, PreviewBlur::Gaussian, 8.0).unwrap();
+        let pixelated = encode_frame_to_base64(&frame, PreviewBlur::Pixelate, 8.0).unwrap();
+
+        assert_ne!(plain.data, blurred.data);
+        assert_ne!(plain.data, pixelated.data);
+        assert_eq!((blurred.width, blurred.height), (plain.width, plain.height));
+        // 检测器使用的原始帧不受影响
+        assert_eq!(frame.data, data);
+    }
+
+    #[tokio::test]
+    async fn test_calibrate_face_size() {
+        use crate::vision::FaceDetection;
+        use std::time::Duration;
+
+        let state = Arc::new(AppState::default());
+        state.config.lock().focus.external_source_enabled = true;
+        let _rx = state.start_external_source().unwrap();
+
+        // 正常坐姿时人脸大小稳定在 0.2，中间有一次前倾和一次离开画面
+        let face = |bbox| FaceDetection {
+            confidence: 0.95,
+            bbox,
+            landmarks: [(0.45, 0.40), (0.55, 0.40), (0.50, 0.50), (0.50, 0.60), (0.36, 0.45), (0.64, 0.45)],
+            landmark_quality: [1.0; 6],
+        };
+        let seated = face((0.3, 0.2, 0.7, 0.7));
+        let leaning = face((0.1, 0.0, 0.9, 0.9));
+        let pusher = {
+            let state = state.clone();
+            let (seated, leaning) = (seated.clone(), leaning.clone());
+            tokio::spawn(async move {
+                for i in 0..12 {
+                    let detection = match i {
+                        3 => None,
+                        5 => Some(&leaning),
+                        _ => Some(&seated),
+                    };
+                    state.push_external_focus_state(FocusState::from_detection(detection, 0.9)).unwrap();
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+        };
+
+        let ideal = state.calibrate_face_size(Duration::from_millis(500)).await.unwrap();
+        pusher.await.unwrap();
+        assert!((ideal - seated.size()).abs() < 1e-6, "{}", ideal);
+        assert_eq!(state.config.lock().focus.ideal_face_size, ideal);
+        assert_eq!(state.config.lock().focus.calculator_config().ideal_face_size, ideal);
+
+        // 视觉未运行时无法校准
+        state.stop_external_source();
+        assert!(matches!(
+            state.calibrate_face_size(Duration::from_millis(10)).await,
+            Err(CommandError::InvalidArgument(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_with_timeout() {
+        use std::time::Duration;
+
+        let slow = blocking_with_timeout("slow_op", Duration::from_millis(50), || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(())
+        });
+        let started = std::time::Instant::now();
+        assert!(matches!(slow.await, Err(CommandError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_millis(400));
+
+        // 按时完成时返回操作本身的结果
+        assert_eq!(blocking_with_timeout("fast_op", Duration::from_secs(5), || Ok(7)).await, Ok(7));
+        assert!(matches!(
+            blocking_with_timeout("failing_op", Duration::from_secs(5), || -> Result<(), _> {
+                Err(CommandError::Vision("boom".to_string()))
+            })
+            .await,
+            Err(CommandError::Vision(_))
+        ));
+    }
+
+    #[test]
+    fn test_vision_start_failure_rolls_back() {
+        let state = AppState::default();
+
+        // 模拟启动中途失败：处理器和接收端已创建，随后出错
+        let start = |fail: bool| -> Result<(), CommandError> {
+            let guard = state.begin_vision_start()?;
+            let processor = Arc::new(VisionProcessor::new(VisionProcessorConfig::default()));
+            *state.focus_state_rx.lock() = Some(processor.subscribe());
+            *state.vision_processor.lock() = Some(processor);
+            if fail {
+                return Err(CommandError::Vision("injected start failure".to_string()));
+            }
+            guard.commit();
+            Ok(())
+        };
+
+        assert!(matches!(start(true), Err(CommandError::Vision(_))));
+        assert!(!*state.vision_running.lock());
+        assert!(state.vision_processor.lock().is_none());
+        assert!(state.focus_state_rx.lock().is_none());
+
+        // 回滚后可以重新启动；运行中再次启动被拒绝且不影响运行状态
+        start(false).unwrap();
+        assert!(*state.vision_running.lock());
+        assert!(matches!(start(false), Err(CommandError::Vision(_))));
+        assert!(*state.vision_running.lock());
+        assert!(state.vision_processor.lock().is_some());
+    }
+
+    #[test]
+    fn test_demo_mood_respects_lock() {
+        let state = AppState::default();
+        assert_eq!(state.set_demo_mood(PetMood::Excited), Ok(PetMood::Excited));
+
+        // 锁定时拒绝，情绪保持锁定的值
+        state.pet_state_machine.lock().set_mood_lock(Some(PetMood::Sleepy));
+        assert!(state.set_demo_mood(PetMood::Happy).unwrap_err().contains("locked"));
+        assert_eq!(state.pet_state_machine.lock().mood, PetMood::Sleepy);
+
+        state.pet_state_machine.lock().set_mood_lock(None);
+        assert_eq!(state.set_demo_mood(PetMood::Happy), Ok(PetMood::Happy));
+    }
+
+    #[test]
+    fn test_lower_screen_calibration_clamped() {
+        let state = AppState::default();
+        assert!(matches!(state.calibrate_lower_screen(), Err(CommandError::InvalidArgument(_))));
+
+        // 低头很深时校准结果超过上限，保存前限制到配置允许的范围
+        state.recent_focus_states.lock().extend((0..10).map(|_| FocusState {
+            face_present: true,
+            pitch: 70.0,
+            ..Default::default()
+        }));
+        assert_eq!(state.calibrate_lower_screen().unwrap(), 60.0);
+        let config = state.config.lock();
+        assert_eq!(config.focus.phone_pitch_threshold, 60.0);
+        assert!(schema::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_secondary_screen_calibration_clamped() {
+        let state = AppState::default();
+
+        // 副屏在最右侧时区间的上限超过 90 度，保存前限制到配置允许的范围
+        state.recent_focus_states.lock().extend((0..10).map(|_| FocusState {
+            face_present: true,
+            yaw: 85.0,
+            ..Default::default()
+        }));
+        let zone = state.calibrate_secondary_screen().unwrap();
+        assert_eq!(zone.max_yaw, 90.0);
+        assert!(zone.min_yaw < 85.0);
+        let config = state.config.lock();
+        assert!(config.focus.secondary_screen_enabled);
+        assert_eq!(config.focus.secondary_screen_yaw_max, 90.0);
+        assert!(schema::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_distraction_flushed_on_stop() {
+        let state = AppState::default();
+        *state.database.lock() = Some(Database::in_memory().unwrap());
+        state.begin_vision_start().unwrap().commit();
+
+        // 停止检测时进行中的分心写入数据库
+        let now = chrono::Local::now().timestamp_millis();
+        {
+            let mut tracker = state.distraction.lock();
+            tracker.observe(now - 60_000, FocusLevel::Focused, true);
+            tracker.observe(now - 30_000, FocusLevel::Away, false);
+        }
+        state.stop_vision().unwrap();
+
+        let saved = with_database(&state, |db| db.get_recent_distractions(10)).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].start_ms, now - 30_000);
+        assert!(saved[0].duration_ms >= 30_000);
+        assert_eq!(saved[0].trigger, crate::storage::distractions::DistractionTrigger::FaceLost);
+        // 跟踪器已重置，再次停止不会重复写入
+        assert!(state.distraction.lock().finish(now + 60_000).is_none());
+    }
+
+    #[test]
+    fn test_start_rejected_while_stopping() {
+        let state = Arc::new(AppState::default());
+        state.begin_vision_start().unwrap().commit();
+
+        // 停止来源时先锁定处理器，持有该锁让停止卡在来源停止完之前
+        let processor = state.vision_processor.lock();
+        let stopper = {
+            let state = Arc::clone(&state);
+            std::thread::spawn(move || state.stop_vision())
+        };
+        while *state.vision_running.lock() {
+            std::thread::yield_now();
+        }
+        assert!(matches!(state.begin_vision_start(), Err(CommandError::Vision(_))));
+        assert!(!*state.vision_running.lock());
+
+        drop(processor);
+        stopper.join().unwrap().unwrap();
+        assert!(!*state.vision_stopping.lock());
+        state.begin_vision_start().unwrap().commit();
+        assert!(*state.vision_running.lock());
+    }
+
+    #[test]
+    fn test_vision_sessions_counted() {
+        let state = AppState::default();
+        assert_eq!(state.vision_status().vision_sessions, 0);
+        assert!(state.stop_vision().is_err());
+
+        for _ in 0..2 {
+            state.begin_vision_start().unwrap().commit();
+            state.stop_vision().unwrap();
+        }
+        // 启动失败不计入
+        drop(state.begin_vision_start().unwrap());
+
+        let status = state.vision_status();
+        assert_eq!(status.vision_sessions, 2);
+        assert!(!status.is_running);
+        assert!(status.uptime_secs < 60);
+    }
+
+    #[test]
+    fn test_resume_resets_timers_and_keeps_running_intent() {
+        let state = AppState::default();
+        state.begin_vision_start().unwrap().commit();
+        {
+            let mut machine = state.pet_state_machine.lock();
+            for _ in 0..100 {
+                machine.update(0.95, true);
+            }
+            assert_ne!(machine.focus_level, FocusLevel::Away);
+        }
+
+        let report = state.handle_resume(600_000);
+        assert!(report.was_running);
+        assert!(report.restart);
+        assert_eq!(report.camera_error, None);
+        // 旧的视觉检测已停止，状态机计时已重置
+        assert!(!*state.vision_running.lock());
+        {
+            let machine = state.pet_state_machine.lock();
+            assert_eq!(machine.focus_level, FocusLevel::Away);
+            assert_eq!(machine.mood, PetMood::Sleepy);
+        }
+
+        // 关闭自动重启时只记录睡眠前在运行；睡眠前未运行时不重启
+        state.config.lock().camera.restart_after_resume = false;
+        state.begin_vision_start().unwrap().commit();
+        let report = state.handle_resume(600_000);
+        assert!(report.was_running && !report.restart);
+        let report = state.handle_resume(600_000);
+        assert!(!report.was_running && !report.restart);
+    }
+
+    #[test]
+    fn test_pet_state_during_vision_start() {
+        let state = Arc::new(AppState::default());
+        let guard = state.begin_vision_start().unwrap();
+
+        // 接收端延迟保存：在此之前不应报告“运行中但没有人脸”
+        let installer = {
+            let state = Arc::clone(&state);
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                let (_tx, rx) = watch::channel(FocusState {
+                    face_present: true,
+                    focus_score: 0.8,
+                    timestamp_ms: chrono::Local::now().timestamp_millis() as u64,
+                    ..Default::default()
+                });
+                *state.focus_state_rx.lock() = Some(rx);
+            })
+        };
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(150);
+        let mut saw_starting = false;
+        while std::time::Instant::now() < deadline {
+            let pet = state.pet_state();
+            assert!(!pet.is_vision_active || pet.face_detected, "inconsistent report during start");
+            saw_starting |= !pet.is_vision_active;
+        }
+        installer.join().unwrap();
+        assert!(saw_starting);
+
+        let pet = state.pet_state();
+        assert!(pet.is_vision_active);
+        assert!(pet.face_detected);
+        assert_eq!(pet.focus_score, 0.8);
+        guard.commit();
+    }
+
+    #[test]
+    fn test_switch_profile_loads_its_config_and_database() {
+        let dir = test_temp_path("switch_profile");
+        std::fs::create_dir_all(&dir).unwrap();
+        let state = AppState::default();
+        *state.data_dir.lock() = Some(dir.clone());
+
+        // 默认档案的配置
+        let mut config = AppConfig::default();
+        config.focus.away_timeout = 42.0;
+        config.save(DataPaths::new(&dir).config_path).unwrap();
+
+        assert!(matches!(state.switch_profile("work"), Err(CommandError::InvalidArgument(_))));
+        state.profile_store().unwrap().create("work").unwrap();
+        let work = state.switch_profile("work").unwrap();
+        assert_eq!(work.focus.away_timeout, AppConfig::default().focus.away_timeout);
+        assert_eq!(*state.active_profile.lock(), "work");
+        let paths = state.data_paths().unwrap();
+        assert_eq!(paths.data_dir, dir.join("profiles").join("work"));
+        assert!(paths.database_path.exists());
+        assert!(paths.config_path.exists());
+
+        let default = state.switch_profile(profiles::DEFAULT_PROFILE).unwrap();
+        assert_eq!(default.focus.away_timeout, 42.0);
+        assert_eq!(state.config.lock().focus.away_timeout, 42.0);
+        assert_eq!(state.pet_state_machine.lock().config().away_timeout, 42.0);
+
+        // 专注检测运行中不能切换
+        state.begin_vision_start().unwrap().commit();
+        assert!(matches!(state.switch_profile("work"), Err(CommandError::InvalidArgument(_))));
+        assert_eq!(*state.active_profile.lock(), profiles::DEFAULT_PROFILE);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
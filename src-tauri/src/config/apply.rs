@@ -0,0 +1,187 @@
+//! 配置更新的部分应用
+//!
+//! 阈值、宠物设置等可以在运行中立即生效；摄像头设备、模型目录等在视觉处理器创建时读取，
+//! 需要重启视觉检测才能生效。更新配置时按字段区分两类，并报告哪些字段已生效、哪些需要重启。
+
+use super::{set_path, AppConfig, ConfigError};
+use crate::state::Personality;
+use serde::{Deserialize, Serialize};
+
+/// 只在视觉处理器创建时读取、需要重启视觉检测才能生效的字段（点分路径或分组前缀）
+///
+/// `camera.fps` 有运行中的设置入口，不需要重启
+const RESTART_FIELDS: &[&str] = &[
+    "camera.device_index",
+    "camera.enabled",
+    "camera.detect_interval_min",
+    "camera.detect_interval_max",
+    "camera.detect_borderline_margin",
+    "camera.mock_seed",
+    "camera.frame_timeout_secs",
+    "camera.preview_fps",
+    "focus.heat_rise_per_minute",
+    "focus.heat_decay_per_minute",
+    "focus.external_source_enabled",
+    "focus.passivity_enabled",
+    "focus.passivity_discount",
+    "focus.turn_grace_enabled",
+    "focus.turn_grace_budget",
+    "focus.turn_grace_window_secs",
+    "focus.turn_grace_max_turn_secs",
+    "focus.turn_grace_max_yaw",
+    "model",
+];
+
+/// 由预设性格控制的字段，单独修改后性格变为自定义
+const PERSONALITY_FIELDS: &[&str] = &["pet.reaction_style", "pet.sad_on_distraction", "pet.break_reminders"];
+
+/// 需要重启的字段如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyMode {
+    /// 应用全部字段，需要时重启视觉检测
+    #[default]
+    ApplyAndRestart,
+    /// 只应用可以立即生效的字段，需要重启的字段保持原值
+    ApplyLiveOnly,
+}
+
+/// 配置更新的应用结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplyReport {
+    /// 已立即生效的字段（点分路径）
+    pub live: Vec<String>,
+    /// 需要重启视觉检测才能生效的字段（点分路径）
+    pub needs_restart: Vec<String>,
+    /// 是否已重启视觉检测
+    pub restarted: bool,
+}
+
+/// 字段是否需要重启视觉检测才能生效
+pub fn requires_restart(path: &str) -> bool {
+    RESTART_FIELDS
+        .iter()
+        .any(|field| path == *field || path.strip_prefix(field).is_some_and(|rest| rest.starts_with('.')))
+}
+
+/// 两份配置中取值不同的叶子字段（点分路径）
+fn changed_fields(old: &serde_json::Value, new: &serde_json::Value, prefix: &str, out: &mut Vec<String>) {
+    if let (Some(old), Some(new)) = (old.as_object(), new.as_object()) {
+        for (key, new_value) in new {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            let old_value = old.get(key).unwrap_or(&serde_json::Value::Null);
+            changed_fields(old_value, new_value, &path, out);
+        }
+    } else if old != new {
+        out.push(prefix.to_string());
+    }
+}
+
+impl AppConfig {
+    /// 用 `update` 更新配置，返回按字段分类的应用结果
+    ///
+    /// `ApplyLiveOnly` 时需要重启的字段保持原值（仍列在 `needs_restart` 中）。
+    /// 新配置超出取值范围时返回错误，配置保持不变；`restarted` 由调用方在重启视觉检测后设置
+    pub fn apply_update(&mut self, update: AppConfig, mode: ApplyMode) -> Result<ApplyReport, ConfigError> {
+        update.validate()?;
+
+        let old = serde_json::to_value(&*self)?;
+        let mut new = serde_json::to_value(&update)?;
+        let mut changed = Vec::new();
+        changed_fields(&old, &new, "", &mut changed);
+
+        // 没有同时切换性格却改了预设性格控制的字段：设置已不再是预设，性格改为自定义
+        let personality_edited = changed.iter().any(|path| path == "pet.personality");
+        if !personality_edited
+            && update.pet.personality != Personality::Custom
+            && changed.iter().any(|path| PERSONALITY_FIELDS.contains(&path.as_str()))
+        {
+            set_path(&mut new, "pet.personality", serde_json::to_value(Personality::Custom)?);
+            changed.push("pet.personality".to_string());
+        }
+        let (needs_restart, live): (Vec<String>, Vec<String>) =
+            changed.into_iter().partition(|path| requires_restart(path));
+
+        if mode == ApplyMode::ApplyLiveOnly {
+            for path in &needs_restart {
+                let previous = path.split('.').fold(&old, |node, key| &node[key]).clone();
+                set_path(&mut new, path, previous);
+            }
+        }
+        *self = serde_json::from_value(new)?;
+
+        Ok(ApplyReport {
+            live,
+            needs_restart,
+            restarted: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing_preset_field_resets_personality() {
+        let mut config = AppConfig::default();
+        config.pet.apply_personality(Personality::Companion);
+
+        // 修改预设性格控制的字段后性格变为自定义，修改的值保留
+        let mut update = config.clone();
+        update.pet.sad_on_distraction = true;
+        let report = config.apply_update(update, ApplyMode::ApplyAndRestart).unwrap();
+        assert_eq!(config.pet.personality, Personality::Custom);
+        assert!(config.pet.sad_on_distraction);
+        assert!(report.live.iter().any(|path| path == "pet.personality"));
+
+        // 其他字段不影响性格
+        config.pet.apply_personality(Personality::Accountability);
+        let mut update = config.clone();
+        update.focus.enter_threshold = 0.8;
+        config.apply_update(update, ApplyMode::ApplyAndRestart).unwrap();
+        assert_eq!(config.pet.personality, Personality::Accountability);
+
+        // 同时切换性格时按请求的性格保存
+        let mut update = config.clone();
+        update.pet.apply_personality(Personality::Companion);
+        config.apply_update(update, ApplyMode::ApplyAndRestart).unwrap();
+        assert_eq!(config.pet.personality, Personality::Companion);
+    }
+
+    #[test]
+    fn test_apply_update_reports_restart_fields() {
+        let mut config = AppConfig::default();
+
+        // 只改阈值：全部立即生效
+        let mut update = config.clone();
+        update.focus.enter_threshold = 0.8;
+        update.focus.exit_threshold = 0.5;
+        let report = config.apply_update(update.clone(), ApplyMode::ApplyAndRestart).unwrap();
+        assert_eq!(report.live, ["focus.enter_threshold", "focus.exit_threshold"]);
+        assert!(report.needs_restart.is_empty());
+        assert_eq!(config.focus.enter_threshold, 0.8);
+
+        // 改摄像头设备：需要重启
+        update.camera.device_index = 2;
+        update.pet.gesture_enabled = !update.pet.gesture_enabled;
+        let report = config.apply_update(update.clone(), ApplyMode::ApplyAndRestart).unwrap();
+        assert_eq!(report.live, ["pet.gesture_enabled"]);
+        assert_eq!(report.needs_restart, ["camera.device_index"]);
+        assert_eq!(config.camera.device_index, 2);
+
+        // 只应用立即生效的字段时，摄像头设备保持原值
+        let mut config = AppConfig::default();
+        let report = config.apply_update(update.clone(), ApplyMode::ApplyLiveOnly).unwrap();
+        assert_eq!(report.needs_restart, ["camera.device_index"]);
+        assert_eq!(config.camera.device_index, 0);
+        assert_eq!(config.focus.enter_threshold, 0.8);
+        assert!(requires_restart("model.dir"));
+        assert!(!requires_restart("camera.fps"));
+
+        // 超出范围的配置被拒绝
+        update.focus.enter_threshold = 2.0;
+        assert!(config.apply_update(update, ApplyMode::ApplyAndRestart).is_err());
+        assert_eq!(config.focus.enter_threshold, 0.8);
+    }
+}
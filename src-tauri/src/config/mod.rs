@@ -1,12 +1,30 @@
 //! 配置管理模块
 //! 加载和保存应用配置
 
+pub mod apply;
+pub mod preset;
+pub mod schema;
+
+use crate::i18n::Locale;
+use crate::state::phone::PhoneGlanceConfig;
+use crate::state::buffers::BufferLimits;
+use crate::state::notification::NotificationPrefs;
+use crate::state::{FocusMilestones, Personality, ReactionStyle};
+use crate::storage::compare::CompareThresholds;
+use crate::storage::streak::StreakFreezePolicy;
+use crate::vision::{
+    DetectionCadenceConfig, FocusCalculatorConfig, FocusHeatConfig, FocusProfile, PassivityConfig, PreviewBlur, TurnGraceConfig, YawZone, DEFAULT_MOCK_SEED,
+    DEFAULT_PREVIEW_FPS,
+};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
 /// 应用配置
+///
+/// 所有字段都有默认值：其他版本写入的配置缺少字段时使用默认值，多出的字段被忽略
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     /// 摄像头设置
     pub camera: CameraSettings,
@@ -16,6 +34,21 @@ pub struct AppConfig {
     pub pet: PetSettings,
     /// 界面设置
     pub ui: UiSettings,
+    /// 隐私设置
+    #[serde(default)]
+    pub privacy: PrivacySettings,
+    /// 模型设置
+    #[serde(default)]
+    pub model: ModelSettings,
+    /// 统计设置
+    #[serde(default)]
+    pub stats: StatsSettings,
+    /// 内存缓冲区上限
+    #[serde(default)]
+    pub buffers: BufferLimits,
+    /// 系统通知偏好
+    #[serde(default)]
+    pub notifications: NotificationPrefs,
 }
 
 impl Default for AppConfig {
@@ -25,12 +58,18 @@ impl Default for AppConfig {
             focus: FocusSettings::default(),
             pet: PetSettings::default(),
             ui: UiSettings::default(),
+            privacy: PrivacySettings::default(),
+            model: ModelSettings::default(),
+            stats: StatsSettings::default(),
+            buffers: BufferLimits::default(),
+            notifications: NotificationPrefs::default(),
         }
     }
 }
 
 /// 摄像头设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CameraSettings {
     /// 摄像头设备索引
     pub device_index: u32,
@@ -38,6 +77,30 @@ pub struct CameraSettings {
     pub fps: u32,
     /// 是否启用摄像头
     pub enabled: bool,
+    /// 最小检测间隔（帧），专注分数接近阈值时使用
+    #[serde(default = "default_detect_interval_min")]
+    pub detect_interval_min: u32,
+    /// 最大检测间隔（帧），明显专注或明显离开时使用
+    #[serde(default = "default_detect_interval_max")]
+    pub detect_interval_max: u32,
+    /// 专注分数与阈值的距离在该范围内时视为临界，使用最小检测间隔
+    #[serde(default = "default_detect_borderline_margin")]
+    pub detect_borderline_margin: f32,
+    /// 模拟模式（未启用 vision 特性）下检测抖动和人脸丢失的随机种子，相同种子得到相同的检测序列
+    #[serde(default = "default_mock_seed")]
+    pub mock_seed: u64,
+    /// 超过该时间（秒）没有新帧时重启摄像头采集和检测（0 表示关闭）
+    #[serde(default = "default_frame_timeout_secs")]
+    pub frame_timeout_secs: u32,
+    /// 预览帧率（与摄像头帧率无关）
+    #[serde(default = "default_preview_fps")]
+    pub preview_fps: u32,
+    /// 实际帧率持续过低时按实际帧间隔累计专注时间
+    #[serde(default = "default_low_fps_compensation")]
+    pub low_fps_compensation: bool,
+    /// 系统睡眠唤醒后，睡眠前在运行的视觉检测自动重新启动
+    #[serde(default = "default_restart_after_resume")]
+    pub restart_after_resume: bool,
 }
 
 impl Default for CameraSettings {
@@ -46,12 +109,66 @@ impl Default for CameraSettings {
             device_index: 0,
             fps: 15,
             enabled: true,
+            detect_interval_min: default_detect_interval_min(),
+            detect_interval_max: default_detect_interval_max(),
+            detect_borderline_margin: default_detect_borderline_margin(),
+            mock_seed: default_mock_seed(),
+            frame_timeout_secs: default_frame_timeout_secs(),
+            preview_fps: default_preview_fps(),
+            low_fps_compensation: default_low_fps_compensation(),
+            restart_after_resume: default_restart_after_resume(),
+        }
+    }
+}
+
+impl CameraSettings {
+    /// 自适应检测频率配置（阈值取自专注检测设置）
+    pub fn detection_cadence(&self, focus: &FocusSettings) -> DetectionCadenceConfig {
+        DetectionCadenceConfig {
+            min_interval: self.detect_interval_min,
+            max_interval: self.detect_interval_max,
+            borderline_margin: self.detect_borderline_margin,
+            enter_threshold: focus.enter_threshold,
+            exit_threshold: focus.exit_threshold,
         }
     }
 }
 
+fn default_low_fps_compensation() -> bool {
+    true
+}
+
+fn default_restart_after_resume() -> bool {
+    true
+}
+
+fn default_detect_interval_min() -> u32 {
+    1
+}
+
+fn default_detect_interval_max() -> u32 {
+    3
+}
+
+fn default_detect_borderline_margin() -> f32 {
+    0.1
+}
+
+fn default_mock_seed() -> u64 {
+    DEFAULT_MOCK_SEED
+}
+
+fn default_frame_timeout_secs() -> u32 {
+    5
+}
+
+fn default_preview_fps() -> u32 {
+    DEFAULT_PREVIEW_FPS
+}
+
 /// 专注检测设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FocusSettings {
     /// 进入专注状态的阈值 (0.0 - 1.0)
     pub enter_threshold: f32,
@@ -61,8 +178,93 @@ pub struct FocusSettings {
     pub confirm_duration: f32,
     /// 判定离开的超时时间（秒）
     pub away_timeout: f32,
+    /// 人脸丢失多久后进入“寻找用户”状态（秒）
+    #[serde(default = "default_away_grace_delay")]
+    pub away_grace_delay: f32,
     /// EMA 平滑系数
     pub ema_alpha: f32,
+    /// 自适应 EMA 系数下限（人脸检测置信度低时使用）
+    #[serde(default = "default_ema_alpha_min")]
+    pub ema_alpha_min: f32,
+    /// 自适应 EMA 系数上限（人脸检测置信度高时使用）
+    #[serde(default = "default_ema_alpha_max")]
+    pub ema_alpha_max: f32,
+    /// 按人脸检测置信度在 [`ema_alpha_min`, `ema_alpha_max`] 之间调整 EMA 系数（关闭时始终使用 `ema_alpha`）
+    #[serde(default)]
+    pub adaptive_ema: bool,
+    /// 情绪判断中短期分数（EMA）的权重，其余为长期滑动平均 (0.0 - 1.0)
+    #[serde(default = "default_short_term_weight")]
+    pub short_term_weight: f32,
+    /// 是否平滑专注分数（关闭后直接用原始分数判断专注，宠物反应更快，用于演示和测试）
+    #[serde(default = "default_smoothing_enabled")]
+    pub smoothing_enabled: bool,
+    /// 专注评分档位
+    #[serde(default)]
+    pub profile: FocusProfile,
+    /// 系统锁屏时视为离开并暂停专注累计
+    #[serde(default)]
+    pub pause_when_locked: bool,
+    /// 检测到有效人脸时的最低专注分数 (0.0 - 1.0)
+    #[serde(default = "default_min_present_score")]
+    pub min_present_score: f32,
+    /// 专注热度每分钟升高的量（满分专注时）
+    #[serde(default = "default_heat_rise_per_minute")]
+    pub heat_rise_per_minute: f32,
+    /// 专注热度在分心时每分钟降低的量
+    #[serde(default = "default_heat_decay_per_minute")]
+    pub heat_decay_per_minute: f32,
+    /// 多显示器：偏航角落在副屏区间内时视为看向副屏，不按转头扣分
+    #[serde(default)]
+    pub secondary_screen_enabled: bool,
+    /// 副屏偏航角区间下限（度，正值表示向右转头）
+    #[serde(default = "default_secondary_screen_yaw_min")]
+    pub secondary_screen_yaw_min: f32,
+    /// 副屏偏航角区间上限（度）
+    #[serde(default = "default_secondary_screen_yaw_max")]
+    pub secondary_screen_yaw_max: f32,
+    /// 翻滚角（歪头）死区（度），小于该角度不扣分，0 表示关闭（按线性扣分）
+    #[serde(default)]
+    pub roll_dead_zone: f32,
+    /// 理想人脸大小比例（人脸面积占画面的比例），可通过校准按实际坐姿设置
+    #[serde(default = "default_ideal_face_size")]
+    pub ideal_face_size: f32,
+    /// 已检测到人脸时，置信度降到该值以下才视为离开（出现仍需达到默认的最低置信度）。
+    /// 默认与最低置信度相同，即不启用滞后
+    #[serde(default = "default_presence_exit_confidence")]
+    pub presence_exit_confidence: f32,
+    /// 持续低头时视为看手机并提醒（默认关闭，避免误判）
+    #[serde(default)]
+    pub phone_detection_enabled: bool,
+    /// 视为低头的俯仰角（度），下方有副屏时可通过校准调高
+    #[serde(default = "default_phone_pitch_threshold")]
+    pub phone_pitch_threshold: f32,
+    /// 持续低头多久（秒）视为看手机
+    #[serde(default = "default_phone_sustain_secs")]
+    pub phone_sustain_secs: f32,
+    /// 允许外部程序推送专注状态（可作为专注来源使用）
+    #[serde(default)]
+    pub external_source_enabled: bool,
+    /// 启用被动观看估计：头部长时间几乎不动（如看视频）时轻微下调专注分数
+    #[serde(default)]
+    pub passivity_enabled: bool,
+    /// 完全被动时专注分数下调的比例
+    #[serde(default = "default_passivity_discount")]
+    pub passivity_discount: f32,
+    /// 转头宽限：适度转头（和同事讨论、看白板）在时间预算内不视为分心
+    #[serde(default)]
+    pub turn_grace_enabled: bool,
+    /// 允许转头的时间占比
+    #[serde(default = "default_turn_grace_budget")]
+    pub turn_grace_budget: f32,
+    /// 转头预算的时间窗口（秒）
+    #[serde(default = "default_turn_grace_window_secs")]
+    pub turn_grace_window_secs: u32,
+    /// 单次转头的最长宽限时间（秒，0 表示不限）
+    #[serde(default = "default_turn_grace_max_turn_secs")]
+    pub turn_grace_max_turn_secs: u32,
+    /// 宽限的最大偏航角（度），转头角度更大时照常扣分
+    #[serde(default = "default_turn_grace_max_yaw")]
+    pub turn_grace_max_yaw: f32,
 }
 
 impl Default for FocusSettings {
@@ -72,34 +274,285 @@ impl Default for FocusSettings {
             exit_threshold: 0.35,
             confirm_duration: 3.0,
             away_timeout: 5.0,
+            away_grace_delay: default_away_grace_delay(),
             ema_alpha: 0.15,
+            ema_alpha_min: default_ema_alpha_min(),
+            ema_alpha_max: default_ema_alpha_max(),
+            adaptive_ema: false,
+            short_term_weight: default_short_term_weight(),
+            smoothing_enabled: true,
+            profile: FocusProfile::default(),
+            pause_when_locked: false,
+            min_present_score: default_min_present_score(),
+            heat_rise_per_minute: default_heat_rise_per_minute(),
+            heat_decay_per_minute: default_heat_decay_per_minute(),
+            secondary_screen_enabled: false,
+            secondary_screen_yaw_min: default_secondary_screen_yaw_min(),
+            secondary_screen_yaw_max: default_secondary_screen_yaw_max(),
+            roll_dead_zone: 0.0,
+            ideal_face_size: default_ideal_face_size(),
+            presence_exit_confidence: default_presence_exit_confidence(),
+            phone_detection_enabled: false,
+            phone_pitch_threshold: default_phone_pitch_threshold(),
+            phone_sustain_secs: default_phone_sustain_secs(),
+            external_source_enabled: false,
+            passivity_enabled: false,
+            passivity_discount: default_passivity_discount(),
+            turn_grace_enabled: false,
+            turn_grace_budget: default_turn_grace_budget(),
+            turn_grace_window_secs: default_turn_grace_window_secs(),
+            turn_grace_max_turn_secs: default_turn_grace_max_turn_secs(),
+            turn_grace_max_yaw: default_turn_grace_max_yaw(),
         }
     }
 }
 
+impl FocusSettings {
+    /// 当前档位的专注度计算器配置（叠加用户设置的分数下限、歪头死区和副屏区间）
+    pub fn calculator_config(&self) -> FocusCalculatorConfig {
+        FocusCalculatorConfig {
+            min_present_score: self.min_present_score,
+            roll_dead_zone: self.roll_dead_zone,
+            ideal_face_size: self.ideal_face_size,
+            presence_exit_confidence: self.presence_exit_confidence,
+            secondary_screen: self.secondary_screen_zone(),
+            ..self.profile.preset().map(|p| p.calculator).unwrap_or_default()
+        }
+    }
+
+    /// 副屏偏航角区间（未开启时为 None）
+    pub fn secondary_screen_zone(&self) -> Option<YawZone> {
+        self.secondary_screen_enabled.then_some(YawZone {
+            min_yaw: self.secondary_screen_yaw_min,
+            max_yaw: self.secondary_screen_yaw_max,
+        })
+    }
+
+    /// 看手机检测配置
+    pub fn phone_glance_config(&self) -> PhoneGlanceConfig {
+        PhoneGlanceConfig {
+            pitch_threshold: self.phone_pitch_threshold,
+            sustain_ms: (self.phone_sustain_secs * 1000.0) as i64,
+        }
+    }
+
+    /// 专注热度配置
+    pub fn heat_config(&self) -> FocusHeatConfig {
+        FocusHeatConfig {
+            rise_per_minute: self.heat_rise_per_minute,
+            decay_per_minute: self.heat_decay_per_minute,
+            ..FocusHeatConfig::default()
+        }
+    }
+
+    /// 被动观看估计配置
+    pub fn passivity_config(&self) -> PassivityConfig {
+        PassivityConfig {
+            enabled: self.passivity_enabled,
+            discount: self.passivity_discount,
+        }
+    }
+
+    /// 转头宽限配置
+    pub fn turn_grace_config(&self) -> TurnGraceConfig {
+        TurnGraceConfig {
+            enabled: self.turn_grace_enabled,
+            budget: self.turn_grace_budget,
+            window_ms: self.turn_grace_window_secs as u64 * 1000,
+            max_turn_ms: self.turn_grace_max_turn_secs as u64 * 1000,
+            max_yaw: self.turn_grace_max_yaw,
+        }
+    }
+}
+
+fn default_away_grace_delay() -> f32 {
+    1.0
+}
+
+fn default_short_term_weight() -> f32 {
+    1.0
+}
+
+fn default_smoothing_enabled() -> bool {
+    true
+}
+
+fn default_min_present_score() -> f32 {
+    0.2
+}
+
+fn default_heat_rise_per_minute() -> f32 {
+    0.1
+}
+
+fn default_heat_decay_per_minute() -> f32 {
+    0.2
+}
+
+fn default_secondary_screen_yaw_min() -> f32 {
+    20.0
+}
+
+fn default_secondary_screen_yaw_max() -> f32 {
+    45.0
+}
+
+fn default_ideal_face_size() -> f32 {
+    0.15
+}
+
+fn default_presence_exit_confidence() -> f32 {
+    0.5
+}
+
+fn default_phone_pitch_threshold() -> f32 {
+    crate::state::phone::DEFAULT_PHONE_PITCH_THRESHOLD
+}
+
+fn default_phone_sustain_secs() -> f32 {
+    crate::state::phone::DEFAULT_PHONE_SUSTAIN_MS as f32 / 1000.0
+}
+
+fn default_passivity_discount() -> f32 {
+    PassivityConfig::default().discount
+}
+
+fn default_turn_grace_budget() -> f32 {
+    TurnGraceConfig::default().budget
+}
+
+fn default_turn_grace_window_secs() -> u32 {
+    (TurnGraceConfig::default().window_ms / 1000) as u32
+}
+
+fn default_turn_grace_max_turn_secs() -> u32 {
+    (TurnGraceConfig::default().max_turn_ms / 1000) as u32
+}
+
+fn default_turn_grace_max_yaw() -> f32 {
+    TurnGraceConfig::default().max_yaw
+}
+
+fn default_ema_alpha_min() -> f32 {
+    0.05
+}
+
+fn default_ema_alpha_max() -> f32 {
+    0.2
+}
+
 /// 宠物设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PetSettings {
     /// 触发兴奋状态的连续专注时间（分钟）
     pub excited_focus_minutes: f32,
+    /// 进入深度专注的连续专注时间（分钟，不设置时不判断深度专注）
+    pub deep_focus_minutes: Option<f32>,
     /// 手势互动持续时间（秒）
     pub interact_duration: f32,
     /// 启用手势识别
     pub gesture_enabled: bool,
+    /// 情绪最短停留时间（毫秒），防止情绪闪烁
+    #[serde(default = "default_min_mood_dwell_ms")]
+    pub min_mood_dwell_ms: u64,
+    /// 反应风格
+    #[serde(default)]
+    pub reaction_style: ReactionStyle,
+    /// 互动结束时按当前专注状态重新计算情绪（关闭则恢复互动前的情绪）
+    #[serde(default = "default_recompute_after_interact")]
+    pub recompute_after_interact: bool,
+    /// 性格（预设性格会覆盖反应风格、分心伤心和休息提醒设置）
+    #[serde(default)]
+    pub personality: Personality,
+    /// 分心时宠物伤心（关闭则保持待机）
+    #[serde(default = "default_sad_on_distraction")]
+    pub sad_on_distraction: bool,
+    /// 专注回落时主动提醒休息
+    #[serde(default)]
+    pub break_reminders: bool,
+    /// 30 秒内出现/离开切换达到该次数时宠物保持待机（0 关闭）
+    #[serde(default = "default_restless_toggle_threshold")]
+    pub restless_toggle_threshold: u32,
+    /// 会话结束时发送专注回顾
+    #[serde(default = "default_session_recap")]
+    pub session_recap: bool,
+    /// 兴奋或深度专注时自动隐藏宠物，离开专注时重新显示
+    #[serde(default)]
+    pub auto_hide_on_focus: bool,
+    /// 手势后该时间（毫秒）内互动优先于离开判断（0 关闭）
+    #[serde(default = "default_gesture_priority_ms")]
+    pub gesture_priority_ms: u64,
+}
+
+fn default_min_mood_dwell_ms() -> u64 {
+    1000
+}
+
+fn default_recompute_after_interact() -> bool {
+    true
+}
+
+fn default_sad_on_distraction() -> bool {
+    true
+}
+
+fn default_restless_toggle_threshold() -> u32 {
+    8
+}
+
+fn default_session_recap() -> bool {
+    true
+}
+
+fn default_gesture_priority_ms() -> u64 {
+    1000
 }
 
 impl Default for PetSettings {
     fn default() -> Self {
         Self {
             excited_focus_minutes: 25.0,
+            deep_focus_minutes: None,
             interact_duration: 3.0,
             gesture_enabled: true,
+            min_mood_dwell_ms: default_min_mood_dwell_ms(),
+            reaction_style: ReactionStyle::default(),
+            recompute_after_interact: default_recompute_after_interact(),
+            personality: Personality::default(),
+            sad_on_distraction: default_sad_on_distraction(),
+            break_reminders: false,
+            restless_toggle_threshold: default_restless_toggle_threshold(),
+            session_recap: default_session_recap(),
+            auto_hide_on_focus: false,
+            gesture_priority_ms: default_gesture_priority_ms(),
+        }
+    }
+}
+
+impl PetSettings {
+    /// 切换性格，预设性格同时覆盖对应的各项设置，`Custom` 保留当前设置
+    pub fn apply_personality(&mut self, personality: Personality) {
+        self.personality = personality;
+        if let Some(settings) = personality.settings() {
+            self.reaction_style = settings.reaction_style;
+            self.sad_on_distraction = settings.sad_on_distraction;
+            self.break_reminders = settings.break_reminders;
+        }
+    }
+
+    /// 专注里程碑
+    pub fn focus_milestones(&self) -> FocusMilestones {
+        FocusMilestones {
+            excited_minutes: self.excited_focus_minutes,
+            deep_focus_minutes: self.deep_focus_minutes,
         }
     }
 }
 
 /// 界面设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UiSettings {
     /// 宠物窗口位置 X
     pub pet_x: i32,
@@ -111,6 +564,18 @@ pub struct UiSettings {
     pub always_on_top: bool,
     /// 开机自启动
     pub auto_start: bool,
+    /// 每日目标进度提醒的档位（百分比），每跨过一档发送一次 `goal_progress` 事件
+    #[serde(default = "default_goal_progress_step")]
+    pub goal_progress_step: u32,
+    /// 界面语言（情绪、建议和通知文案）
+    #[serde(default)]
+    pub locale: Locale,
+    /// 无障碍模式：状态变化时发送直白的文字描述（`accessible_state` 事件）
+    #[serde(default)]
+    pub accessibility_mode: bool,
+    /// 无障碍模式下同时提供朗读文本
+    #[serde(default)]
+    pub spoken_descriptions: bool,
 }
 
 impl Default for UiSettings {
@@ -121,18 +586,169 @@ impl Default for UiSettings {
             pet_scale: 1.0,
             always_on_top: true,
             auto_start: false,
+            goal_progress_step: default_goal_progress_step(),
+            locale: Locale::default(),
+            accessibility_mode: false,
+            spoken_descriptions: false,
+        }
+    }
+}
+
+fn default_goal_progress_step() -> u32 {
+    crate::state::goal_progress::DEFAULT_GOAL_PROGRESS_STEP
+}
+
+/// 隐私设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacySettings {
+    /// 记录专注状态采样日志（用于改进模型，需用户同意，默认关闭）
+    pub focus_log_enabled: bool,
+    /// 单个采样日志文件的最大字节数
+    pub focus_log_max_bytes: u64,
+    /// 预览画面模糊方式（只影响预览，检测仍使用原始画面）
+    #[serde(default)]
+    pub preview_blur: PreviewBlur,
+    /// 预览模糊强度（高斯模糊的 sigma / 马赛克色块边长，像素）
+    #[serde(default = "default_preview_blur_strength")]
+    pub preview_blur_strength: f32,
+    /// 保留的会话录制数量（0 表示不录制）
+    pub max_recordings: u32,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            focus_log_enabled: false,
+            focus_log_max_bytes: 5 * 1024 * 1024,
+            preview_blur: PreviewBlur::default(),
+            preview_blur_strength: default_preview_blur_strength(),
+            max_recordings: 0,
+        }
+    }
+}
+
+fn default_preview_blur_strength() -> f32 {
+    8.0
+}
+
+/// 模型设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelSettings {
+    /// 模型资源目录（包含 `models/blazeface.onnx`），资源目录和程序目录都找不到模型时使用
+    pub dir: Option<String>,
+    /// 记录每次推理的原始输出摘要，用于排查模型输出的检测框错误
+    pub debug_raw_output: bool,
+}
+
+/// 统计设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsSettings {
+    /// 两天对比时，专注时长差超过该值（分钟）才判定为“更专注 / 更分心”
+    pub compare_min_focus_minutes: f32,
+    /// 两天对比时，专注占比差超过该值才判定为“更专注 / 更分心” (0.0 - 1.0)
+    pub compare_min_ratio_delta: f32,
+    /// 每个周期内可用的连续专注冻结次数
+    #[serde(default = "default_streak_freezes_per_period")]
+    pub streak_freezes_per_period: u32,
+    /// 冻结次数的恢复周期（天）
+    #[serde(default = "default_streak_freeze_period_days")]
+    pub streak_freeze_period_days: u32,
+    /// 统计更新（`stats_update` 事件）的推送间隔（秒）
+    #[serde(default = "default_stats_stream_interval_secs")]
+    pub stream_interval_secs: u32,
+}
+
+impl Default for StatsSettings {
+    fn default() -> Self {
+        Self {
+            compare_min_focus_minutes: 10.0,
+            compare_min_ratio_delta: 0.05,
+            streak_freezes_per_period: default_streak_freezes_per_period(),
+            streak_freeze_period_days: default_streak_freeze_period_days(),
+            stream_interval_secs: default_stats_stream_interval_secs(),
+        }
+    }
+}
+
+fn default_streak_freezes_per_period() -> u32 {
+    2
+}
+
+fn default_streak_freeze_period_days() -> u32 {
+    30
+}
+
+fn default_stats_stream_interval_secs() -> u32 {
+    5
+}
+
+impl StatsSettings {
+    /// 转换为两天对比使用的阈值
+    pub fn compare_thresholds(&self) -> CompareThresholds {
+        CompareThresholds {
+            min_focus_delta_ms: (self.compare_min_focus_minutes * 60_000.0) as i64,
+            min_ratio_delta: self.compare_min_ratio_delta,
+        }
+    }
+
+    /// 转换为冻结名额的恢复规则
+    pub fn streak_freeze_policy(&self) -> StreakFreezePolicy {
+        StreakFreezePolicy {
+            tokens_per_period: self.streak_freezes_per_period,
+            period_days: self.streak_freeze_period_days,
         }
     }
 }
 
 impl AppConfig {
     /// 从文件加载配置
+    ///
+    /// 兼容其他版本写入的配置：缺少或取值无效的字段使用默认值，未知字段被忽略，只有文件不是 JSON 时才返回错误
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
         let content = fs::read_to_string(path)?;
-        let config: Self = serde_json::from_str(&content)?;
+        let (config, report) = Self::from_json_lenient(&content)?;
+        if !report.defaulted.is_empty() {
+            tracing::warn!("Config fields defaulted: {}", report.defaulted.join(", "));
+        }
+        if !report.ignored.is_empty() {
+            tracing::warn!("Unknown config fields ignored: {}", report.ignored.join(", "));
+        }
         Ok(config)
     }
 
+    /// 宽松地解析配置 JSON，返回配置和被默认 / 忽略的字段
+    ///
+    /// 先把文件中的值逐层合并到默认配置上；合并后仍无法解析（某些字段类型不对）时，
+    /// 逐个字段应用，跳过会导致解析失败的字段
+    pub fn from_json_lenient(content: &str) -> Result<(Self, LoadReport), ConfigError> {
+        let file: serde_json::Value = serde_json::from_str(content)?;
+        let mut merged = serde_json::to_value(Self::default())?;
+        let mut report = LoadReport::default();
+        let mut overrides = Vec::new();
+        collect_overrides(&merged, &file, "", &mut overrides, &mut report);
+
+        for (path, value) in &overrides {
+            set_path(&mut merged, path, value.clone());
+        }
+        if let Ok(config) = serde_json::from_value(merged) {
+            return Ok((config, report));
+        }
+
+        let mut merged = serde_json::to_value(Self::default())?;
+        for (path, value) in overrides {
+            let previous = set_path(&mut merged, &path, value);
+            if serde_json::from_value::<Self>(merged.clone()).is_err() {
+                set_path(&mut merged, &path, previous);
+                report.defaulted.push(path);
+            }
+        }
+        let config = serde_json::from_value(merged)?;
+        Ok((config, report))
+    }
+
     /// 保存配置到文件
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
         let content = serde_json::to_string_pretty(self)?;
@@ -140,6 +756,12 @@ impl AppConfig {
         Ok(())
     }
 
+    /// 按 Schema 中定义的取值范围校验配置，并检查专注里程碑的先后顺序
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        schema::validate(self)?;
+        self.pet.focus_milestones().validate().map_err(ConfigError::ValidationError)
+    }
+
     /// 加载或创建默认配置
     pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self {
         Self::load(&path).unwrap_or_else(|_| {
@@ -151,11 +773,59 @@ impl AppConfig {
     }
 }
 
+/// 宽松加载配置的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// 缺少或取值无效、使用默认值的字段（点分路径）
+    pub defaulted: Vec<String>,
+    /// 当前版本不认识、被忽略的字段（点分路径）
+    pub ignored: Vec<String>,
+}
+
+/// 对比默认配置和文件中的值，收集需要覆盖的叶子字段 (点分路径, 值)
+fn collect_overrides(
+    defaults: &serde_json::Value,
+    file: &serde_json::Value,
+    prefix: &str,
+    overrides: &mut Vec<(String, serde_json::Value)>,
+    report: &mut LoadReport,
+) {
+    let join = |key: &str| if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+    let (Some(defaults), Some(file)) = (defaults.as_object(), file.as_object()) else {
+        return;
+    };
+
+    for (key, default_value) in defaults {
+        let path = join(key);
+        match file.get(key) {
+            Some(value) if default_value.is_object() && value.is_object() => {
+                collect_overrides(default_value, value, &path, overrides, report);
+            }
+            // 分组的类型不对时整组使用默认值
+            Some(_) if default_value.is_object() => report.defaulted.push(path),
+            Some(value) => overrides.push((path, value.clone())),
+            None => report.defaulted.push(path),
+        }
+    }
+    for key in file.keys() {
+        if !defaults.contains_key(key) {
+            report.ignored.push(join(key));
+        }
+    }
+}
+
+/// 设置点分路径处的值，返回原来的值（路径必须已存在）
+fn set_path(root: &mut serde_json::Value, path: &str, value: serde_json::Value) -> serde_json::Value {
+    let target = path.split('.').fold(root, |node, key| &mut node[key]);
+    std::mem::replace(target, value)
+}
+
 /// 配置错误
 #[derive(Debug)]
 pub enum ConfigError {
     IoError(std::io::Error),
     ParseError(serde_json::Error),
+    ValidationError(String),
 }
 
 impl From<std::io::Error> for ConfigError {
@@ -175,6 +845,7 @@ impl std::fmt::Display for ConfigError {
         match self {
             ConfigError::IoError(e) => write!(f, "IO error: {}", e),
             ConfigError::ParseError(e) => write!(f, "Parse error: {}", e),
+            ConfigError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
         }
     }
 }
@@ -199,4 +870,45 @@ mod tests {
         let parsed: AppConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.camera.fps, config.camera.fps);
     }
+
+    #[test]
+    fn test_load_config_missing_field() {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value["camera"]["fps"] = serde_json::json!(30);
+        value["focus"].as_object_mut().unwrap().remove("enter_threshold");
+        value.as_object_mut().unwrap().remove("stats");
+        // 类型不对的字段同样使用默认值，不影响其他字段
+        value["pet"]["interact_duration"] = serde_json::json!("long");
+
+        let (config, report) = AppConfig::from_json_lenient(&value.to_string()).unwrap();
+        assert_eq!(config.camera.fps, 30);
+        assert_eq!(config.focus.enter_threshold, 0.75);
+        assert_eq!(config.pet.interact_duration, PetSettings::default().interact_duration);
+        assert_eq!(report.defaulted, vec!["focus.enter_threshold", "stats", "pet.interact_duration"]);
+        assert!(report.ignored.is_empty());
+
+        // 普通反序列化同样接受缺少字段的配置
+        let parsed: AppConfig = serde_json::from_str(r#"{"camera": {"fps": 10}}"#).unwrap();
+        assert_eq!(parsed.camera.fps, 10);
+        assert_eq!(parsed.ui.pet_scale, UiSettings::default().pet_scale);
+    }
+
+    #[test]
+    fn test_load_config_extra_field() {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value["camera"]["fps"] = serde_json::json!(24);
+        value["camera"]["hdr"] = serde_json::json!(true);
+        value["cloud_sync"] = serde_json::json!({ "enabled": true });
+
+        let (config, report) = AppConfig::from_json_lenient(&value.to_string()).unwrap();
+        assert_eq!(config.camera.fps, 24);
+        assert!(report.defaulted.is_empty());
+        assert_eq!(report.ignored, vec!["camera.hdr", "cloud_sync"]);
+
+        // 不是 JSON 时才报错
+        assert!(matches!(
+            AppConfig::from_json_lenient("not json"),
+            Err(ConfigError::ParseError(_))
+        ));
+    }
 }
@@ -0,0 +1,89 @@
+//! 配置预设分享
+//!
+//! 把专注和宠物设置导出为一段紧凑的字符串（base64 编码的 JSON），方便分享给其他用户导入。
+//! 窗口位置、摄像头设备等和本机相关的设置不包含在预设中。
+
+use super::{AppConfig, ConfigError, FocusSettings, PetSettings};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+/// 预设格式版本
+const PRESET_VERSION: u32 = 1;
+
+/// 可分享的配置预设
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SharedPreset {
+    /// 预设格式版本
+    version: u32,
+    /// 专注检测设置
+    focus: FocusSettings,
+    /// 宠物设置
+    pet: PetSettings,
+}
+
+impl AppConfig {
+    /// 导出当前的专注和宠物设置为预设字符串
+    pub fn export_preset(&self) -> String {
+        let preset = SharedPreset {
+            version: PRESET_VERSION,
+            focus: self.focus.clone(),
+            pet: self.pet.clone(),
+        };
+        let json = serde_json::to_vec(&preset).expect("preset serialization cannot fail");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// 导入预设字符串，替换专注和宠物设置
+    ///
+    /// 预设无法解码、版本不支持或取值超出范围时返回错误，配置保持不变
+    pub fn import_preset(&mut self, preset: &str) -> Result<(), ConfigError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(preset.trim().trim_end_matches('='))
+            .map_err(|e| ConfigError::ValidationError(format!("Invalid preset encoding: {}", e)))?;
+        let preset: SharedPreset = serde_json::from_slice(&bytes)?;
+        if preset.version > PRESET_VERSION {
+            return Err(ConfigError::ValidationError(format!(
+                "Unsupported preset version: {}",
+                preset.version
+            )));
+        }
+
+        let mut candidate = self.clone();
+        candidate.focus = preset.focus;
+        candidate.pet = preset.pet;
+        candidate.validate()?;
+        *self = candidate;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_round_trip() {
+        let mut source = AppConfig::default();
+        source.focus.enter_threshold = 0.8;
+        source.focus.phone_detection_enabled = true;
+        source.pet.break_reminders = true;
+        source.camera.device_index = 3;
+        let preset = source.export_preset();
+        assert!(preset.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        let mut target = AppConfig::default();
+        target.import_preset(&preset).unwrap();
+        assert_eq!(target.focus.enter_threshold, 0.8);
+        assert!(target.focus.phone_detection_enabled);
+        assert!(target.pet.break_reminders);
+        // 摄像头设备不随预设导入
+        assert_eq!(target.camera.device_index, AppConfig::default().camera.device_index);
+
+        // 无法解码或取值超出范围的预设被拒绝，配置保持不变
+        assert!(target.import_preset("not a preset!").is_err());
+        assert!(target.import_preset(&URL_SAFE_NO_PAD.encode("{}")).is_err());
+        source.focus.enter_threshold = 5.0;
+        assert!(target.import_preset(&source.export_preset()).is_err());
+        assert_eq!(target.focus.enter_threshold, 0.8);
+    }
+}
@@ -0,0 +1,328 @@
+//! 配置 Schema 模块
+//!
+//! 手写的字段描述表，用于：
+//! - 生成 JSON Schema，供前端自动构建设置表单
+//! - 校验配置取值范围（与 Schema 共用同一份 min/max）
+
+use super::{AppConfig, ConfigError};
+use serde_json::{json, Map, Value};
+
+/// 字段类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// 整数
+    Integer,
+    /// 浮点数
+    Number,
+    /// 布尔值
+    Boolean,
+    /// 字符串
+    String,
+    /// 字符串枚举（可选值列表）
+    Enum(&'static [&'static str]),
+}
+
+impl FieldType {
+    /// JSON Schema 中的类型名
+    fn as_str(&self) -> &'static str {
+        match self {
+            FieldType::Integer => "integer",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::String => "string",
+            FieldType::Enum(_) => "string",
+        }
+    }
+}
+
+/// 单个配置字段的描述
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    /// 字段路径，如 `camera.fps`
+    pub path: &'static str,
+    /// 字段类型
+    pub field_type: FieldType,
+    /// 最小值（仅数值类型）
+    pub minimum: Option<f64>,
+    /// 最大值（仅数值类型）
+    pub maximum: Option<f64>,
+    /// 字段说明
+    pub description: &'static str,
+}
+
+impl FieldSpec {
+    const fn new(
+        path: &'static str,
+        field_type: FieldType,
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            path,
+            field_type,
+            minimum,
+            maximum,
+            description,
+        }
+    }
+}
+
+/// 所有配置字段的描述表
+///
+/// 新增配置字段时需要同步在此处登记
+pub const FIELD_SPECS: &[FieldSpec] = &[
+    // 摄像头设置
+    FieldSpec::new("camera.device_index", FieldType::Integer, Some(0.0), None, "摄像头设备索引"),
+    FieldSpec::new("camera.fps", FieldType::Integer, Some(1.0), Some(60.0), "目标帧率"),
+    FieldSpec::new("camera.enabled", FieldType::Boolean, None, None, "是否启用摄像头"),
+    FieldSpec::new("camera.detect_interval_min", FieldType::Integer, Some(1.0), Some(10.0), "最小检测间隔（帧）"),
+    FieldSpec::new("camera.detect_interval_max", FieldType::Integer, Some(1.0), Some(10.0), "最大检测间隔（帧）"),
+    FieldSpec::new("camera.detect_borderline_margin", FieldType::Number, Some(0.0), Some(0.5), "专注分数接近阈值的范围（该范围内每次都用最小检测间隔）"),
+    FieldSpec::new("camera.mock_seed", FieldType::Integer, Some(0.0), None, "模拟模式下检测抖动和人脸丢失的随机种子"),
+    FieldSpec::new("camera.frame_timeout_secs", FieldType::Integer, Some(0.0), Some(60.0), "超过该时间（秒）没有新帧时重启摄像头采集和检测（0 表示关闭）"),
+    FieldSpec::new("camera.preview_fps", FieldType::Integer, Some(1.0), Some(30.0), "预览帧率（与摄像头帧率无关）"),
+    FieldSpec::new("camera.low_fps_compensation", FieldType::Boolean, None, None, "实际帧率持续过低时按实际帧间隔累计专注时间"),
+    FieldSpec::new("camera.restart_after_resume", FieldType::Boolean, None, None, "系统睡眠唤醒后自动重新启动视觉检测"),
+    // 专注检测设置
+    FieldSpec::new("focus.enter_threshold", FieldType::Number, Some(0.0), Some(1.0), "进入专注状态的阈值"),
+    FieldSpec::new("focus.exit_threshold", FieldType::Number, Some(0.0), Some(1.0), "退出专注状态的阈值"),
+    FieldSpec::new("focus.confirm_duration", FieldType::Number, Some(0.0), Some(60.0), "状态确认时间（秒）"),
+    FieldSpec::new("focus.away_timeout", FieldType::Number, Some(0.5), Some(600.0), "判定离开的超时时间（秒）"),
+    FieldSpec::new("focus.away_grace_delay", FieldType::Number, Some(0.0), Some(600.0), "人脸丢失多久后进入寻找用户状态（秒）"),
+    FieldSpec::new("focus.ema_alpha", FieldType::Number, Some(0.01), Some(1.0), "EMA 平滑系数"),
+    FieldSpec::new("focus.ema_alpha_min", FieldType::Number, Some(0.01), Some(1.0), "自适应 EMA 系数下限"),
+    FieldSpec::new("focus.ema_alpha_max", FieldType::Number, Some(0.01), Some(1.0), "自适应 EMA 系数上限"),
+    FieldSpec::new("focus.adaptive_ema", FieldType::Boolean, None, None, "按检测置信度自适应调整 EMA 系数（关闭时使用固定的平滑系数）"),
+    FieldSpec::new("focus.short_term_weight", FieldType::Number, Some(0.0), Some(1.0), "情绪判断中短期分数的权重（其余为长期平均）"),
+    FieldSpec::new("focus.smoothing_enabled", FieldType::Boolean, None, None, "平滑专注分数（关闭后宠物立即反应）"),
+    FieldSpec::new("focus.min_present_score", FieldType::Number, Some(0.0), Some(1.0), "检测到人脸时的最低专注分数"),
+    FieldSpec::new("focus.heat_rise_per_minute", FieldType::Number, Some(0.0), Some(1.0), "专注热度每分钟升高的量"),
+    FieldSpec::new("focus.heat_decay_per_minute", FieldType::Number, Some(0.0), Some(1.0), "专注热度在分心时每分钟降低的量"),
+    FieldSpec::new("focus.secondary_screen_enabled", FieldType::Boolean, None, None, "偏航角落在副屏区间内时视为看向副屏"),
+    FieldSpec::new("focus.secondary_screen_yaw_min", FieldType::Number, Some(-90.0), Some(90.0), "副屏偏航角区间下限（度）"),
+    FieldSpec::new("focus.secondary_screen_yaw_max", FieldType::Number, Some(-90.0), Some(90.0), "副屏偏航角区间上限（度）"),
+    FieldSpec::new("focus.roll_dead_zone", FieldType::Number, Some(0.0), Some(45.0), "歪头死区（度），小于该角度不扣分，0 表示关闭"),
+    FieldSpec::new("focus.ideal_face_size", FieldType::Number, Some(0.01), Some(1.0), "理想人脸大小比例（相对于画面）"),
+    FieldSpec::new("focus.presence_exit_confidence", FieldType::Number, Some(0.0), Some(1.0), "已检测到人脸时视为离开的置信度阈值"),
+    FieldSpec::new("focus.phone_detection_enabled", FieldType::Boolean, None, None, "持续低头时视为看手机并提醒"),
+    FieldSpec::new("focus.phone_pitch_threshold", FieldType::Number, Some(5.0), Some(60.0), "视为低头的俯仰角（度）"),
+    FieldSpec::new("focus.phone_sustain_secs", FieldType::Number, Some(1.0), Some(120.0), "持续低头多久视为看手机（秒）"),
+    FieldSpec::new("focus.external_source_enabled", FieldType::Boolean, None, None, "允许外部程序推送专注状态"),
+    FieldSpec::new("focus.passivity_enabled", FieldType::Boolean, None, None, "启用被动观看估计（头部长时间几乎不动时轻微下调专注分数）"),
+    FieldSpec::new("focus.passivity_discount", FieldType::Number, Some(0.0), Some(1.0), "完全被动时专注分数下调的比例"),
+    FieldSpec::new("focus.turn_grace_enabled", FieldType::Boolean, None, None, "启用转头宽限（适度转头在时间预算内不视为分心）"),
+    FieldSpec::new("focus.turn_grace_budget", FieldType::Number, Some(0.0), Some(1.0), "允许转头的时间占比"),
+    FieldSpec::new("focus.turn_grace_window_secs", FieldType::Integer, Some(60.0), Some(7200.0), "转头预算的时间窗口（秒）"),
+    FieldSpec::new("focus.turn_grace_max_turn_secs", FieldType::Integer, Some(0.0), Some(3600.0), "单次转头的最长宽限时间（秒，0 表示不限）"),
+    FieldSpec::new("focus.turn_grace_max_yaw", FieldType::Number, Some(0.0), Some(90.0), "宽限的最大偏航角（度）"),
+    FieldSpec::new("focus.profile", FieldType::Enum(&["strict", "normal", "relaxed", "custom"]), None, None, "专注评分档位"),
+    FieldSpec::new("focus.pause_when_locked", FieldType::Boolean, None, None, "系统锁屏时暂停专注累计"),
+    // 宠物设置
+    FieldSpec::new("pet.excited_focus_minutes", FieldType::Number, Some(1.0), Some(240.0), "触发兴奋状态的连续专注时间（分钟）"),
+    FieldSpec::new("pet.deep_focus_minutes", FieldType::Number, Some(1.0), Some(480.0), "进入深度专注的连续专注时间（分钟，需长于兴奋时间）"),
+    FieldSpec::new("pet.interact_duration", FieldType::Number, Some(0.5), Some(30.0), "手势互动持续时间（秒）"),
+    FieldSpec::new("pet.gesture_enabled", FieldType::Boolean, None, None, "启用手势识别"),
+    FieldSpec::new("pet.min_mood_dwell_ms", FieldType::Integer, Some(0.0), Some(10000.0), "情绪最短停留时间（毫秒）"),
+    FieldSpec::new("pet.recompute_after_interact", FieldType::Boolean, None, None, "互动结束时按当前专注状态重新计算情绪"),
+    FieldSpec::new("pet.reaction_style", FieldType::Enum(&["calm", "expressive", "stoic"]), None, None, "宠物反应风格"),
+    FieldSpec::new("pet.personality", FieldType::Enum(&["companion", "accountability", "custom"]), None, None, "宠物性格"),
+    FieldSpec::new("pet.sad_on_distraction", FieldType::Boolean, None, None, "分心时宠物伤心"),
+    FieldSpec::new("pet.break_reminders", FieldType::Boolean, None, None, "专注回落时提醒休息"),
+    FieldSpec::new("pet.restless_toggle_threshold", FieldType::Integer, Some(0.0), Some(100.0), "30 秒内进出画面达到该次数时保持待机（0 关闭）"),
+    FieldSpec::new("pet.session_recap", FieldType::Boolean, None, None, "会话结束时发送专注回顾"),
+    FieldSpec::new("pet.auto_hide_on_focus", FieldType::Boolean, None, None, "兴奋或深度专注时自动隐藏宠物"),
+    FieldSpec::new("pet.gesture_priority_ms", FieldType::Integer, Some(0.0), Some(10000.0), "手势后互动优先于离开判断的时间（毫秒，0 关闭）"),
+    // 界面设置
+    FieldSpec::new("ui.pet_x", FieldType::Integer, None, None, "宠物窗口位置 X"),
+    FieldSpec::new("ui.pet_y", FieldType::Integer, None, None, "宠物窗口位置 Y"),
+    FieldSpec::new("ui.pet_scale", FieldType::Number, Some(0.25), Some(4.0), "宠物大小缩放"),
+    FieldSpec::new("ui.always_on_top", FieldType::Boolean, None, None, "是否置顶显示"),
+    FieldSpec::new("ui.auto_start", FieldType::Boolean, None, None, "开机自启动"),
+    FieldSpec::new("ui.goal_progress_step", FieldType::Integer, Some(1.0), Some(100.0), "每日目标进度提醒档位（百分比）"),
+    FieldSpec::new("ui.locale", FieldType::Enum(&["zh", "en"]), None, None, "界面语言"),
+    FieldSpec::new("ui.accessibility_mode", FieldType::Boolean, None, None, "无障碍模式（状态变化时提供文字描述）"),
+    FieldSpec::new("ui.spoken_descriptions", FieldType::Boolean, None, None, "无障碍模式下朗读状态描述"),
+    // 隐私设置
+    FieldSpec::new("privacy.focus_log_enabled", FieldType::Boolean, None, None, "记录专注状态采样日志"),
+    FieldSpec::new("privacy.focus_log_max_bytes", FieldType::Integer, Some(65536.0), Some(104857600.0), "单个采样日志文件的最大字节数"),
+    FieldSpec::new("privacy.preview_blur", FieldType::Enum(&["off", "gaussian", "pixelate"]), None, None, "预览画面模糊方式"),
+    FieldSpec::new("privacy.preview_blur_strength", FieldType::Number, Some(1.0), Some(32.0), "预览模糊强度（像素）"),
+    FieldSpec::new("privacy.max_recordings", FieldType::Integer, Some(0.0), Some(100.0), "保留的会话录制数量（0 表示不录制）"),
+    // 模型设置
+    FieldSpec::new("model.dir", FieldType::String, None, None, "模型资源目录（找不到内置模型时使用）"),
+    FieldSpec::new("model.debug_raw_output", FieldType::Boolean, None, None, "记录模型原始输出（调试用）"),
+    // 统计设置
+    FieldSpec::new("stats.compare_min_focus_minutes", FieldType::Number, Some(0.0), Some(240.0), "两天对比的专注时长差阈值（分钟）"),
+    FieldSpec::new("stats.compare_min_ratio_delta", FieldType::Number, Some(0.0), Some(1.0), "两天对比的专注占比差阈值"),
+    FieldSpec::new("stats.streak_freezes_per_period", FieldType::Integer, Some(0.0), Some(31.0), "每个周期可用的连续专注冻结次数"),
+    FieldSpec::new("stats.streak_freeze_period_days", FieldType::Integer, Some(1.0), Some(365.0), "冻结次数的恢复周期（天）"),
+    FieldSpec::new("stats.stream_interval_secs", FieldType::Integer, Some(1.0), Some(3600.0), "统计更新的推送间隔（秒）"),
+    // 缓冲区上限
+    FieldSpec::new("buffers.focus_history", FieldType::Integer, Some(1.0), Some(10_000.0), "保留的最近专注状态条数"),
+    FieldSpec::new("buffers.sparkline", FieldType::Integer, Some(1.0), Some(60.0), "迷你折线图保留的分钟数"),
+    FieldSpec::new("buffers.trend", FieldType::Integer, Some(10.0), Some(100_000.0), "趋势窗口的最大采样数"),
+    FieldSpec::new("buffers.pending_samples", FieldType::Integer, Some(100.0), Some(1_000_000.0), "等待写入的专注分数采样上限"),
+    FieldSpec::new("buffers.pending_events", FieldType::Integer, Some(100.0), Some(1_000_000.0), "等待写入的活动事件上限"),
+    FieldSpec::new("buffers.session_recording", FieldType::Integer, Some(60.0), Some(1_000_000.0), "会话录制的最大状态数"),
+    // 通知设置
+    FieldSpec::new("notifications.break_reminder", FieldType::Boolean, None, None, "休息提醒通知"),
+    FieldSpec::new("notifications.goal_progress", FieldType::Boolean, None, None, "每日目标进度通知"),
+    FieldSpec::new("notifications.goal_reached", FieldType::Boolean, None, None, "每日目标完成通知"),
+    FieldSpec::new("notifications.day_rollover", FieldType::Boolean, None, None, "新的一天通知"),
+    FieldSpec::new("notifications.phone_distraction", FieldType::Boolean, None, None, "看手机提醒通知"),
+    FieldSpec::new("notifications.challenge_complete", FieldType::Boolean, None, None, "专注挑战完成通知"),
+    FieldSpec::new("notifications.quiet_hours_enabled", FieldType::Boolean, None, None, "启用免打扰时段"),
+    FieldSpec::new("notifications.quiet_start_hour", FieldType::Integer, Some(0.0), Some(23.0), "免打扰开始时间（时）"),
+    FieldSpec::new("notifications.quiet_end_hour", FieldType::Integer, Some(0.0), Some(23.0), "免打扰结束时间（时，跨过午夜时小于开始时间）"),
+    FieldSpec::new("notifications.sound", FieldType::Boolean, None, None, "通知时播放提示音"),
+];
+
+/// 按路径查找 JSON 值
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |v, key| v.get(key))
+}
+
+/// 生成 `AppConfig` 的 JSON Schema
+///
+/// 每个字段包含类型、取值范围、默认值和说明
+pub fn config_schema() -> Value {
+    let defaults = serde_json::to_value(AppConfig::default()).unwrap_or(Value::Null);
+    let mut root = Map::new();
+
+    for spec in FIELD_SPECS {
+        let mut field = Map::new();
+        field.insert("type".into(), json!(spec.field_type.as_str()));
+        if let FieldType::Enum(values) = spec.field_type {
+            field.insert("enum".into(), json!(values));
+        }
+        if let Some(min) = spec.minimum {
+            field.insert("minimum".into(), json!(min));
+        }
+        if let Some(max) = spec.maximum {
+            field.insert("maximum".into(), json!(max));
+        }
+        if let Some(default) = lookup(&defaults, spec.path) {
+            field.insert("default".into(), default.clone());
+        }
+        field.insert("description".into(), json!(spec.description));
+
+        // 按路径逐级创建嵌套的 object 节点
+        let mut segments: Vec<&str> = spec.path.split('.').collect();
+        let leaf = segments.pop().unwrap_or(spec.path);
+        let mut node = &mut root;
+        for segment in segments {
+            let child = node
+                .entry(segment)
+                .or_insert_with(|| json!({ "type": "object", "properties": {} }));
+            node = child["properties"].as_object_mut().expect("object node");
+        }
+        node.insert(leaf.into(), Value::Object(field));
+    }
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "AppConfig",
+        "type": "object",
+        "properties": root,
+    })
+}
+
+/// 把数值限制到字段的取值范围内，未知字段或没有范围时原样返回（用于写入校准等计算出的值）
+pub fn clamp_to_range(path: &str, value: f32) -> f32 {
+    let Some(spec) = FIELD_SPECS.iter().find(|spec| spec.path == path) else {
+        return value;
+    };
+    let value = spec.minimum.map_or(value, |min| value.max(min as f32));
+    spec.maximum.map_or(value, |max| value.min(max as f32))
+}
+
+/// 按字段描述表校验配置
+pub fn validate(config: &AppConfig) -> Result<(), ConfigError> {
+    let value = serde_json::to_value(config)?;
+
+    for spec in FIELD_SPECS {
+        let Some(field) = lookup(&value, spec.path) else {
+            continue;
+        };
+        let Some(number) = field.as_f64() else {
+            continue;
+        };
+
+        if spec.minimum.is_some_and(|min| number < min) || spec.maximum.is_some_and(|max| number > max) {
+            return Err(ConfigError::ValidationError(format!(
+                "{} out of range: {} (expected {}..={})",
+                spec.path,
+                number,
+                spec.minimum.map_or("-inf".to_string(), |v| v.to_string()),
+                spec.maximum.map_or("inf".to_string(), |v| v.to_string()),
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 收集 JSON 值中所有叶子字段的路径
+    fn leaf_paths(value: &Value, prefix: &str, out: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    leaf_paths(child, &path, out);
+                }
+            }
+            _ => out.push(prefix.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_schema_camera_fps() {
+        let schema = config_schema();
+        let fps = &schema["properties"]["camera"]["properties"]["fps"];
+
+        assert_eq!(fps["type"], "integer");
+        assert_eq!(fps["minimum"].as_f64(), Some(1.0));
+        assert_eq!(fps["default"], 15);
+    }
+
+    #[test]
+    fn test_clamp_to_range() {
+        assert_eq!(clamp_to_range("focus.phone_pitch_threshold", 75.0), 60.0);
+        assert_eq!(clamp_to_range("focus.phone_pitch_threshold", 1.0), 5.0);
+        assert_eq!(clamp_to_range("focus.phone_pitch_threshold", 30.0), 30.0);
+        assert_eq!(clamp_to_range("no.such.field", 1e6), 1e6);
+    }
+
+    #[test]
+    fn test_schema_covers_all_fields() {
+        let defaults = serde_json::to_value(AppConfig::default()).unwrap();
+        let mut paths = Vec::new();
+        leaf_paths(&defaults, "", &mut paths);
+
+        for path in paths {
+            assert!(
+                FIELD_SPECS.iter().any(|spec| spec.path == path),
+                "Missing schema entry for {}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_out_of_range() {
+        let mut config = AppConfig::default();
+        assert!(validate(&config).is_ok());
+
+        config.camera.fps = 0;
+        assert!(matches!(validate(&config), Err(ConfigError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,152 @@
+//! 界面文案
+//!
+//! 宠物情绪、教练建议、提醒通知等文案由后端统一提供，界面和系统通知使用同一份文案。
+//! 英文是基准语言：其他语言缺少的条目使用英文文案。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// 中文
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl Locale {
+    /// 从字符串解析语言，忽略地区部分（`zh-CN`、`en_US` 等）
+    pub fn parse(name: &str) -> Option<Self> {
+        let language = name.split(['-', '_']).next().unwrap_or_default();
+        match language.to_lowercase().as_str() {
+            "zh" => Some(Self::Zh),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    /// 该语言的文案表
+    fn table(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Zh => ZH,
+            Self::En => EN,
+        }
+    }
+}
+
+/// 英文文案（基准，包含所有条目）
+const EN: &[(&str, &str)] = &[
+    ("app.name", "FocusMochi"),
+    ("mood.idle", "Idle"),
+    ("mood.happy", "Happy"),
+    ("mood.excited", "Excited"),
+    ("mood.sad", "Sad"),
+    ("mood.sleepy", "Sleepy"),
+    ("mood.interact", "Playing"),
+    ("tip.short_sessions", "Short sessions"),
+    ("tip.focus_dips_long_sessions", "Focus dips in long sessions"),
+    ("tip.best_time_of_day", "Your best time of day"),
+    ("tip.streak_keep", "Keep the streak going"),
+    ("tip.streak_start", "Start a new streak"),
+    ("notification.break_reminder.title", "Time for a break"),
+    ("notification.break_reminder.body", "Your focus is slipping. Rest for a few minutes."),
+    ("notification.goal_progress.title", "Goal progress"),
+    ("notification.goal_reached.title", "Daily goal reached!"),
+    ("notification.day_rollover.title", "A new day begins"),
+    ("notification.phone_distraction.title", "Phone down?"),
+    ("notification.phone_distraction.body", "Looks like you've been on your phone. Back to it!"),
+    ("notification.challenge_complete.title", "Challenge complete!"),
+    ("a11y.focused_start", "Focused, keep it up"),
+    ("a11y.focused", "Focused for {minutes} min, doing great"),
+    ("a11y.excited", "Focused for {minutes} min, Mochi is thrilled"),
+    ("a11y.distracted", "Focus is drifting"),
+    ("a11y.distracted_sad", "Focus is drifting, Mochi looks sad"),
+    ("a11y.looking", "Mochi is looking for you"),
+    ("a11y.away", "You seem to be away, Mochi is sleeping"),
+    ("a11y.interact", "Mochi is playing with you"),
+];
+
+/// 中文文案
+const ZH: &[(&str, &str)] = &[
+    ("mood.idle", "待机"),
+    ("mood.happy", "开心"),
+    ("mood.excited", "兴奋"),
+    ("mood.sad", "伤心"),
+    ("mood.sleepy", "睡觉"),
+    ("mood.interact", "互动中"),
+    ("tip.short_sessions", "专注时间偏短"),
+    ("tip.focus_dips_long_sessions", "长时间专注后状态下滑"),
+    ("tip.best_time_of_day", "你最专注的时段"),
+    ("tip.streak_keep", "继续保持连续专注"),
+    ("tip.streak_start", "开始新的连续专注"),
+    ("notification.break_reminder.title", "休息一下吧"),
+    ("notification.break_reminder.body", "专注度开始下滑了，休息几分钟再继续"),
+    ("notification.goal_progress.title", "目标进度"),
+    ("notification.goal_reached.title", "今日目标已完成！"),
+    ("notification.day_rollover.title", "新的一天开始了"),
+    ("notification.phone_distraction.title", "放下手机吧"),
+    ("notification.phone_distraction.body", "好像在看手机哦，回来继续专注吧"),
+    ("notification.challenge_complete.title", "挑战完成！"),
+    ("a11y.focused_start", "进入专注了，继续保持"),
+    ("a11y.focused", "已专注 {minutes} 分钟，做得很好"),
+    ("a11y.excited", "已专注 {minutes} 分钟，麻糬超开心"),
+    ("a11y.distracted", "注意力有点分散"),
+    ("a11y.distracted_sad", "注意力有点分散，麻糬有点伤心"),
+    ("a11y.looking", "麻糬在找你"),
+    ("a11y.away", "你好像离开了，麻糬在睡觉"),
+    ("a11y.interact", "麻糬在和你互动"),
+];
+
+/// 查找单条文案，当前语言缺少时使用英文，都没有时返回 None
+pub fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    let find = |table: &'static [(&'static str, &'static str)]| {
+        table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+    };
+    find(locale.table()).or_else(|| find(EN))
+}
+
+/// 指定语言的全部文案（缺少的条目使用英文）
+pub fn strings(locale: Locale) -> HashMap<String, String> {
+    EN.iter()
+        .chain(locale.table())
+        .map(|(key, text)| (key.to_string(), text.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localized_strings() {
+        let zh = strings(Locale::Zh);
+        let en = strings(Locale::En);
+        assert_eq!(zh["mood.happy"], "开心");
+        assert_eq!(en["mood.happy"], "Happy");
+        assert_eq!(lookup(Locale::Zh, "mood.sleepy"), Some("睡觉"));
+        assert_eq!(lookup(Locale::En, "mood.sleepy"), Some("Sleepy"));
+
+        // 中文缺少的条目使用英文
+        assert_eq!(zh["app.name"], "FocusMochi");
+        assert_eq!(lookup(Locale::Zh, "app.name"), Some("FocusMochi"));
+        assert_eq!(lookup(Locale::Zh, "no.such.key"), None);
+
+        // 中文条目都在英文中存在，英文条目除品牌名外都有自己的中文翻译（不依赖回退）
+        let en_keys: Vec<&str> = EN.iter().map(|(key, _)| *key).collect();
+        for (key, _) in ZH {
+            assert!(en_keys.contains(key), "{} is missing from EN", key);
+        }
+        for key in en_keys {
+            let translated = ZH.iter().any(|(k, _)| *k == key);
+            assert_eq!(translated, key != "app.name", "{} has no ZH translation", key);
+        }
+        assert_eq!(zh.len(), en.len());
+
+        assert_eq!(Locale::parse("zh-CN"), Some(Locale::Zh));
+        assert_eq!(Locale::parse("en_US"), Some(Locale::En));
+        assert_eq!(Locale::parse("fr"), None);
+    }
+}
@@ -6,12 +6,17 @@
 // 模块声明
 pub mod commands;
 pub mod config;
+pub mod i18n;
 pub mod state;
 pub mod storage;
 pub mod vision;
 
 use commands::AppState;
+use config::AppConfig;
+use state::{PetStateConfig, PetStateMachine};
 use std::sync::Arc;
+use storage::paths::DataPaths;
+use storage::Database;
 use tauri::Manager;
 use tauri::window::Color;
 
@@ -30,6 +35,7 @@ pub fn run() {
 
     // 创建应用状态（使用 Arc 包装以便在异步任务中共享）
     let app_state = Arc::new(AppState::default());
+    let setup_state = Arc::clone(&app_state);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -40,25 +46,153 @@ pub fn run() {
             commands::get_pet_state,
             commands::start_vision,
             commands::stop_vision,
+            commands::get_focus_source,
+            commands::set_focus_source,
+            commands::push_focus_state,
             commands::trigger_gesture,
             commands::set_demo_mood,
             commands::get_focus_stats,
             commands::reset_stats,
             commands::get_vision_status,
+            commands::get_config_schema,
+            commands::set_goal,
+            commands::set_weekday_goal,
+            commands::get_goal_progress,
+            commands::goal_on_track,
+            commands::get_focus_by_hour_profile,
+            commands::get_lifetime_stats,
+            commands::get_mood_snapshot,
+            commands::export_focus_log,
+            commands::collect_diagnostics,
+            commands::start_manual_break,
+            commands::end_manual_break,
+            commands::get_model_info,
+            commands::cache_anchors,
+            commands::rollover_day,
+            commands::checkpoint_session,
+            commands::add_manual_session,
+            commands::set_focus_profile,
+            commands::set_personality,
+            commands::calibrate_secondary_screen,
+            commands::calibrate_face_size,
+            commands::calibrate_lower_screen,
+            commands::get_localized_strings,
+            commands::export_preset,
+            commands::import_preset,
+            commands::update_config,
+            commands::get_session_peak,
+            commands::reset_session_peak,
+            commands::benchmark_focus_calc,
+            commands::set_day_note,
+            commands::get_day_note,
+            commands::get_day_digest,
+            commands::classify_score,
+            commands::list_streaks,
+            commands::get_last_raw_output,
+            commands::seed_demo_data,
+            commands::reset_all_data,
+            commands::set_mood_lock,
+            commands::get_day_focus_buckets,
+            commands::verify_resources,
+            commands::subscribe_stats_updates,
+            commands::unsubscribe_stats_updates,
+            commands::set_focus_milestones,
+            commands::set_pet_visible,
+            commands::get_model_io,
+            commands::get_buffer_stats,
+            commands::start_challenge,
+            commands::abandon_challenge,
+            commands::get_challenge,
+            commands::get_recent_challenges,
+            commands::set_smoothing_enabled,
+            commands::measure_jitter,
+            commands::start_detection_dump,
+            commands::stop_detection_dump,
+            commands::rebuild_daily_stats,
+            commands::set_notification_prefs,
+            commands::preview_notification,
+            commands::get_longest_focus_block,
+            commands::export_anonymized_report,
+            commands::set_camera_fps,
+            commands::auto_tune_camera,
+            commands::get_next_milestone,
+            commands::get_coaching_tips,
+            commands::compare_days,
+            commands::evaluate_model,
+            commands::save_focus_card,
+            commands::save_timeline_image,
+            commands::set_nms_threshold,
+            commands::get_nms_threshold,
+            commands::get_events,
+            commands::get_focus_consistency,
+            commands::get_recent_distractions,
+            commands::get_streak_freeze_status,
+            commands::use_streak_freeze,
+            commands::get_data_paths,
+            commands::get_focus_trend,
+            commands::get_focus_sparkline,
+            commands::suggest_break,
+            commands::set_temporary_away_timeout,
+            commands::list_profiles,
+            commands::get_active_profile,
+            commands::create_profile,
+            commands::switch_profile,
+            commands::rename_profile,
+            commands::delete_profile,
+            commands::list_recordings,
+            commands::get_recording,
+            commands::delete_recording,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             tracing::info!("FocusMochi setup complete");
 
+            // 打开数据库（位于应用数据目录）
+            match app.path().app_data_dir() {
+                Ok(data_dir) => {
+                    if let Err(e) = std::fs::create_dir_all(&data_dir) {
+                        tracing::warn!("Failed to create data dir: {}", e);
+                    }
+                    let paths = DataPaths::new(&data_dir);
+                    match Database::open(&paths.database_path) {
+                        Ok(db) => *setup_state.database.lock() = Some(db),
+                        Err(e) => tracing::warn!("Failed to open database: {}", e),
+                    }
+
+                    // 加载配置（不存在时写入默认配置），并据此重建状态机
+                    let config = AppConfig::load_or_default(&paths.config_path);
+                    *setup_state.pet_state_machine.lock() =
+                        PetStateMachine::new(PetStateConfig::from_app_config(&config));
+                    *setup_state.config.lock() = config;
+                    *setup_state.data_dir.lock() = Some(data_dir);
+                }
+                Err(e) => tracing::warn!("Failed to get app data dir: {}", e),
+            }
+
+            // 跨天自动结算
+            commands::spawn_day_rollover_watch(Arc::clone(&setup_state), app.handle().clone());
+
+            // 锁屏时暂停专注累计
+            commands::spawn_screen_lock_watch(Arc::clone(&setup_state), app.handle().clone());
+
+            // 系统睡眠唤醒后重置计时、按需重启视觉检测
+            commands::spawn_suspend_watch(Arc::clone(&setup_state), app.handle().clone());
+
+            // 活动事件批量写入、目标进度提醒
+            commands::spawn_event_flush(Arc::clone(&setup_state), app.handle().clone());
+
             // 获取窗口并设置透明背景
-            if let Some(window) = app.get_webview_window("pet") {
-                tracing::info!("Pet window found, configuring...");
+            match commands::pet_window(app.handle()) {
+                Ok(window) => {
+                    tracing::info!("Pet window found, configuring...");
 
-                // 设置 WebView 背景为透明
-                // Windows 上需要通过 webview 的 set_background_color 方法
-                // Color(r, g, b, a) - 设置 alpha 为 0 实现透明
-                if let Err(e) = window.set_background_color(Some(Color(0, 0, 0, 0))) {
-                    tracing::warn!("Failed to set background color: {}", e);
+                    // 设置 WebView 背景为透明
+                    // Windows 上需要通过 webview 的 set_background_color 方法
+                    // Color(r, g, b, a) - 设置 alpha 为 0 实现透明
+                    if let Err(e) = window.set_background_color(Some(Color(0, 0, 0, 0))) {
+                        tracing::warn!("Failed to set background color: {}", e);
+                    }
                 }
+                Err(e) => tracing::warn!("Skipping pet window setup: {}", e),
             }
 
             Ok(())
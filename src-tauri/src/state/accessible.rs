@@ -0,0 +1,102 @@
+//! 无障碍状态描述
+//!
+//! 宠物主要通过动画的细微差别表达状态，部分用户难以分辨。开启无障碍模式后，
+//! 根据情绪、专注等级和本次专注时长生成一句直白的描述（如“已专注 12 分钟，做得很好”），
+//! 描述变化时发送 `accessible_state` 事件；开启朗读时同时给出供语音合成使用的文本。
+//! 普通的情绪事件照常发送。
+
+use super::{FocusLevel, PetMood};
+use crate::i18n::{self, Locale};
+use serde::{Deserialize, Serialize};
+
+/// `accessible_state` 事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessibleState {
+    /// 当前情绪
+    pub mood: PetMood,
+    /// 当前专注等级
+    pub focus_level: FocusLevel,
+    /// 本次连续专注的分钟数
+    pub focus_minutes: u64,
+    /// 状态描述
+    pub description: String,
+    /// 需要朗读的文本（未开启朗读时为 None）
+    pub speech: Option<String>,
+}
+
+/// 按情绪、专注等级和本次专注分钟数生成描述
+pub fn describe(locale: Locale, mood: PetMood, level: FocusLevel, focus_minutes: u64) -> String {
+    let key = match (mood, level) {
+        (PetMood::Interact, _) => "a11y.interact",
+        (PetMood::Sleepy, FocusLevel::Away) => "a11y.away",
+        (_, FocusLevel::Away) => "a11y.looking",
+        (_, FocusLevel::Focused) if focus_minutes == 0 => "a11y.focused_start",
+        (PetMood::Excited, FocusLevel::Focused) => "a11y.excited",
+        (_, FocusLevel::Focused) => "a11y.focused",
+        (PetMood::Sad, FocusLevel::Distracted) => "a11y.distracted_sad",
+        (_, FocusLevel::Distracted) => "a11y.distracted",
+    };
+    i18n::lookup(locale, key)
+        .unwrap_or_default()
+        .replace("{minutes}", &focus_minutes.to_string())
+}
+
+/// 无障碍状态播报：只在描述变化时产生事件
+#[derive(Debug, Clone, Default)]
+pub struct AccessibleNarrator {
+    /// 上一次发送的描述
+    last_description: Option<String>,
+}
+
+impl AccessibleNarrator {
+    /// 观察当前状态，描述与上一次不同时返回新的状态事件
+    pub fn observe(
+        &mut self,
+        locale: Locale,
+        speak: bool,
+        mood: PetMood,
+        level: FocusLevel,
+        focus_minutes: u64,
+    ) -> Option<AccessibleState> {
+        let description = describe(locale, mood, level, focus_minutes);
+        if self.last_description.as_ref() == Some(&description) {
+            return None;
+        }
+        self.last_description = Some(description.clone());
+        Some(AccessibleState {
+            mood,
+            focus_level: level,
+            focus_minutes,
+            speech: speak.then(|| description.clone()),
+            description,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessible_descriptions_follow_transitions() {
+        let mut narrator = AccessibleNarrator::default();
+        let mut observe =
+            |mood, level, minutes| narrator.observe(Locale::Zh, true, mood, level, minutes).map(|s| s.description);
+
+        assert_eq!(observe(PetMood::Sleepy, FocusLevel::Away, 0).as_deref(), Some("你好像离开了，麻糬在睡觉"));
+        assert_eq!(observe(PetMood::Happy, FocusLevel::Focused, 0).as_deref(), Some("进入专注了，继续保持"));
+        // 描述不变时不重复发送，专注分钟数变化时更新
+        assert_eq!(observe(PetMood::Happy, FocusLevel::Focused, 0), None);
+        assert_eq!(observe(PetMood::Happy, FocusLevel::Focused, 12).as_deref(), Some("已专注 12 分钟，做得很好"));
+        assert_eq!(observe(PetMood::Excited, FocusLevel::Focused, 25).as_deref(), Some("已专注 25 分钟，麻糬超开心"));
+        assert_eq!(observe(PetMood::Sad, FocusLevel::Distracted, 0).as_deref(), Some("注意力有点分散，麻糬有点伤心"));
+        assert_eq!(observe(PetMood::Interact, FocusLevel::Distracted, 0).as_deref(), Some("麻糬在和你互动"));
+
+        // 英文描述；未开启朗读时没有朗读文本
+        let state = AccessibleNarrator::default()
+            .observe(Locale::En, false, PetMood::Happy, FocusLevel::Focused, 12)
+            .unwrap();
+        assert_eq!(state.description, "Focused for 12 min, doing great");
+        assert_eq!(state.speech, None);
+    }
+}
@@ -0,0 +1,117 @@
+//! 专注时自动隐藏宠物
+//!
+//! 有的用户希望深度专注时宠物不在眼前，休息时再出现。开启后，进入兴奋或深度专注时隐藏宠物窗口，
+//! 离开专注时重新显示。两次自动切换之间至少间隔 `AUTO_HIDE_MIN_INTERVAL_MS`，避免在边界附近闪烁；
+//! 用户手动显示或隐藏后，保持手动设置直到专注状态下一次变化。
+
+use super::{FocusLevel, PetMood};
+use serde::{Deserialize, Serialize};
+
+/// 两次自动显示/隐藏之间的最短间隔（毫秒）
+pub const AUTO_HIDE_MIN_INTERVAL_MS: i64 = 10_000;
+
+/// 宠物窗口操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowAction {
+    /// 隐藏窗口
+    Hide,
+    /// 显示窗口
+    Show,
+}
+
+/// 自动隐藏的决策
+#[derive(Debug, Clone, Default)]
+pub struct AutoHide {
+    /// 窗口是否被自动隐藏
+    hidden: bool,
+    /// 上一次自动操作的时间戳（毫秒）
+    last_action_ms: Option<i64>,
+    /// 手动设置时“是否应该隐藏”的判断；判断变化前不做自动操作
+    manual_override: Option<bool>,
+}
+
+impl AutoHide {
+    /// 根据当前情绪和专注状态决定是否需要显示或隐藏窗口
+    ///
+    /// 兴奋或深度专注时隐藏，离开专注时显示；关闭自动隐藏时，已自动隐藏的窗口会重新显示
+    pub fn observe(
+        &mut self,
+        now_ms: i64,
+        enabled: bool,
+        mood: PetMood,
+        focus_level: FocusLevel,
+        deep_focus: bool,
+    ) -> Option<WindowAction> {
+        let want_hidden = if !enabled {
+            false
+        } else if mood == PetMood::Excited || deep_focus {
+            true
+        } else if focus_level == FocusLevel::Focused {
+            // 专注但还没到兴奋：保持当前状态
+            self.hidden
+        } else {
+            false
+        };
+
+        match self.manual_override {
+            Some(at_override) if at_override == want_hidden => return None,
+            Some(_) => self.manual_override = None,
+            None => {}
+        }
+        if want_hidden == self.hidden {
+            return None;
+        }
+        if enabled && self
+            .last_action_ms
+            .is_some_and(|last| now_ms.saturating_sub(last) < AUTO_HIDE_MIN_INTERVAL_MS)
+        {
+            return None;
+        }
+
+        self.hidden = want_hidden;
+        self.last_action_ms = Some(now_ms);
+        Some(if want_hidden { WindowAction::Hide } else { WindowAction::Show })
+    }
+
+    /// 用户手动显示或隐藏了窗口：保持手动设置，直到专注状态下一次变化
+    pub fn set_manual(&mut self, visible: bool, mood: PetMood, deep_focus: bool) {
+        self.hidden = !visible;
+        self.manual_override = Some(mood == PetMood::Excited || deep_focus);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_hide_decisions() {
+        let mut auto_hide = AutoHide::default();
+        let focused = FocusLevel::Focused;
+
+        // 开心时不隐藏，兴奋时隐藏，兴奋期间不重复操作
+        assert_eq!(auto_hide.observe(0, true, PetMood::Happy, focused, false), None);
+        assert_eq!(auto_hide.observe(1_000, true, PetMood::Excited, focused, false), Some(WindowAction::Hide));
+        assert_eq!(auto_hide.observe(2_000, true, PetMood::Excited, focused, false), None);
+
+        // 刚隐藏就分心：等到最短间隔之后再显示
+        assert_eq!(auto_hide.observe(5_000, true, PetMood::Sad, FocusLevel::Distracted, false), None);
+        assert_eq!(
+            auto_hide.observe(11_000, true, PetMood::Sad, FocusLevel::Distracted, false),
+            Some(WindowAction::Show)
+        );
+
+        // 深度专注也隐藏；关闭后已隐藏的窗口立即显示
+        assert_eq!(auto_hide.observe(30_000, true, PetMood::Happy, focused, true), Some(WindowAction::Hide));
+        assert_eq!(auto_hide.observe(31_000, false, PetMood::Happy, focused, true), Some(WindowAction::Show));
+        assert_eq!(auto_hide.observe(50_000, false, PetMood::Excited, focused, false), None);
+
+        // 兴奋时手动显示：保持显示，专注结束后恢复自动
+        assert_eq!(auto_hide.observe(60_000, true, PetMood::Excited, focused, false), Some(WindowAction::Hide));
+        auto_hide.set_manual(true, PetMood::Excited, false);
+        assert_eq!(auto_hide.observe(80_000, true, PetMood::Excited, focused, false), None);
+        assert_eq!(auto_hide.observe(90_000, true, PetMood::Idle, FocusLevel::Away, false), None);
+        assert_eq!(auto_hide.observe(100_000, true, PetMood::Excited, focused, false), Some(WindowAction::Hide));
+    }
+}
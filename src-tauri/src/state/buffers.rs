@@ -0,0 +1,78 @@
+//! 内存缓冲区上限
+//!
+//! 诊断用的最近专注状态、迷你折线图、趋势窗口、待写入的采样和事件、会话录制都保存在内存中。
+//! 这里统一规定每个缓冲区的最大条目数，超出时丢弃最旧的条目，并估算缓冲区占用的内存。
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 各内存缓冲区的最大条目数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BufferLimits {
+    /// 最近的专注状态（诊断包使用）
+    pub focus_history: usize,
+    /// 迷你折线图的分钟数
+    pub sparkline: usize,
+    /// 趋势窗口的采样数
+    pub trend: usize,
+    /// 等待写入数据库的专注分数采样（数据库不可用时积累）
+    pub pending_samples: usize,
+    /// 等待写入数据库的活动事件（数据库不可用时积累）
+    pub pending_events: usize,
+    /// 当前会话录制的状态数（每秒一条）
+    pub session_recording: usize,
+}
+
+impl Default for BufferLimits {
+    fn default() -> Self {
+        Self {
+            focus_history: crate::storage::diagnostics::RECENT_FOCUS_STATES,
+            sparkline: super::sparkline::SPARKLINE_MINUTES,
+            trend: 1_000,
+            pending_samples: 10_000,
+            pending_events: 10_000,
+            session_recording: 4 * 3600,
+        }
+    }
+}
+
+/// 内存缓冲区的当前大小
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BufferStats {
+    /// 最近的专注状态条数
+    pub focus_history_len: usize,
+    /// 迷你折线图的分钟数
+    pub sparkline_len: usize,
+    /// 趋势窗口的采样数
+    pub trend_len: usize,
+    /// 等待写入的采样数
+    pub pending_samples_len: usize,
+    /// 等待写入的事件数
+    pub pending_events_len: usize,
+    /// 当前会话录制的状态数
+    pub session_recording_len: usize,
+    /// 估算的内存占用（字节，只计条目本身，不含事件详情等堆上数据）
+    pub est_bytes: u64,
+    /// 当前的上限
+    pub limits: BufferLimits,
+}
+
+/// `len` 个 `T` 占用的字节数
+pub fn bytes_of<T>(len: usize) -> u64 {
+    (len * std::mem::size_of::<T>()) as u64
+}
+
+/// 超出上限时丢弃队首（最旧）的条目，返回丢弃的数量
+pub fn evict_oldest<T>(buffer: &mut VecDeque<T>, limit: usize) -> usize {
+    let excess = buffer.len().saturating_sub(limit);
+    buffer.drain(..excess);
+    excess
+}
+
+/// 超出上限时丢弃开头（最旧）的条目，返回丢弃的数量
+pub fn truncate_oldest<T>(buffer: &mut Vec<T>, limit: usize) -> usize {
+    let excess = buffer.len().saturating_sub(limit);
+    buffer.drain(..excess);
+    excess
+}
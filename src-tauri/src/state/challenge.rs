@@ -0,0 +1,148 @@
+//! 专注挑战
+//!
+//! 给单次专注设定一个目标时长（例如 25 分钟），只累计专注中的时间：分心或离开时倒计时暂停，
+//! 专注累计达到目标即完成，中途放弃则失败。和番茄钟不同，挑战没有固定的休息；和每日目标不同，
+//! 挑战只针对一次专注。
+
+use super::FocusLevel;
+use serde::{Deserialize, Serialize};
+
+/// 挑战目标的最大时长（分钟）
+pub const MAX_CHALLENGE_MINUTES: u32 = 8 * 60;
+
+/// 进度事件的最小间隔（毫秒）
+pub const CHALLENGE_PROGRESS_INTERVAL_MS: i64 = 1000;
+
+/// 两次观察之间的最大计入时长（毫秒），避免检测中断后一次计入过长的时间
+const MAX_CHALLENGE_STEP_MS: i64 = 5000;
+
+/// `challenge_progress` / `challenge_complete` / `challenge_failed` 事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeProgress {
+    /// 开始时间 (Unix 时间戳，毫秒)
+    pub started_at_ms: i64,
+    /// 目标专注时长（毫秒）
+    pub target_ms: i64,
+    /// 已累计的专注时长（毫秒）
+    pub focused_ms: i64,
+    /// 剩余的专注时长（毫秒）
+    pub remaining_ms: i64,
+    /// 倒计时是否暂停（分心或离开）
+    pub paused: bool,
+}
+
+/// 一次观察的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChallengeUpdate {
+    /// 进度更新（每秒最多一次）
+    Progress(ChallengeProgress),
+    /// 达到目标
+    Complete(ChallengeProgress),
+}
+
+/// 进行中的专注挑战
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    started_at_ms: i64,
+    target_ms: i64,
+    focused_ms: i64,
+    paused: bool,
+    /// 上一次观察的时间戳
+    last_ms: i64,
+    /// 上一次发送进度的时间戳
+    last_progress_ms: Option<i64>,
+}
+
+impl Challenge {
+    /// 从 `now_ms` 开始一次目标为 `target_ms` 的挑战
+    pub fn new(now_ms: i64, target_ms: i64) -> Self {
+        Self {
+            started_at_ms: now_ms,
+            target_ms: target_ms.max(1),
+            focused_ms: 0,
+            paused: false,
+            last_ms: now_ms,
+            last_progress_ms: None,
+        }
+    }
+
+    /// 当前进度
+    pub fn progress(&self) -> ChallengeProgress {
+        ChallengeProgress {
+            started_at_ms: self.started_at_ms,
+            target_ms: self.target_ms,
+            focused_ms: self.focused_ms,
+            remaining_ms: (self.target_ms - self.focused_ms).max(0),
+            paused: self.paused,
+        }
+    }
+
+    /// 观察当前的专注等级
+    ///
+    /// 专注中的时间计入挑战，分心或离开时倒计时暂停。达到目标时返回 `Complete`，
+    /// 否则距上次进度至少一秒时返回 `Progress`
+    pub fn observe(&mut self, now_ms: i64, level: FocusLevel) -> Option<ChallengeUpdate> {
+        let elapsed = (now_ms - self.last_ms).clamp(0, MAX_CHALLENGE_STEP_MS);
+        self.last_ms = now_ms.max(self.last_ms);
+        self.paused = level != FocusLevel::Focused;
+        if !self.paused {
+            self.focused_ms = (self.focused_ms + elapsed).min(self.target_ms);
+        }
+
+        if self.focused_ms >= self.target_ms {
+            return Some(ChallengeUpdate::Complete(self.progress()));
+        }
+        if self
+            .last_progress_ms
+            .is_some_and(|last| now_ms - last < CHALLENGE_PROGRESS_INTERVAL_MS)
+        {
+            return None;
+        }
+        self.last_progress_ms = Some(now_ms);
+        Some(ChallengeUpdate::Progress(self.progress()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenge_pauses_on_distraction() {
+        let mut challenge = Challenge::new(0, 10_000);
+        let mut progress = Vec::new();
+        let mut complete = None;
+
+        // 每 200ms 一帧：专注 4 秒、分心 3 秒、离开 2 秒，然后一直专注
+        for frame in 1..=100 {
+            let now = frame * 200;
+            let level = match now {
+                0..=4000 => FocusLevel::Focused,
+                4001..=7000 => FocusLevel::Distracted,
+                7001..=9000 => FocusLevel::Away,
+                _ => FocusLevel::Focused,
+            };
+            match challenge.observe(now, level) {
+                Some(ChallengeUpdate::Progress(p)) => progress.push(p),
+                Some(ChallengeUpdate::Complete(p)) => {
+                    complete = Some((now, p));
+                    break;
+                }
+                None => {}
+            }
+        }
+
+        // 进度每秒最多一次，分心期间倒计时暂停
+        assert_eq!(progress.len(), 15);
+        assert!(progress.windows(2).all(|w| w[1].focused_ms >= w[0].focused_ms));
+        let paused: Vec<_> = progress.iter().filter(|p| p.paused).collect();
+        assert!(!paused.is_empty());
+        assert!(paused.iter().all(|p| p.focused_ms == 4000));
+
+        // 专注 4 秒、暂停 5 秒、再专注 6 秒时完成
+        let (at, done) = complete.unwrap();
+        assert_eq!(at, 15_000);
+        assert_eq!(done.focused_ms, 10_000);
+        assert_eq!(done.remaining_ms, 0);
+    }
+}
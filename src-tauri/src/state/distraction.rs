@@ -0,0 +1,107 @@
+//! 分心跟踪
+//! 根据专注等级的变化识别一次分心的开始和结束，结束时生成分心记录写入数据库
+
+use super::FocusLevel;
+use crate::storage::distractions::{DistractionEvent, DistractionTrigger};
+
+/// 短于该时长（毫秒）的分心不记录
+const MIN_DISTRACTION_MS: i64 = 2_000;
+
+/// 分心跟踪器
+///
+/// 从专注状态掉出时开始计时，重新进入专注时结束
+#[derive(Debug, Clone, Default)]
+pub struct DistractionTracker {
+    /// 当前是否处于专注
+    focused: bool,
+    /// 进行中的分心 (开始时间, 触发原因)
+    current: Option<(i64, DistractionTrigger)>,
+}
+
+impl DistractionTracker {
+    /// 观察一次状态更新，一次分心结束时返回记录
+    pub fn observe(
+        &mut self,
+        now_ms: i64,
+        level: FocusLevel,
+        face_present: bool,
+    ) -> Option<DistractionEvent> {
+        match level {
+            FocusLevel::Focused => {
+                self.focused = true;
+                self.finish(now_ms)
+            }
+            FocusLevel::Distracted | FocusLevel::Away => {
+                if self.focused {
+                    self.focused = false;
+                    let trigger = if face_present {
+                        DistractionTrigger::LowFocus
+                    } else {
+                        DistractionTrigger::FaceLost
+                    };
+                    self.current = Some((now_ms, trigger));
+                }
+                None
+            }
+        }
+    }
+
+    /// 结束进行中的分心（例如停止视觉检测时），时长过短时不记录
+    pub fn finish(&mut self, now_ms: i64) -> Option<DistractionEvent> {
+        let (start_ms, trigger) = self.current.take()?;
+        let duration_ms = now_ms - start_ms;
+        if duration_ms < MIN_DISTRACTION_MS {
+            return None;
+        }
+        Some(DistractionEvent {
+            id: 0,
+            start_ms,
+            duration_ms,
+            trigger,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distraction_episode() {
+        let mut tracker = DistractionTracker::default();
+        // 还没进入过专注时的分心不计
+        assert_eq!(tracker.observe(0, FocusLevel::Distracted, true), None);
+        assert_eq!(tracker.observe(1_000, FocusLevel::Focused, true), None);
+
+        // 从专注掉出时开始，重新专注时结束；持续分心不会重新计时
+        assert_eq!(tracker.observe(10_000, FocusLevel::Distracted, true), None);
+        assert_eq!(tracker.observe(12_000, FocusLevel::Away, false), None);
+        let event = tracker.observe(16_000, FocusLevel::Focused, true).unwrap();
+        assert_eq!(event.start_ms, 10_000);
+        assert_eq!(event.duration_ms, 6_000);
+        assert_eq!(event.trigger, DistractionTrigger::LowFocus);
+
+        // 人脸丢失引起的分心
+        tracker.observe(20_000, FocusLevel::Away, false);
+        let event = tracker.observe(30_000, FocusLevel::Focused, true).unwrap();
+        assert_eq!(event.trigger, DistractionTrigger::FaceLost);
+        assert_eq!(tracker.observe(31_000, FocusLevel::Focused, true), None);
+    }
+
+    #[test]
+    fn test_short_distraction_ignored() {
+        let mut tracker = DistractionTracker::default();
+        tracker.observe(0, FocusLevel::Focused, true);
+        tracker.observe(1_000, FocusLevel::Distracted, true);
+        assert_eq!(tracker.observe(1_000 + MIN_DISTRACTION_MS - 1, FocusLevel::Focused, true), None);
+
+        tracker.observe(5_000, FocusLevel::Distracted, true);
+        let event = tracker.observe(5_000 + MIN_DISTRACTION_MS, FocusLevel::Focused, true).unwrap();
+        assert_eq!(event.duration_ms, MIN_DISTRACTION_MS);
+
+        // 停止检测时结束进行中的分心，同样过滤过短的
+        tracker.observe(10_000, FocusLevel::Distracted, true);
+        assert_eq!(tracker.finish(10_500), None);
+        assert_eq!(tracker.finish(20_000), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! 专注疲劳检测
+//! 持续高度专注一段时间后，专注分数的滑动平均明显回落，通常说明开始疲劳，此时建议休息（仅作提示，不强制）
+
+use crate::storage::samples::FocusSample;
+use serde::{Deserialize, Serialize};
+
+/// 分析的时间范围（毫秒）
+pub const FATIGUE_LOOKBACK_MS: i64 = 2 * 60 * 60_000;
+
+/// 滑动平均的窗口长度（毫秒）
+const ROLLING_WINDOW_MS: i64 = 5 * 60_000;
+
+/// 两个采样之间超过该间隔（毫秒）视为已经离开休息过，只分析之后的采样
+const BREAK_GAP_MS: i64 = 5 * 60_000;
+
+/// 滑动平均不低于该值视为高度专注
+const HIGH_FOCUS_SCORE: f32 = 0.7;
+
+/// 高度专注至少持续该时长（毫秒）后才判断疲劳
+const SUSTAINED_FOCUS_MS: i64 = 20 * 60_000;
+
+/// 滑动平均比峰值低出该值视为明显回落
+const FATIGUE_DROP: f32 = 0.15;
+
+/// 建议的休息时长范围（分钟）
+const MIN_BREAK_MINUTES: u32 = 5;
+const MAX_BREAK_MINUTES: u32 = 20;
+
+/// 每持续高度专注多少分钟建议休息 1 分钟
+const FOCUS_MINUTES_PER_BREAK_MINUTE: i64 = 5;
+
+/// 建议休息的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakReason {
+    /// 持续高度专注后专注分数明显回落
+    FocusDeclining,
+}
+
+/// 休息建议
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakSuggestion {
+    /// 原因
+    pub reason: BreakReason,
+    /// 建议的休息时长（分钟）
+    pub recommended_minutes: u32,
+}
+
+/// 根据最近的专注分数采样（按时间升序）判断是否需要休息，专注状态良好时返回 None
+pub fn suggest_break(samples: &[FocusSample]) -> Option<BreakSuggestion> {
+    // 只看最近一次休息之后的采样
+    let start = samples
+        .windows(2)
+        .rposition(|pair| pair[1].timestamp_ms - pair[0].timestamp_ms > BREAK_GAP_MS)
+        .map_or(0, |i| i + 1);
+    let samples = &samples[start..];
+
+    let rolling = rolling_averages(samples);
+    let (peak_index, &peak) = rolling
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    let current = *rolling.last()?;
+
+    // 峰值所在的连续高度专注区间
+    let run_start = rolling[..=peak_index]
+        .iter()
+        .rposition(|&avg| avg < HIGH_FOCUS_SCORE)
+        .map_or(0, |i| i + 1);
+    let run_end = rolling[peak_index..]
+        .iter()
+        .position(|&avg| avg < HIGH_FOCUS_SCORE)
+        .map_or(rolling.len(), |i| peak_index + i);
+    if run_start >= run_end {
+        return None;
+    }
+    let sustained_ms = samples[run_end - 1].timestamp_ms - samples[run_start].timestamp_ms;
+
+    if sustained_ms < SUSTAINED_FOCUS_MS || peak - current < FATIGUE_DROP {
+        return None;
+    }
+
+    let minutes = (sustained_ms / 60_000 / FOCUS_MINUTES_PER_BREAK_MINUTE) as u32;
+    Some(BreakSuggestion {
+        reason: BreakReason::FocusDeclining,
+        recommended_minutes: minutes.clamp(MIN_BREAK_MINUTES, MAX_BREAK_MINUTES),
+    })
+}
+
+/// 每个采样处最近 `ROLLING_WINDOW_MS` 内采样分数的平均值
+fn rolling_averages(samples: &[FocusSample]) -> Vec<f32> {
+    let mut averages = Vec::with_capacity(samples.len());
+    let mut start = 0;
+    let mut sum = 0.0f64;
+    for (i, sample) in samples.iter().enumerate() {
+        sum += sample.focus_score as f64;
+        while sample.timestamp_ms - samples[start].timestamp_ms >= ROLLING_WINDOW_MS {
+            sum -= samples[start].focus_score as f64;
+            start += 1;
+        }
+        averages.push((sum / (i + 1 - start) as f64) as f32);
+    }
+    averages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每 5 秒一个采样，分数由 `score_at(分钟)` 给出
+    fn samples(minutes: i64, score_at: impl Fn(f32) -> f32) -> Vec<FocusSample> {
+        (0..minutes * 12)
+            .map(|i| FocusSample {
+                timestamp_ms: i * 5_000,
+                focus_score: score_at(i as f32 / 12.0),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_break_suggested_after_decline() {
+        // 30 分钟高度专注，之后 15 分钟逐渐下滑到 0.45
+        let declining = samples(45, |m| {
+            if m < 30.0 {
+                0.85
+            } else {
+                0.85 - (m - 30.0) / 15.0 * 0.4
+            }
+        });
+        let suggestion = suggest_break(&declining).unwrap();
+        assert_eq!(suggestion.reason, BreakReason::FocusDeclining);
+        assert!((MIN_BREAK_MINUTES..=MAX_BREAK_MINUTES).contains(&suggestion.recommended_minutes));
+        assert!(suggestion.recommended_minutes >= 6, "{:?}", suggestion);
+    }
+
+    #[test]
+    fn test_no_break_when_healthy() {
+        // 一直保持高度专注
+        assert_eq!(suggest_break(&samples(45, |_| 0.85)), None);
+
+        // 有回落，但之前没有持续足够长的高度专注
+        let short_peak = samples(20, |m| if m < 10.0 { 0.85 } else { 0.5 });
+        assert_eq!(suggest_break(&short_peak), None);
+
+        // 回落之后休息过（采样中断），从休息后重新计算
+        let mut rested = samples(45, |m| if m < 30.0 { 0.85 } else { 0.5 });
+        rested.extend(samples(10, |_| 0.8).into_iter().map(|s| FocusSample {
+            timestamp_ms: s.timestamp_ms + 60 * 60_000,
+            ..s
+        }));
+        assert!(suggest_break(&rested[..45 * 12]).is_some());
+        assert_eq!(suggest_break(&rested), None);
+
+        assert_eq!(suggest_break(&[]), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! 低帧率检测
+//!
+//! 部分摄像头驱动实际出帧远低于设置的帧率。宠物状态机默认按每帧约 66ms 累计专注时间，
+//! 帧率过低时累计的专注时间会明显偏少。这里统计最近一段时间的实际帧率，持续低于目标帧率的一半时
+//! 提醒用户摄像头跟不上，并让状态机改为按实际的帧间隔累计时间；帧率恢复到目标的 3/4 以上时解除。
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 统计实际帧率的时间窗口（毫秒），低帧率需要持续一个完整窗口才会判定
+pub const LOW_FPS_WINDOW_MS: i64 = 10_000;
+
+/// 实际帧率低于目标帧率的该比例时视为低帧率
+const LOW_FPS_RATIO: f32 = 0.5;
+
+/// 实际帧率恢复到目标帧率的该比例以上时解除低帧率
+const RECOVER_FPS_RATIO: f32 = 0.75;
+
+/// `low_fps_warning` 事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LowFpsWarning {
+    /// 最近一个窗口内的实际帧率
+    pub measured_fps: f32,
+    /// 设置的目标帧率
+    pub target_fps: u32,
+}
+
+/// 实际帧率监测
+#[derive(Debug, Clone, Default)]
+pub struct FrameRateMonitor {
+    /// 窗口内的帧时间戳（毫秒）
+    timestamps: VecDeque<i64>,
+    /// 第一帧的时间戳（窗口是否已填满）
+    first_ms: Option<i64>,
+    /// 当前是否处于低帧率
+    low: bool,
+}
+
+impl FrameRateMonitor {
+    /// 记录一帧，刚进入低帧率时返回提醒（每次进入只提醒一次）
+    pub fn observe(&mut self, timestamp_ms: i64, target_fps: u32) -> Option<LowFpsWarning> {
+        let first = *self.first_ms.get_or_insert(timestamp_ms);
+        self.timestamps.push_back(timestamp_ms);
+        while self
+            .timestamps
+            .front()
+            .is_some_and(|t| timestamp_ms - *t > LOW_FPS_WINDOW_MS)
+        {
+            self.timestamps.pop_front();
+        }
+        if timestamp_ms - first < LOW_FPS_WINDOW_MS {
+            return None;
+        }
+
+        let measured = self.measured_fps()?;
+        let target = target_fps.max(1) as f32;
+        if !self.low && measured < target * LOW_FPS_RATIO {
+            self.low = true;
+            return Some(LowFpsWarning {
+                measured_fps: measured,
+                target_fps,
+            });
+        }
+        if self.low && measured >= target * RECOVER_FPS_RATIO {
+            self.low = false;
+        }
+        None
+    }
+
+    /// 窗口内的实际帧率，帧数不足两帧时为 None
+    pub fn measured_fps(&self) -> Option<f32> {
+        let (first, last) = (*self.timestamps.front()?, *self.timestamps.back()?);
+        if self.timestamps.len() < 2 || last <= first {
+            return None;
+        }
+        Some((self.timestamps.len() - 1) as f32 * 1000.0 / (last - first) as f32)
+    }
+
+    /// 当前是否处于低帧率
+    pub fn is_low(&self) -> bool {
+        self.low
+    }
+
+    /// 清空记录（切换或停止专注来源时）
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{FocusLevel, PetStateConfig, PetStateMachine};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_low_fps_warning_and_real_time_accrual() {
+        // 目标 15fps，实际每 500ms 一帧
+        let mut monitor = FrameRateMonitor::default();
+        let warnings: Vec<(i64, LowFpsWarning)> = (0..60)
+            .filter_map(|i| monitor.observe(i * 500, 15).map(|w| (i * 500, w)))
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        let (at, warning) = &warnings[0];
+        assert_eq!(*at, LOW_FPS_WINDOW_MS);
+        assert!((warning.measured_fps - 2.0).abs() < 0.01);
+        assert!(monitor.is_low());
+
+        // 帧率恢复后解除
+        for i in 0..200 {
+            monitor.observe(30_000 + i * 66, 15);
+        }
+        assert!(!monitor.is_low());
+
+        // 同样每 500ms 一帧专注 20 秒：按实际间隔累计接近真实时长，按固定每帧 66ms 则明显偏少
+        let focused_ms = |real_time: bool| {
+            let mut machine = PetStateMachine::new(PetStateConfig::default());
+            machine.set_real_time_accrual(real_time);
+            let start = Instant::now();
+            for i in 0..=40 {
+                machine.update_at(start + Duration::from_millis(i * 500), 0.95, true);
+            }
+            assert_eq!(machine.focus_level, FocusLevel::Focused);
+            machine.total_focus_ms
+        };
+        let real = focused_ms(true);
+        assert!((15_000..=20_000).contains(&real), "real-time accrual {}", real);
+        assert!(focused_ms(false) < 3_000);
+    }
+}
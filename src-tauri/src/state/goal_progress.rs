@@ -0,0 +1,143 @@
+//! 每日目标进度提醒
+//! 累计专注时长每跨过目标的一个百分比档位（默认 10%）时产生一次进度事件，前端据此更新进度条而无需轮询。
+//! 两次检查之间跨过多个档位（例如补录了一段会话）时只产生最高档位的一个事件，跳过的档位不再补发
+
+use serde::{Deserialize, Serialize};
+
+/// 默认的进度档位（百分比）
+pub const DEFAULT_GOAL_PROGRESS_STEP: u32 = 10;
+
+/// `goal_progress` 事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalProgressEvent {
+    /// 跨过的档位（百分比，最大 100）
+    pub percent: u32,
+    /// 已完成专注时长（毫秒）
+    pub achieved_ms: i64,
+    /// 目标专注时长（毫秒）
+    pub target_ms: i64,
+}
+
+/// 目标进度档位跟踪器
+///
+/// 每天每个档位只提醒一次：分数抖动导致进度短暂回落再回升不会重复提醒，100% 只提醒一次
+#[derive(Debug, Clone)]
+pub struct GoalProgressTracker {
+    /// 档位大小（百分比）
+    step: u32,
+    /// 当前统计日
+    day: String,
+    /// 已提醒的最高档位（百分比）
+    reached: u32,
+}
+
+impl GoalProgressTracker {
+    /// 创建跟踪器，`step` 会被限制在 1-100
+    pub fn new(step: u32) -> Self {
+        Self {
+            step: step.clamp(1, 100),
+            day: String::new(),
+            reached: 0,
+        }
+    }
+
+    /// 修改档位大小（不影响已提醒的进度）
+    pub fn set_step(&mut self, step: u32) {
+        self.step = step.clamp(1, 100);
+    }
+
+    /// 观察当前进度，跨过新档位时返回事件
+    ///
+    /// 一次跨过多个档位时只提醒最高的一个；换日后重新开始
+    pub fn observe(
+        &mut self,
+        day: &str,
+        achieved_ms: i64,
+        target_ms: i64,
+    ) -> Option<GoalProgressEvent> {
+        if self.day != day {
+            self.day = day.to_string();
+            self.reached = 0;
+        }
+        if target_ms <= 0 || achieved_ms <= 0 {
+            return None;
+        }
+
+        let percent = (achieved_ms as f64 / target_ms as f64 * 100.0).min(100.0) as u32;
+        let crossed = percent / self.step * self.step;
+        // 100% 总是作为最后一档，即使不是档位大小的整数倍
+        let crossed = if percent >= 100 { 100 } else { crossed };
+        if crossed <= self.reached {
+            return None;
+        }
+
+        self.reached = crossed;
+        Some(GoalProgressEvent {
+            percent: crossed,
+            achieved_ms,
+            target_ms,
+        })
+    }
+}
+
+impl Default for GoalProgressTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_GOAL_PROGRESS_STEP)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goal_progress_increments() {
+        let mut tracker = GoalProgressTracker::default();
+        let target = 100 * 60_000;
+        let minute = 60_000;
+        let mut fired = Vec::new();
+
+        // 进度在 9% / 10% 附近抖动，只提醒一次 10%
+        for achieved in [
+            5, 9, 10, 9, 10, 11, 19, 20, 20, 19, 20, 35, 99, 100, 101, 100, 130,
+        ] {
+            if let Some(event) = tracker.observe("2026-10-16", achieved * minute, target) {
+                assert_eq!(event.target_ms, target);
+                fired.push(event.percent);
+            }
+        }
+        assert_eq!(fired, vec![10, 20, 30, 90, 100]);
+
+        // 换日后重新开始
+        let event = tracker.observe("2026-10-17", 10 * minute, target).unwrap();
+        assert_eq!(event.percent, 10);
+
+        // 档位不能整除 100 时，100% 仍然提醒一次
+        let mut tracker = GoalProgressTracker::new(30);
+        let fired: Vec<u32> = [30, 60, 95, 100, 120]
+            .iter()
+            .filter_map(|&a| tracker.observe("2026-10-16", a * minute, target))
+            .map(|e| e.percent)
+            .collect();
+        assert_eq!(fired, vec![30, 60, 90, 100]);
+    }
+
+    #[test]
+    fn test_goal_progress_jump_fires_highest_step_only() {
+        let mut tracker = GoalProgressTracker::default();
+        let target = 100 * 60_000;
+        let minute = 60_000;
+
+        assert_eq!(tracker.observe("2026-10-16", 5 * minute, target), None);
+        // 一次从 5% 跳到 45%：只提醒 40%，跳过的 10%-30% 不补发
+        let event = tracker.observe("2026-10-16", 45 * minute, target).unwrap();
+        assert_eq!(event.percent, 40);
+        assert_eq!(event.achieved_ms, 45 * minute);
+        assert_eq!(tracker.observe("2026-10-16", 45 * minute, target), None);
+
+        // 之后照常按档位提醒；直接跳过 100% 时提醒 100%
+        assert_eq!(tracker.observe("2026-10-16", 52 * minute, target).unwrap().percent, 50);
+        assert_eq!(tracker.observe("2026-10-16", 150 * minute, target).unwrap().percent, 100);
+        assert_eq!(tracker.observe("2026-10-16", 200 * minute, target), None);
+    }
+}
@@ -1,6 +1,26 @@
 //! 宠物状态管理模块
 //! 负责管理宠物的情绪状态和状态转换逻辑
 
+pub mod accessible;
+pub mod auto_hide;
+pub mod buffers;
+pub mod challenge;
+pub mod distraction;
+pub mod fatigue;
+pub mod frame_rate;
+pub mod goal_progress;
+pub mod notification;
+pub mod personality;
+pub mod phone;
 pub mod pet_state;
+pub mod reaction;
+pub mod screen_lock;
+pub mod session;
+pub mod sparkline;
+pub mod suspend;
+pub mod trend;
 
+pub use personality::{Personality, PersonalitySettings};
 pub use pet_state::*;
+pub use reaction::{ReactionModifier, ReactionStyle};
+pub use session::{SessionPeak, SessionRecap, SessionTracker};
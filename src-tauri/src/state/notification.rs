@@ -0,0 +1,196 @@
+//! 系统通知偏好
+//!
+//! 休息提醒、目标进度、看手机等事件除了驱动界面，还会生成一条 `notification` 事件供界面弹出系统通知。
+//! 用户可以选择哪些事件需要通知、设置免打扰时段和是否播放提示音。
+//! 预览通知用于检查通知权限和样式，不受开关和免打扰时段限制。
+
+use crate::i18n::{self, Locale};
+use serde::{Deserialize, Serialize};
+
+/// 通知类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// 休息提醒
+    BreakReminder,
+    /// 每日目标进度档位
+    GoalProgress,
+    /// 每日目标完成
+    GoalReached,
+    /// 新的一天
+    DayRollover,
+    /// 看手机提醒
+    PhoneDistraction,
+    /// 专注挑战完成
+    ChallengeComplete,
+}
+
+impl NotificationKind {
+    /// 从字符串解析通知类型
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            Self::BreakReminder,
+            Self::GoalProgress,
+            Self::GoalReached,
+            Self::DayRollover,
+            Self::PhoneDistraction,
+            Self::ChallengeComplete,
+        ]
+        .into_iter()
+        .find(|kind| kind.key() == name)
+    }
+
+    /// 文案键中的名称
+    fn key(&self) -> &'static str {
+        match self {
+            Self::BreakReminder => "break_reminder",
+            Self::GoalProgress => "goal_progress",
+            Self::GoalReached => "goal_reached",
+            Self::DayRollover => "day_rollover",
+            Self::PhoneDistraction => "phone_distraction",
+            Self::ChallengeComplete => "challenge_complete",
+        }
+    }
+}
+
+/// 通知偏好（配置 `notifications`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPrefs {
+    /// 休息提醒
+    pub break_reminder: bool,
+    /// 每日目标进度档位
+    pub goal_progress: bool,
+    /// 每日目标完成
+    pub goal_reached: bool,
+    /// 新的一天
+    pub day_rollover: bool,
+    /// 看手机提醒
+    pub phone_distraction: bool,
+    /// 专注挑战完成
+    pub challenge_complete: bool,
+    /// 启用免打扰时段
+    pub quiet_hours_enabled: bool,
+    /// 免打扰开始的小时 (0 - 23)
+    pub quiet_start_hour: u32,
+    /// 免打扰结束的小时 (0 - 23，不含)，小于开始时跨过午夜
+    pub quiet_end_hour: u32,
+    /// 通知时播放提示音
+    pub sound: bool,
+}
+
+impl Default for NotificationPrefs {
+    fn default() -> Self {
+        Self {
+            break_reminder: true,
+            goal_progress: true,
+            goal_reached: true,
+            day_rollover: false,
+            phone_distraction: true,
+            challenge_complete: true,
+            quiet_hours_enabled: false,
+            quiet_start_hour: 22,
+            quiet_end_hour: 8,
+            sound: true,
+        }
+    }
+}
+
+impl NotificationPrefs {
+    /// 该类型的通知是否开启
+    pub fn allows(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::BreakReminder => self.break_reminder,
+            NotificationKind::GoalProgress => self.goal_progress,
+            NotificationKind::GoalReached => self.goal_reached,
+            NotificationKind::DayRollover => self.day_rollover,
+            NotificationKind::PhoneDistraction => self.phone_distraction,
+            NotificationKind::ChallengeComplete => self.challenge_complete,
+        }
+    }
+
+    /// 指定小时 (0 - 23) 是否处于免打扰时段
+    pub fn is_quiet_at(&self, hour: u32) -> bool {
+        if !self.quiet_hours_enabled {
+            return false;
+        }
+        let (start, end) = (self.quiet_start_hour, self.quiet_end_hour);
+        if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        }
+    }
+}
+
+/// `notification` 事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    /// 通知类型
+    pub kind: NotificationKind,
+    /// 标题
+    pub title: String,
+    /// 正文（部分类型没有）
+    pub body: Option<String>,
+    /// 是否播放提示音
+    pub sound: bool,
+    /// 是否为预览通知
+    pub preview: bool,
+}
+
+impl Notification {
+    /// 按偏好生成通知，`hour` 为当前本地时间的小时
+    ///
+    /// 类型关闭或处于免打扰时段时返回 None；预览通知总是生成
+    pub fn build(prefs: &NotificationPrefs, locale: Locale, kind: NotificationKind, hour: u32, preview: bool) -> Option<Self> {
+        if !preview && (!prefs.allows(kind) || prefs.is_quiet_at(hour)) {
+            return None;
+        }
+        let text = |part: &str| i18n::lookup(locale, &format!("notification.{}.{}", kind.key(), part));
+        Some(Self {
+            kind,
+            title: text("title").unwrap_or_default().to_string(),
+            body: text("body").map(str::to_string),
+            sound: prefs.sound,
+            preview,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_hours_suppress_but_preview_bypasses() {
+        let prefs = NotificationPrefs {
+            quiet_hours_enabled: true,
+            quiet_start_hour: 22,
+            quiet_end_hour: 7,
+            ..Default::default()
+        };
+        let build = |kind, hour, preview| Notification::build(&prefs, Locale::En, kind, hour, preview);
+
+        // 免打扰时段跨过午夜
+        for hour in [22, 23, 0, 6] {
+            assert!(prefs.is_quiet_at(hour), "{}", hour);
+            assert!(build(NotificationKind::BreakReminder, hour, false).is_none());
+        }
+        for hour in [7, 12, 21] {
+            assert!(!prefs.is_quiet_at(hour), "{}", hour);
+        }
+        let reminder = build(NotificationKind::BreakReminder, 12, false).unwrap();
+        assert_eq!(reminder.title, "Time for a break");
+        assert!(reminder.body.is_some());
+        assert!(!reminder.preview);
+
+        // 关闭的类型不通知
+        assert!(build(NotificationKind::DayRollover, 12, false).is_none());
+
+        // 预览不受免打扰时段和开关限制
+        let preview = build(NotificationKind::DayRollover, 23, true).unwrap();
+        assert!(preview.preview);
+        assert_eq!(preview.title, "A new day begins");
+        assert!(build(NotificationKind::BreakReminder, 23, true).is_some());
+    }
+}
@@ -0,0 +1,113 @@
+//! 宠物性格
+//!
+//! 性格把反应风格、分心时是否伤心、是否提醒休息组合成一键切换的预设，
+//! `Custom` 保留用户分别调整的设置。
+
+use super::ReactionStyle;
+use serde::{Deserialize, Serialize};
+
+/// 宠物性格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Personality {
+    /// 陪伴：以鼓励为主，分心时不伤心，专注时照常开心庆祝
+    Companion,
+    /// 督促：更容易因分心而伤心，并在专注回落时提醒休息
+    Accountability,
+    /// 自定义
+    #[default]
+    Custom,
+}
+
+/// 性格对应的设置组合
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonalitySettings {
+    /// 反应风格
+    pub reaction_style: ReactionStyle,
+    /// 分心时宠物伤心（false 时保持待机）
+    pub sad_on_distraction: bool,
+    /// 专注回落时主动提醒休息
+    pub break_reminders: bool,
+}
+
+impl Personality {
+    /// 从字符串解析性格
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "companion" => Some(Self::Companion),
+            "accountability" => Some(Self::Accountability),
+            "custom" => Some(Self::Custom),
+            _ => None,
+        }
+    }
+
+    /// 性格对应的设置组合，`Custom` 返回 None
+    pub fn settings(&self) -> Option<PersonalitySettings> {
+        match self {
+            Self::Companion => Some(PersonalitySettings {
+                reaction_style: ReactionStyle::Calm,
+                sad_on_distraction: false,
+                break_reminders: false,
+            }),
+            // 夸张风格的滞后区间更窄，分数一回落就会进入分心
+            Self::Accountability => Some(PersonalitySettings {
+                reaction_style: ReactionStyle::Expressive,
+                sad_on_distraction: true,
+                break_reminders: true,
+            }),
+            Self::Custom => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::state::PetStateConfig;
+
+    /// 应用性格后实际生效的设置
+    fn effective(personality: Personality) -> (PetStateConfig, bool) {
+        let mut config = AppConfig::default();
+        config.pet.apply_personality(personality);
+        assert_eq!(config.pet.personality, personality);
+        (PetStateConfig::from_app_config(&config), config.pet.break_reminders)
+    }
+
+    #[test]
+    fn test_personality_effective_settings() {
+        let (companion, companion_reminders) = effective(Personality::Companion);
+        assert_eq!(companion.reaction_style, ReactionStyle::Calm);
+        assert!(!companion.sad_on_distraction);
+        assert!(!companion_reminders);
+
+        let (strict, strict_reminders) = effective(Personality::Accountability);
+        assert_eq!(strict.reaction_style, ReactionStyle::Expressive);
+        assert!(strict.sad_on_distraction);
+        assert!(strict_reminders);
+
+        // 督促性格更早退出专注
+        let (_, companion_exit) = companion
+            .reaction_style
+            .modifier()
+            .thresholds(companion.focus_enter_threshold, companion.focus_exit_threshold);
+        let (_, strict_exit) = strict
+            .reaction_style
+            .modifier()
+            .thresholds(strict.focus_enter_threshold, strict.focus_exit_threshold);
+        assert!(strict_exit > companion_exit);
+
+        // 自定义保留原有设置
+        let mut config = AppConfig::default();
+        config.pet.reaction_style = ReactionStyle::Stoic;
+        config.pet.break_reminders = true;
+        config.pet.apply_personality(Personality::Custom);
+        assert_eq!(config.pet.personality, Personality::Custom);
+        assert_eq!(config.pet.reaction_style, ReactionStyle::Stoic);
+        assert!(config.pet.sad_on_distraction);
+        assert!(config.pet.break_reminders);
+
+        assert_eq!(Personality::parse("Companion"), Some(Personality::Companion));
+        assert_eq!(Personality::parse("grumpy"), None);
+    }
+}
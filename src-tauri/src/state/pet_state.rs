@@ -1,9 +1,16 @@
 //! 宠物状态机
 //! 定义宠物的各种情绪状态和状态转换规则
 
+use super::ReactionStyle;
+use crate::config::AppConfig;
+use crate::vision::RollingFocus;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+/// 统计出现/离开切换次数的时间窗口
+const RESTLESS_WINDOW: Duration = Duration::from_secs(30);
+
 /// 宠物的情绪状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -28,6 +35,21 @@ impl Default for PetMood {
     }
 }
 
+impl PetMood {
+    /// 从名称解析情绪（不区分大小写）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "idle" => Some(Self::Idle),
+            "happy" => Some(Self::Happy),
+            "excited" => Some(Self::Excited),
+            "sad" => Some(Self::Sad),
+            "sleepy" => Some(Self::Sleepy),
+            "interact" => Some(Self::Interact),
+            _ => None,
+        }
+    }
+}
+
 /// 专注状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -60,8 +82,83 @@ pub enum GestureType {
     ThumbsUp,
 }
 
+/// 手势反应的表现方式（由互动前的情绪决定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionCue {
+    /// 伤心时被安慰，明显振作起来
+    CheerUp,
+    /// 睡觉时被叫醒
+    WakeUp,
+    /// 待机时打招呼
+    Greet,
+    /// 本来就开心，简单回应
+    Celebrate,
+}
+
+impl ReactionCue {
+    /// 根据互动前的情绪选择反应方式和基础强度（越低落的情绪反应越强烈）
+    pub fn for_mood(mood: PetMood) -> (Self, f32) {
+        match mood {
+            PetMood::Sad => (Self::CheerUp, 1.0),
+            PetMood::Sleepy => (Self::WakeUp, 0.8),
+            PetMood::Idle | PetMood::Interact => (Self::Greet, 0.6),
+            PetMood::Happy => (Self::Celebrate, 0.4),
+            PetMood::Excited => (Self::Celebrate, 0.3),
+        }
+    }
+}
+
+/// 一次手势互动的反应
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GestureReaction {
+    /// 触发的手势
+    pub gesture: GestureType,
+    /// 互动后的情绪
+    pub mood: PetMood,
+    /// 互动前的情绪
+    pub from_mood: PetMood,
+    /// 反应方式
+    pub cue: ReactionCue,
+    /// 反应强度 (0.0 - 1.0，已按反应风格修正)
+    pub intensity: f32,
+}
+
+/// 连续专注的里程碑（分钟）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FocusMilestones {
+    /// 进入兴奋状态的连续专注时间
+    pub excited_minutes: f32,
+    /// 进入深度专注的连续专注时间（None 表示不判断深度专注），需要长于 `excited_minutes`
+    pub deep_focus_minutes: Option<f32>,
+}
+
+impl FocusMilestones {
+    /// 校验取值：兴奋时间为正，深度专注时间长于兴奋时间
+    pub fn validate(&self) -> Result<(), String> {
+        if self.excited_minutes <= 0.0 {
+            return Err(format!("Excited threshold must be positive: {}", self.excited_minutes));
+        }
+        if let Some(deep) = self.deep_focus_minutes {
+            if deep <= self.excited_minutes {
+                return Err(format!(
+                    "Deep focus threshold ({}) must be longer than the excited threshold ({})",
+                    deep, self.excited_minutes
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 按实际帧间隔累计专注时间时，单次更新最多计入的时长（毫秒），避免检测中断后一次计入过长的时间
+const MAX_ACCRUAL_STEP_MS: u64 = 5_000;
+
+/// 非专注情绪的强度达到最大所需的持续时间（秒）
+const INTENSITY_HOLD_SECS: f32 = 60.0;
+
 /// 宠物状态机配置
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PetStateConfig {
     /// 进入专注状态的阈值
     pub focus_enter_threshold: f32,
@@ -71,10 +168,47 @@ pub struct PetStateConfig {
     pub focus_confirm_duration: f32,
     /// 触发兴奋状态的连续专注时间（分钟）
     pub excited_focus_minutes: f32,
+    /// 进入深度专注的连续专注时间（分钟，None 表示不判断）
+    pub deep_focus_minutes: Option<f32>,
     /// 判定离开的时间（秒）
     pub away_timeout: f32,
+    /// 人脸丢失多久后进入“寻找用户”的中性状态（秒）
+    /// 在此之后、`away_timeout` 之前宠物保持待机，而不是伤心
+    pub away_grace_delay: f32,
     /// 手势互动持续时间（秒）
     pub interact_duration: f32,
+    /// 运动唤醒后等待人脸确认的时间（秒），超时未检测到人脸则恢复睡觉
+    pub motion_wake_window: f32,
+    /// 情绪最短停留时间（毫秒），防止相邻帧之间情绪闪烁
+    /// 离开、手势等高优先级转换不受限制
+    pub min_mood_dwell_ms: u64,
+    /// 反应风格，修正阈值、停留时间和情绪强度
+    pub reaction_style: ReactionStyle,
+    /// 分心时宠物伤心（false 时保持待机）
+    pub sad_on_distraction: bool,
+    /// 两次更新之间的墙钟间隔超过该值（秒）时视为系统睡眠，不计入专注
+    pub time_jump_threshold: f32,
+    /// 判断专注等级时短期分数（EMA）的权重，其余为长期滑动平均
+    /// 1.0 只看 EMA，反应最快；降低后短暂的分数波动会被长期平均吸收
+    pub short_term_weight: f32,
+    /// 长期滑动平均的窗口长度（秒）
+    pub long_term_window: f32,
+    /// 是否平滑专注分数，关闭时直接用原始分数判断专注等级（演示、测试用）
+    pub smoothing_enabled: bool,
+    /// 互动结束时按当前专注状态重新计算情绪（false 时恢复互动前的情绪）
+    pub recompute_after_interact: bool,
+    /// EMA 平滑系数（未开启自适应或没有检测置信度时使用）
+    pub ema_alpha: f32,
+    /// 是否按检测置信度自适应调整 EMA 系数
+    pub adaptive_ema: bool,
+    /// 自适应 EMA 系数下限（检测置信度为 0 时使用）
+    pub ema_alpha_min: f32,
+    /// 自适应 EMA 系数上限（检测置信度为 1 时使用）
+    pub ema_alpha_max: f32,
+    /// 30 秒内出现/离开切换达到该次数时视为坐立不安，宠物保持待机（0 关闭）
+    pub restless_toggle_threshold: u32,
+    /// 手势后该时间（毫秒）内优先保持互动，不被离开判断覆盖（0 关闭）
+    pub gesture_priority_ms: u64,
 }
 
 impl Default for PetStateConfig {
@@ -84,8 +218,54 @@ impl Default for PetStateConfig {
             focus_exit_threshold: 0.35,
             focus_confirm_duration: 3.0,
             excited_focus_minutes: 25.0,
+            deep_focus_minutes: None,
             away_timeout: 5.0,
+            away_grace_delay: 1.0,
             interact_duration: 3.0,
+            motion_wake_window: 10.0,
+            min_mood_dwell_ms: 1000,
+            reaction_style: ReactionStyle::default(),
+            sad_on_distraction: true,
+            time_jump_threshold: 30.0,
+            short_term_weight: 1.0,
+            long_term_window: 10.0,
+            smoothing_enabled: true,
+            recompute_after_interact: true,
+            ema_alpha: 0.15,
+            adaptive_ema: false,
+            ema_alpha_min: 0.05,
+            ema_alpha_max: 0.2,
+            restless_toggle_threshold: 8,
+            gesture_priority_ms: 1000,
+        }
+    }
+}
+
+impl PetStateConfig {
+    /// 从应用配置构建状态机配置
+    pub fn from_app_config(config: &AppConfig) -> Self {
+        Self {
+            focus_enter_threshold: config.focus.enter_threshold,
+            focus_exit_threshold: config.focus.exit_threshold,
+            focus_confirm_duration: config.focus.confirm_duration,
+            excited_focus_minutes: config.pet.excited_focus_minutes,
+            deep_focus_minutes: config.pet.deep_focus_minutes,
+            away_timeout: config.focus.away_timeout,
+            away_grace_delay: config.focus.away_grace_delay,
+            interact_duration: config.pet.interact_duration,
+            min_mood_dwell_ms: config.pet.min_mood_dwell_ms,
+            reaction_style: config.pet.reaction_style,
+            sad_on_distraction: config.pet.sad_on_distraction,
+            short_term_weight: config.focus.short_term_weight,
+            smoothing_enabled: config.focus.smoothing_enabled,
+            recompute_after_interact: config.pet.recompute_after_interact,
+            ema_alpha: config.focus.ema_alpha,
+            adaptive_ema: config.focus.adaptive_ema,
+            ema_alpha_min: config.focus.ema_alpha_min,
+            ema_alpha_max: config.focus.ema_alpha_max,
+            restless_toggle_threshold: config.pet.restless_toggle_threshold,
+            gesture_priority_ms: config.pet.gesture_priority_ms,
+            ..Self::default()
         }
     }
 }
@@ -103,32 +283,94 @@ pub struct PetStateMachine {
     mood_entered_at: Instant,
     /// 专注开始时间
     focus_started_at: Option<Instant>,
+    /// 本次连续专注使用的里程碑（专注开始时确定，中途修改配置从下一次专注开始生效）
+    span_milestones: FocusMilestones,
     /// 最后一次检测到人脸的时间
     last_face_detected_at: Option<Instant>,
     /// 当前专注分数（EMA 平滑后）
     smoothed_focus_score: f32,
-    /// EMA 平滑系数
-    ema_alpha: f32,
+    /// 本次更新的人脸检测置信度（用于自适应 EMA 系数）
+    detection_confidence: Option<f32>,
+    /// 原始分数的长期滑动平均
+    long_term_focus: RollingFocus,
+    /// 短期与长期混合后的分数（用于判断专注等级）
+    blended_focus_score: f32,
+    /// 状态机创建时间（长期平均的时间基准）
+    created_at: Instant,
     /// 互动前的状态（用于互动结束后恢复）
     mood_before_interact: Option<PetMood>,
+    /// 本次互动的基础强度（由互动前的情绪决定）
+    interact_intensity: f32,
     /// 累计专注时间（毫秒）
     pub total_focus_ms: u64,
+    /// 运动唤醒时间（等待人脸确认期间有效）
+    motion_wake_at: Option<Instant>,
+    /// 情绪变化序号（每次状态转换递增）
+    change_seq: u64,
+    /// 进入当前情绪的时间（Unix 时间戳，毫秒）
+    mood_entered_at_ms: u64,
+    /// 是否处于手动休息（暂停专注累计，宠物保持待机）
+    manual_break: bool,
+    /// 人脸短暂丢失，宠物正在寻找用户
+    looking_for_user: bool,
+    /// 临时离开超时 (超时秒数, 失效时间)，到期后恢复配置中的值
+    away_timeout_override: Option<(f32, Instant)>,
+    /// 系统屏幕已锁定（视为离开，忽略人脸检测）
+    screen_locked: bool,
+    /// 上一次更新时是否检测到人脸
+    last_presence: Option<bool>,
+    /// 窗口内出现/离开切换的时间
+    presence_toggles: VecDeque<Instant>,
+    /// 出现/离开频繁切换，宠物保持待机
+    restless: bool,
+    /// 锁定的情绪（演示、共享屏幕时），锁定期间情绪不再变化，专注仍然正常累计
+    mood_lock: Option<PetMood>,
+    /// 按实际的更新间隔累计专注时间（摄像头帧率过低时），否则按每帧约 66ms 累计
+    real_time_accrual: bool,
+    /// 上一次更新的时间
+    last_update_at: Option<Instant>,
+    /// 上一次手势的时间
+    last_gesture_at: Option<Instant>,
 }
 
 impl PetStateMachine {
     /// 创建新的状态机
     pub fn new(config: PetStateConfig) -> Self {
+        let long_term_window_ms = (config.long_term_window * 1000.0) as u64;
+        let span_milestones = FocusMilestones {
+            excited_minutes: config.excited_focus_minutes,
+            deep_focus_minutes: config.deep_focus_minutes,
+        };
         Self {
             mood: PetMood::Idle,
             focus_level: FocusLevel::Away,
             config,
             mood_entered_at: Instant::now(),
             focus_started_at: None,
+            span_milestones,
             last_face_detected_at: None,
             smoothed_focus_score: 0.0,
-            ema_alpha: 0.15,
+            detection_confidence: None,
+            long_term_focus: RollingFocus::new(long_term_window_ms),
+            blended_focus_score: 0.0,
+            created_at: Instant::now(),
             mood_before_interact: None,
+            interact_intensity: 1.0,
             total_focus_ms: 0,
+            motion_wake_at: None,
+            change_seq: 0,
+            mood_entered_at_ms: Self::wall_clock_ms(),
+            manual_break: false,
+            looking_for_user: false,
+            away_timeout_override: None,
+            screen_locked: false,
+            last_presence: None,
+            presence_toggles: VecDeque::new(),
+            restless: false,
+            mood_lock: None,
+            real_time_accrual: false,
+            last_update_at: None,
+            last_gesture_at: None,
         }
     }
 
@@ -141,44 +383,133 @@ impl PetStateMachine {
     /// # Returns
     /// 如果状态发生变化，返回新的状态；否则返回 None
     pub fn update(&mut self, raw_focus_score: f32, face_detected: bool) -> Option<PetMood> {
-        let now = Instant::now();
+        self.update_at(Instant::now(), raw_focus_score, face_detected)
+    }
+
+    /// 以指定时间点更新状态（便于测试时模拟时钟）
+    pub fn update_at(&mut self, now: Instant, raw_focus_score: f32, face_detected: bool) -> Option<PetMood> {
+        self.update_with_confidence_at(now, raw_focus_score, face_detected, None)
+    }
+
+    /// 更新专注分数，并按人脸检测置信度调整本次的 EMA 系数
+    ///
+    /// 置信度越高，新分数对平滑分数的影响越大；置信度低时分数噪声大，平滑分数变化更慢
+    pub fn update_with_confidence(
+        &mut self,
+        raw_focus_score: f32,
+        face_detected: bool,
+        confidence: Option<f32>,
+    ) -> Option<PetMood> {
+        self.update_with_confidence_at(Instant::now(), raw_focus_score, face_detected, confidence)
+    }
+
+    /// 以指定时间点按检测置信度更新状态
+    pub fn update_with_confidence_at(
+        &mut self,
+        now: Instant,
+        raw_focus_score: f32,
+        face_detected: bool,
+        confidence: Option<f32>,
+    ) -> Option<PetMood> {
         let old_mood = self.mood;
+        self.detection_confidence = confidence;
+        let since_last_update = self
+            .last_update_at
+            .replace(now)
+            .map(|last| now.saturating_duration_since(last));
+
+        // 锁屏时残留的人脸检测无效：强制离开，不累计专注
+        if self.screen_locked {
+            self.force_away(now);
+            return if old_mood != self.mood { Some(self.mood) } else { None };
+        }
 
         // 更新人脸检测时间
         if face_detected {
             self.last_face_detected_at = Some(now);
+            self.motion_wake_at = None;
+            self.looking_for_user = false;
+        }
+        self.track_presence_toggle(now, face_detected);
+
+        // 手动休息期间不累计、不切换情绪
+        if self.manual_break {
+            return None;
+        }
+
+        // 刚做过手势时互动优先：手势和离开超时同时出现时不吞掉用户的互动
+        if !face_detected && self.mood == PetMood::Interact && self.gesture_has_priority(now) {
+            return None;
         }
 
         // 检查是否离开
         if let Some(last_face) = self.last_face_detected_at {
-            if now.duration_since(last_face).as_secs_f32() > self.config.away_timeout {
-                self.transition_to(PetMood::Sleepy);
+            if now.duration_since(last_face).as_secs_f32() > self.away_timeout_at(now) {
+                if !self.motion_wake_pending(now) {
+                    self.force_transition_to(PetMood::Sleepy, now);
+                }
+                self.looking_for_user = false;
                 self.focus_level = FocusLevel::Away;
                 self.focus_started_at = None;
                 return if old_mood != self.mood { Some(self.mood) } else { None };
             }
         } else {
             // 从未检测到人脸
-            self.transition_to(PetMood::Sleepy);
+            if !self.motion_wake_pending(now) {
+                self.force_transition_to(PetMood::Sleepy, now);
+            }
             self.focus_level = FocusLevel::Away;
             return if old_mood != self.mood { Some(self.mood) } else { None };
         }
 
         // 如果正在互动中，检查是否应该结束互动
         if self.mood == PetMood::Interact {
-            if now.duration_since(self.mood_entered_at).as_secs_f32() > self.config.interact_duration {
-                // 恢复互动前的状态
-                if let Some(prev_mood) = self.mood_before_interact.take() {
-                    self.mood = prev_mood;
-                    self.mood_entered_at = now;
+            let ended = now.duration_since(self.mood_entered_at).as_secs_f32() > self.config.interact_duration;
+            // 没有保存互动前的情绪时（例如演示模式直接设置为互动）无法恢复，改为重新计算
+            let restore = !self.config.recompute_after_interact && self.mood_before_interact.is_some();
+            if !ended || restore {
+                // 互动期间继续平滑分数，保证结束后的判断基于最新的专注状态
+                self.smooth_focus_score(now, raw_focus_score);
+                if ended {
+                    // 恢复互动前的状态
+                    if let Some(prev_mood) = self.mood_before_interact.take() {
+                        self.force_transition_to(prev_mood, now);
+                    }
                 }
+                return if old_mood != self.mood { Some(self.mood) } else { None };
             }
+
+            // 按当前专注状态重新计算情绪（继续执行下面的正常流程）
+            self.mood_before_interact = None;
+        }
+
+        // 频繁进出画面：保持待机，不在开心和寻找用户之间来回切换，也不累计专注
+        if self.restless {
+            if face_detected {
+                self.smooth_focus_score(now, raw_focus_score);
+            }
+            self.looking_for_user = false;
+            self.focus_level = FocusLevel::Away;
+            self.focus_started_at = None;
+            self.force_transition_to(PetMood::Idle, now);
             return if old_mood != self.mood { Some(self.mood) } else { None };
         }
 
-        // EMA 平滑专注分数
-        self.smoothed_focus_score = self.ema_alpha * raw_focus_score
-            + (1.0 - self.ema_alpha) * self.smoothed_focus_score;
+        // 人脸短暂丢失：进入“寻找用户”的中性状态，等待用户回来或超时睡觉
+        if let Some(last_face) = self.last_face_detected_at {
+            if !face_detected && now.duration_since(last_face).as_secs_f32() >= self.config.away_grace_delay {
+                if !self.looking_for_user {
+                    tracing::debug!("Face lost, looking for user");
+                    self.looking_for_user = true;
+                }
+                self.focus_level = FocusLevel::Away;
+                self.focus_started_at = None;
+                self.force_transition_to(PetMood::Idle, now);
+                return if old_mood != self.mood { Some(self.mood) } else { None };
+            }
+        }
+
+        self.smooth_focus_score(now, raw_focus_score);
 
         // 更新专注等级（带滞后）
         let new_focus_level = self.determine_focus_level();
@@ -189,6 +520,7 @@ impl PetStateMachine {
                 // 首次进入专注
                 if self.focus_level != FocusLevel::Focused {
                     self.focus_started_at = Some(now);
+                    self.span_milestones = self.focus_milestones();
                     self.focus_level = FocusLevel::Focused;
                 }
 
@@ -196,28 +528,34 @@ impl PetStateMachine {
                 if let Some(start) = self.focus_started_at {
                     let focus_duration = now.duration_since(start);
                     let excited_threshold = Duration::from_secs_f32(
-                        self.config.excited_focus_minutes * 60.0
+                        self.span_milestones.excited_minutes * 60.0
                     );
 
                     if focus_duration >= excited_threshold {
-                        self.transition_to(PetMood::Excited);
+                        self.transition_to(PetMood::Excited, now);
                     } else {
-                        self.transition_to(PetMood::Happy);
+                        self.transition_to(PetMood::Happy, now);
                     }
 
                     // 累计专注时间
-                    self.total_focus_ms += 66; // 约 15fps，每帧约 66ms
+                    self.total_focus_ms += match since_last_update {
+                        Some(elapsed) if self.real_time_accrual => {
+                            (elapsed.as_millis() as u64).min(MAX_ACCRUAL_STEP_MS)
+                        }
+                        _ => 66, // 约 15fps，每帧约 66ms
+                    };
                 }
             }
             FocusLevel::Distracted => {
                 self.focus_level = FocusLevel::Distracted;
                 self.focus_started_at = None;
-                self.transition_to(PetMood::Sad);
+                let mood = if self.config.sad_on_distraction { PetMood::Sad } else { PetMood::Idle };
+                self.transition_to(mood, now);
             }
             FocusLevel::Away => {
                 self.focus_level = FocusLevel::Away;
                 self.focus_started_at = None;
-                self.transition_to(PetMood::Sleepy);
+                self.transition_to(PetMood::Sleepy, now);
             }
         }
 
@@ -228,29 +566,279 @@ impl PetStateMachine {
         }
     }
 
+    /// 记录出现/离开切换，更新坐立不安状态
+    ///
+    /// 窗口内切换次数达到阈值时进入，降到阈值一半以下时退出
+    fn track_presence_toggle(&mut self, now: Instant, present: bool) {
+        if self.last_presence.is_some_and(|last| last != present) {
+            self.presence_toggles.push_back(now);
+        }
+        self.last_presence = Some(present);
+        while self
+            .presence_toggles
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) > RESTLESS_WINDOW)
+        {
+            self.presence_toggles.pop_front();
+        }
+
+        let threshold = self.config.restless_toggle_threshold;
+        let toggles = self.presence_toggles.len() as u32;
+        if threshold == 0 {
+            self.restless = false;
+        } else if !self.restless && toggles >= threshold {
+            tracing::info!(
+                "Restless presence: {} toggles in {}s, holding neutral mood",
+                toggles,
+                RESTLESS_WINDOW.as_secs()
+            );
+            self.restless = true;
+        } else if self.restless && toggles < (threshold / 2).max(1) {
+            tracing::info!("Presence settled");
+            self.restless = false;
+        }
+    }
+
+    /// 更新平滑后的专注分数
+    ///
+    /// 关闭平滑时直接使用原始分数（长期平均照常更新，重新开启后不需要重新积累）
+    fn smooth_focus_score(&mut self, now: Instant, raw_focus_score: f32) {
+        let elapsed_ms = now.saturating_duration_since(self.created_at).as_millis() as u64;
+        let long_term = self.long_term_focus.push(elapsed_ms, raw_focus_score);
+        if !self.config.smoothing_enabled {
+            self.smoothed_focus_score = raw_focus_score;
+            self.blended_focus_score = raw_focus_score;
+            return;
+        }
+
+        // EMA 平滑专注分数
+        let alpha = self.effective_ema_alpha();
+        self.smoothed_focus_score = alpha * raw_focus_score + (1.0 - alpha) * self.smoothed_focus_score;
+
+        // 混合长期滑动平均，兼顾响应速度和稳定性
+        let weight = self.config.short_term_weight.clamp(0.0, 1.0);
+        self.blended_focus_score = weight * self.smoothed_focus_score + (1.0 - weight) * long_term;
+    }
+
+    /// 本次更新使用的 EMA 系数
+    ///
+    /// 开启自适应且有检测置信度时在 [`ema_alpha_min`, `ema_alpha_max`] 之间按置信度线性插值，
+    /// 否则使用配置的固定系数
+    fn effective_ema_alpha(&self) -> f32 {
+        let Some(confidence) = self.detection_confidence.filter(|_| self.config.adaptive_ema) else {
+            return self.config.ema_alpha;
+        };
+        let min = self.config.ema_alpha_min.clamp(0.0, 1.0);
+        let max = self.config.ema_alpha_max.clamp(min, 1.0);
+        min + (max - min) * confidence.clamp(0.0, 1.0)
+    }
+
+    /// 处理墙钟时间跳变（系统睡眠唤醒后）
+    ///
+    /// 部分平台的 `Instant` 在睡眠期间继续计时，会把整个睡眠时段当作连续专注。
+    /// 间隔超过 `time_jump_threshold` 时视为用户离开：重置专注计时并进入睡觉，
+    /// 返回是否检测到跳变
+    pub fn handle_time_jump(&mut self, elapsed: Duration) -> bool {
+        self.handle_time_jump_at(Instant::now(), elapsed)
+    }
+
+    /// 以指定时间点处理墙钟时间跳变
+    pub fn handle_time_jump_at(&mut self, now: Instant, elapsed: Duration) -> bool {
+        if elapsed.as_secs_f32() < self.config.time_jump_threshold {
+            return false;
+        }
+
+        tracing::info!("Wall clock jumped {:?}, treating the gap as away", elapsed);
+        self.reset_after_gap(now);
+        true
+    }
+
+    /// 系统睡眠唤醒后重置所有基于时间的状态：专注计时、人脸检测时间、分数平滑，
+    /// 并进入睡觉，等待重新检测到人脸
+    pub fn reset_after_suspend(&mut self) {
+        tracing::info!("Resetting pet timers after system resume");
+        self.reset_after_gap(Instant::now());
+    }
+
+    /// 把中断的时段视为离开
+    fn reset_after_gap(&mut self, now: Instant) {
+        self.focus_level = FocusLevel::Away;
+        self.focus_started_at = None;
+        self.last_face_detected_at = None;
+        self.looking_for_user = false;
+        self.motion_wake_at = None;
+        self.mood_before_interact = None;
+        self.smoothed_focus_score = 0.0;
+        self.blended_focus_score = 0.0;
+        self.long_term_focus = RollingFocus::new((self.config.long_term_window * 1000.0) as u64);
+        self.last_update_at = None;
+        self.last_gesture_at = None;
+        self.force_transition_to(PetMood::Sleepy, now);
+    }
+
     /// 处理手势事件
-    pub fn on_gesture(&mut self, gesture: GestureType) -> PetMood {
+    pub fn on_gesture(&mut self, gesture: GestureType) -> GestureReaction {
+        self.on_gesture_at(Instant::now(), gesture)
+    }
+
+    /// 以指定时间点处理手势事件
+    ///
+    /// 反应取决于互动前的情绪：同样挥手，伤心时比开心时反应更强烈。
+    /// 互动中重复手势时沿用最初的互动前情绪
+    pub fn on_gesture_at(&mut self, now: Instant, gesture: GestureType) -> GestureReaction {
+        // 情绪锁定时不进入互动
+        if self.mood_lock.is_some() {
+            let (cue, _) = ReactionCue::for_mood(self.mood);
+            return GestureReaction {
+                gesture,
+                mood: self.mood,
+                from_mood: self.mood,
+                cue,
+                intensity: self.intensity_at(now),
+            };
+        }
+
         // 保存当前状态
         if self.mood != PetMood::Interact {
             self.mood_before_interact = Some(self.mood);
         }
+        let from_mood = self.mood_before_interact.unwrap_or(self.mood);
+        let (cue, intensity) = ReactionCue::for_mood(from_mood);
+        self.interact_intensity = intensity;
 
-        self.mood = PetMood::Interact;
-        self.mood_entered_at = Instant::now();
+        self.force_transition_to(PetMood::Interact, now);
+        // 重复手势时重新计时
+        self.mood_entered_at = now;
+        self.last_gesture_at = Some(now);
 
-        tracing::info!("Gesture detected: {:?}, entering Interact mode", gesture);
+        tracing::info!("Gesture detected: {:?} while {:?}, reacting with {:?}", gesture, from_mood, cue);
 
-        self.mood
+        GestureReaction {
+            gesture,
+            mood: self.mood,
+            from_mood,
+            cue,
+            intensity: self.intensity_at(now),
+        }
+    }
+
+    /// 处理运动唤醒事件
+    ///
+    /// 用户坐下时人脸检测需要一段时间才能稳定锁定，
+    /// 检测到明显运动时先把宠物从睡觉唤醒到待机，
+    /// 如果在 `motion_wake_window` 内没有检测到人脸则恢复睡觉
+    pub fn on_motion(&mut self) -> Option<PetMood> {
+        self.on_motion_at(Instant::now())
+    }
+
+    /// 以指定时间点处理运动唤醒事件
+    pub fn on_motion_at(&mut self, now: Instant) -> Option<PetMood> {
+        if self.mood != PetMood::Sleepy {
+            return None;
+        }
+
+        tracing::debug!("Motion detected while sleepy, waking up to Idle");
+        self.motion_wake_at = Some(now);
+        self.force_transition_to(PetMood::Idle, now);
+        Some(self.mood)
+    }
+
+    /// 运动唤醒是否仍在等待人脸确认
+    fn motion_wake_pending(&mut self, now: Instant) -> bool {
+        match self.motion_wake_at {
+            Some(woke_at) if now.duration_since(woke_at).as_secs_f32() <= self.config.motion_wake_window => true,
+            Some(_) => {
+                tracing::debug!("No face after motion wake, going back to sleep");
+                self.motion_wake_at = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// 下一个情绪里程碑（未处于专注状态时返回 None）
+    pub fn next_milestone(&self) -> Option<Milestone> {
+        self.next_milestone_at(Instant::now())
+    }
+
+    /// 指定时刻的下一个情绪里程碑
+    ///
+    /// 达到兴奋后，设置了深度专注时间时下一个里程碑为深度专注
+    pub fn next_milestone_at(&self, now: Instant) -> Option<Milestone> {
+        if self.focus_level != FocusLevel::Focused {
+            return None;
+        }
+
+        let focus_secs = now.duration_since(self.focus_started_at?).as_secs_f32();
+        let excited_secs = self.span_milestones.excited_minutes * 60.0;
+        if focus_secs < excited_secs {
+            return Some(Milestone {
+                target_mood: PetMood::Excited,
+                seconds_remaining: excited_secs - focus_secs,
+                deep_focus: false,
+            });
+        }
+
+        let deep_secs = self.span_milestones.deep_focus_minutes? * 60.0;
+        (focus_secs < deep_secs).then_some(Milestone {
+            target_mood: PetMood::Excited,
+            seconds_remaining: deep_secs - focus_secs,
+            deep_focus: true,
+        })
+    }
+
+    /// 当前情绪的强度 (0.0 - 1.0)
+    pub fn intensity(&self) -> f32 {
+        self.intensity_at(Instant::now())
+    }
+
+    /// 指定时刻的情绪强度
+    ///
+    /// 由平滑分数超出相关阈值的程度和情绪持续的时间共同决定：
+    /// - 开心/兴奋：分数高于退出阈值越多、连续专注越久越强
+    /// - 伤心：分数低于退出阈值越多、分心越久越强
+    /// - 睡觉：离开越久越强
+    pub fn intensity_at(&self, now: Instant) -> f32 {
+        let score = self.smoothed_focus_score;
+        let exit = self.config.focus_exit_threshold;
+        let held_secs = now.duration_since(self.mood_entered_at).as_secs_f32();
+
+        let intensity = match self.mood {
+            PetMood::Happy | PetMood::Excited => {
+                let depth = (score - exit) / (1.0 - exit).max(f32::EPSILON);
+                let focus_secs = self
+                    .focus_started_at
+                    .map(|start| now.duration_since(start).as_secs_f32())
+                    .unwrap_or(0.0);
+                let hold = focus_secs / (self.span_milestones.excited_minutes * 60.0).max(1.0);
+                0.6 * depth.clamp(0.0, 1.0) + 0.4 * hold.clamp(0.0, 1.0)
+            }
+            PetMood::Sad => {
+                let depth = (exit - score) / exit.max(f32::EPSILON);
+                let hold = held_secs / INTENSITY_HOLD_SECS;
+                0.6 * depth.clamp(0.0, 1.0) + 0.4 * hold.clamp(0.0, 1.0)
+            }
+            PetMood::Sleepy => held_secs / INTENSITY_HOLD_SECS,
+            PetMood::Interact => self.interact_intensity,
+            PetMood::Idle => 0.0,
+        };
+
+        self.config.reaction_style.modifier().intensity(intensity)
     }
 
     /// 判断专注等级
     fn determine_focus_level(&self) -> FocusLevel {
-        let score = self.smoothed_focus_score;
+        let score = self.blended_focus_score;
+        let (enter, exit) = self
+            .config
+            .reaction_style
+            .modifier()
+            .thresholds(self.config.focus_enter_threshold, self.config.focus_exit_threshold);
 
         match self.focus_level {
             FocusLevel::Focused => {
                 // 当前是专注状态，只有分数低于退出阈值才退出
-                if score < self.config.focus_exit_threshold {
+                if score < exit {
                     FocusLevel::Distracted
                 } else {
                     FocusLevel::Focused
@@ -258,7 +846,7 @@ impl PetStateMachine {
             }
             FocusLevel::Distracted | FocusLevel::Away => {
                 // 当前不是专注状态，只有分数高于进入阈值才进入
-                if score > self.config.focus_enter_threshold {
+                if score > enter {
                     FocusLevel::Focused
                 } else {
                     FocusLevel::Distracted
@@ -267,12 +855,283 @@ impl PetStateMachine {
         }
     }
 
-    /// 转换到新状态
-    fn transition_to(&mut self, new_mood: PetMood) {
+    /// 开始手动休息
+    ///
+    /// 用户主动离开（看书、接电话）时暂停专注累计，
+    /// 宠物保持中性的待机状态，视觉检测继续运行以便快速恢复
+    pub fn start_manual_break(&mut self) -> PetMood {
+        let now = Instant::now();
+        self.manual_break = true;
+        // 重置专注计时，休息结束后重新进入专注
+        self.focus_level = FocusLevel::Away;
+        self.focus_started_at = None;
+        self.mood_before_interact = None;
+        self.force_transition_to(PetMood::Idle, now);
+        tracing::info!("Manual break started");
+        self.mood
+    }
+
+    /// 更新系统锁屏状态，情绪变化时返回新情绪
+    ///
+    /// 锁定时立即进入离开状态；解锁后需要重新检测到人脸才恢复
+    pub fn set_screen_locked(&mut self, locked: bool) -> Option<PetMood> {
+        self.set_screen_locked_at(Instant::now(), locked)
+    }
+
+    /// 以指定时间点更新系统锁屏状态
+    pub fn set_screen_locked_at(&mut self, now: Instant, locked: bool) -> Option<PetMood> {
+        if self.screen_locked == locked {
+            return None;
+        }
+
+        let old_mood = self.mood;
+        self.screen_locked = locked;
+        if locked {
+            tracing::info!("Screen locked, treating user as away");
+            self.force_away(now);
+        } else {
+            tracing::info!("Screen unlocked, waiting for face");
+        }
+        if old_mood != self.mood { Some(self.mood) } else { None }
+    }
+
+    /// 系统屏幕是否锁定
+    pub fn is_screen_locked(&self) -> bool {
+        self.screen_locked
+    }
+
+    /// 立即进入离开状态，丢弃人脸记录和专注计时
+    fn force_away(&mut self, now: Instant) {
+        self.focus_level = FocusLevel::Away;
+        self.focus_started_at = None;
+        self.last_face_detected_at = None;
+        self.looking_for_user = false;
+        self.motion_wake_at = None;
+        self.mood_before_interact = None;
+        self.force_transition_to(PetMood::Sleepy, now);
+    }
+
+    /// 临时延长离开超时（例如在纸上写字，需要频繁低头），`duration` 后自动恢复
+    pub fn set_temporary_away_timeout(&mut self, away_timeout_secs: f32, duration: Duration) -> Instant {
+        self.set_temporary_away_timeout_at(Instant::now(), away_timeout_secs, duration)
+    }
+
+    /// 以指定时间点设置临时离开超时，返回失效时间
+    ///
+    /// 已有临时超时时不会叠加：超时秒数替换为新值，失效时间取两者中较晚的一个
+    pub fn set_temporary_away_timeout_at(&mut self, now: Instant, away_timeout_secs: f32, duration: Duration) -> Instant {
+        let mut until = now + duration;
+        if let Some((_, previous_until)) = self.away_timeout_override {
+            if previous_until > now {
+                until = until.max(previous_until);
+            }
+        }
+        self.away_timeout_override = Some((away_timeout_secs, until));
+        tracing::info!(
+            "Away timeout temporarily set to {}s for {:.0}s",
+            away_timeout_secs,
+            until.duration_since(now).as_secs_f32()
+        );
+        until
+    }
+
+    /// 指定时间点生效的离开超时（秒）
+    pub fn away_timeout_at(&self, now: Instant) -> f32 {
+        match self.away_timeout_override {
+            Some((secs, until)) if now < until => secs,
+            _ => self.config.away_timeout,
+        }
+    }
+
+    /// 结束手动休息，恢复正常的状态转换
+    pub fn end_manual_break(&mut self) {
+        self.manual_break = false;
+        tracing::info!("Manual break ended");
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> &PetStateConfig {
+        &self.config
+    }
+
+    /// 应用运行中修改的配置，专注里程碑从下一次连续专注开始生效，其余立即生效
+    pub fn apply_config(&mut self, config: PetStateConfig) {
+        if config.long_term_window != self.config.long_term_window {
+            self.long_term_focus = RollingFocus::new((config.long_term_window * 1000.0) as u64);
+        }
+        self.config = config;
+    }
+
+    /// 更新专注阈值（切换专注评分档位时使用）
+    pub fn set_focus_thresholds(&mut self, enter: f32, exit: f32) {
+        self.config.focus_enter_threshold = enter;
+        self.config.focus_exit_threshold = exit;
+    }
+
+    /// 切换反应风格
+    pub fn set_reaction_style(&mut self, style: ReactionStyle) {
+        self.config.reaction_style = style;
+    }
+
+    /// 设置分心时是否伤心
+    pub fn set_sad_on_distraction(&mut self, sad: bool) {
+        self.config.sad_on_distraction = sad;
+    }
+
+    /// 设置是否按实际的更新间隔累计专注时间（摄像头帧率过低时开启）
+    pub fn set_real_time_accrual(&mut self, enabled: bool) {
+        self.real_time_accrual = enabled;
+    }
+
+    /// 设置是否平滑专注分数
+    pub fn set_smoothing_enabled(&mut self, enabled: bool) {
+        self.config.smoothing_enabled = enabled;
+    }
+
+    /// 设置手势优先于离开判断的时间窗口（毫秒，0 关闭）
+    pub fn set_gesture_priority_ms(&mut self, ms: u64) {
+        self.config.gesture_priority_ms = ms;
+    }
+
+    /// 最近一次手势是否仍在优先窗口内
+    fn gesture_has_priority(&self, now: Instant) -> bool {
+        self.last_gesture_at.is_some_and(|at| {
+            now.saturating_duration_since(at) < Duration::from_millis(self.config.gesture_priority_ms)
+        })
+    }
+
+    /// 当前配置的专注里程碑
+    pub fn focus_milestones(&self) -> FocusMilestones {
+        FocusMilestones {
+            excited_minutes: self.config.excited_focus_minutes,
+            deep_focus_minutes: self.config.deep_focus_minutes,
+        }
+    }
+
+    /// 设置专注里程碑，从下一次连续专注开始生效（调用方负责校验）
+    pub fn set_focus_milestones(&mut self, milestones: FocusMilestones) {
+        self.config.excited_focus_minutes = milestones.excited_minutes;
+        self.config.deep_focus_minutes = milestones.deep_focus_minutes;
+    }
+
+    /// 是否处于深度专注（连续专注达到本次专注的深度专注时间）
+    pub fn is_deep_focus(&self) -> bool {
+        self.is_deep_focus_at(Instant::now())
+    }
+
+    /// 指定时刻是否处于深度专注
+    pub fn is_deep_focus_at(&self, now: Instant) -> bool {
+        match (self.focus_level, self.focus_started_at, self.span_milestones.deep_focus_minutes) {
+            (FocusLevel::Focused, Some(start), Some(minutes)) => now.duration_since(start).as_secs_f32() >= minutes * 60.0,
+            _ => false,
+        }
+    }
+
+    /// 设置坐立不安的判定次数（0 关闭）
+    pub fn set_restless_toggle_threshold(&mut self, threshold: u32) {
+        self.config.restless_toggle_threshold = threshold;
+    }
+
+    /// 是否因频繁进出画面而保持待机
+    pub fn is_restless(&self) -> bool {
+        self.restless
+    }
+
+    /// 是否处于手动休息
+    pub fn is_on_manual_break(&self) -> bool {
+        self.manual_break
+    }
+
+    /// 人脸短暂丢失，宠物是否正在寻找用户
+    pub fn is_looking_for_user(&self) -> bool {
+        self.looking_for_user
+    }
+
+    /// 锁定情绪（None 解除锁定）
+    ///
+    /// 锁定后检测、手势、休息等都不会改变情绪，专注时长照常累计；解除后下一次更新按当前专注状态恢复。
+    /// 和 [`Self::set_mood`] 不同，设置的情绪不会被之后的更新覆盖
+    pub fn set_mood_lock(&mut self, mood: Option<PetMood>) {
+        self.mood_lock = None;
+        if let Some(mood) = mood {
+            self.mood_before_interact = None;
+            self.force_transition_to(mood, Instant::now());
+        }
+        self.mood_lock = mood;
+        tracing::info!("Mood lock: {:?}", mood);
+    }
+
+    /// 当前锁定的情绪
+    pub fn mood_lock(&self) -> Option<PetMood> {
+        self.mood_lock
+    }
+
+    /// 直接设置情绪（Demo 模式使用）
+    pub fn set_mood(&mut self, mood: PetMood) {
+        if mood == PetMood::Interact {
+            self.interact_intensity = 1.0;
+        }
+        self.force_transition_to(mood, Instant::now());
+    }
+
+    /// 转换到新状态（受最短停留时间限制）
+    ///
+    /// 待机是中性的初始状态，离开待机不受限制
+    fn transition_to(&mut self, new_mood: PetMood, now: Instant) {
+        if self.mood == new_mood {
+            return;
+        }
+
+        let dwell = now.saturating_duration_since(self.mood_entered_at);
+        let min_dwell_ms = self.config.reaction_style.modifier().dwell_ms(self.config.min_mood_dwell_ms);
+        if self.mood != PetMood::Idle && dwell < Duration::from_millis(min_dwell_ms) {
+            return;
+        }
+
+        self.force_transition_to(new_mood, now);
+    }
+
+    /// 立即转换到新状态（离开、手势等高优先级转换使用），情绪锁定时不转换
+    fn force_transition_to(&mut self, new_mood: PetMood, now: Instant) {
+        if self.mood_lock.is_some() {
+            return;
+        }
         if self.mood != new_mood {
             tracing::debug!("Pet mood: {:?} -> {:?}", self.mood, new_mood);
             self.mood = new_mood;
-            self.mood_entered_at = Instant::now();
+            self.mood_entered_at = now;
+            self.mood_entered_at_ms = Self::wall_clock_ms();
+            self.change_seq += 1;
+        }
+    }
+
+    /// 当前 Unix 时间戳（毫秒）
+    fn wall_clock_ms() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    /// 获取情绪快照（供前端轮询，通过序号检测遗漏的变化）
+    pub fn mood_snapshot(&self) -> MoodSnapshot {
+        MoodSnapshot {
+            mood: self.mood,
+            change_seq: self.change_seq,
+            entered_at_ms: self.mood_entered_at_ms,
+        }
+    }
+
+    /// 本次连续专注的时长（秒），未处于专注时为 0
+    pub fn current_focus_secs(&self) -> f32 {
+        self.current_focus_secs_at(Instant::now())
+    }
+
+    /// 指定时刻本次连续专注的时长（秒）
+    pub fn current_focus_secs_at(&self, now: Instant) -> f32 {
+        match (self.focus_level, self.focus_started_at) {
+            (FocusLevel::Focused, Some(started)) => now.saturating_duration_since(started).as_secs_f32(),
+            _ => 0.0,
         }
     }
 
@@ -290,6 +1149,61 @@ impl PetStateMachine {
     pub fn reset_daily_stats(&mut self) {
         self.total_focus_ms = 0;
     }
+
+    /// 预览按 `config` 持续收到同一个分数时宠物的专注等级和情绪
+    ///
+    /// 在一个新的状态机上用模拟时钟连续更新 `CLASSIFY_SETTLE_SECS` 秒，不影响任何正在运行的状态机
+    pub fn classify_score(config: PetStateConfig, focus_score: f32, face_present: bool) -> ScoreClassification {
+        let mut machine = Self::new(config);
+        let start = Instant::now();
+        let steps = (CLASSIFY_SETTLE_SECS * 1000 / CLASSIFY_STEP_MS) as u32;
+        for step in 0..=steps {
+            let now = start + Duration::from_millis(step as u64 * CLASSIFY_STEP_MS);
+            machine.update_at(now, focus_score, face_present);
+        }
+
+        ScoreClassification {
+            focus_level: machine.focus_level,
+            would_be_mood: machine.mood,
+        }
+    }
+}
+
+/// 分数预览的模拟时长（秒），足够完成平滑和专注确认，又远短于兴奋所需的连续专注时间
+const CLASSIFY_SETTLE_SECS: u64 = 30;
+
+/// 分数预览的模拟更新间隔（毫秒）
+const CLASSIFY_STEP_MS: u64 = 100;
+
+/// 分数预览结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreClassification {
+    /// 专注等级
+    pub focus_level: FocusLevel,
+    /// 宠物会呈现的情绪
+    pub would_be_mood: PetMood,
+}
+
+/// 情绪快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoodSnapshot {
+    /// 当前情绪
+    pub mood: PetMood,
+    /// 情绪变化序号（每次状态转换递增）
+    pub change_seq: u64,
+    /// 进入当前情绪的时间（Unix 时间戳，毫秒）
+    pub entered_at_ms: u64,
+}
+
+/// 下一个情绪里程碑
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    /// 将要进入的情绪
+    pub target_mood: PetMood,
+    /// 距离进入该情绪的剩余时间（秒）
+    pub seconds_remaining: f32,
+    /// 是否为深度专注里程碑（情绪保持兴奋）
+    pub deep_focus: bool,
 }
 
 /// 专注统计数据
@@ -319,13 +1233,732 @@ mod tests {
     #[test]
     fn test_focus_transition() {
         let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+
+        // 模拟持续高专注分数（约 15fps）
+        for i in 0..100 {
+            machine.update_at(start + Duration::from_millis(i * 66), 0.9, true);
+        }
+
+        assert_eq!(machine.focus_level, FocusLevel::Focused);
+        assert!(matches!(machine.mood, PetMood::Happy | PetMood::Excited));
+    }
+
+    #[test]
+    fn test_classify_score() {
+        let config = PetStateConfig::default();
+        let above = config.focus_enter_threshold + 0.1;
+        let below = config.focus_exit_threshold - 0.1;
+
+        assert_eq!(
+            PetStateMachine::classify_score(config.clone(), above, true),
+            ScoreClassification {
+                focus_level: FocusLevel::Focused,
+                would_be_mood: PetMood::Happy,
+            }
+        );
+        assert_eq!(
+            PetStateMachine::classify_score(config.clone(), below, true),
+            ScoreClassification {
+                focus_level: FocusLevel::Distracted,
+                would_be_mood: PetMood::Sad,
+            }
+        );
+        assert_eq!(
+            PetStateMachine::classify_score(config, above, false).focus_level,
+            FocusLevel::Away
+        );
+    }
+
+    #[test]
+    fn test_away_grace_before_sleepy() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+
+        for i in 0..100 {
+            machine.update_at(start + Duration::from_millis(i * 66), 0.9, true);
+        }
+        assert!(matches!(machine.mood, PetMood::Happy | PetMood::Excited));
+        let lost_at = start + Duration::from_millis(99 * 66);
+
+        // 人脸丢失超过宽限时间：寻找用户
+        machine.update_at(lost_at + Duration::from_millis(1500), 0.0, false);
+        assert_eq!(machine.mood, PetMood::Idle);
+        assert!(machine.is_looking_for_user());
+
+        // 仍在离开超时之内：保持寻找
+        machine.update_at(lost_at + Duration::from_secs(4), 0.0, false);
+        assert_eq!(machine.mood, PetMood::Idle);
+
+        // 超过离开超时：睡觉
+        machine.update_at(lost_at + Duration::from_secs(6), 0.0, false);
+        assert_eq!(machine.mood, PetMood::Sleepy);
+        assert!(!machine.is_looking_for_user());
+    }
+
+    #[test]
+    fn test_gesture_wins_over_away_timeout() {
+        let run = |gesture_priority_ms: u64| {
+            let mut machine = PetStateMachine::new(PetStateConfig {
+                gesture_priority_ms,
+                ..Default::default()
+            });
+            let start = Instant::now();
+            machine.update_at(start, 0.9, true);
+
+            // 离开超时（5 秒）前一刻做了手势，下一帧已经超时
+            machine.on_gesture_at(start + Duration::from_millis(4900), GestureType::Wave);
+            machine.update_at(start + Duration::from_millis(5100), 0.0, false);
+            let after_timeout = machine.mood;
+            // 优先窗口过后仍然判定离开
+            machine.update_at(start + Duration::from_millis(6000), 0.0, false);
+            (after_timeout, machine.mood)
+        };
+
+        assert_eq!(run(1000), (PetMood::Interact, PetMood::Sleepy));
+        assert_eq!(run(0), (PetMood::Sleepy, PetMood::Sleepy));
+    }
+
+    #[test]
+    fn test_restless_presence_holds_neutral_mood() {
+        fn run(threshold: u32) -> (PetStateMachine, u64, Instant) {
+            let mut machine = PetStateMachine::new(PetStateConfig {
+                restless_toggle_threshold: threshold,
+                ..Default::default()
+            });
+            let start = Instant::now();
+            for i in 0..100 {
+                machine.update_at(start + Duration::from_millis(i * 100), 0.9, true);
+            }
+            assert_eq!(machine.mood, PetMood::Happy);
+
+            // 每 1.5 秒进出一次画面，持续 30 秒，统计后 15 秒的情绪变化
+            let mut changes = 0;
+            for i in 100..400u64 {
+                let present = (i / 15) % 2 == 0;
+                let score = if present { 0.9 } else { 0.0 };
+                let changed = machine.update_at(start + Duration::from_millis(i * 100), score, present);
+                if i >= 250 && changed.is_some() {
+                    changes += 1;
+                }
+            }
+            (machine, changes, start)
+        }
+
+        // 关闭时在开心和寻找用户之间来回切换
+        let (_, changes, _) = run(0);
+        assert!(changes >= 5, "disabled changes {}", changes);
+
+        // 开启后稳定在待机
+        let (mut machine, changes, start) = run(8);
+        assert_eq!(changes, 0);
+        assert!(machine.is_restless());
+        assert_eq!(machine.mood, PetMood::Idle);
+        let focus_ms = machine.total_focus_ms;
+
+        // 安定下来后恢复正常
+        for i in 400..800u64 {
+            machine.update_at(start + Duration::from_millis(i * 100), 0.9, true);
+        }
+        assert!(!machine.is_restless());
+        assert_eq!(machine.mood, PetMood::Happy);
+        assert!(machine.total_focus_ms > focus_ms);
+    }
+
+    #[test]
+    fn test_mood_lock_pins_mood() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+        let at = |i: u64| start + Duration::from_millis(i * 100);
+
+        machine.set_mood_lock(Some(PetMood::Happy));
+        assert_eq!(machine.mood, PetMood::Happy);
+        let seq = machine.mood_snapshot().change_seq;
+
+        // 专注、分心、离开和手势都不改变情绪，专注时长照常累计
+        for i in 0..100 {
+            assert_eq!(machine.update_at(at(i), 0.9, true), None);
+        }
+        assert!(machine.total_focus_ms > 0);
+        for i in 100..200 {
+            assert_eq!(machine.update_at(at(i), 0.1, true), None);
+        }
+        for i in 200..300 {
+            assert_eq!(machine.update_at(at(i), 0.0, false), None);
+        }
+        machine.on_gesture_at(at(300), GestureType::Wave);
+        machine.set_mood(PetMood::Sad);
+        assert_eq!(machine.mood, PetMood::Happy);
+        assert_eq!(machine.mood_snapshot().change_seq, seq);
+
+        // 解除后按当前状态恢复
+        machine.set_mood_lock(None);
+        assert_eq!(machine.mood_lock(), None);
+        assert_eq!(machine.update_at(at(301), 0.0, false), Some(PetMood::Sleepy));
+    }
+
+    #[test]
+    fn test_focus_milestones_apply_to_next_span() {
+        let mut machine = PetStateMachine::new(PetStateConfig {
+            excited_focus_minutes: 1.0,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        let at = |i: u64| start + Duration::from_millis(i * 100);
+
+        // 持续专注，返回进入兴奋状态的帧
+        let run_until_excited = |machine: &mut PetStateMachine, from: u64| {
+            (from..from + 6000).find(|&i| {
+                machine.update_at(at(i), 0.95, true);
+                machine.mood == PetMood::Excited
+            })
+        };
+
+        // 专注中途修改：本次专注仍按 1 分钟进入兴奋
+        for i in 0..300 {
+            machine.update_at(at(i), 0.95, true);
+        }
+        machine.set_focus_milestones(FocusMilestones {
+            excited_minutes: 2.0,
+            deep_focus_minutes: Some(3.0),
+        });
+        let first = run_until_excited(&mut machine, 300).unwrap();
+        assert!((600..700).contains(&first), "first excited at frame {}", first);
+        assert!(!machine.is_deep_focus_at(at(first)));
+
+        // 分心结束本次专注，下一次专注按 2 分钟进入兴奋，3 分钟进入深度专注
+        for i in first + 1..first + 200 {
+            machine.update_at(at(i), 0.1, true);
+        }
+        let span_start = first + 200;
+        let second = run_until_excited(&mut machine, span_start).unwrap() - span_start;
+        assert!((1200..1300).contains(&second), "second excited after {} frames", second);
+        let now = span_start + second;
+        assert!(!machine.is_deep_focus_at(at(now)));
+        for i in now + 1..now + 700 {
+            machine.update_at(at(i), 0.95, true);
+        }
+        assert!(machine.is_deep_focus_at(at(now + 699)));
+
+        // 深度专注时间需要长于兴奋时间
+        let invalid = FocusMilestones {
+            excited_minutes: 30.0,
+            deep_focus_minutes: Some(20.0),
+        };
+        assert!(invalid.validate().is_err());
+        assert!(machine.focus_milestones().validate().is_ok());
+    }
+
+    #[test]
+    fn test_temporary_away_timeout() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+
+        for i in 0..100 {
+            machine.update_at(start + Duration::from_millis(i * 66), 0.9, true);
+        }
+        let lost_at = start + Duration::from_millis(99 * 66);
+
+        // 临时把离开超时延长到 60 秒，持续 2 分钟
+        machine.set_temporary_away_timeout_at(lost_at, 60.0, Duration::from_secs(120));
+        assert_eq!(machine.away_timeout_at(lost_at), 60.0);
+
+        // 重叠调用延长失效时间，而不是缩短
+        machine.set_temporary_away_timeout_at(lost_at + Duration::from_secs(10), 60.0, Duration::from_secs(30));
+        assert_eq!(machine.away_timeout_at(lost_at + Duration::from_secs(119)), 60.0);
+        machine.set_temporary_away_timeout_at(lost_at + Duration::from_secs(20), 60.0, Duration::from_secs(150));
+        assert_eq!(machine.away_timeout_at(lost_at + Duration::from_secs(160)), 60.0);
+
+        // 超过原本的 5 秒仍未睡觉
+        machine.update_at(lost_at + Duration::from_secs(30), 0.0, false);
+        assert_ne!(machine.mood, PetMood::Sleepy);
+
+        // 到期后恢复配置中的超时
+        let expired = lost_at + Duration::from_secs(171);
+        assert_eq!(machine.away_timeout_at(expired), PetStateConfig::default().away_timeout);
+        machine.update_at(expired, 0.0, false);
+        assert_eq!(machine.mood, PetMood::Sleepy);
+    }
+
+    #[test]
+    fn test_intensity_grows_with_depth_and_duration() {
+        let start = Instant::now();
+
+        // 分数刚好进入专注
+        let mut shallow = PetStateMachine::new(PetStateConfig::default());
+        for i in 0..100 {
+            shallow.update_at(start + Duration::from_millis(i * 66), 0.8, true);
+        }
+        // 分数很高且持续更久
+        let mut deep = PetStateMachine::new(PetStateConfig::default());
+        for i in 0..1000 {
+            deep.update_at(start + Duration::from_millis(i * 66), 1.0, true);
+        }
+
+        let now = start + Duration::from_millis(1000 * 66);
+        assert_eq!(shallow.mood, PetMood::Happy);
+        assert_eq!(deep.mood, PetMood::Happy);
+        let shallow_intensity = shallow.intensity_at(start + Duration::from_millis(99 * 66));
+        let deep_intensity = deep.intensity_at(now);
+        assert!(deep_intensity > shallow_intensity);
+        assert!((0.0..=1.0).contains(&deep_intensity));
+
+        // 同样的分数，专注越久越强（兴奋状态下依然递增）
+        assert!(deep.intensity_at(now + Duration::from_secs(600)) > deep_intensity);
+        let excited_at = now + Duration::from_secs(25 * 60);
+        deep.update_at(excited_at, 1.0, true);
+        assert_eq!(deep.mood, PetMood::Excited);
+        assert!(deep.intensity_at(excited_at) >= deep.intensity_at(now + Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn test_next_milestone() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+        assert!(machine.next_milestone_at(start).is_none());
+
+        // 进入专注
+        let mut focused_at = None;
+        for i in 0..100 {
+            let now = start + Duration::from_millis(i * 66);
+            machine.update_at(now, 0.9, true);
+            if focused_at.is_none() && machine.focus_level == FocusLevel::Focused {
+                focused_at = Some(now);
+            }
+        }
+        let focused_at = focused_at.unwrap();
+
+        // 专注 10 分钟后，距离兴奋还剩 15 分钟
+        let milestone = machine.next_milestone_at(focused_at + Duration::from_secs(600)).unwrap();
+        assert_eq!(milestone.target_mood, PetMood::Excited);
+        assert!((milestone.seconds_remaining - 900.0).abs() < 0.01);
+
+        assert!(!milestone.deep_focus);
+
+        // 已达到兴奋且未设置深度专注时没有下一个里程碑
+        assert!(machine.next_milestone_at(focused_at + Duration::from_secs(25 * 60)).is_none());
+
+        // 分心后没有里程碑
+        for i in 0..100 {
+            machine.update_at(start + Duration::from_millis((100 + i) * 66), 0.0, true);
+        }
+        assert!(machine.next_milestone_at(start + Duration::from_secs(60)).is_none());
+
+        // 设置深度专注后，兴奋之后的下一个里程碑为深度专注
+        machine.set_focus_milestones(FocusMilestones {
+            excited_minutes: 25.0,
+            deep_focus_minutes: Some(40.0),
+        });
+        let restart = start + Duration::from_millis(200 * 66);
+        let mut focused_at = None;
+        for i in 0..100 {
+            let now = restart + Duration::from_millis(i * 66);
+            machine.update_at(now, 0.9, true);
+            if focused_at.is_none() && machine.focus_level == FocusLevel::Focused {
+                focused_at = Some(now);
+            }
+        }
+        let focused_at = focused_at.unwrap();
+        let milestone = machine.next_milestone_at(focused_at + Duration::from_secs(600)).unwrap();
+        assert!(!milestone.deep_focus);
+        let milestone = machine.next_milestone_at(focused_at + Duration::from_secs(30 * 60)).unwrap();
+        assert_eq!(milestone.target_mood, PetMood::Excited);
+        assert!(milestone.deep_focus);
+        assert!((milestone.seconds_remaining - 600.0).abs() < 0.01);
+        assert!(machine.next_milestone_at(focused_at + Duration::from_secs(40 * 60)).is_none());
+    }
+
+    #[test]
+    fn test_min_mood_dwell() {
+        let config = PetStateConfig {
+            focus_enter_threshold: 0.5,
+            focus_exit_threshold: 0.45,
+            min_mood_dwell_ms: 2000,
+            ..Default::default()
+        };
+        let mut machine = PetStateMachine::new(config);
+        let start = Instant::now();
+        let mut changes = Vec::new();
+
+        // 分数快速振荡，平滑值反复穿越阈值
+        for i in 0..300u64 {
+            let now = start + Duration::from_millis(i * 66);
+            let raw = if (i / 4) % 2 == 0 { 1.0 } else { 0.0 };
+            if machine.update_at(now, raw, true).is_some() {
+                changes.push(now);
+            }
+        }
+
+        // 除离开待机外，相邻两次情绪变化至少间隔一个停留窗口
+        assert!(changes.len() > 2);
+        for pair in changes[1..].windows(2) {
+            assert!(pair[1].duration_since(pair[0]) >= Duration::from_millis(2000));
+        }
+    }
+
+    /// 以相同的输入运行状态机，返回情绪变化次数
+    fn count_transitions(style: ReactionStyle) -> u64 {
+        let config = PetStateConfig {
+            reaction_style: style,
+            ..Default::default()
+        };
+        let mut machine = PetStateMachine::new(config);
+        let start = Instant::now();
+
+        // 分数在不同幅度之间振荡
+        for i in 0..2000u64 {
+            let amplitude = [0.15, 0.25, 0.35][(i / 200 % 3) as usize];
+            let raw = if (i / 20) % 2 == 0 { 0.55 + amplitude } else { 0.55 - amplitude };
+            machine.update_at(start + Duration::from_millis(i * 66), raw, true);
+        }
+
+        machine.mood_snapshot().change_seq
+    }
+
+    #[test]
+    fn test_reaction_style_transition_counts() {
+        let calm = count_transitions(ReactionStyle::Calm);
+        let expressive = count_transitions(ReactionStyle::Expressive);
+        let stoic = count_transitions(ReactionStyle::Stoic);
+
+        assert!(expressive > calm, "expressive {} <= calm {}", expressive, calm);
+        assert!(calm > stoic, "calm {} <= stoic {}", calm, stoic);
+    }
+
+    #[test]
+    fn test_reaction_style_intensity() {
+        let start = Instant::now();
+        let intensity = |style: ReactionStyle| {
+            let mut machine = PetStateMachine::new(PetStateConfig {
+                reaction_style: style,
+                ..Default::default()
+            });
+            for i in 0..100 {
+                machine.update_at(start + Duration::from_millis(i * 66), 0.8, true);
+            }
+            machine.intensity_at(start + Duration::from_millis(99 * 66))
+        };
+
+        assert!(intensity(ReactionStyle::Expressive) > intensity(ReactionStyle::Calm));
+        assert!(intensity(ReactionStyle::Calm) > intensity(ReactionStyle::Stoic));
+    }
+
+    #[test]
+    fn test_gesture_reaction_depends_on_mood() {
+        let react = |mood: PetMood| {
+            let mut machine = PetStateMachine::new(PetStateConfig::default());
+            machine.set_mood(mood);
+            machine.on_gesture_at(Instant::now(), GestureType::Wave)
+        };
+
+        let from_sad = react(PetMood::Sad);
+        let from_excited = react(PetMood::Excited);
+        assert_eq!(from_sad.mood, PetMood::Interact);
+        assert_eq!(from_sad.from_mood, PetMood::Sad);
+        assert_eq!(from_sad.cue, ReactionCue::CheerUp);
+        assert_eq!(from_excited.from_mood, PetMood::Excited);
+        assert_eq!(from_excited.cue, ReactionCue::Celebrate);
+        assert!(from_sad.intensity > from_excited.intensity);
+        assert_ne!(
+            serde_json::to_value(from_sad).unwrap(),
+            serde_json::to_value(from_excited).unwrap()
+        );
+
+        // 互动中重复手势沿用最初的互动前情绪
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        machine.set_mood(PetMood::Sad);
+        let now = Instant::now();
+        machine.on_gesture_at(now, GestureType::Wave);
+        let again = machine.on_gesture_at(now + Duration::from_millis(500), GestureType::Heart);
+        assert_eq!(again.from_mood, PetMood::Sad);
+        assert_eq!(again.cue, ReactionCue::CheerUp);
+    }
+
+    #[test]
+    fn test_screen_lock_overrides_face() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+        for i in 0..100 {
+            machine.update_at(start + Duration::from_millis(i * 66), 0.9, true);
+        }
+        assert_eq!(machine.focus_level, FocusLevel::Focused);
+
+        // 锁屏：即使仍检测到人脸也立即离开，且不再累计专注时间
+        let locked_at = start + Duration::from_millis(100 * 66);
+        assert_eq!(machine.set_screen_locked_at(locked_at, true), Some(PetMood::Sleepy));
+        let focused_ms = machine.total_focus_ms;
+        for i in 1..100 {
+            machine.update_at(locked_at + Duration::from_millis(i * 66), 0.9, true);
+        }
+        assert_eq!(machine.focus_level, FocusLevel::Away);
+        assert_eq!(machine.mood, PetMood::Sleepy);
+        assert_eq!(machine.total_focus_ms, focused_ms);
+
+        // 解锁后重新检测到人脸才恢复
+        let unlocked_at = locked_at + Duration::from_secs(60);
+        assert_eq!(machine.set_screen_locked_at(unlocked_at, false), None);
+        for i in 0..100 {
+            machine.update_at(unlocked_at + Duration::from_millis(i * 66), 0.9, true);
+        }
+        assert_eq!(machine.focus_level, FocusLevel::Focused);
+        assert!(machine.total_focus_ms > focused_ms);
+    }
+
+    #[test]
+    fn test_adaptive_ema_alpha() {
+        let start = Instant::now();
+        let adaptive = PetStateConfig {
+            adaptive_ema: true,
+            ..PetStateConfig::default()
+        };
+        let smoothed_after = |confidence: f32| {
+            let mut machine = PetStateMachine::new(adaptive.clone());
+            for i in 0..10 {
+                machine.update_with_confidence_at(start + Duration::from_millis(i * 66), 0.9, true, Some(confidence));
+            }
+            machine.smoothed_focus_score
+        };
+
+        // 相同的原始分数，低置信度收敛更慢
+        let low = smoothed_after(0.2);
+        let high = smoothed_after(0.95);
+        assert!(low < high, "low {} should lag high {}", low, high);
+        assert!(high < 0.9);
+
+        // 系数限制在配置的范围内
+        let mut machine = PetStateMachine::new(adaptive.clone());
+        machine.detection_confidence = Some(5.0);
+        assert_eq!(machine.effective_ema_alpha(), 0.2);
+        machine.detection_confidence = Some(-1.0);
+        assert_eq!(machine.effective_ema_alpha(), 0.05);
+        machine.detection_confidence = None;
+        assert_eq!(machine.effective_ema_alpha(), 0.15);
+
+        // 默认不开启自适应，始终使用用户配置的系数
+        let mut config = AppConfig::default();
+        config.focus.ema_alpha = 0.4;
+        let mut machine = PetStateMachine::new(PetStateConfig::from_app_config(&config));
+        machine.detection_confidence = Some(0.95);
+        assert_eq!(machine.effective_ema_alpha(), 0.4);
+    }
+
+    #[test]
+    fn test_time_jump_not_credited() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+
+        for i in 0..100 {
+            machine.update_at(start + Duration::from_millis(i * 66), 0.9, true);
+        }
+        assert_eq!(machine.focus_level, FocusLevel::Focused);
+        let focused_ms = machine.total_focus_ms;
+
+        // 小于阈值的间隔不处理
+        assert!(!machine.handle_time_jump_at(start + Duration::from_secs(7), Duration::from_secs(1)));
+        assert_eq!(machine.focus_level, FocusLevel::Focused);
+
+        // 睡眠 1 小时后唤醒：不计入专注，情绪重置
+        let woke_at = start + Duration::from_secs(3600);
+        assert!(machine.handle_time_jump_at(woke_at, Duration::from_secs(3600)));
+        assert_eq!(machine.mood, PetMood::Sleepy);
+        assert_eq!(machine.focus_level, FocusLevel::Away);
+        assert_eq!(machine.total_focus_ms, focused_ms);
+        assert!(machine.next_milestone_at(woke_at).is_none());
+
+        // 用户回来后重新开始专注计时，不会因为睡眠时长直接进入兴奋
+        for i in 0..100 {
+            machine.update_at(woke_at + Duration::from_millis(i * 66), 0.9, true);
+        }
+        assert_eq!(machine.mood, PetMood::Happy);
+    }
+
+    #[test]
+    fn test_short_long_term_blend() {
+        /// 专注后出现一段分数下降，返回下降期间是否进入伤心
+        fn dips_to_sad(weight: f32, dip_frames: u64) -> bool {
+            let mut machine = PetStateMachine::new(PetStateConfig {
+                short_term_weight: weight,
+                ..Default::default()
+            });
+            let start = Instant::now();
+            for i in 0..300 {
+                machine.update_at(start + Duration::from_millis(i * 66), 0.9, true);
+            }
+            assert_eq!(machine.mood, PetMood::Happy);
+
+            (300..300 + dip_frames).any(|i| {
+                machine.update_at(start + Duration::from_millis(i * 66), 0.0, true);
+                machine.mood == PetMood::Sad
+            })
+        }
+
+        // 只看 EMA 时短暂下降也会触发伤心
+        assert!(dips_to_sad(1.0, 8));
+
+        for weight in [0.3, 0.6] {
+            // 短暂下降被长期平均吸收
+            assert!(!dips_to_sad(weight, 8), "weight {}", weight);
+            // 持续下降仍然进入伤心
+            assert!(dips_to_sad(weight, 300), "weight {}", weight);
+        }
+    }
+
+    #[test]
+    fn test_smoothing_bypass() {
+        /// 从离开开始，返回第几次高分更新后进入专注
+        fn updates_until_focused(smoothing_enabled: bool) -> u64 {
+            let mut machine = PetStateMachine::new(PetStateConfig {
+                smoothing_enabled,
+                ..Default::default()
+            });
+            let start = Instant::now();
+            machine.update_at(start, 0.0, true);
+            (1..100)
+                .find(|&i| {
+                    machine.update_at(start + Duration::from_millis(i * 66), 0.95, true);
+                    machine.focus_level == FocusLevel::Focused
+                })
+                .unwrap()
+        }
+
+        // 关闭平滑时一次高分立即进入专注，开启时需要多次
+        assert_eq!(updates_until_focused(false), 1);
+        assert!(updates_until_focused(true) > 3);
+    }
+
+    #[test]
+    fn test_interact_end_behavior() {
+        /// 专注时互动，互动期间专注下降，返回互动结束后的情绪
+        fn mood_after_interact(recompute: bool) -> PetMood {
+            let mut machine = PetStateMachine::new(PetStateConfig {
+                recompute_after_interact: recompute,
+                ..Default::default()
+            });
+            let start = Instant::now();
+            let at = |i: u64| start + Duration::from_millis(i * 66);
+
+            for i in 0..100 {
+                machine.update_at(at(i), 0.9, true);
+            }
+            assert_eq!(machine.mood, PetMood::Happy);
+
+            machine.on_gesture_at(at(100), GestureType::Wave);
+            for i in 101..140 {
+                machine.update_at(at(i), 0.0, true);
+                assert_eq!(machine.mood, PetMood::Interact);
+            }
+
+            // 互动持续 3 秒，之后的第一帧结束互动
+            for i in 140..150 {
+                machine.update_at(at(i), 0.0, true);
+                if machine.mood != PetMood::Interact {
+                    break;
+                }
+            }
+            machine.mood
+        }
+
+        assert_eq!(mood_after_interact(false), PetMood::Happy);
+        assert_eq!(mood_after_interact(true), PetMood::Sad);
+    }
+
+    #[test]
+    fn test_interact_without_previous_mood_ends() {
+        for recompute in [false, true] {
+            let mut machine = PetStateMachine::new(PetStateConfig {
+                recompute_after_interact: recompute,
+                ..Default::default()
+            });
+            let start = Instant::now();
+            let at = |i: u64| start + Duration::from_millis(i * 66);
+            machine.update_at(at(0), 0.9, true);
+
+            // 演示模式直接进入互动，再触发手势：没有互动前的情绪
+            machine.set_mood(PetMood::Interact);
+            machine.on_gesture_at(at(1), GestureType::Wave);
+            assert_eq!(machine.mood_before_interact, None);
+
+            // 互动时长过后离开互动
+            let left = (2..100).any(|i| {
+                machine.update_at(at(i), 0.9, true);
+                machine.mood != PetMood::Interact
+            });
+            assert!(left, "stuck in interact (recompute: {})", recompute);
+        }
+    }
+
+    #[test]
+    fn test_manual_break_freezes_focus() {
+        let config = PetStateConfig {
+            min_mood_dwell_ms: 0,
+            ..Default::default()
+        };
+        let mut machine = PetStateMachine::new(config);
 
-        // 模拟持续高专注分数
         for _ in 0..100 {
             machine.update(0.9, true);
         }
+        let focused_ms = machine.total_focus_ms;
+        assert!(focused_ms > 0);
 
-        assert_eq!(machine.focus_level, FocusLevel::Focused);
+        // 休息期间专注时间不增加，宠物保持待机
+        machine.start_manual_break();
+        for _ in 0..50 {
+            machine.update(0.9, true);
+        }
+        assert_eq!(machine.total_focus_ms, focused_ms);
+        assert_eq!(machine.mood, PetMood::Idle);
+
+        // 结束休息后恢复累计
+        machine.end_manual_break();
+        for _ in 0..10 {
+            machine.update(0.9, true);
+        }
+        assert!(machine.total_focus_ms > focused_ms);
         assert!(matches!(machine.mood, PetMood::Happy | PetMood::Excited));
     }
+
+    #[test]
+    fn test_change_seq() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+        assert_eq!(machine.mood_snapshot().change_seq, 0);
+
+        // Idle -> Sleepy
+        machine.update_at(start, 0.0, false);
+        assert_eq!(machine.mood_snapshot().change_seq, 1);
+
+        // 保持 Sleepy，序号不变
+        for i in 1..10 {
+            machine.update_at(start + Duration::from_millis(i * 100), 0.0, false);
+        }
+        assert_eq!(machine.mood_snapshot().change_seq, 1);
+
+        // Sleepy -> Interact
+        machine.on_gesture(GestureType::Wave);
+        let snapshot = machine.mood_snapshot();
+        assert_eq!(snapshot.mood, PetMood::Interact);
+        assert_eq!(snapshot.change_seq, 2);
+    }
+
+    #[test]
+    fn test_motion_wake_from_sleepy() {
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+
+        // 没有人脸，进入睡觉
+        machine.update_at(start, 0.0, false);
+        assert_eq!(machine.mood, PetMood::Sleepy);
+
+        // 静止后出现运动，立即唤醒
+        assert_eq!(machine.on_motion_at(start), Some(PetMood::Idle));
+
+        // 等待窗口内仍无人脸，保持唤醒
+        machine.update_at(start + Duration::from_secs(5), 0.0, false);
+        assert_eq!(machine.mood, PetMood::Idle);
+
+        // 超过等待窗口仍无人脸，恢复睡觉
+        machine.update_at(start + Duration::from_secs(11), 0.0, false);
+        assert_eq!(machine.mood, PetMood::Sleepy);
+    }
 }
@@ -0,0 +1,163 @@
+//! 看手机检测
+//!
+//! 持续明显低头（俯仰角超过阈值）通常是在看手机，此时发出 `phone_distraction` 信号让宠物针对性地提醒。
+//! 下方有副屏时，可以按看副屏时的俯仰角校准阈值，避免把看副屏误判为看手机。
+
+use serde::{Deserialize, Serialize};
+
+/// 默认的低头阈值（度）
+pub const DEFAULT_PHONE_PITCH_THRESHOLD: f32 = 20.0;
+
+/// 默认的持续时长（毫秒）
+pub const DEFAULT_PHONE_SUSTAIN_MS: i64 = 8_000;
+
+/// 校准时在看下方副屏的平均俯仰角基础上增加的余量（度）
+const CALIBRATION_MARGIN: f32 = 10.0;
+
+/// 校准所需的最少俯仰角采样数
+const MIN_CALIBRATION_SAMPLES: usize = 5;
+
+/// 看手机检测配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhoneGlanceConfig {
+    /// 俯仰角超过该值（度，正值表示低头）视为低头
+    pub pitch_threshold: f32,
+    /// 持续低头超过该时长（毫秒）才发出信号
+    pub sustain_ms: i64,
+}
+
+impl Default for PhoneGlanceConfig {
+    fn default() -> Self {
+        Self {
+            pitch_threshold: DEFAULT_PHONE_PITCH_THRESHOLD,
+            sustain_ms: DEFAULT_PHONE_SUSTAIN_MS,
+        }
+    }
+}
+
+impl PhoneGlanceConfig {
+    /// 根据看下方副屏时采集的俯仰角校准低头阈值：平均值加上余量
+    ///
+    /// 采样不足时返回 None
+    pub fn calibrate_threshold(pitches: &[f32]) -> Option<f32> {
+        if pitches.len() < MIN_CALIBRATION_SAMPLES {
+            return None;
+        }
+        let mean = pitches.iter().sum::<f32>() / pitches.len() as f32;
+        Some(mean.max(0.0) + CALIBRATION_MARGIN)
+    }
+}
+
+/// 看手机信号
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhoneDistraction {
+    /// 开始低头的时间戳（毫秒）
+    pub started_ms: i64,
+    /// 已持续低头的时长（毫秒）
+    pub duration_ms: i64,
+    /// 当前俯仰角（度）
+    pub pitch: f32,
+}
+
+/// 看手机检测器
+///
+/// 每次持续低头只发出一次信号，抬头或人脸离开后重新计时
+#[derive(Debug, Clone, Default)]
+pub struct PhoneGlanceDetector {
+    config: PhoneGlanceConfig,
+    /// 本次低头的开始时间
+    since: Option<i64>,
+    /// 本次低头是否已发出信号
+    fired: bool,
+}
+
+impl PhoneGlanceDetector {
+    /// 创建检测器
+    pub fn new(config: PhoneGlanceConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// 替换配置（不影响进行中的计时）
+    pub fn set_config(&mut self, config: PhoneGlanceConfig) {
+        self.config = config;
+    }
+
+    /// 观察一次专注状态，持续低头达到设定时长时返回信号
+    pub fn observe(&mut self, now_ms: i64, pitch: f32, face_present: bool) -> Option<PhoneDistraction> {
+        if !face_present || pitch < self.config.pitch_threshold {
+            self.since = None;
+            self.fired = false;
+            return None;
+        }
+
+        let started_ms = *self.since.get_or_insert(now_ms);
+        let duration_ms = now_ms - started_ms;
+        if self.fired || duration_ms < self.config.sustain_ms {
+            return None;
+        }
+        self.fired = true;
+        Some(PhoneDistraction {
+            started_ms,
+            duration_ms,
+            pitch,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::FaceDetection;
+
+    /// 鼻子相对双眼下移 `nose_drop` 的人脸（越大越低头）
+    fn face(nose_drop: f32) -> FaceDetection {
+        let mut face = FaceDetection::frontal();
+        face.landmarks[2].1 = face.landmarks[0].1 + nose_drop;
+        face
+    }
+
+    #[test]
+    fn test_phone_glance_fires_after_sustain() {
+        let mut detector = PhoneGlanceDetector::default();
+        let down = face(0.3).estimate_pitch();
+        let level = face(0.1).estimate_pitch();
+        assert!(down > DEFAULT_PHONE_PITCH_THRESHOLD && level < DEFAULT_PHONE_PITCH_THRESHOLD);
+
+        // 短暂低头不触发
+        for i in 0..30 {
+            assert_eq!(detector.observe(i * 100, down, true), None);
+        }
+        assert_eq!(detector.observe(3_000, level, true), None);
+
+        // 持续低头，达到 8 秒时触发一次
+        let start = 10_000;
+        let fired: Vec<(i64, PhoneDistraction)> = (0..=120)
+            .filter_map(|i| {
+                let now = start + i * 100;
+                detector.observe(now, down, true).map(|signal| (now, signal))
+            })
+            .collect();
+        assert_eq!(fired.len(), 1);
+        let (fired_at, signal) = &fired[0];
+        assert_eq!(*fired_at, start + DEFAULT_PHONE_SUSTAIN_MS);
+        assert_eq!(signal.started_ms, start);
+        assert_eq!(signal.duration_ms, DEFAULT_PHONE_SUSTAIN_MS);
+
+        // 人脸离开后重新计时
+        assert_eq!(detector.observe(30_000, down, false), None);
+        assert_eq!(detector.observe(31_000, down, true), None);
+        assert!(detector.observe(31_000 + DEFAULT_PHONE_SUSTAIN_MS, down, true).is_some());
+
+        // 按下方副屏校准后，看副屏不再触发
+        let threshold = PhoneGlanceConfig::calibrate_threshold(&[down; 5]).unwrap();
+        let mut calibrated = PhoneGlanceDetector::new(PhoneGlanceConfig {
+            pitch_threshold: threshold,
+            ..Default::default()
+        });
+        assert!((0..=100).all(|i| calibrated.observe(i * 100, down, true).is_none()));
+        assert_eq!(PhoneGlanceConfig::calibrate_threshold(&[down; 2]), None);
+    }
+}
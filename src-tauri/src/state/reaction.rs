@@ -0,0 +1,83 @@
+//! 宠物反应风格
+//!
+//! 在基础状态转换之上叠加一层修正：同样的专注分数下，
+//! 不同风格的宠物情绪变化的难易程度、停留时间和强度不同。
+
+use serde::{Deserialize, Serialize};
+
+/// 反应风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionStyle {
+    /// 平和：使用基础参数
+    #[default]
+    Calm,
+    /// 夸张：情绪更容易变化，表现更强烈
+    Expressive,
+    /// 淡定：情绪很少变化，表现克制
+    Stoic,
+}
+
+/// 反应风格对基础参数的修正系数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReactionModifier {
+    /// 专注阈值滞后区间的缩放（以两个阈值的中点为中心）
+    /// 小于 1 时区间变窄，更容易在专注与分心之间切换
+    pub hysteresis_scale: f32,
+    /// 情绪最短停留时间的缩放
+    pub dwell_scale: f32,
+    /// 情绪强度的缩放
+    pub intensity_scale: f32,
+}
+
+impl ReactionStyle {
+    /// 从字符串解析反应风格
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "calm" => Some(Self::Calm),
+            "expressive" => Some(Self::Expressive),
+            "stoic" => Some(Self::Stoic),
+            _ => None,
+        }
+    }
+
+    /// 风格对应的修正系数
+    pub fn modifier(&self) -> ReactionModifier {
+        match self {
+            Self::Calm => ReactionModifier {
+                hysteresis_scale: 1.0,
+                dwell_scale: 1.0,
+                intensity_scale: 1.0,
+            },
+            Self::Expressive => ReactionModifier {
+                hysteresis_scale: 0.5,
+                dwell_scale: 0.5,
+                intensity_scale: 1.5,
+            },
+            Self::Stoic => ReactionModifier {
+                hysteresis_scale: 1.5,
+                dwell_scale: 3.0,
+                intensity_scale: 0.5,
+            },
+        }
+    }
+}
+
+impl ReactionModifier {
+    /// 修正后的进入 / 退出阈值
+    pub fn thresholds(&self, enter: f32, exit: f32) -> (f32, f32) {
+        let mid = (enter + exit) / 2.0;
+        let half = (enter - exit) / 2.0 * self.hysteresis_scale;
+        ((mid + half).clamp(0.0, 1.0), (mid - half).clamp(0.0, 1.0))
+    }
+
+    /// 修正后的最短停留时间（毫秒）
+    pub fn dwell_ms(&self, dwell_ms: u64) -> u64 {
+        (dwell_ms as f32 * self.dwell_scale) as u64
+    }
+
+    /// 修正后的情绪强度
+    pub fn intensity(&self, intensity: f32) -> f32 {
+        (intensity * self.intensity_scale).clamp(0.0, 1.0)
+    }
+}
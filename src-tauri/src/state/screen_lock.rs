@@ -0,0 +1,102 @@
+//! 系统锁屏检测
+//!
+//! 屏幕锁定时用户一定不在电脑前，即使摄像头仍然检测到人脸（例如照片或残留的检测结果）。
+//! 各平台通过轮询获取锁屏状态，无法判断时返回 `None`，调用方应视为未锁定。
+//!
+//! - Windows：无法打开接收输入的桌面时视为锁定（`OpenInputDesktop`）
+//! - macOS：读取 `ioreg` 中当前控制台会话的 `CGSSessionScreenIsLocked`
+//! - Linux：读取 systemd-logind 会话的 `LockedHint`
+
+/// 当前屏幕是否锁定（无法判断时返回 None）
+#[cfg(target_os = "windows")]
+pub fn is_screen_locked() -> Option<bool> {
+    use std::ffi::c_void;
+
+    const DESKTOP_SWITCHDESKTOP: u32 = 0x0100;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn OpenInputDesktop(flags: u32, inherit: i32, desired_access: u32) -> *mut c_void;
+        fn SwitchDesktop(desktop: *mut c_void) -> i32;
+        fn CloseDesktop(desktop: *mut c_void) -> i32;
+    }
+
+    // 锁屏时输入桌面切换为安全桌面，普通进程无法打开或切换到它
+    unsafe {
+        let desktop = OpenInputDesktop(0, 0, DESKTOP_SWITCHDESKTOP);
+        if desktop.is_null() {
+            return Some(true);
+        }
+        let switched = SwitchDesktop(desktop) != 0;
+        CloseDesktop(desktop);
+        Some(!switched)
+    }
+}
+
+/// 当前屏幕是否锁定（无法判断时返回 None）
+#[cfg(target_os = "macos")]
+pub fn is_screen_locked() -> Option<bool> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-n", "Root", "-d1"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_ioreg_locked(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// 当前屏幕是否锁定（无法判断时返回 None）
+#[cfg(target_os = "linux")]
+pub fn is_screen_locked() -> Option<bool> {
+    let session = std::env::var("XDG_SESSION_ID").ok()?;
+    let output = std::process::Command::new("loginctl")
+        .args(["show-session", &session, "-p", "LockedHint", "--value"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_locked_hint(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// 不支持的平台
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn is_screen_locked() -> Option<bool> {
+    None
+}
+
+/// 解析 `loginctl ... -p LockedHint --value` 的输出
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_locked_hint(output: &str) -> Option<bool> {
+    match output.trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// 在 `ioreg -n Root -d1` 的输出中查找锁屏标记
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ioreg_locked(output: &str) -> bool {
+    output.contains("\"CGSSessionScreenIsLocked\"=Yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_output_parsing() {
+        assert_eq!(parse_locked_hint("yes\n"), Some(true));
+        assert_eq!(parse_locked_hint("no\n"), Some(false));
+        assert_eq!(parse_locked_hint(""), None);
+
+        assert!(parse_ioreg_locked(
+            r#"| "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes,"CGSSessionScreenIsLocked"=Yes})"#
+        ));
+        assert!(!parse_ioreg_locked(
+            r#"| "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes})"#
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+//! 专注会话累计
+//! 视觉检测运行期间累计一次会话的数据，结束时生成会话记录写入数据库
+
+use super::FocusLevel;
+use crate::storage::FocusSession;
+use serde::{Deserialize, Serialize};
+
+/// 两个采样之间计入分心时长的最大间隔（毫秒），避免处理暂停时把空档计入
+const MAX_SAMPLE_GAP_MS: i64 = 1000;
+
+/// 会话中的最高专注分数
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SessionPeak {
+    /// 最高专注分数
+    pub peak_score: f32,
+    /// 首次达到最高分数的时间 (Unix 时间戳，毫秒)
+    pub at_ms: i64,
+}
+
+/// 会话结束时的回顾
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecap {
+    /// 会话时长（毫秒）
+    pub duration_ms: i64,
+    /// 专注时长（毫秒）
+    pub focus_ms: i64,
+    /// 专注期间的平均专注分数
+    pub avg_focus_score: f32,
+    /// 最高专注分数（没有采样时为 0）
+    pub peak_score: f32,
+    /// 进入分心的次数
+    pub distraction_count: u32,
+    /// 专注时长是否超过了之前所有会话
+    pub personal_best: bool,
+}
+
+/// 会话累计器
+#[derive(Debug, Clone)]
+pub struct SessionTracker {
+    /// 开始时间 (Unix 时间戳，毫秒)
+    start_time: i64,
+    /// 开始时状态机的累计专注时长
+    start_focus_ms: u64,
+    /// 上一个采样的时间戳
+    last_sample_ms: Option<i64>,
+    /// 分心时长
+    distracted_ms: i64,
+    /// 专注采样的分数之和
+    score_sum: f64,
+    /// 专注采样数量
+    score_count: u64,
+    /// 专注采样的最低分数
+    score_min: f32,
+    /// 专注采样的最高分数
+    score_max: f32,
+    /// 已写入数据库的会话记录 ID（第一次保存后存在）
+    saved_id: Option<i64>,
+    /// 上一次保存时的专注时长
+    saved_focus_ms: i64,
+    /// 上一次保存时的分心时长
+    saved_distracted_ms: i64,
+    /// 最高专注分数（所有采样，和专注期间的分数统计分开）
+    peak: Option<SessionPeak>,
+    /// 上一个采样的专注等级
+    last_level: Option<FocusLevel>,
+    /// 进入分心的次数
+    distraction_count: u32,
+}
+
+impl SessionTracker {
+    /// 开始一个新会话
+    ///
+    /// `total_focus_ms` 为状态机当前的累计专注时长，结束时用差值计算本次会话的专注时长
+    pub fn start(now_ms: i64, total_focus_ms: u64) -> Self {
+        Self {
+            start_time: now_ms,
+            start_focus_ms: total_focus_ms,
+            last_sample_ms: None,
+            distracted_ms: 0,
+            score_sum: 0.0,
+            score_count: 0,
+            score_min: f32::MAX,
+            score_max: f32::MIN,
+            saved_id: None,
+            saved_focus_ms: 0,
+            saved_distracted_ms: 0,
+            peak: None,
+            last_level: None,
+            distraction_count: 0,
+        }
+    }
+
+    /// 记录一个采样
+    pub fn record(&mut self, now_ms: i64, focus_score: f32, focus_level: FocusLevel) {
+        if let Some(last) = self.last_sample_ms {
+            if focus_level == FocusLevel::Distracted {
+                self.distracted_ms += (now_ms - last).clamp(0, MAX_SAMPLE_GAP_MS);
+            }
+        }
+        self.last_sample_ms = Some(now_ms);
+        if focus_level == FocusLevel::Distracted && self.last_level != Some(FocusLevel::Distracted) {
+            self.distraction_count += 1;
+        }
+        self.last_level = Some(focus_level);
+
+        if self.peak.is_none_or(|peak| focus_score > peak.peak_score) {
+            self.peak = Some(SessionPeak {
+                peak_score: focus_score,
+                at_ms: now_ms,
+            });
+        }
+
+        // 只统计专注期间的分数，反映专注的强度
+        if focus_level == FocusLevel::Focused {
+            self.score_sum += focus_score as f64;
+            self.score_count += 1;
+            self.score_min = self.score_min.min(focus_score);
+            self.score_max = self.score_max.max(focus_score);
+        }
+    }
+
+    /// 结束会话，生成会话记录
+    ///
+    /// 没有专注采样时分数统计均为 0
+    pub fn finish(self, now_ms: i64, total_focus_ms: u64) -> FocusSession {
+        self.snapshot(now_ms, total_focus_ms)
+    }
+
+    /// 截至 `now_ms` 的会话记录（不结束会话）
+    ///
+    /// 已保存过的会话带有数据库中的记录 ID，否则 ID 为 0
+    pub fn snapshot(&self, now_ms: i64, total_focus_ms: u64) -> FocusSession {
+        let (avg, min, max) = if self.score_count > 0 {
+            (
+                (self.score_sum / self.score_count as f64) as f32,
+                self.score_min,
+                self.score_max,
+            )
+        } else {
+            (0.0, 0.0, 0.0)
+        };
+
+        FocusSession {
+            id: self.saved_id.unwrap_or(0),
+            start_time: self.start_time,
+            end_time: now_ms.max(self.start_time),
+            focus_duration_ms: total_focus_ms.saturating_sub(self.start_focus_ms) as i64,
+            distracted_duration_ms: self.distracted_ms,
+            avg_focus_score: avg,
+            min_focus_score: min,
+            max_focus_score: max,
+        }
+    }
+
+    /// 本次会话（或上一次重置后）的最高专注分数，没有采样时为 None
+    pub fn peak(&self) -> Option<SessionPeak> {
+        self.peak
+    }
+
+    /// 重置最高专注分数，之后的采样重新计算
+    pub fn reset_peak(&mut self) {
+        self.peak = None;
+    }
+
+    /// 由会话记录生成回顾，`previous_best_ms` 为之前会话的最长专注时长
+    pub fn recap(&self, session: &FocusSession, previous_best_ms: Option<i64>) -> SessionRecap {
+        SessionRecap {
+            duration_ms: session.end_time - session.start_time,
+            focus_ms: session.focus_duration_ms,
+            avg_focus_score: session.avg_focus_score,
+            peak_score: self.peak.map_or(0.0, |peak| peak.peak_score),
+            distraction_count: self.distraction_count,
+            personal_best: session.focus_duration_ms > previous_best_ms.unwrap_or(0),
+        }
+    }
+
+    /// 已写入数据库的会话记录 ID
+    pub fn saved_id(&self) -> Option<i64> {
+        self.saved_id
+    }
+
+    /// 会话记录中上一次保存后新增的 (专注时长, 分心时长)
+    pub fn unsaved_durations(&self, session: &FocusSession) -> (i64, i64) {
+        (
+            session.focus_duration_ms - self.saved_focus_ms,
+            session.distracted_duration_ms - self.saved_distracted_ms,
+        )
+    }
+
+    /// 记录会话已保存到数据库
+    pub fn mark_saved(&mut self, session: &FocusSession) {
+        self.saved_id = Some(session.id);
+        self.saved_focus_ms = session.focus_duration_ms;
+        self.saved_distracted_ms = session.distracted_duration_ms;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Database;
+
+    #[test]
+    fn test_session_score_stats() {
+        let mut tracker = SessionTracker::start(1_000, 500);
+
+        tracker.record(1_000, 0.2, FocusLevel::Distracted);
+        tracker.record(1_100, 0.9, FocusLevel::Focused);
+        tracker.record(1_200, 0.7, FocusLevel::Focused);
+        tracker.record(1_300, 0.8, FocusLevel::Focused);
+        tracker.record(1_400, 0.1, FocusLevel::Distracted);
+
+        let session = tracker.finish(2_000, 3_500);
+        assert_eq!(session.focus_duration_ms, 3_000);
+        assert_eq!(session.distracted_duration_ms, 100);
+        assert!((session.avg_focus_score - 0.8).abs() < 1e-5);
+        assert_eq!(session.min_focus_score, 0.7);
+        assert_eq!(session.max_focus_score, 0.9);
+
+        // 写入数据库后统计保持一致
+        let db = Database::in_memory().unwrap();
+        db.insert_session(&session).unwrap();
+        let stored = &db.get_recent_sessions(1).unwrap()[0];
+        assert!((stored.avg_focus_score - 0.8).abs() < 1e-5);
+        assert_eq!(stored.min_focus_score, 0.7);
+        assert_eq!(stored.max_focus_score, 0.9);
+    }
+
+    #[test]
+    fn test_session_peak() {
+        let mut tracker = SessionTracker::start(0, 0);
+        assert_eq!(tracker.peak(), None);
+
+        let scores = [0.3, 0.5, 0.8, 0.95, 0.95, 0.7, 0.4];
+        for (i, score) in scores.into_iter().enumerate() {
+            let level = if score > 0.75 { FocusLevel::Focused } else { FocusLevel::Distracted };
+            tracker.record(i as i64 * 1_000, score, level);
+        }
+        // 相同的最高分数保留第一次出现的时间
+        assert_eq!(
+            tracker.peak(),
+            Some(SessionPeak {
+                peak_score: 0.95,
+                at_ms: 3_000,
+            })
+        );
+
+        tracker.reset_peak();
+        assert_eq!(tracker.peak(), None);
+        tracker.record(8_000, 0.6, FocusLevel::Distracted);
+        assert_eq!(tracker.peak().unwrap().at_ms, 8_000);
+
+        // 新会话重新计算
+        assert_eq!(SessionTracker::start(9_000, 0).peak(), None);
+    }
+
+    #[test]
+    fn test_session_without_focus() {
+        let mut tracker = SessionTracker::start(0, 0);
+        tracker.record(0, 0.1, FocusLevel::Away);
+
+        let session = tracker.finish(100, 0);
+        assert_eq!(session.avg_focus_score, 0.0);
+        assert_eq!(session.min_focus_score, 0.0);
+        assert_eq!(session.max_focus_score, 0.0);
+    }
+}
@@ -0,0 +1,111 @@
+//! 每分钟专注分数
+//! 把专注分数按分钟取平均，保留最近 60 分钟，供界面绘制迷你折线图（不需要保存每个采样）
+
+use std::collections::VecDeque;
+
+/// 保留的分钟数
+pub const SPARKLINE_MINUTES: usize = 60;
+
+/// 一分钟内的采样汇总
+#[derive(Debug, Clone, Copy)]
+struct MinuteBucket {
+    /// 分钟序号（时间戳 / 60000）
+    minute: i64,
+    /// 分数之和
+    sum: f64,
+    /// 采样数
+    count: u32,
+}
+
+/// 最近 60 分钟的每分钟平均专注分数
+///
+/// 只记录有采样的分钟（视觉检测停止期间没有数据点）
+#[derive(Debug, Clone, Default)]
+pub struct FocusSparkline {
+    /// 按时间递增
+    buckets: VecDeque<MinuteBucket>,
+}
+
+impl FocusSparkline {
+    /// 加入一个采样；进入新的一分钟时开始新的数据点，超出 60 个时丢弃最旧的
+    pub fn push(&mut self, timestamp_ms: i64, score: f32) {
+        let minute = timestamp_ms.div_euclid(60_000);
+        match self.buckets.back_mut() {
+            // 时钟回拨时并入当前分钟，保持数据点按时间递增
+            Some(bucket) if minute <= bucket.minute => {
+                bucket.sum += score as f64;
+                bucket.count += 1;
+            }
+            _ => {
+                if self.buckets.len() == SPARKLINE_MINUTES {
+                    self.buckets.pop_front();
+                }
+                self.buckets.push_back(MinuteBucket {
+                    minute,
+                    sum: score as f64,
+                    count: 1,
+                });
+            }
+        }
+    }
+
+    /// 数据点个数
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// 是否没有数据点
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// 数据点占用的字节数（估算）
+    pub fn estimated_bytes(&self) -> u64 {
+        super::buffers::bytes_of::<MinuteBucket>(self.buckets.len())
+    }
+
+    /// 数据点超过 `limit` 个时丢弃最旧的，返回丢弃的数量
+    pub fn evict_oldest(&mut self, limit: usize) -> usize {
+        super::buffers::evict_oldest(&mut self.buckets, limit)
+    }
+
+    /// 每分钟的平均分数（最多 60 个，最近的在最后，包括进行中的这一分钟）
+    pub fn values(&self) -> Vec<f32> {
+        self.buckets
+            .iter()
+            .map(|bucket| (bucket.sum / bucket.count as f64) as f32)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_minute_buckets() {
+        let mut sparkline = FocusSparkline::default();
+        assert!(sparkline.values().is_empty());
+
+        // 同一分钟内的采样合并为一个数据点
+        sparkline.push(60_000, 0.2);
+        sparkline.push(90_000, 0.4);
+        sparkline.push(119_999, 0.6);
+        assert_eq!(sparkline.values().len(), 1);
+        assert!((sparkline.values()[0] - 0.4).abs() < 1e-6);
+
+        // 跨过分钟边界开始新的数据点
+        sparkline.push(120_000, 1.0);
+        assert_eq!(sparkline.values().len(), 2);
+        assert_eq!(sparkline.values()[1], 1.0);
+
+        // 最多保留 60 分钟，最近的在最后
+        for minute in 3..100 {
+            sparkline.push(minute * 60_000, minute as f32 / 100.0);
+        }
+        let values = sparkline.values();
+        assert_eq!(values.len(), SPARKLINE_MINUTES);
+        assert_eq!(values[0], 0.40);
+        assert_eq!(*values.last().unwrap(), 0.99);
+    }
+}
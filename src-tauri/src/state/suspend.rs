@@ -0,0 +1,60 @@
+//! 系统睡眠唤醒检测
+//!
+//! Tauri 没有跨平台的睡眠/唤醒事件。后台任务按固定间隔记录墙钟时间，两次检查之间的间隔
+//! 远超检查间隔时视为系统刚从睡眠中唤醒：睡眠期间摄像头句柄、视觉处理线程和各种计时都处于
+//! 不确定状态，需要停止旧的视觉检测、重置状态机的计时，并按配置重新启动。
+//! 与状态机的时间跳变检测互补：后者只在视觉检测运行且有新帧时才会触发。
+
+use serde::{Deserialize, Serialize};
+
+/// 唤醒检测的检查间隔（毫秒）
+pub const SUSPEND_CHECK_INTERVAL_MS: i64 = 5_000;
+
+/// 两次检查之间多出的时间超过该值（毫秒）时视为睡眠唤醒
+const SUSPEND_GAP_MS: i64 = 30_000;
+
+/// `system_resumed` 事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumeReport {
+    /// 推测的睡眠时长（毫秒）
+    pub slept_ms: i64,
+    /// 睡眠前视觉检测是否在运行
+    pub was_running: bool,
+    /// 重新检查摄像头时的错误（睡眠前未运行时不检查）
+    pub camera_error: Option<String>,
+    /// 是否将重新启动视觉检测
+    pub restart: bool,
+}
+
+/// 睡眠唤醒检测
+#[derive(Debug, Clone, Default)]
+pub struct SuspendDetector {
+    /// 上一次检查的墙钟时间（Unix 时间戳，毫秒）
+    last_check_ms: Option<i64>,
+}
+
+impl SuspendDetector {
+    /// 记录一次检查，检测到睡眠唤醒时返回推测的睡眠时长（毫秒）
+    pub fn observe(&mut self, now_ms: i64) -> Option<i64> {
+        let last = self.last_check_ms.replace(now_ms)?;
+        let gap = now_ms - last;
+        (gap - SUSPEND_CHECK_INTERVAL_MS > SUSPEND_GAP_MS).then_some(gap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspend_detector() {
+        let mut detector = SuspendDetector::default();
+        assert_eq!(detector.observe(0), None);
+        // 正常的检查间隔（含少量延迟）
+        assert_eq!(detector.observe(5_000), None);
+        assert_eq!(detector.observe(11_500), None);
+        // 睡眠十分钟后唤醒
+        assert_eq!(detector.observe(611_500), Some(600_000));
+        assert_eq!(detector.observe(616_500), None);
+    }
+}
@@ -0,0 +1,188 @@
+//! 专注分数趋势
+//! 对最近几秒的专注分数做最小二乘直线拟合，按斜率判断分数在上升、下降还是持平，供界面显示上下箭头
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 默认的趋势窗口（毫秒）
+pub const DEFAULT_TREND_WINDOW_MS: i64 = 5_000;
+
+/// 判断趋势所需的最少采样数，不足时总是返回持平
+const MIN_TREND_SAMPLES: usize = 5;
+
+/// 斜率超过该值（每秒分数变化）才视为上升或下降
+const TREND_SLOPE_THRESHOLD: f32 = 0.02;
+
+/// 趋势方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trend {
+    /// 上升
+    Rising,
+    /// 下降
+    Falling,
+    /// 持平（或采样不足）
+    Steady,
+}
+
+/// 专注分数趋势
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusTrend {
+    /// 趋势方向
+    pub trend: Trend,
+    /// 拟合斜率（每秒分数变化）
+    pub slope: f32,
+    /// 参与计算的采样数
+    pub samples: usize,
+}
+
+/// 最近一段时间的专注分数采样
+#[derive(Debug, Clone)]
+pub struct FocusTrendWindow {
+    /// 窗口长度（毫秒）
+    window_ms: i64,
+    /// 采样 (时间戳毫秒, 分数)，按时间递增
+    samples: VecDeque<(i64, f32)>,
+}
+
+impl FocusTrendWindow {
+    /// 创建指定窗口长度的采样窗口
+    pub fn new(window_ms: i64) -> Self {
+        Self {
+            window_ms,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// 加入一个采样，并丢弃窗口外的旧采样
+    pub fn push(&mut self, timestamp_ms: i64, score: f32) {
+        self.samples.push_back((timestamp_ms, score));
+        self.expire(timestamp_ms);
+    }
+
+    /// 丢弃早于 `now_ms - window_ms` 的采样
+    fn expire(&mut self, now_ms: i64) {
+        while let Some(&(timestamp, _)) = self.samples.front() {
+            if now_ms - timestamp <= self.window_ms {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// 采样数
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// 是否没有采样
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// 采样占用的字节数（估算）
+    pub fn estimated_bytes(&self) -> u64 {
+        super::buffers::bytes_of::<(i64, f32)>(self.samples.len())
+    }
+
+    /// 采样超过 `limit` 个时丢弃最旧的，返回丢弃的数量
+    pub fn evict_oldest(&mut self, limit: usize) -> usize {
+        super::buffers::evict_oldest(&mut self.samples, limit)
+    }
+
+    /// 截至 `now_ms` 的趋势（只使用窗口内的采样）
+    pub fn trend(&self, now_ms: i64) -> FocusTrend {
+        let points: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .filter(|(timestamp, _)| now_ms - timestamp <= self.window_ms)
+            .map(|&(timestamp, score)| (timestamp as f64 / 1000.0, score as f64))
+            .collect();
+
+        let slope = least_squares_slope(&points).unwrap_or(0.0) as f32;
+        let trend = if points.len() < MIN_TREND_SAMPLES {
+            Trend::Steady
+        } else if slope > TREND_SLOPE_THRESHOLD {
+            Trend::Rising
+        } else if slope < -TREND_SLOPE_THRESHOLD {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        };
+
+        FocusTrend {
+            trend,
+            slope,
+            samples: points.len(),
+        }
+    }
+}
+
+impl Default for FocusTrendWindow {
+    fn default() -> Self {
+        Self::new(DEFAULT_TREND_WINDOW_MS)
+    }
+}
+
+/// 最小二乘拟合的斜率（采样少于 2 个或时间都相同时返回 None）
+fn least_squares_slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (x, y) in points {
+        cov += (x - mean_x) * (y - mean_y);
+        var += (x - mean_x) * (x - mean_x);
+    }
+    (var > 0.0).then(|| cov / var)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trend_of(scores: &[f32]) -> FocusTrend {
+        let mut window = FocusTrendWindow::default();
+        for (i, &score) in scores.iter().enumerate() {
+            window.push(i as i64 * 500, score);
+        }
+        window.trend((scores.len() as i64 - 1) * 500)
+    }
+
+    #[test]
+    fn test_trend_classification() {
+        let rising = trend_of(&[0.3, 0.35, 0.4, 0.45, 0.5, 0.55, 0.6]);
+        assert_eq!(rising.trend, Trend::Rising);
+        assert!((rising.slope - 0.1).abs() < 1e-4);
+
+        let falling = trend_of(&[0.9, 0.8, 0.85, 0.7, 0.6, 0.55, 0.5]);
+        assert_eq!(falling.trend, Trend::Falling);
+        assert!(falling.slope < 0.0);
+
+        // 轻微抖动视为持平
+        let flat = trend_of(&[0.7, 0.71, 0.69, 0.7, 0.71, 0.7, 0.69]);
+        assert_eq!(flat.trend, Trend::Steady);
+
+        // 采样不足时不报告方向
+        let few = trend_of(&[0.1, 0.5, 0.9]);
+        assert_eq!(few.trend, Trend::Steady);
+        assert_eq!(few.samples, 3);
+    }
+
+    #[test]
+    fn test_trend_window_expires() {
+        let mut window = FocusTrendWindow::new(2_000);
+        for i in 0..10 {
+            window.push(i * 500, 0.1 * i as f32);
+        }
+        assert_eq!(window.trend(4_500).samples, 5);
+
+        // 很久没有新采样：窗口内没有数据，视为持平
+        let stale = window.trend(60_000);
+        assert_eq!(stale.trend, Trend::Steady);
+        assert_eq!(stale.samples, 0);
+    }
+}
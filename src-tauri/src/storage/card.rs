@@ -0,0 +1,248 @@
+//! 专注分享卡片
+//!
+//! 把某一天的专注总时长、连续专注天数和按小时分布的柱状图绘制成 PNG 图片。
+//! 文字使用内置的 5x7 点阵字体，只覆盖卡片用到的字符，不依赖字体文件。
+
+use super::hour_profile::BucketGranularity;
+use super::Database;
+use chrono::NaiveDate;
+use image::{ImageFormat, Rgb, RgbImage};
+use rusqlite::Result as SqliteResult;
+use std::io::Cursor;
+
+/// 卡片宽度（像素）
+pub const CARD_WIDTH: u32 = 480;
+
+/// 卡片高度（像素）
+pub const CARD_HEIGHT: u32 = 270;
+
+/// 背景色
+const BACKGROUND: Rgb<u8> = Rgb([255, 244, 230]);
+
+/// 文字颜色
+const TEXT: Rgb<u8> = Rgb([92, 64, 51]);
+
+/// 柱状图颜色
+const BAR: Rgb<u8> = Rgb([255, 153, 102]);
+
+/// 柱状图底色
+const BAR_TRACK: Rgb<u8> = Rgb([245, 222, 200]);
+
+/// 卡片数据
+#[derive(Debug, Clone)]
+struct CardData {
+    /// 总专注时长（毫秒）
+    total_focus_ms: i64,
+    /// 连续专注天数
+    streak: u32,
+    /// 每小时的专注时长（毫秒）
+    hourly_focus_ms: [i64; 24],
+}
+
+impl Database {
+    /// 绘制指定日期的专注卡片，返回 PNG 数据
+    ///
+    /// 没有专注记录的日期绘制“NO DATA”卡片
+    pub fn render_focus_card(&self, date: &str) -> SqliteResult<Vec<u8>> {
+        let total_focus_ms = self
+            .get_stats_by_date(date)?
+            .map(|stats| stats.total_focus_ms)
+            .unwrap_or(0);
+
+        let data = if total_focus_ms > 0 {
+            let streak = match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                Ok(day) => self.focus_streak(day)?,
+                Err(_) => 0,
+            };
+            Some(CardData {
+                total_focus_ms,
+                streak,
+                hourly_focus_ms: self.hourly_focus_ms(date)?,
+            })
+        } else {
+            None
+        };
+
+        Ok(encode_png(&draw_card(date, data.as_ref())))
+    }
+
+    /// 指定日期每小时的专注时长（毫秒）
+    ///
+    /// 会话的专注时长按会话与每个小时重叠的比例分摊
+    pub fn hourly_focus_ms(&self, date: &str) -> SqliteResult<[i64; 24]> {
+        let hours = self.focus_by_bucket_ms(date, BucketGranularity::Hour)?;
+        Ok(hours.try_into().unwrap_or([0; 24]))
+    }
+}
+
+/// 绘制卡片
+fn draw_card(date: &str, data: Option<&CardData>) -> RgbImage {
+    let mut image = RgbImage::from_pixel(CARD_WIDTH, CARD_HEIGHT, BACKGROUND);
+
+    draw_text(&mut image, "FOCUS", 24, 20, 3);
+    draw_text(
+        &mut image,
+        date,
+        CARD_WIDTH.saturating_sub(24 + text_width(date, 2)),
+        26,
+        2,
+    );
+
+    let Some(data) = data else {
+        let text = "NO DATA";
+        draw_text(
+            &mut image,
+            text,
+            CARD_WIDTH.saturating_sub(text_width(text, 6)) / 2,
+            110,
+            6,
+        );
+        return image;
+    };
+
+    let minutes = data.total_focus_ms / 60_000;
+    draw_text(
+        &mut image,
+        &format!("{}H {}M", minutes / 60, minutes % 60),
+        24,
+        70,
+        6,
+    );
+    draw_text(
+        &mut image,
+        &format!("STREAK {} DAYS", data.streak),
+        24,
+        130,
+        2,
+    );
+
+    // 每小时柱状图，满格为 60 分钟
+    let (left, bottom, bar_height) = (24u32, CARD_HEIGHT - 20, 80u32);
+    let slot = CARD_WIDTH.saturating_sub(2 * left) / 24;
+    for (hour, &focus_ms) in data.hourly_focus_ms.iter().enumerate() {
+        let x = left + hour as u32 * slot;
+        let filled = (focus_ms.clamp(0, 3_600_000) as u64 * bar_height as u64 / 3_600_000) as u32;
+        fill_rect(
+            &mut image,
+            x + 2,
+            bottom - bar_height,
+            slot - 4,
+            bar_height,
+            BAR_TRACK,
+        );
+        fill_rect(&mut image, x + 2, bottom - filled, slot - 4, filled, BAR);
+    }
+
+    image
+}
+
+/// 编码为 PNG
+pub(super) fn encode_png(image: &RgbImage) -> Vec<u8> {
+    let mut buffer = Cursor::new(Vec::new());
+    // 写入内存缓冲区不会出现 IO 错误
+    image
+        .write_to(&mut buffer, ImageFormat::Png)
+        .expect("PNG encoding into memory");
+    buffer.into_inner()
+}
+
+/// 填充矩形（超出画布的部分忽略）
+fn fill_rect(image: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: Rgb<u8>) {
+    for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
+
+/// 文字宽度（像素）
+fn text_width(text: &str, scale: u32) -> u32 {
+    text.chars().count() as u32 * 6 * scale
+}
+
+/// 用点阵字体绘制文字，`scale` 为每个点的边长
+fn draw_text(image: &mut RgbImage, text: &str, x: u32, y: u32, scale: u32) {
+    for (i, c) in text.chars().enumerate() {
+        let Some(rows) = glyph(c) else {
+            continue;
+        };
+        let origin_x = x + i as u32 * 6 * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0x10 >> col) != 0 {
+                    fill_rect(
+                        image,
+                        origin_x + col * scale,
+                        y + row as u32 * scale,
+                        scale,
+                        scale,
+                        TEXT,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 5x7 点阵字形（每行低 5 位，高位在左），不支持的字符返回 None
+fn glyph(c: char) -> Option<[u8; 7]> {
+    Some(match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'Y' => [0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_session;
+
+    #[test]
+    fn test_render_focus_card_png() {
+        let db = Database::in_memory().unwrap();
+        let (day_start, _) = crate::storage::day_bounds_ms("2026-10-16").unwrap();
+        db.add_daily_stats("2026-10-16", 90 * 60_000, 0, 1, 90 * 60_000).unwrap();
+        // 9:30 - 11:00 的会话，分摊到 9 点和 10 点
+        db.insert_session(&test_session(day_start + 9 * 3_600_000 + 1_800_000, 90 * 60_000)).unwrap();
+
+        let hours = db.hourly_focus_ms("2026-10-16").unwrap();
+        assert_eq!(hours[9], 30 * 60_000);
+        assert_eq!(hours[10], 60 * 60_000);
+
+        // 有数据和无数据的日期都生成可解码的 PNG
+        for date in ["2026-10-16", "2026-10-01"] {
+            let png = db.render_focus_card(date).unwrap();
+            let image = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+            assert_eq!((image.width(), image.height()), (CARD_WIDTH, CARD_HEIGHT));
+        }
+
+        // 超出卡片宽度的日期文字不会让布局计算溢出
+        let image = draw_card(&"2026-10-16".repeat(10), None);
+        assert_eq!((image.width(), image.height()), (CARD_WIDTH, CARD_HEIGHT));
+    }
+}
@@ -0,0 +1,53 @@
+//! 已完成的专注挑战
+//!
+//! 每次挑战达到目标时记录一条：何时开始、何时完成、目标多长，用于回顾完成过的挑战。
+
+use super::Database;
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 一次已完成的挑战
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeRecord {
+    /// 记录 ID（写入前为 0）
+    pub id: i64,
+    /// 开始时间 (Unix 时间戳，毫秒)
+    pub started_at_ms: i64,
+    /// 完成时间 (Unix 时间戳，毫秒)
+    pub completed_at_ms: i64,
+    /// 目标专注时长（毫秒）
+    pub target_ms: i64,
+}
+
+impl Database {
+    /// 写入一条已完成的挑战
+    pub fn insert_challenge(&self, record: &ChallengeRecord) -> SqliteResult<i64> {
+        self.conn.execute(
+            "INSERT INTO challenges (started_at_ms, completed_at_ms, target_ms) VALUES (?1, ?2, ?3)",
+            (record.started_at_ms, record.completed_at_ms, record.target_ms),
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 最近完成的挑战（按完成时间倒序，最多 `limit` 条）
+    pub fn get_recent_challenges(&self, limit: u32) -> SqliteResult<Vec<ChallengeRecord>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, started_at_ms, completed_at_ms, target_ms
+            FROM challenges
+            ORDER BY completed_at_ms DESC, id DESC
+            LIMIT ?1
+            "#,
+        )?;
+
+        let rows = stmt.query_map([limit], |row| {
+            Ok(ChallengeRecord {
+                id: row.get(0)?,
+                started_at_ms: row.get(1)?,
+                completed_at_ms: row.get(2)?,
+                target_ms: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
@@ -0,0 +1,248 @@
+//! 专注教练
+//!
+//! 根据最近的会话和每日统计生成简短的建议，供宠物展示。
+//! 完全基于规则，结果只取决于数据库内容和参考日期。
+
+use super::report::session_hour;
+use super::Database;
+use chrono::NaiveDate;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+/// 参与分析的天数
+const ANALYSIS_DAYS: i64 = 14;
+
+/// 参与分析所需的最少会话数
+const MIN_SESSIONS: usize = 3;
+
+/// 判定为长会话的专注时长（分钟）
+const LONG_SESSION_MINUTES: i64 = 40;
+
+/// 判定为短会话的平均专注时长（分钟）
+const SHORT_SESSION_MINUTES: i64 = 15;
+
+/// 时段名称：夜间 / 上午 / 下午 / 晚上
+const PART_OF_DAY_NAMES: [&str; 4] = ["深夜", "上午", "下午", "晚上"];
+
+/// 教练建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tip {
+    /// 建议类型 ID（前端可据此选择图标或动画）
+    pub id: String,
+    /// 展示文案
+    pub message: String,
+}
+
+impl Tip {
+    fn new(id: &str, message: String) -> Self {
+        Self {
+            id: id.to_string(),
+            message,
+        }
+    }
+}
+
+impl Database {
+    /// 基于今天之前的数据生成建议
+    pub fn generate_coaching_tips(&self) -> SqliteResult<Vec<Tip>> {
+        self.generate_coaching_tips_for(&super::today_key())
+    }
+
+    /// 以指定日期为“今天”生成建议
+    pub fn generate_coaching_tips_for(&self, today: &str) -> SqliteResult<Vec<Tip>> {
+        let Ok(today_date) = NaiveDate::parse_from_str(today, "%Y-%m-%d") else {
+            return Ok(Vec::new());
+        };
+        let since = today_date - chrono::Duration::days(ANALYSIS_DAYS);
+
+        let sessions: Vec<_> = self
+            .get_recent_real_sessions(u32::MAX)?
+            .into_iter()
+            .filter(|s| {
+                super::report::session_date(s).is_some_and(|d| d > since && d <= today_date)
+            })
+            .collect();
+
+        let mut tips = Vec::new();
+
+        if sessions.len() >= MIN_SESSIONS {
+            // 平均会话时长过短
+            let avg_ms =
+                sessions.iter().map(|s| s.focus_duration_ms).sum::<i64>() / sessions.len() as i64;
+            if avg_ms < SHORT_SESSION_MINUTES * 60_000 {
+                tips.push(Tip::new(
+                    "short_sessions",
+                    format!(
+                        "你的专注平均只有 {} 分钟，试试先专注 20 分钟再休息",
+                        avg_ms / 60_000
+                    ),
+                ));
+            }
+
+            // 长会话后半程专注度下滑
+            let (long, short): (Vec<_>, Vec<_>) = sessions
+                .iter()
+                .partition(|s| s.focus_duration_ms >= LONG_SESSION_MINUTES * 60_000);
+            if long.len() >= 2 && short.len() >= 2 {
+                let avg_score = |list: &[&super::FocusSession]| {
+                    list.iter().map(|s| s.avg_focus_score).sum::<f32>() / list.len() as f32
+                };
+                if avg_score(&short) - avg_score(&long) > 0.1 {
+                    tips.push(Tip::new(
+                        "focus_dips_long_sessions",
+                        format!(
+                            "专注超过 {} 分钟后状态会下滑，记得在此之前休息一下",
+                            LONG_SESSION_MINUTES
+                        ),
+                    ));
+                }
+            }
+
+            // 最专注的时段
+            let mut by_part = [0i64; 4];
+            for session in &sessions {
+                if let Some(hour) = session_hour(session) {
+                    by_part[(hour / 6) as usize] += session.focus_duration_ms;
+                }
+            }
+            if let Some((best, _)) = by_part
+                .iter()
+                .enumerate()
+                .filter(|(_, ms)| **ms > 0)
+                .max_by_key(|(i, ms)| (**ms, std::cmp::Reverse(*i)))
+            {
+                tips.push(Tip::new(
+                    "best_time_of_day",
+                    format!(
+                        "你在{}最专注，把重要的工作安排在这个时段吧",
+                        PART_OF_DAY_NAMES[best]
+                    ),
+                ));
+            }
+        }
+
+        // 连续专注天数
+        let streak = self.focus_streak(today_date)?;
+        if streak >= 3 {
+            tips.push(Tip::new(
+                "streak_keep",
+                format!("已经连续专注 {} 天了，继续保持！", streak),
+            ));
+        } else if streak == 0 && self.get_lifetime_stats()?.total_days > 0 {
+            tips.push(Tip::new(
+                "streak_start",
+                "今天还没有专注记录，从一个短会话开始吧".to_string(),
+            ));
+        }
+
+        Ok(tips)
+    }
+
+    /// 截至指定日期的连续专注天数（今天还没有记录时从昨天开始计算）
+    ///
+    /// 被冻结的日子不计入天数，也不打断连续；示例数据不算专注
+    pub(super) fn focus_streak(&self, today: NaiveDate) -> SqliteResult<u32> {
+        let has_focus = |date: NaiveDate| -> SqliteResult<bool> {
+            let key = date.format("%Y-%m-%d").to_string();
+            Ok(self
+                .conn
+                .query_row(
+                    "SELECT 1 FROM daily_stats WHERE date = ?1 AND total_focus_ms > 0 AND demo = 0",
+                    [key],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some())
+        };
+        let is_frozen =
+            |date: NaiveDate| self.is_day_frozen(&date.format("%Y-%m-%d").to_string());
+
+        let mut day = if has_focus(today)? {
+            today
+        } else {
+            today - chrono::Duration::days(1)
+        };
+
+        let mut streak = 0;
+        loop {
+            if has_focus(day)? {
+                streak += 1;
+            } else if !is_frozen(day)? {
+                break;
+            }
+            day -= chrono::Duration::days(1);
+        }
+
+        Ok(streak)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{test_session, FocusSession};
+    use chrono::TimeZone;
+
+    fn session(date: &str, hour: u32, focus_minutes: i64, score: f32) -> FocusSession {
+        let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let start_time = chrono::Local
+            .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .timestamp_millis();
+        FocusSession {
+            avg_focus_score: score,
+            min_focus_score: score,
+            max_focus_score: score,
+            ..test_session(start_time, focus_minutes * 60_000)
+        }
+    }
+
+    fn tip_ids(tips: &[Tip]) -> Vec<&str> {
+        tips.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_short_sessions_and_streak() {
+        let db = Database::in_memory().unwrap();
+        for date in ["2026-10-14", "2026-10-15", "2026-10-16"] {
+            db.add_daily_stats(date, 20 * 60_000, 0, 2, 20 * 60_000).unwrap();
+            db.insert_session(&session(date, 9, 10, 0.8)).unwrap();
+        }
+        db.insert_session(&session("2026-10-16", 15, 5, 0.8))
+            .unwrap();
+
+        let tips = db.generate_coaching_tips_for("2026-10-16").unwrap();
+        assert_eq!(
+            tip_ids(&tips),
+            vec!["short_sessions", "best_time_of_day", "streak_keep"]
+        );
+        assert!(tips[1].message.contains("上午"));
+    }
+
+    #[test]
+    fn test_focus_dips_and_streak_start() {
+        let db = Database::in_memory().unwrap();
+        db.add_daily_stats("2026-10-10", 200 * 60_000, 0, 4, 200 * 60_000)
+            .unwrap();
+        db.insert_session(&session("2026-10-10", 14, 20, 0.9))
+            .unwrap();
+        db.insert_session(&session("2026-10-10", 15, 25, 0.85))
+            .unwrap();
+        db.insert_session(&session("2026-10-10", 19, 60, 0.6))
+            .unwrap();
+        db.insert_session(&session("2026-10-10", 20, 90, 0.65))
+            .unwrap();
+
+        let tips = db.generate_coaching_tips_for("2026-10-16").unwrap();
+        assert_eq!(
+            tip_ids(&tips),
+            vec![
+                "focus_dips_long_sessions",
+                "best_time_of_day",
+                "streak_start"
+            ]
+        );
+        assert!(tips[1].message.contains("晚上"));
+    }
+}
@@ -0,0 +1,181 @@
+//! 两天统计对比
+//!
+//! 基于 daily_stats 计算两天之间的专注差值，并给出一句话结论（“更专注 / 更分心 / 差不多”）。
+
+use super::{DailyStats, Database};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 对比结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// date_a 比 date_b 更专注
+    MoreFocused,
+    /// date_a 比 date_b 更分心
+    LessFocused,
+    /// 差别不大
+    AboutTheSame,
+}
+
+/// 判定结论使用的阈值
+#[derive(Debug, Clone, Copy)]
+pub struct CompareThresholds {
+    /// 专注时长差达到该值（毫秒）即视为有明显差别
+    pub min_focus_delta_ms: i64,
+    /// 专注占比差达到该值即视为有明显差别 (0.0 - 1.0)
+    pub min_ratio_delta: f32,
+}
+
+impl Default for CompareThresholds {
+    fn default() -> Self {
+        Self {
+            min_focus_delta_ms: 10 * 60 * 1000,
+            min_ratio_delta: 0.05,
+        }
+    }
+}
+
+/// 两天统计的对比结果（差值均为 date_a 减 date_b）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayComparison {
+    pub date_a: String,
+    pub date_b: String,
+    /// 专注时长差（毫秒）
+    pub focus_delta_ms: i64,
+    /// 专注占比差（专注 / (专注 + 分心)）
+    pub ratio_delta: f32,
+    /// 会话数差
+    pub session_delta: i32,
+    /// 结论
+    pub verdict: Verdict,
+    /// date_a 的用户备注
+    #[serde(default)]
+    pub note_a: Option<String>,
+    /// date_b 的用户备注
+    #[serde(default)]
+    pub note_b: Option<String>,
+}
+
+/// 专注占比，没有任何记录时为 0
+fn focus_ratio(stats: &DailyStats) -> f32 {
+    let total = stats.total_focus_ms + stats.total_distracted_ms;
+    if total <= 0 {
+        0.0
+    } else {
+        stats.total_focus_ms as f32 / total as f32
+    }
+}
+
+/// 没有记录的日期按全 0 处理
+fn empty_stats(date: &str) -> DailyStats {
+    DailyStats {
+        date: date.to_string(),
+        total_focus_ms: 0,
+        total_distracted_ms: 0,
+        session_count: 0,
+        longest_focus_ms: 0,
+    }
+}
+
+impl DayComparison {
+    /// 对比两天的统计
+    pub fn between(a: &DailyStats, b: &DailyStats, thresholds: &CompareThresholds) -> Self {
+        let focus_delta_ms = a.total_focus_ms - b.total_focus_ms;
+        let ratio_delta = focus_ratio(a) - focus_ratio(b);
+        let session_delta = a.session_count - b.session_count;
+
+        // 专注时长明显变化时以时长为准；否则看占比，且时长不能反向
+        let verdict = if focus_delta_ms >= thresholds.min_focus_delta_ms
+            || (ratio_delta >= thresholds.min_ratio_delta && focus_delta_ms >= 0)
+        {
+            Verdict::MoreFocused
+        } else if focus_delta_ms <= -thresholds.min_focus_delta_ms
+            || (ratio_delta <= -thresholds.min_ratio_delta && focus_delta_ms <= 0)
+        {
+            Verdict::LessFocused
+        } else {
+            Verdict::AboutTheSame
+        };
+
+        Self {
+            date_a: a.date.clone(),
+            date_b: b.date.clone(),
+            focus_delta_ms,
+            ratio_delta,
+            session_delta,
+            verdict,
+            note_a: None,
+            note_b: None,
+        }
+    }
+}
+
+impl Database {
+    /// 指定日期的统计，没有记录时返回全 0
+    pub fn stats_or_empty(&self, date: &str) -> SqliteResult<DailyStats> {
+        Ok(self
+            .get_stats_by_date(date)?
+            .unwrap_or_else(|| empty_stats(date)))
+    }
+
+    /// 对比两天的统计（差值为 date_a 减 date_b），带上两天的备注
+    pub fn compare_days(
+        &self,
+        date_a: &str,
+        date_b: &str,
+        thresholds: &CompareThresholds,
+    ) -> SqliteResult<DayComparison> {
+        let a = self.stats_or_empty(date_a)?;
+        let b = self.stats_or_empty(date_b)?;
+        Ok(DayComparison {
+            note_a: self.get_day_note(date_a)?,
+            note_b: self.get_day_note(date_b)?,
+            ..DayComparison::between(&a, &b, thresholds)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_days() {
+        let db = Database::in_memory().unwrap();
+        let thresholds = CompareThresholds::default();
+        db.add_daily_stats("2026-10-15", 60 * 60_000, 60 * 60_000, 2, 60 * 60_000)
+            .unwrap();
+        db.add_daily_stats("2026-10-16", 90 * 60_000, 30 * 60_000, 3, 90 * 60_000)
+            .unwrap();
+
+        let cmp = db
+            .compare_days("2026-10-16", "2026-10-15", &thresholds)
+            .unwrap();
+        assert_eq!(cmp.focus_delta_ms, 30 * 60_000);
+        assert!((cmp.ratio_delta - 0.25).abs() < 1e-6);
+        assert_eq!(cmp.session_delta, 1);
+        assert_eq!(cmp.verdict, Verdict::MoreFocused);
+
+        let reverse = db
+            .compare_days("2026-10-15", "2026-10-16", &thresholds)
+            .unwrap();
+        assert_eq!(reverse.verdict, Verdict::LessFocused);
+
+        // 没有记录的日期按 0 处理
+        let missing = db
+            .compare_days("2026-10-14", "2026-10-15", &thresholds)
+            .unwrap();
+        assert_eq!(missing.focus_delta_ms, -60 * 60_000);
+        assert_eq!(missing.session_delta, -2);
+        assert_eq!(missing.verdict, Verdict::LessFocused);
+
+        // 差别低于阈值
+        db.add_daily_stats("2026-10-13", 62 * 60_000, 58 * 60_000, 2, 62 * 60_000)
+            .unwrap();
+        let same = db
+            .compare_days("2026-10-13", "2026-10-15", &thresholds)
+            .unwrap();
+        assert_eq!(same.verdict, Verdict::AboutTheSame);
+    }
+}
@@ -0,0 +1,90 @@
+//! 每日备注
+//!
+//! 用户可以给某一天写一句备注（“生病”“出差”），查看统计时能看到当天专注偏少的原因。
+
+use super::{DailyStats, Database, GoalProgress};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+/// 备注的最大长度（字符）
+pub const MAX_DAY_NOTE_CHARS: usize = 500;
+
+/// 单日摘要：统计、目标进度和备注
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayDigest {
+    /// 日期 (YYYY-MM-DD 格式)
+    pub date: String,
+    /// 当天统计（没有记录时全为 0）
+    pub stats: DailyStats,
+    /// 目标进度
+    pub goal: GoalProgress,
+    /// 用户备注
+    pub note: Option<String>,
+}
+
+impl Database {
+    /// 设置指定日期的备注，备注为空时删除
+    pub fn set_day_note(&self, date: &str, note: &str) -> SqliteResult<()> {
+        let note = note.trim();
+        if note.is_empty() {
+            self.conn.execute("DELETE FROM day_notes WHERE date = ?1", [date])?;
+        } else {
+            self.conn.execute(
+                "INSERT INTO day_notes (date, note) VALUES (?1, ?2)
+                 ON CONFLICT(date) DO UPDATE SET note = excluded.note",
+                (date, note),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// 指定日期的备注
+    pub fn get_day_note(&self, date: &str) -> SqliteResult<Option<String>> {
+        self.conn
+            .query_row("SELECT note FROM day_notes WHERE date = ?1", [date], |row| row.get(0))
+            .optional()
+    }
+
+    /// 指定日期的摘要
+    pub fn day_digest(&self, date: &str) -> SqliteResult<DayDigest> {
+        Ok(DayDigest {
+            date: date.to_string(),
+            stats: self.stats_or_empty(date)?,
+            goal: self.get_goal_progress(date)?,
+            note: self.get_day_note(date)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::compare::CompareThresholds;
+
+    #[test]
+    fn test_day_notes() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(db.get_day_note("2026-10-14").unwrap(), None);
+
+        db.set_day_note("2026-10-14", "  sick  ").unwrap();
+        assert_eq!(db.get_day_note("2026-10-14").unwrap().as_deref(), Some("sick"));
+        db.set_day_note("2026-10-14", "traveling").unwrap();
+        assert_eq!(db.get_day_note("2026-10-14").unwrap().as_deref(), Some("traveling"));
+
+        // 摘要和两天对比都带上备注
+        db.add_daily_stats("2026-10-14", 10 * 60_000, 0, 1, 10 * 60_000).unwrap();
+        let digest = db.day_digest("2026-10-14").unwrap();
+        assert_eq!(digest.note.as_deref(), Some("traveling"));
+        assert_eq!(digest.stats.total_focus_ms, 10 * 60_000);
+        assert_eq!(digest.goal.achieved_ms, 10 * 60_000);
+        let cmp = db
+            .compare_days("2026-10-15", "2026-10-14", &CompareThresholds::default())
+            .unwrap();
+        assert_eq!(cmp.note_a, None);
+        assert_eq!(cmp.note_b.as_deref(), Some("traveling"));
+
+        // 空备注删除
+        db.set_day_note("2026-10-14", "").unwrap();
+        assert_eq!(db.day_digest("2026-10-14").unwrap().note, None);
+    }
+}
@@ -0,0 +1,131 @@
+//! 示例数据
+//!
+//! 新用户第一次打开统计页面时什么都没有。这里可以写入最近一周的模拟会话和每日统计，
+//! 让界面在引导阶段有内容可看。示例数据带有 `demo` 标记，不计入连续专注、累计统计和教练建议，
+//! 重置全部数据（`reset_all_data`）时一并清除。
+
+use super::{day_bounds_ms, Database, FocusSession};
+use chrono::{Datelike, NaiveDate};
+use rusqlite::Result as SqliteResult;
+
+/// 写入示例数据的天数（截至昨天）
+pub const DEMO_DAYS: u32 = 7;
+
+/// 示例会话的备注
+pub const DEMO_LABEL: &str = "示例数据";
+
+/// 一天中的示例会话：(开始小时, 专注分钟, 分心分钟, 平均专注分数)
+const DEMO_DAY: &[(i64, i64, i64, f32)] = &[
+    (9, 45, 6, 0.82),
+    (11, 30, 8, 0.74),
+    (14, 25, 12, 0.63),
+    (16, 40, 5, 0.79),
+];
+
+impl Database {
+    /// 写入截至 `today` 前一天的示例数据，返回写入的天数
+    ///
+    /// 已经有统计记录的日子跳过，不会覆盖真实数据
+    pub fn seed_demo_data(&self, today: NaiveDate) -> SqliteResult<u32> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut seeded = 0;
+
+        for offset in 1..=DEMO_DAYS as i64 {
+            let day = today - chrono::Duration::days(offset);
+            let date = day.format("%Y-%m-%d").to_string();
+            let Some((day_start, _)) = day_bounds_ms(&date) else {
+                continue;
+            };
+            if self.get_stats_by_date(&date)?.is_some() {
+                continue;
+            }
+
+            // 周末和每隔几天的日子专注少一些，让趋势图有起伏
+            let scale = match (day.weekday().num_days_from_monday(), offset % 3) {
+                (5 | 6, _) => 0.5,
+                (_, 0) => 0.8,
+                _ => 1.0,
+            };
+
+            let (mut focus_total, mut distracted_total, mut longest) = (0, 0, 0);
+            for &(hour, focus_minutes, distracted_minutes, score) in DEMO_DAY {
+                let focus_ms = (focus_minutes as f64 * scale) as i64 * 60_000;
+                let distracted_ms = distracted_minutes * 60_000;
+                let start_time = day_start + hour * 3_600_000;
+                let session = FocusSession {
+                    id: 0,
+                    start_time,
+                    end_time: start_time + focus_ms + distracted_ms,
+                    focus_duration_ms: focus_ms,
+                    distracted_duration_ms: distracted_ms,
+                    avg_focus_score: score,
+                    min_focus_score: score - 0.2,
+                    max_focus_score: (score + 0.12).min(1.0),
+                };
+                let id = self.insert_session(&session)?;
+                tx.execute(
+                    "UPDATE sessions SET demo = 1, label = ?2 WHERE id = ?1",
+                    (id, DEMO_LABEL),
+                )?;
+                focus_total += focus_ms;
+                distracted_total += distracted_ms;
+                longest = longest.max(focus_ms);
+            }
+
+            tx.execute(
+                r#"
+                INSERT INTO daily_stats (date, total_focus_ms, total_distracted_ms, session_count, longest_focus_ms, demo)
+                VALUES (?1, ?2, ?3, ?4, ?5, 1)
+                "#,
+                (&date, focus_total, distracted_total, DEMO_DAY.len() as i64, longest),
+            )?;
+            seeded += 1;
+        }
+
+        tx.commit()?;
+        tracing::info!("Seeded {} days of demo data", seeded);
+        Ok(seeded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_demo_data() {
+        let db = Database::in_memory().unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        // 已有真实记录的日子不被覆盖
+        db.add_daily_stats("2026-10-15", 10 * 60_000, 0, 1, 10 * 60_000).unwrap();
+
+        assert_eq!(db.seed_demo_data(today).unwrap(), DEMO_DAYS - 1);
+        let stats = db.get_recent_stats(30).unwrap();
+        assert_eq!(stats.len(), DEMO_DAYS as usize);
+        assert_eq!(stats[0].total_focus_ms, 10 * 60_000);
+        for day in &stats[1..] {
+            assert_eq!(day.session_count, DEMO_DAY.len() as i32);
+            assert!((60 * 60_000..=3 * 60 * 60_000).contains(&day.total_focus_ms), "{:?}", day);
+            assert!(day.longest_focus_ms <= day.total_focus_ms);
+        }
+        assert_eq!(stats.last().unwrap().date, "2026-10-09");
+        assert_eq!(db.get_recent_sessions(100).unwrap().len(), 6 * DEMO_DAY.len());
+
+        // 示例数据不计入连续专注、累计统计和教练建议
+        let lifetime = db.get_lifetime_stats().unwrap();
+        assert_eq!(lifetime.total_days, 1);
+        assert_eq!(lifetime.total_focus_ms, 10 * 60_000);
+        let streaks = db.list_streaks(0).unwrap();
+        assert_eq!(streaks.len(), 1);
+        assert_eq!(streaks[0].length_days, 1);
+        assert_eq!(db.focus_streak(today).unwrap(), 1);
+        assert!(db.get_recent_real_sessions(100).unwrap().is_empty());
+        let tips = db.generate_coaching_tips_for("2026-10-16").unwrap();
+        assert!(tips.iter().all(|tip| tip.id != "best_time_of_day" && tip.id != "streak_keep"));
+
+        // 重置全部数据时一并清除
+        db.reset_all_data().unwrap();
+        assert!(db.get_recent_stats(30).unwrap().is_empty());
+        assert!(db.get_recent_sessions(100).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! 诊断信息打包
+//!
+//! 反馈问题时把配置、运行状态、模型信息、最近的专注状态和采样日志打包成一个 zip，
+//! 用户直接附到 issue 上即可。除配置本身控制的内容外（例如未开启采样日志时没有日志），不做额外脱敏。
+
+use crate::config::AppConfig;
+use crate::vision::{FocusState, ModelInfo};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// 诊断包中的文件（按写入顺序）
+pub const DIAGNOSTICS_ENTRIES: [&str; 5] = [
+    "config.json",
+    "health.json",
+    "model_info.json",
+    "focus_states.json",
+    "focus_log.jsonl",
+];
+
+/// 诊断包中保留的最近专注状态数量
+pub const RECENT_FOCUS_STATES: usize = 30;
+
+/// 诊断包中保留的采样日志行数
+pub const RECENT_LOG_LINES: usize = 500;
+
+/// 运行状态检查
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheck {
+    /// 应用版本
+    pub app_version: String,
+    /// 操作系统
+    pub os: String,
+    /// 是否为模拟视觉模式（未启用 vision 特性）
+    pub mock_vision: bool,
+    /// 视觉检测是否正在运行
+    pub vision_running: bool,
+    /// 数据库是否可用
+    pub database_available: bool,
+    /// 数据目录
+    pub data_dir: Option<String>,
+    /// 运行中设置的摄像头帧率
+    pub camera_fps: Option<u32>,
+    /// 等待写入数据库的活动事件数
+    pub pending_events: usize,
+    /// 生成时间 (Unix 时间戳，毫秒)
+    pub generated_at_ms: i64,
+}
+
+/// 一次诊断收集的全部内容
+#[derive(Debug, Clone)]
+pub struct DiagnosticsBundle {
+    /// 当前配置
+    pub config: AppConfig,
+    /// 运行状态
+    pub health: HealthCheck,
+    /// 模型信息（找不到模型文件时为 None）
+    pub model_info: Option<ModelInfo>,
+    /// 最近的专注状态（从旧到新）
+    pub focus_states: Vec<FocusState>,
+    /// 采样日志末尾的行（未开启采样日志时为空）
+    pub focus_log_lines: Vec<String>,
+}
+
+impl DiagnosticsBundle {
+    /// 写入 zip 文件
+    pub fn write_zip(&self, path: &Path) -> io::Result<()> {
+        let mut zip = ZipWriter::new(BufWriter::new(File::create(path)?));
+        let options = SimpleFileOptions::default();
+
+        for name in DIAGNOSTICS_ENTRIES {
+            let content = match name {
+                "config.json" => serde_json::to_vec_pretty(&self.config)?,
+                "health.json" => serde_json::to_vec_pretty(&self.health)?,
+                "model_info.json" => serde_json::to_vec_pretty(&self.model_info)?,
+                "focus_states.json" => serde_json::to_vec_pretty(&self.focus_states)?,
+                _ => self
+                    .focus_log_lines
+                    .iter()
+                    .map(|line| format!("{}\n", line))
+                    .collect::<String>()
+                    .into_bytes(),
+            };
+            zip.start_file(name, options).map_err(io::Error::other)?;
+            zip.write_all(&content)?;
+        }
+
+        zip.finish().map_err(io::Error::other)?.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+
+    #[test]
+    fn test_diagnostics_zip_entries() {
+        let path = test_temp_path("diagnostics").with_extension("zip");
+
+        let bundle = DiagnosticsBundle {
+            config: AppConfig::default(),
+            health: HealthCheck {
+                app_version: "0.1.0".to_string(),
+                os: std::env::consts::OS.to_string(),
+                mock_vision: true,
+                vision_running: false,
+                database_available: true,
+                data_dir: None,
+                camera_fps: None,
+                pending_events: 0,
+                generated_at_ms: 0,
+            },
+            model_info: None,
+            focus_states: vec![FocusState::default()],
+            focus_log_lines: vec!["{\"focus_score\":0.5}".to_string()],
+        };
+        bundle.write_zip(&path).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        for name in DIAGNOSTICS_ENTRIES {
+            assert!(names.contains(&name), "missing {}", name);
+        }
+        assert_eq!(names.len(), DIAGNOSTICS_ENTRIES.len());
+
+        let mut log = String::new();
+        io::Read::read_to_string(&mut archive.by_name("focus_log.jsonl").unwrap(), &mut log)
+            .unwrap();
+        assert_eq!(log, "{\"focus_score\":0.5}\n");
+
+        std::fs::remove_file(&path).ok();
+    }
+}
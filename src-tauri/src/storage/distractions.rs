@@ -0,0 +1,169 @@
+//! 分心记录
+//!
+//! 每次从专注状态掉出都记录一条：何时开始、持续多久、因何触发，供用户回顾“今天什么时候分心了”。
+
+use super::Database;
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 分心触发原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistractionTrigger {
+    /// 人还在，但专注分数下降（转头、低头等）
+    LowFocus,
+    /// 人脸丢失（离开座位或离开画面）
+    FaceLost,
+}
+
+impl DistractionTrigger {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LowFocus => "low_focus",
+            Self::FaceLost => "face_lost",
+        }
+    }
+
+    /// 从数据库中存储的名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "low_focus" => Some(Self::LowFocus),
+            "face_lost" => Some(Self::FaceLost),
+            _ => None,
+        }
+    }
+}
+
+/// 一次分心
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DistractionEvent {
+    /// 记录 ID（写入前为 0）
+    pub id: i64,
+    /// 开始时间 (Unix 时间戳，毫秒)
+    pub start_ms: i64,
+    /// 持续时长（毫秒）
+    pub duration_ms: i64,
+    /// 触发原因
+    pub trigger: DistractionTrigger,
+}
+
+impl Database {
+    /// 写入一条分心记录
+    pub fn insert_distraction(&self, event: &DistractionEvent) -> SqliteResult<i64> {
+        self.conn.execute(
+            "INSERT INTO distractions (start_ms, duration_ms, trigger) VALUES (?1, ?2, ?3)",
+            (event.start_ms, event.duration_ms, event.trigger.as_str()),
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 最近的分心记录（按开始时间倒序，最多 `limit` 条）
+    ///
+    /// 无法识别的触发原因会被跳过
+    pub fn get_recent_distractions(&self, limit: u32) -> SqliteResult<Vec<DistractionEvent>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, start_ms, duration_ms, trigger
+            FROM distractions
+            ORDER BY start_ms DESC, id DESC
+            LIMIT ?1
+            "#,
+        )?;
+
+        let rows = stmt.query_map([limit], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (id, start_ms, duration_ms, trigger) = row?;
+            if let Some(trigger) = DistractionTrigger::parse(&trigger) {
+                events.push(DistractionEvent {
+                    id,
+                    start_ms,
+                    duration_ms,
+                    trigger,
+                });
+            }
+        }
+        Ok(events)
+    }
+
+    /// 与 `[start_ms, end_ms)` 有重叠的分心记录（按开始时间升序）
+    ///
+    /// 无法识别的触发原因会被跳过
+    pub fn get_distractions_between(&self, start_ms: i64, end_ms: i64) -> SqliteResult<Vec<DistractionEvent>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, start_ms, duration_ms, trigger
+            FROM distractions
+            WHERE start_ms + duration_ms > ?1 AND start_ms < ?2
+            ORDER BY start_ms, id
+            "#,
+        )?;
+
+        let rows = stmt.query_map([start_ms, end_ms], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (id, start_ms, duration_ms, trigger) = row?;
+            if let Some(trigger) = DistractionTrigger::parse(&trigger) {
+                events.push(DistractionEvent {
+                    id,
+                    start_ms,
+                    duration_ms,
+                    trigger,
+                });
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_distractions() {
+        let db = Database::in_memory().unwrap();
+        assert!(db.get_recent_distractions(10).unwrap().is_empty());
+
+        for (start_ms, trigger) in [
+            (1_000, DistractionTrigger::LowFocus),
+            (3_000, DistractionTrigger::FaceLost),
+            (2_000, DistractionTrigger::LowFocus),
+        ] {
+            db.insert_distraction(&DistractionEvent {
+                id: 0,
+                start_ms,
+                duration_ms: 500,
+                trigger,
+            })
+            .unwrap();
+        }
+
+        let recent = db.get_recent_distractions(10).unwrap();
+        let starts: Vec<i64> = recent.iter().map(|e| e.start_ms).collect();
+        assert_eq!(starts, vec![3_000, 2_000, 1_000]);
+        assert_eq!(recent[0].trigger, DistractionTrigger::FaceLost);
+        assert_eq!(recent[0].duration_ms, 500);
+
+        let limited = db.get_recent_distractions(2).unwrap();
+        assert_eq!(limited.len(), 2);
+        assert_eq!(limited[0].start_ms, 3_000);
+    }
+}
@@ -0,0 +1,132 @@
+//! 活动事件日志
+//!
+//! 记录情绪变化、手势、会话开始/结束和目标完成等事件，供统一的活动动态使用。
+//! 事件在内存中缓冲，由后台任务批量写入，避免阻塞视觉状态循环。
+
+use super::Database;
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// 宠物情绪变化
+    MoodChanged,
+    /// 手势互动
+    Gesture,
+    /// 专注会话开始
+    SessionStarted,
+    /// 专注会话结束
+    SessionEnded,
+    /// 完成今日目标
+    GoalCompleted,
+}
+
+impl EventKind {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MoodChanged => "mood_changed",
+            Self::Gesture => "gesture",
+            Self::SessionStarted => "session_started",
+            Self::SessionEnded => "session_ended",
+            Self::GoalCompleted => "goal_completed",
+        }
+    }
+
+    /// 从数据库中存储的名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "mood_changed" => Some(Self::MoodChanged),
+            "gesture" => Some(Self::Gesture),
+            "session_started" => Some(Self::SessionStarted),
+            "session_ended" => Some(Self::SessionEnded),
+            "goal_completed" => Some(Self::GoalCompleted),
+            _ => None,
+        }
+    }
+}
+
+/// 活动事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// 事件 ID（写入数据库前为 0）
+    pub id: i64,
+    /// 发生时间 (Unix 时间戳，毫秒)
+    pub timestamp_ms: i64,
+    /// 事件类型
+    pub kind: EventKind,
+    /// 事件详情
+    pub payload: serde_json::Value,
+}
+
+impl Event {
+    /// 创建发生在当前时刻的事件
+    pub fn now(kind: EventKind, payload: serde_json::Value) -> Self {
+        Self {
+            id: 0,
+            timestamp_ms: chrono::Local::now().timestamp_millis(),
+            kind,
+            payload,
+        }
+    }
+}
+
+impl Database {
+    /// 批量写入事件（同一个事务）
+    pub fn insert_events(&self, events: &[Event]) -> SqliteResult<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO events (timestamp_ms, kind, payload) VALUES (?1, ?2, ?3)")?;
+            for event in events {
+                stmt.execute((event.timestamp_ms, event.kind.as_str(), event.payload.to_string()))?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// 查询指定时间之后的事件（按时间升序，最多 `limit` 条）
+    ///
+    /// 无法识别的事件类型会被跳过
+    pub fn get_events(&self, since_ms: i64, limit: u32) -> SqliteResult<Vec<Event>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, timestamp_ms, kind, payload
+            FROM events
+            WHERE timestamp_ms >= ?1
+            ORDER BY timestamp_ms ASC, id ASC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let rows = stmt.query_map((since_ms, limit), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (id, timestamp_ms, kind, payload) = row?;
+            let Some(kind) = EventKind::parse(&kind) else {
+                continue;
+            };
+            events.push(Event {
+                id,
+                timestamp_ms,
+                kind,
+                payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+            });
+        }
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,294 @@
+//! 专注状态采样日志
+//!
+//! 用户同意后，把 `FocusState` 采样以 JSONL 格式追加写入数据目录，
+//! 用于后续改进专注度模型。文件按大小轮转，写入在独立线程中进行，
+//! 不会阻塞视觉处理循环。
+
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::time::Duration;
+
+/// 当前日志文件名
+const LOG_FILE_NAME: &str = "focus_log.jsonl";
+
+/// 写入线程的队列长度（队列满时丢弃采样，保证不阻塞）
+const QUEUE_CAPACITY: usize = 1024;
+
+/// 采样日志配置
+#[derive(Debug, Clone)]
+pub struct FocusLogConfig {
+    /// 日志目录
+    pub dir: PathBuf,
+    /// 单个文件最大字节数，超过后轮转
+    pub max_file_bytes: u64,
+    /// 保留的历史文件数量
+    pub max_files: usize,
+}
+
+impl FocusLogConfig {
+    /// 当前日志文件路径
+    pub fn current_path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE_NAME)
+    }
+
+    /// 第 n 个历史文件路径（1 为最新）
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        self.dir.join(format!("focus_log.{}.jsonl", n))
+    }
+
+    /// 所有现存日志文件，按从旧到新排列
+    pub fn files_oldest_first(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = (1..=self.max_files)
+            .rev()
+            .map(|n| self.rotated_path(n))
+            .filter(|p| p.exists())
+            .collect();
+
+        let current = self.current_path();
+        if current.exists() {
+            files.push(current);
+        }
+
+        files
+    }
+}
+
+/// 同步日志写入器（按大小轮转）
+pub struct FocusLogWriter {
+    config: FocusLogConfig,
+    writer: BufWriter<File>,
+    /// 当前文件已写入的字节数
+    written: u64,
+}
+
+impl FocusLogWriter {
+    /// 打开（或创建）日志文件
+    pub fn open(config: FocusLogConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        let (writer, written) = Self::open_current(&config)?;
+
+        Ok(Self {
+            config,
+            writer,
+            written,
+        })
+    }
+
+    fn open_current(config: &FocusLogConfig) -> io::Result<(BufWriter<File>, u64)> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(config.current_path())?;
+        let written = file.metadata()?.len();
+        Ok((BufWriter::new(file), written))
+    }
+
+    /// 追加一行
+    pub fn append_line(&mut self, line: &str) -> io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 + 1 > self.config.max_file_bytes {
+            self.rotate()?;
+        }
+
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// 刷新缓冲区
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// 轮转：current -> .1 -> .2 ...，超出保留数量的最旧文件被删除
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+
+        let oldest = self.config.rotated_path(self.config.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for n in (1..self.config.max_files).rev() {
+            let from = self.config.rotated_path(n);
+            if from.exists() {
+                fs::rename(&from, self.config.rotated_path(n + 1))?;
+            }
+        }
+        if self.config.max_files > 0 {
+            fs::rename(self.config.current_path(), self.config.rotated_path(1))?;
+        } else {
+            fs::remove_file(self.config.current_path())?;
+        }
+
+        let (writer, written) = Self::open_current(&self.config)?;
+        self.writer = writer;
+        self.written = written;
+
+        tracing::debug!("Focus log rotated");
+        Ok(())
+    }
+}
+
+/// 写入线程消息
+enum LogMessage {
+    /// 一行 JSON
+    Line(String),
+    /// 刷新缓冲区，完成后通知
+    Flush(SyncSender<()>),
+}
+
+/// 异步采样日志记录器
+///
+/// 采样通过有界队列发送到独立写入线程，队列满时直接丢弃
+pub struct FocusLogger {
+    config: FocusLogConfig,
+    tx: SyncSender<LogMessage>,
+}
+
+impl FocusLogger {
+    /// 启动写入线程
+    pub fn spawn(config: FocusLogConfig) -> io::Result<Self> {
+        let writer = FocusLogWriter::open(config.clone())?;
+        let (tx, rx) = mpsc::sync_channel(QUEUE_CAPACITY);
+
+        std::thread::spawn(move || Self::run_writer(writer, rx));
+
+        tracing::info!("Focus log enabled at {:?}", config.dir);
+        Ok(Self { config, tx })
+    }
+
+    fn run_writer(mut writer: FocusLogWriter, rx: Receiver<LogMessage>) {
+        while let Ok(message) = rx.recv() {
+            match message {
+                LogMessage::Line(line) => {
+                    if let Err(e) = writer.append_line(&line) {
+                        tracing::warn!("Failed to write focus log: {}", e);
+                    }
+                }
+                LogMessage::Flush(done) => {
+                    if let Err(e) = writer.flush() {
+                        tracing::warn!("Failed to flush focus log: {}", e);
+                    }
+                    let _ = done.send(());
+                }
+            }
+        }
+
+        let _ = writer.flush();
+        tracing::info!("Focus log writer stopped");
+    }
+
+    /// 记录一个采样（不阻塞）
+    pub fn log<T: Serialize>(&self, sample: &T) {
+        let Ok(line) = serde_json::to_string(sample) else {
+            return;
+        };
+
+        if let Err(TrySendError::Full(_)) = self.tx.try_send(LogMessage::Line(line)) {
+            tracing::trace!("Focus log queue full, dropping sample");
+        }
+    }
+
+    /// 等待写入线程把缓冲区刷到磁盘
+    pub fn flush(&self) {
+        let (done_tx, done_rx) = mpsc::sync_channel(1);
+        if self.tx.send(LogMessage::Flush(done_tx)).is_ok() {
+            let _ = done_rx.recv_timeout(Duration::from_secs(2));
+        }
+    }
+
+    /// 日志配置
+    pub fn config(&self) -> &FocusLogConfig {
+        &self.config
+    }
+}
+
+/// 把所有日志文件按时间顺序合并导出到指定路径
+///
+/// # Returns
+/// 导出的字节数
+pub fn export_focus_log(config: &FocusLogConfig, dest: &Path) -> io::Result<u64> {
+    let mut out = BufWriter::new(File::create(dest)?);
+    let mut total = 0;
+
+    for path in config.files_oldest_first() {
+        let mut file = File::open(&path)?;
+        total += io::copy(&mut file, &mut out)?;
+    }
+
+    out.flush()?;
+    Ok(total)
+}
+
+/// 读取日志末尾最多 `max_lines` 行（跨越轮转文件，按从旧到新排列）
+pub fn tail_focus_log(config: &FocusLogConfig, max_lines: usize) -> io::Result<Vec<String>> {
+    let mut lines = std::collections::VecDeque::with_capacity(max_lines);
+    for path in config.files_oldest_first() {
+        for line in fs::read_to_string(&path)?.lines() {
+            if lines.len() == max_lines {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+    }
+    Ok(lines.into_iter().take(max_lines).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = test_temp_path(name);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[derive(Serialize)]
+    struct Sample {
+        focus_score: f32,
+        timestamp_ms: u64,
+    }
+
+    #[test]
+    fn test_samples_written_and_rotated() {
+        let dir = temp_dir("focus_log");
+        let config = FocusLogConfig {
+            dir: dir.clone(),
+            max_file_bytes: 200,
+            max_files: 3,
+        };
+
+        let logger = FocusLogger::spawn(config.clone()).unwrap();
+        for i in 0..20 {
+            logger.log(&Sample {
+                focus_score: 0.5,
+                timestamp_ms: i,
+            });
+        }
+        logger.flush();
+
+        // 超过大小上限后发生了轮转
+        assert!(config.rotated_path(1).exists());
+        assert!(fs::metadata(config.current_path()).unwrap().len() <= 200);
+
+        // 导出内容是按顺序排列的完整 JSONL
+        let dest = dir.join("export.jsonl");
+        export_focus_log(&config, &dest).unwrap();
+        let exported = fs::read_to_string(&dest).unwrap();
+        let lines: Vec<&str> = exported.lines().collect();
+        assert!(!lines.is_empty());
+        for line in &lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(value.get("focus_score").is_some());
+        }
+        let last: serde_json::Value = serde_json::from_str(lines.last().unwrap()).unwrap();
+        assert_eq!(last["timestamp_ms"], 19);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,184 @@
+//! 按小时的专注分布
+//!
+//! 统计一段日期内每个小时平均专注多少分钟，用于找出一天中最专注的时段，
+//! 方便把需要深度专注的工作安排在这些时段。也可以按 15 分钟细分，查看一小时内的专注规律。
+
+use super::Database;
+use chrono::NaiveDate;
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 按时段统计的粒度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BucketGranularity {
+    /// 每小时一段（24 段）
+    #[default]
+    Hour,
+    /// 每 15 分钟一段（96 段），第 `hour * 4 + quarter` 段
+    QuarterHour,
+}
+
+impl BucketGranularity {
+    /// 每段的时长（毫秒）
+    pub fn bucket_ms(&self) -> i64 {
+        match self {
+            Self::Hour => 3_600_000,
+            Self::QuarterHour => 900_000,
+        }
+    }
+
+    /// 一天的段数
+    pub fn buckets_per_day(&self) -> usize {
+        (86_400_000 / self.bucket_ms()) as usize
+    }
+}
+
+impl Database {
+    /// 指定日期每段的专注时长（毫秒），会话的专注时长按会话与每段重叠的比例分摊
+    pub fn focus_by_bucket_ms(&self, date: &str, granularity: BucketGranularity) -> SqliteResult<Vec<i64>> {
+        let mut buckets = vec![0i64; granularity.buckets_per_day()];
+        let Some((day_start, day_end)) = super::day_bounds_ms(date) else {
+            return Ok(buckets);
+        };
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT start_time, end_time, focus_duration_ms
+            FROM sessions
+            WHERE end_time > ?1 AND start_time < ?2
+            "#,
+        )?;
+        let rows = stmt.query_map([day_start, day_end], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+
+        let bucket_ms = granularity.bucket_ms();
+        for row in rows {
+            let (start, end, focus_ms) = row?;
+            let length = (end - start).max(1);
+            for (index, slot) in buckets.iter_mut().enumerate() {
+                let bucket_start = day_start + index as i64 * bucket_ms;
+                let overlap = end.min(bucket_start + bucket_ms) - start.max(bucket_start);
+                if overlap > 0 {
+                    *slot += focus_ms * overlap / length;
+                }
+            }
+        }
+
+        Ok(buckets)
+    }
+
+    /// `start` 到 `end`（含，YYYY-MM-DD）之间每个小时的平均专注时长（分钟）
+    pub fn focus_by_hour_profile(&self, start: &str, end: &str) -> SqliteResult<[f64; 24]> {
+        let profile = self.focus_by_bucket_profile(start, end, BucketGranularity::Hour)?;
+        Ok(profile.try_into().unwrap_or([0.0; 24]))
+    }
+
+    /// `start` 到 `end`（含，YYYY-MM-DD）之间每段的平均专注时长（分钟）
+    ///
+    /// 每段只在该段有专注记录的日期之间平均，没有专注的日期不会拉低平均值；
+    /// 日期无法解析或 `end` 早于 `start` 时全部为 0
+    pub fn focus_by_bucket_profile(
+        &self,
+        start: &str,
+        end: &str,
+        granularity: BucketGranularity,
+    ) -> SqliteResult<Vec<f64>> {
+        let count = granularity.buckets_per_day();
+        let mut profile = vec![0.0; count];
+        let (Ok(start), Ok(end)) = (
+            NaiveDate::parse_from_str(start, "%Y-%m-%d"),
+            NaiveDate::parse_from_str(end, "%Y-%m-%d"),
+        ) else {
+            return Ok(profile);
+        };
+
+        let mut totals = vec![0i64; count];
+        let mut days = vec![0u32; count];
+        for day in start.iter_days().take_while(|day| *day <= end) {
+            let buckets = self.focus_by_bucket_ms(&day.format("%Y-%m-%d").to_string(), granularity)?;
+            for (index, &focus_ms) in buckets.iter().enumerate() {
+                if focus_ms > 0 {
+                    totals[index] += focus_ms;
+                    days[index] += 1;
+                }
+            }
+        }
+
+        for (index, average) in profile.iter_mut().enumerate() {
+            if days[index] > 0 {
+                *average = totals[index] as f64 / days[index] as f64 / 60_000.0;
+            }
+        }
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{day_bounds_ms, test_session};
+
+    /// 在 `date` 的 `hour` 点写入一段 `minutes` 分钟的专注会话
+    fn seed_session(db: &Database, date: &str, hour: i64, minutes: i64) {
+        seed_session_at(db, date, hour * 60, minutes);
+    }
+
+    /// 在 `date` 当天第 `minute_of_day` 分钟写入一段 `minutes` 分钟的专注会话
+    fn seed_session_at(db: &Database, date: &str, minute_of_day: i64, minutes: i64) {
+        let (day_start, _) = day_bounds_ms(date).unwrap();
+        let start_time = day_start + minute_of_day * 60_000;
+        db.insert_session(&test_session(start_time, minutes * 60_000)).unwrap();
+    }
+
+    #[test]
+    fn test_focus_by_hour_profile_peaks_in_morning() {
+        let db = Database::in_memory().unwrap();
+        seed_session(&db, "2026-10-14", 9, 50);
+        seed_session(&db, "2026-10-14", 15, 10);
+        seed_session(&db, "2026-10-15", 9, 40);
+        seed_session(&db, "2026-10-15", 10, 30);
+        // 范围之外的记录不参与统计
+        seed_session(&db, "2026-10-16", 20, 60);
+
+        let profile = db.focus_by_hour_profile("2026-10-14", "2026-10-15").unwrap();
+        let peak = (0..24).max_by(|&a, &b| profile[a].total_cmp(&profile[b])).unwrap();
+        assert_eq!(peak, 9);
+        assert!((profile[9] - 45.0).abs() < 1e-9);
+        // 只有一天有专注的小时按一天平均
+        assert!((profile[10] - 30.0).abs() < 1e-9);
+        assert!((profile[15] - 10.0).abs() < 1e-9);
+        assert_eq!(profile[20], 0.0);
+
+        assert_eq!(db.focus_by_hour_profile("2026-10-15", "2026-10-14").unwrap(), [0.0; 24]);
+    }
+
+    #[test]
+    fn test_quarter_hour_buckets() {
+        let db = Database::in_memory().unwrap();
+        // 10:05 和 10:20 各专注 5 分钟
+        seed_session_at(&db, "2026-10-14", 10 * 60 + 5, 5);
+        seed_session_at(&db, "2026-10-14", 10 * 60 + 20, 5);
+
+        let quarters = db.focus_by_bucket_ms("2026-10-14", BucketGranularity::QuarterHour).unwrap();
+        assert_eq!(quarters.len(), 96);
+        assert_eq!(quarters[40], 5 * 60_000);
+        assert_eq!(quarters[41], 5 * 60_000);
+        assert_eq!(quarters.iter().sum::<i64>(), 10 * 60_000);
+
+        let hours = db.focus_by_bucket_ms("2026-10-14", BucketGranularity::Hour).unwrap();
+        assert_eq!(hours.len(), 24);
+        assert_eq!(hours[10], 10 * 60_000);
+        assert_eq!(hours, db.hourly_focus_ms("2026-10-14").unwrap());
+
+        let profile = db
+            .focus_by_bucket_profile("2026-10-14", "2026-10-14", BucketGranularity::QuarterHour)
+            .unwrap();
+        assert!((profile[41] - 5.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,98 @@
+//! 累计统计
+//!
+//! 汇总所有每日统计，供“自安装以来”的总结卡片使用。
+
+use super::Database;
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 专注时长达到该值（毫秒）的日期才算活跃日，避免误触产生的几秒记录被计入
+pub const ACTIVE_DAY_MIN_FOCUS_MS: i64 = 5 * 60_000;
+
+/// 累计统计
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifetimeStats {
+    /// 累计专注时长（毫秒）
+    pub total_focus_ms: i64,
+    /// 有记录的天数
+    pub total_days: u32,
+    /// 活跃天数（专注时长达到 `ACTIVE_DAY_MIN_FOCUS_MS`）
+    pub active_days: u32,
+    /// 活跃日的平均专注时长（毫秒，没有活跃日时为 0）
+    pub avg_daily_ms: i64,
+    /// 最早有记录的日期（没有记录时为 None）
+    pub first_date: Option<String>,
+}
+
+impl Database {
+    /// 所有每日统计的累计值（不含示例数据）
+    pub fn get_lifetime_stats(&self) -> SqliteResult<LifetimeStats> {
+        let (total_focus_ms, total_days, active_days, active_focus_ms, first_date) = self.conn.query_row(
+            r#"
+            SELECT
+                COALESCE(SUM(total_focus_ms), 0),
+                COUNT(*),
+                COALESCE(SUM(total_focus_ms >= ?1), 0),
+                COALESCE(SUM(CASE WHEN total_focus_ms >= ?1 THEN total_focus_ms ELSE 0 END), 0),
+                MIN(date)
+            FROM daily_stats
+            WHERE demo = 0
+            "#,
+            [ACTIVE_DAY_MIN_FOCUS_MS],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, u32>(1)?,
+                    row.get::<_, u32>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            },
+        )?;
+
+        Ok(LifetimeStats {
+            total_focus_ms,
+            total_days,
+            active_days,
+            avg_daily_ms: if active_days > 0 {
+                active_focus_ms / active_days as i64
+            } else {
+                0
+            },
+            first_date,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifetime_stats() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(
+            db.get_lifetime_stats().unwrap(),
+            LifetimeStats {
+                total_focus_ms: 0,
+                total_days: 0,
+                active_days: 0,
+                avg_daily_ms: 0,
+                first_date: None,
+            }
+        );
+
+        db.add_daily_stats("2026-10-12", 60 * 60_000, 0, 2, 60 * 60_000).unwrap();
+        db.add_daily_stats("2026-10-10", 30_000, 0, 1, 30_000).unwrap();
+        db.add_daily_stats("2026-10-14", 30 * 60_000, 5 * 60_000, 1, 30 * 60_000).unwrap();
+        db.add_daily_stats("2026-10-15", 4 * 60_000, 0, 1, 4 * 60_000).unwrap();
+
+        let stats = db.get_lifetime_stats().unwrap();
+        assert_eq!(stats.total_focus_ms, 94 * 60_000 + 30_000);
+        assert_eq!(stats.total_days, 4);
+        // 只有不足 5 分钟的两天不算活跃
+        assert_eq!(stats.active_days, 2);
+        assert_eq!(stats.avg_daily_ms, 45 * 60_000);
+        assert_eq!(stats.first_date.as_deref(), Some("2026-10-10"));
+    }
+}
@@ -1,10 +1,92 @@
 //! 数据存储模块
 //! 使用 SQLite 存储专注会话数据和每日统计
 
-use rusqlite::{Connection, Result as SqliteResult};
+pub mod card;
+pub mod challenges;
+pub mod coach;
+pub mod compare;
+pub mod day_notes;
+pub mod demo;
+pub mod diagnostics;
+pub mod distractions;
+pub mod events;
+pub mod focus_log;
+pub mod hour_profile;
+pub mod lifetime;
+pub mod pace;
+pub mod paths;
+pub mod profiles;
+pub mod recordings;
+pub mod report;
+pub mod samples;
+pub mod streak;
+pub mod timeline;
+
+use chrono::{Datelike, NaiveDate, TimeZone};
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+/// 默认的每周目标（分钟），顺序为周一到周日
+pub const DEFAULT_WEEKDAY_GOALS: [i64; 7] = [120, 120, 120, 120, 120, 60, 60];
+
+/// 生成日期键 (YYYY-MM-DD，本地时区)
+///
+/// 所有按天聚合的数据都使用此函数生成日期，保证时区处理一致
+pub fn day_key(time: chrono::DateTime<chrono::Local>) -> String {
+    time.format("%Y-%m-%d").to_string()
+}
+
+/// Unix 时间戳（毫秒）所在本地日期的日期键，超出范围时返回 None
+pub fn day_key_ms(timestamp_ms: i64) -> Option<String> {
+    chrono::Local.timestamp_millis_opt(timestamp_ms).single().map(day_key)
+}
+
+/// 今天的日期键
+pub fn today_key() -> String {
+    day_key(chrono::Local::now())
+}
+
+/// 从日期键解析星期（0 = 周一，6 = 周日）
+///
+/// 直接从日期字符串推导，避免与当前时区的“今天”混淆
+pub fn weekday_of(date: &str) -> Option<usize> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .map(|d| d.weekday().num_days_from_monday() as usize)
+}
+
+/// 日期键对应的本地时间范围 [开始, 结束)，Unix 时间戳（毫秒）
+pub fn day_bounds_ms(date: &str) -> Option<(i64, i64)> {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let start = chrono::Local
+        .from_local_datetime(&day.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    let end = chrono::Local
+        .from_local_datetime(&day.succ_opt()?.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    Some((start.timestamp_millis(), end.timestamp_millis()))
+}
+
+/// 按本地日期边界切分时间段 [start, end)，返回每一天的日期键和所占毫秒数
+///
+/// 结束时间不晚于开始时间时整段归入开始当天（占用 0 毫秒）
+fn split_by_day(start_ms: i64, end_ms: i64) -> Option<Vec<(String, i64)>> {
+    let mut pieces = Vec::new();
+    let mut cursor = start_ms;
+    loop {
+        let date = day_key_ms(cursor)?;
+        let (_, day_end) = day_bounds_ms(&date)?;
+        let piece_end = day_end.min(end_ms).max(cursor);
+        pieces.push((date, piece_end - cursor));
+        if piece_end >= end_ms || piece_end <= cursor {
+            break;
+        }
+        cursor = piece_end;
+    }
+    Some(pieces)
+}
+
 /// 专注会话记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FocusSession {
@@ -18,6 +100,15 @@ pub struct FocusSession {
     pub focus_duration_ms: i64,
     /// 分心时长 (毫秒)
     pub distracted_duration_ms: i64,
+    /// 专注期间的平均专注分数（无专注采样时为 0）
+    #[serde(default)]
+    pub avg_focus_score: f32,
+    /// 专注期间的最低专注分数
+    #[serde(default)]
+    pub min_focus_score: f32,
+    /// 专注期间的最高专注分数
+    #[serde(default)]
+    pub max_focus_score: f32,
 }
 
 /// 每日统计
@@ -35,6 +126,47 @@ pub struct DailyStats {
     pub longest_focus_ms: i64,
 }
 
+/// 目标来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalSource {
+    /// 为该日期单独设置的目标
+    Explicit,
+    /// 按星期的默认目标
+    WeekdayDefault,
+}
+
+/// 目标进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalProgress {
+    /// 日期 (YYYY-MM-DD 格式)
+    pub date: String,
+    /// 目标专注时长（分钟）
+    pub target_minutes: i64,
+    /// 已完成专注时长（毫秒）
+    pub achieved_ms: i64,
+    /// 完成百分比 (0.0 - 100.0+)
+    pub percent: f32,
+    /// 目标来源
+    pub source: GoalSource,
+}
+
+impl GoalProgress {
+    /// 叠加尚未写入数据库的专注时长
+    pub fn with_extra_focus(mut self, extra_ms: i64) -> Self {
+        self.achieved_ms += extra_ms;
+        self.percent = Self::percent_of(self.achieved_ms, self.target_minutes);
+        self
+    }
+
+    fn percent_of(achieved_ms: i64, target_minutes: i64) -> f32 {
+        if target_minutes <= 0 {
+            return 100.0;
+        }
+        achieved_ms as f32 / (target_minutes * 60_000) as f32 * 100.0
+    }
+}
+
 /// 数据库管理器
 pub struct Database {
     conn: Connection,
@@ -73,6 +205,11 @@ impl Database {
                 end_time INTEGER NOT NULL,
                 focus_duration_ms INTEGER NOT NULL,
                 distracted_duration_ms INTEGER NOT NULL,
+                avg_focus_score REAL NOT NULL DEFAULT 0,
+                min_focus_score REAL NOT NULL DEFAULT 0,
+                max_focus_score REAL NOT NULL DEFAULT 0,
+                manual INTEGER NOT NULL DEFAULT 0,
+                label TEXT,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP
             );
 
@@ -86,12 +223,103 @@ impl Database {
                 updated_at TEXT DEFAULT CURRENT_TIMESTAMP
             );
 
+            -- 每日目标表（为特定日期单独设置的目标）
+            CREATE TABLE IF NOT EXISTS daily_goals (
+                date TEXT PRIMARY KEY,
+                target_minutes INTEGER NOT NULL
+            );
+
+            -- 每周默认目标表（0 = 周一，6 = 周日）
+            CREATE TABLE IF NOT EXISTS weekday_goals (
+                weekday INTEGER PRIMARY KEY,
+                target_minutes INTEGER NOT NULL
+            );
+
+            -- 活动事件表
+            CREATE TABLE IF NOT EXISTS events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp_ms INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                payload TEXT NOT NULL
+            );
+
+            -- 专注分数采样表
+            CREATE TABLE IF NOT EXISTS focus_samples (
+                timestamp_ms INTEGER NOT NULL,
+                focus_score REAL NOT NULL
+            );
+
+            -- 分心记录表
+            CREATE TABLE IF NOT EXISTS distractions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                start_ms INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                trigger TEXT NOT NULL
+            );
+
+            -- 连续专注冻结日表
+            CREATE TABLE IF NOT EXISTS streak_freezes (
+                date TEXT PRIMARY KEY,
+                used_at_ms INTEGER NOT NULL
+            );
+
+            -- 每日备注表
+            CREATE TABLE IF NOT EXISTS day_notes (
+                date TEXT PRIMARY KEY,
+                note TEXT NOT NULL
+            );
+
+            -- 已完成的专注挑战表
+            CREATE TABLE IF NOT EXISTS challenges (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at_ms INTEGER NOT NULL,
+                completed_at_ms INTEGER NOT NULL,
+                target_ms INTEGER NOT NULL
+            );
+
             -- 创建索引
             CREATE INDEX IF NOT EXISTS idx_sessions_start_time ON sessions(start_time);
             CREATE INDEX IF NOT EXISTS idx_sessions_end_time ON sessions(end_time);
+            CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(timestamp_ms);
+            CREATE INDEX IF NOT EXISTS idx_focus_samples_timestamp ON focus_samples(timestamp_ms);
+            CREATE INDEX IF NOT EXISTS idx_distractions_start ON distractions(start_ms);
             "#,
         )?;
 
+        // 旧版本数据库缺少的列
+        for column in ["avg_focus_score", "min_focus_score", "max_focus_score"] {
+            self.ensure_column("sessions", column, "REAL NOT NULL DEFAULT 0")?;
+        }
+        self.ensure_column("sessions", "manual", "INTEGER NOT NULL DEFAULT 0")?;
+        self.ensure_column("sessions", "label", "TEXT")?;
+        // 示例数据标记
+        self.ensure_column("sessions", "demo", "INTEGER NOT NULL DEFAULT 0")?;
+        self.ensure_column("daily_stats", "demo", "INTEGER NOT NULL DEFAULT 0")?;
+
+        // 写入默认的每周目标（已存在则保留用户设置）
+        for (weekday, minutes) in DEFAULT_WEEKDAY_GOALS.iter().enumerate() {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO weekday_goals (weekday, target_minutes) VALUES (?1, ?2)",
+                (weekday as i64, minutes),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// 表中缺少指定列时添加该列
+    fn ensure_column(&self, table: &str, column: &str, definition: &str) -> SqliteResult<()> {
+        let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<SqliteResult<Vec<_>>>()?
+            .iter()
+            .any(|name| name == column);
+
+        if !exists {
+            self.conn
+                .execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {};", table, column, definition))?;
+        }
         Ok(())
     }
 
@@ -99,24 +327,124 @@ impl Database {
     pub fn insert_session(&self, session: &FocusSession) -> SqliteResult<i64> {
         self.conn.execute(
             r#"
-            INSERT INTO sessions (start_time, end_time, focus_duration_ms, distracted_duration_ms)
-            VALUES (?1, ?2, ?3, ?4)
+            INSERT INTO sessions (
+                start_time, end_time, focus_duration_ms, distracted_duration_ms,
+                avg_focus_score, min_focus_score, max_focus_score
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
             "#,
             (
                 session.start_time,
                 session.end_time,
                 session.focus_duration_ms,
                 session.distracted_duration_ms,
+                session.avg_focus_score,
+                session.min_focus_score,
+                session.max_focus_score,
             ),
         )?;
 
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// 更新已存在的专注会话（按 ID）
+    pub fn update_session(&self, session: &FocusSession) -> SqliteResult<()> {
+        self.conn.execute(
+            r#"
+            UPDATE sessions
+            SET end_time = ?2, focus_duration_ms = ?3, distracted_duration_ms = ?4,
+                avg_focus_score = ?5, min_focus_score = ?6, max_focus_score = ?7
+            WHERE id = ?1
+            "#,
+            (
+                session.id,
+                session.end_time,
+                session.focus_duration_ms,
+                session.distracted_duration_ms,
+                session.avg_focus_score,
+                session.min_focus_score,
+                session.max_focus_score,
+            ),
+        )?;
+        Ok(())
+    }
+
+    /// 写入一条已结束的会话，并累加到会话开始当天的统计，返回会话 ID
+    ///
+    /// `manual` 表示手动补录的会话（从其他工具迁移、离线专注等），可以附带备注
+    pub fn record_session(&self, session: &FocusSession, manual: bool, label: Option<&str>) -> SqliteResult<i64> {
+        let date = day_key_ms(session.start_time).ok_or_else(|| {
+            rusqlite::Error::ToSqlConversionFailure(format!("Invalid session start time: {}", session.start_time).into())
+        })?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let id = self.insert_session(session)?;
+        tx.execute(
+            "UPDATE sessions SET manual = ?2, label = ?3 WHERE id = ?1",
+            (id, manual, label),
+        )?;
+        self.add_daily_stats(
+            &date,
+            session.focus_duration_ms,
+            session.distracted_duration_ms,
+            1,
+            session.focus_duration_ms,
+        )?;
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    /// 获取最近 N 个专注会话（按开始时间倒序）
+    pub fn get_recent_sessions(&self, limit: u32) -> SqliteResult<Vec<FocusSession>> {
+        self.recent_sessions(limit, true)
+    }
+
+    /// 获取最近 N 个真实的专注会话（不含示例数据），用于教练建议等分析
+    pub(crate) fn get_recent_real_sessions(&self, limit: u32) -> SqliteResult<Vec<FocusSession>> {
+        self.recent_sessions(limit, false)
+    }
+
+    fn recent_sessions(&self, limit: u32, include_demo: bool) -> SqliteResult<Vec<FocusSession>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, start_time, end_time, focus_duration_ms, distracted_duration_ms,
+                   avg_focus_score, min_focus_score, max_focus_score
+            FROM sessions
+            WHERE ?2 OR demo = 0
+            ORDER BY start_time DESC, id DESC
+            LIMIT ?1
+            "#,
+        )?;
+
+        let rows = stmt.query_map((limit, include_demo), |row| {
+            Ok(FocusSession {
+                id: row.get(0)?,
+                start_time: row.get(1)?,
+                end_time: row.get(2)?,
+                focus_duration_ms: row.get(3)?,
+                distracted_duration_ms: row.get(4)?,
+                avg_focus_score: row.get::<_, f64>(5)? as f32,
+                min_focus_score: row.get::<_, f64>(6)? as f32,
+                max_focus_score: row.get::<_, f64>(7)? as f32,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// 最长的会话专注时长（毫秒），可以排除指定的会话，没有会话时为 None
+    pub fn longest_session_ms(&self, exclude_id: Option<i64>) -> SqliteResult<Option<i64>> {
+        self.conn.query_row(
+            "SELECT MAX(focus_duration_ms) FROM sessions WHERE id != ?1",
+            [exclude_id.unwrap_or(0)],
+            |row| row.get(0),
+        )
+    }
+
     /// 获取今日统计
     pub fn get_today_stats(&self) -> SqliteResult<Option<DailyStats>> {
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-        self.get_stats_by_date(&today)
+        self.get_stats_by_date(&today_key())
     }
 
     /// 获取指定日期的统计
@@ -146,25 +474,194 @@ impl Database {
 
     /// 更新今日统计
     pub fn update_today_stats(&self, focus_ms: i64, distracted_ms: i64) -> SqliteResult<()> {
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        self.add_daily_stats(&today_key(), focus_ms, distracted_ms, 1, focus_ms)
+    }
+
+    /// 写入进行中或已结束的会话（`session.id` 为 0 时插入，否则更新同一条记录），
+    /// 并把本次新增的时长累加到 `date` 的统计，返回会话 ID
+    ///
+    /// 会话和统计在同一事务中写入，中途失败不会让两者不一致
+    pub fn save_session_progress(
+        &self,
+        session: &FocusSession,
+        date: &str,
+        focus_ms: i64,
+        distracted_ms: i64,
+    ) -> SqliteResult<i64> {
+        let tx = self.conn.unchecked_transaction()?;
+        let (id, new_sessions) = if session.id > 0 {
+            self.update_session(session)?;
+            (session.id, 0)
+        } else {
+            (self.insert_session(session)?, 1)
+        };
+        if focus_ms != 0 || distracted_ms != 0 || new_sessions > 0 {
+            self.add_daily_stats(date, focus_ms, distracted_ms, new_sessions, session.focus_duration_ms)?;
+        }
+        tx.commit()?;
+        Ok(id)
+    }
 
+    /// 向指定日期的统计累加数据
+    ///
+    /// `sessions` 为本次写入对应的会话数量（跨天写入未结束的会话时为 0）；
+    /// `longest_ms` 为所属会话到目前为止的完整专注时长（检查点只累加增量，不能用增量比较最长专注），
+    /// 不属于任何会话时传 0
+    pub fn add_daily_stats(
+        &self,
+        date: &str,
+        focus_ms: i64,
+        distracted_ms: i64,
+        sessions: i32,
+        longest_ms: i64,
+    ) -> SqliteResult<()> {
         self.conn.execute(
             r#"
             INSERT INTO daily_stats (date, total_focus_ms, total_distracted_ms, session_count, longest_focus_ms)
-            VALUES (?1, ?2, ?3, 1, ?2)
+            VALUES (?1, ?2, ?3, ?4, ?5)
             ON CONFLICT(date) DO UPDATE SET
                 total_focus_ms = total_focus_ms + ?2,
                 total_distracted_ms = total_distracted_ms + ?3,
-                session_count = session_count + 1,
-                longest_focus_ms = MAX(longest_focus_ms, ?2),
+                session_count = session_count + ?4,
+                longest_focus_ms = MAX(longest_focus_ms, ?5),
                 updated_at = CURRENT_TIMESTAMP
             "#,
-            (&today, focus_ms, distracted_ms),
+            (date, focus_ms, distracted_ms, sessions, longest_ms),
         )?;
 
         Ok(())
     }
 
+    /// 清空指定日期的统计：统计行归零并删除当天开始的会话，其他日期不受影响
+    pub fn clear_day(&self, date: &str) -> SqliteResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        tx.execute(
+            r#"
+            UPDATE daily_stats
+            SET total_focus_ms = 0, total_distracted_ms = 0, session_count = 0, longest_focus_ms = 0,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE date = ?1
+            "#,
+            [date],
+        )?;
+
+        if let Some((start, end)) = day_bounds_ms(date) {
+            tx.execute(
+                "DELETE FROM sessions WHERE start_time >= ?1 AND start_time < ?2",
+                (start, end),
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    /// 清空全部记录（会话、每日统计、事件、采样、分心、冻结、备注和挑战），包括示例数据
+    ///
+    /// 目标设置不属于记录，保留不变
+    pub fn reset_all_data(&self) -> SqliteResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for table in [
+            "sessions",
+            "daily_stats",
+            "events",
+            "focus_samples",
+            "distractions",
+            "streak_freezes",
+            "day_notes",
+            "challenges",
+        ] {
+            tx.execute(&format!("DELETE FROM {}", table), [])?;
+        }
+        tx.commit()
+    }
+
+    /// 按会话表重建每日统计，返回重建的天数
+    ///
+    /// 统计与会话不一致时（程序错误或手动修改数据库）用于修复：按会话重新累计
+    /// 专注时长、分心时长、会话数和最长专注。跨过午夜的会话在日期边界处切分，
+    /// 时长按各天所占时间比例分配，每一段计为当天的一次会话。
+    /// 没有会话的日子保留原有统计；全部由示例会话组成的日子保留示例标记
+    pub fn rebuild_daily_stats(&self) -> SqliteResult<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let mut days: std::collections::BTreeMap<String, (DailyStats, bool)> = std::collections::BTreeMap::new();
+        {
+            let mut stmt = tx
+                .prepare("SELECT start_time, end_time, focus_duration_ms, distracted_duration_ms, demo FROM sessions")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let (start_time, end_time): (i64, i64) = (row.get(0)?, row.get(1)?);
+                let Some(pieces) = split_by_day(start_time, end_time) else {
+                    tracing::warn!("Skipping session with invalid time range {}..{}", start_time, end_time);
+                    continue;
+                };
+                let (focus_ms, distracted_ms): (i64, i64) = (row.get(2)?, row.get(3)?);
+                let demo: bool = row.get(4)?;
+                let total_ms = (end_time - start_time).max(0);
+                // 按比例分配，余数归入最后一段，保证各段之和等于会话总时长
+                let (mut focus_left, mut distracted_left) = (focus_ms, distracted_ms);
+                let last = pieces.len() - 1;
+                for (i, (date, piece_ms)) in pieces.into_iter().enumerate() {
+                    let (focus, distracted) = if i == last {
+                        (focus_left, distracted_left)
+                    } else {
+                        let share = |value: i64| (value as i128 * piece_ms as i128 / total_ms as i128) as i64;
+                        (share(focus_ms), share(distracted_ms))
+                    };
+                    focus_left -= focus;
+                    distracted_left -= distracted;
+
+                    let (stats, all_demo) = days.entry(date.clone()).or_insert_with(|| {
+                        (
+                            DailyStats {
+                                date,
+                                total_focus_ms: 0,
+                                total_distracted_ms: 0,
+                                session_count: 0,
+                                longest_focus_ms: 0,
+                            },
+                            true,
+                        )
+                    });
+                    stats.total_focus_ms += focus;
+                    stats.total_distracted_ms += distracted;
+                    stats.session_count += 1;
+                    stats.longest_focus_ms = stats.longest_focus_ms.max(focus);
+                    *all_demo &= demo;
+                }
+            }
+        }
+
+        for (stats, demo) in days.values() {
+            tx.execute(
+                r#"
+                INSERT INTO daily_stats (date, total_focus_ms, total_distracted_ms, session_count, longest_focus_ms, demo)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT(date) DO UPDATE SET
+                    total_focus_ms = ?2,
+                    total_distracted_ms = ?3,
+                    session_count = ?4,
+                    longest_focus_ms = ?5,
+                    demo = ?6,
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+                (
+                    &stats.date,
+                    stats.total_focus_ms,
+                    stats.total_distracted_ms,
+                    stats.session_count,
+                    stats.longest_focus_ms,
+                    demo,
+                ),
+            )?;
+        }
+        tx.commit()?;
+
+        tracing::info!("Rebuilt daily stats for {} days", days.len());
+        Ok(days.len())
+    }
+
     /// 获取最近 N 天的统计数据
     pub fn get_recent_stats(&self, days: u32) -> SqliteResult<Vec<DailyStats>> {
         let mut stmt = self.conn.prepare(
@@ -188,6 +685,115 @@ impl Database {
 
         rows.collect()
     }
+
+    /// 为指定日期设置目标（分钟）
+    pub fn set_goal(&self, date: &str, target_minutes: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO daily_goals (date, target_minutes) VALUES (?1, ?2)
+            ON CONFLICT(date) DO UPDATE SET target_minutes = ?2
+            "#,
+            (date, target_minutes),
+        )?;
+        Ok(())
+    }
+
+    /// 获取为指定日期单独设置的目标
+    pub fn get_goal(&self, date: &str) -> SqliteResult<Option<i64>> {
+        self.conn
+            .query_row(
+                "SELECT target_minutes FROM daily_goals WHERE date = ?1",
+                [date],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// 设置某个星期的默认目标（0 = 周一，6 = 周日）
+    pub fn set_weekday_goal(&self, weekday: usize, target_minutes: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO weekday_goals (weekday, target_minutes) VALUES (?1, ?2)
+            ON CONFLICT(weekday) DO UPDATE SET target_minutes = ?2
+            "#,
+            (weekday as i64, target_minutes),
+        )?;
+        Ok(())
+    }
+
+    /// 获取每周默认目标，顺序为周一到周日
+    pub fn get_weekday_goals(&self) -> SqliteResult<[i64; 7]> {
+        let mut goals = DEFAULT_WEEKDAY_GOALS;
+        let mut stmt = self
+            .conn
+            .prepare("SELECT weekday, target_minutes FROM weekday_goals")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+
+        for row in rows {
+            let (weekday, minutes) = row?;
+            if let Some(slot) = goals.get_mut(weekday as usize) {
+                *slot = minutes;
+            }
+        }
+
+        Ok(goals)
+    }
+
+    /// 解析指定日期的目标：优先使用单独设置的目标，否则回退到星期默认值
+    ///
+    /// 日期格式无效时返回错误
+    pub fn resolve_goal(&self, date: &str) -> SqliteResult<(i64, GoalSource)> {
+        let weekday = weekday_of(date)
+            .ok_or_else(|| rusqlite::Error::ToSqlConversionFailure(format!("Invalid date: {}", date).into()))?;
+        if let Some(minutes) = self.get_goal(date)? {
+            return Ok((minutes, GoalSource::Explicit));
+        }
+
+        let goals = self.get_weekday_goals()?;
+        Ok((goals[weekday], GoalSource::WeekdayDefault))
+    }
+
+    /// 获取指定日期的目标进度（仅统计已写入数据库的专注时长）
+    pub fn get_goal_progress(&self, date: &str) -> SqliteResult<GoalProgress> {
+        let (target_minutes, source) = self.resolve_goal(date)?;
+        let achieved_ms = self
+            .get_stats_by_date(date)?
+            .map(|stats| stats.total_focus_ms)
+            .unwrap_or(0);
+
+        Ok(GoalProgress {
+            date: date.to_string(),
+            target_minutes,
+            achieved_ms,
+            percent: GoalProgress::percent_of(achieved_ms, target_minutes),
+            source,
+        })
+    }
+}
+
+/// 测试用的临时路径：位于系统临时目录，按 `name`、进程号和当前时间命名，避免并行测试互相覆盖（不创建文件）
+#[cfg(test)]
+pub(crate) fn test_temp_path(name: &str) -> std::path::PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!("focus_mochi_{}_{}_{}", name, std::process::id(), nanos))
+}
+
+/// 测试用的专注会话：从 `start_time` 开始连续专注 `focus_ms` 毫秒，没有分心
+#[cfg(test)]
+pub(crate) fn test_session(start_time: i64, focus_ms: i64) -> FocusSession {
+    FocusSession {
+        id: 0,
+        start_time,
+        end_time: start_time + focus_ms,
+        focus_duration_ms: focus_ms,
+        distracted_duration_ms: 0,
+        avg_focus_score: 0.8,
+        min_focus_score: 0.6,
+        max_focus_score: 0.9,
+    }
 }
 
 #[cfg(test)]
@@ -211,4 +817,103 @@ mod tests {
         assert_eq!(stats.total_distracted_ms, 10000);
         assert_eq!(stats.session_count, 1);
     }
+
+    #[test]
+    fn test_record_session_marks_manual() {
+        let db = Database::in_memory().unwrap();
+        let (day_start, _) = day_bounds_ms("2026-10-15").unwrap();
+        // 跨过午夜的会话计入开始当天
+        let session = FocusSession {
+            end_time: day_start + 25 * 3_600_000,
+            ..test_session(day_start + 23 * 3_600_000, 90 * 60_000)
+        };
+        let id = db.record_session(&session, true, Some("离线写作")).unwrap();
+
+        let (manual, label): (bool, Option<String>) = db
+            .conn
+            .query_row("SELECT manual, label FROM sessions WHERE id = ?1", [id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert!(manual);
+        assert_eq!(label.as_deref(), Some("离线写作"));
+        assert_eq!(db.get_stats_by_date("2026-10-15").unwrap().unwrap().total_focus_ms, 90 * 60_000);
+        assert!(db.get_stats_by_date("2026-10-16").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rebuild_daily_stats() {
+        let db = Database::in_memory().unwrap();
+        let (day_start, _) = day_bounds_ms("2026-10-15").unwrap();
+        let session = |hour: i64, focus_minutes: i64| FocusSession {
+            distracted_duration_ms: 60_000,
+            ..test_session(day_start + hour * 3_600_000, focus_minutes * 60_000)
+        };
+        db.record_session(&session(9, 30), false, None).unwrap();
+        db.record_session(&session(14, 50), false, None).unwrap();
+        db.record_session(&session(30, 20), false, None).unwrap();
+        // 23:00 开始、次日 01:00 结束的会话，专注和分心时长按两天各占一半切分
+        db.record_session(
+            &FocusSession {
+                end_time: day_start + 25 * 3_600_000,
+                distracted_duration_ms: 20 * 60_000,
+                ..test_session(day_start + 23 * 3_600_000, 100 * 60_000)
+            },
+            true,
+            None,
+        )
+        .unwrap();
+        let expected = db.get_stats_by_date("2026-10-15").unwrap().unwrap();
+
+        // 统计被改坏，另有一天没有任何会话
+        db.conn
+            .execute(
+                "UPDATE daily_stats SET total_focus_ms = 1, session_count = 9, longest_focus_ms = 0 WHERE date = ?1",
+                ["2026-10-15"],
+            )
+            .unwrap();
+        db.add_daily_stats("2026-10-01", 60_000, 0, 1, 60_000).unwrap();
+
+        assert_eq!(db.rebuild_daily_stats().unwrap(), 2);
+        let rebuilt = db.get_stats_by_date("2026-10-15").unwrap().unwrap();
+        assert_eq!(rebuilt.total_focus_ms, 130 * 60_000);
+        assert_eq!(rebuilt.total_distracted_ms, 12 * 60_000);
+        assert_eq!(rebuilt.session_count, 3);
+        assert_eq!(rebuilt.longest_focus_ms, 50 * 60_000);
+        assert_eq!(expected.total_focus_ms, 180 * 60_000);
+        let next_day = db.get_stats_by_date("2026-10-16").unwrap().unwrap();
+        assert_eq!(next_day.total_focus_ms, 70 * 60_000);
+        assert_eq!(next_day.total_distracted_ms, 11 * 60_000);
+        assert_eq!(next_day.session_count, 2);
+
+        // 没有会话的日子保留原有统计
+        let kept = db.get_stats_by_date("2026-10-01").unwrap().unwrap();
+        assert_eq!(kept.total_focus_ms, 60_000);
+        assert_eq!(kept.session_count, 1);
+    }
+
+    #[test]
+    fn test_weekday_goal_fallback() {
+        let db = Database::in_memory().unwrap();
+        db.set_weekday_goal(5, 30).unwrap();
+
+        // 2026-10-17 是周六，使用周末默认目标
+        let saturday = db.get_goal_progress("2026-10-17").unwrap();
+        assert_eq!(saturday.target_minutes, 30);
+        assert_eq!(saturday.source, GoalSource::WeekdayDefault);
+
+        // 2026-10-16 是周五，使用工作日默认目标
+        let friday = db.get_goal_progress("2026-10-16").unwrap();
+        assert_eq!(friday.target_minutes, DEFAULT_WEEKDAY_GOALS[4]);
+
+        // 单独设置的目标优先
+        db.set_goal("2026-10-17", 90).unwrap();
+        let saturday = db.get_goal_progress("2026-10-17").unwrap();
+        assert_eq!(saturday.target_minutes, 90);
+        assert_eq!(saturday.source, GoalSource::Explicit);
+
+        // 无效日期不会被当作周一
+        assert!(db.resolve_goal("2026-13-01").is_err());
+        assert!(db.get_goal_progress("not-a-date").is_err());
+    }
 }
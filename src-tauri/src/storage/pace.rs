@@ -0,0 +1,142 @@
+//! 今日目标进度预测
+//!
+//! 用前几天同一时刻之后的平均专注时长估计今天剩余时间还能专注多久，
+//! 加上已完成的专注时长得到今天结束时的预计专注时长，再与目标比较。
+
+use super::Database;
+use chrono::NaiveDate;
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 参考的历史天数
+pub const PACE_HISTORY_DAYS: u32 = 14;
+
+/// 今日目标是否有望完成
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoalOnTrack {
+    /// 按预计专注时长能否完成目标（没有目标时为 true）
+    pub on_track: bool,
+    /// 预计今天结束时的专注时长（毫秒）
+    pub projected_ms: i64,
+    /// 目标专注时长（毫秒，0 表示没有目标）
+    pub target_ms: i64,
+    /// 已完成的专注时长（毫秒）
+    pub achieved_ms: i64,
+    /// 参与估计的历史天数（0 表示没有历史数据，预计值只包含已完成的时长）
+    pub history_days: u32,
+}
+
+impl Database {
+    /// 预测 `date` 结束时的专注时长并与目标比较
+    ///
+    /// `elapsed_ms` 为当天已经过去的时长，`extra_focus_ms` 为尚未写入数据库的专注时长。
+    /// 只参考之前 `PACE_HISTORY_DAYS` 天中有专注记录的日期
+    pub fn goal_on_track(&self, date: &str, elapsed_ms: i64, extra_focus_ms: i64) -> SqliteResult<GoalOnTrack> {
+        let progress = self.get_goal_progress(date)?.with_extra_focus(extra_focus_ms);
+        let target_ms = progress.target_minutes.max(0) * 60_000;
+
+        let mut remaining_total = 0;
+        let mut history_days = 0;
+        if let Ok(mut day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            for _ in 0..PACE_HISTORY_DAYS {
+                let Some(previous) = day.pred_opt() else {
+                    break;
+                };
+                day = previous;
+                let key = day.format("%Y-%m-%d").to_string();
+                let had_focus = self
+                    .get_stats_by_date(&key)?
+                    .is_some_and(|stats| stats.total_focus_ms > 0);
+                if had_focus {
+                    remaining_total += remaining_focus_ms(&self.hourly_focus_ms(&key)?, elapsed_ms);
+                    history_days += 1;
+                }
+            }
+        }
+
+        let typical_remaining = if history_days > 0 {
+            remaining_total / history_days as i64
+        } else {
+            0
+        };
+        let projected_ms = progress.achieved_ms + typical_remaining;
+
+        Ok(GoalOnTrack {
+            on_track: target_ms == 0 || projected_ms >= target_ms,
+            projected_ms,
+            target_ms,
+            achieved_ms: progress.achieved_ms,
+            history_days,
+        })
+    }
+}
+
+/// 一天中 `elapsed_ms` 之后的专注时长（毫秒），当前小时按剩余比例计算
+fn remaining_focus_ms(hourly_focus_ms: &[i64; 24], elapsed_ms: i64) -> i64 {
+    hourly_focus_ms
+        .iter()
+        .enumerate()
+        .map(|(hour, &focus_ms)| {
+            let hour_end = (hour as i64 + 1) * 3_600_000;
+            let remaining = (hour_end - elapsed_ms).clamp(0, 3_600_000);
+            focus_ms * remaining / 3_600_000
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{day_bounds_ms, test_session};
+
+    /// 在 `date` 的 `start_hour` 点写入一段 `minutes` 分钟的专注会话
+    fn seed_session(db: &Database, date: &str, start_hour: i64, minutes: i64) {
+        let (day_start, _) = day_bounds_ms(date).unwrap();
+        let start_time = day_start + start_hour * 3_600_000;
+        db.record_session(&test_session(start_time, minutes * 60_000), false, None).unwrap();
+    }
+
+    #[test]
+    fn test_goal_on_track_projection() {
+        let db = Database::in_memory().unwrap();
+        // 2026-10-16 是周五，默认目标 120 分钟
+        let today = "2026-10-16";
+
+        // 没有历史数据：预计值只包含已完成的时长
+        seed_session(&db, today, 9, 60);
+        let cold = db.goal_on_track(today, 12 * 3_600_000, 0).unwrap();
+        assert_eq!(cold.history_days, 0);
+        assert_eq!(cold.projected_ms, 60 * 60_000);
+        assert_eq!(cold.target_ms, 120 * 60_000);
+        assert!(!cold.on_track);
+
+        // 前几天下午 14-16 点通常专注 2 小时，上午也有一些
+        for date in ["2026-10-13", "2026-10-14", "2026-10-15"] {
+            seed_session(&db, date, 9, 30);
+            seed_session(&db, date, 14, 120);
+        }
+
+        // 中午：上午已完成 60 分钟 + 尚未写入的 10 分钟，下午预计还有 120 分钟
+        let midday = db.goal_on_track(today, 12 * 3_600_000, 10 * 60_000).unwrap();
+        assert_eq!(midday.history_days, 3);
+        assert_eq!(midday.achieved_ms, 70 * 60_000);
+        assert_eq!(midday.projected_ms, 190 * 60_000);
+        assert!(midday.on_track);
+
+        // 15 点：下午的专注时段只剩一半
+        let afternoon = db.goal_on_track(today, 15 * 3_600_000, 0).unwrap();
+        assert_eq!(afternoon.projected_ms, 120 * 60_000);
+        assert!(afternoon.on_track);
+
+        // 傍晚：通常不会再有专注，无法完成
+        let evening = db.goal_on_track(today, 17 * 3_600_000, 0).unwrap();
+        assert_eq!(evening.projected_ms, 60 * 60_000);
+        assert!(!evening.on_track);
+
+        // 没有目标时总是视为有望完成
+        db.set_goal(today, 0).unwrap();
+        let no_goal = db.goal_on_track(today, 17 * 3_600_000, 0).unwrap();
+        assert_eq!(no_goal.target_ms, 0);
+        assert!(no_goal.on_track);
+    }
+}
@@ -0,0 +1,63 @@
+//! 数据文件路径
+//!
+//! 配置、数据库和采样日志都位于应用数据目录下，所有位置统一在这里确定，
+//! 避免各处分别拼接路径导致不一致。
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 配置文件名
+pub const CONFIG_FILE: &str = "config.json";
+
+/// 数据库文件名
+pub const DATABASE_FILE: &str = "focus_mochi.db";
+
+/// 采样日志目录名
+pub const FOCUS_LOG_DIR: &str = "focus_log";
+
+/// 应用使用的数据文件路径
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataPaths {
+    /// 应用数据目录
+    pub data_dir: PathBuf,
+    /// 配置文件
+    pub config_path: PathBuf,
+    /// 数据库文件
+    pub database_path: PathBuf,
+    /// 采样日志目录（包含当前日志和轮转的历史日志）
+    pub log_path: PathBuf,
+}
+
+impl DataPaths {
+    /// 数据目录下的各文件路径（相对路径会基于当前工作目录转换为绝对路径）
+    pub fn new(data_dir: &Path) -> Self {
+        let data_dir = std::path::absolute(data_dir).unwrap_or_else(|_| data_dir.to_path_buf());
+        Self {
+            config_path: data_dir.join(CONFIG_FILE),
+            database_path: data_dir.join(DATABASE_FILE),
+            log_path: data_dir.join(FOCUS_LOG_DIR),
+            data_dir,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_paths_absolute_under_data_dir() {
+        for dir in [
+            std::env::temp_dir().join("focus_mochi_paths"),
+            PathBuf::from("relative_data"),
+        ] {
+            let paths = DataPaths::new(&dir);
+            assert!(paths.data_dir.is_absolute());
+            assert!(paths.data_dir.ends_with(dir.file_name().unwrap()));
+            for path in [&paths.config_path, &paths.database_path, &paths.log_path] {
+                assert!(path.is_absolute(), "{}", path.display());
+                assert!(path.starts_with(&paths.data_dir), "{}", path.display());
+            }
+        }
+    }
+}
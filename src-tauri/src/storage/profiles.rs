@@ -0,0 +1,287 @@
+//! 已保存的配置档案
+//!
+//! 每个档案是 `<数据目录>/profiles/<名称>/` 下的一个目录，包含该档案的 `config.json` 和 `focus_mochi.db`。
+//! 当前使用中的档案（默认档案直接使用数据目录根部的文件）的数据库处于打开状态，因此不能被重命名或删除。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 默认档案名称（使用数据目录根部的配置和数据库）
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 档案目录名
+const PROFILES_DIR: &str = "profiles";
+
+/// 档案名称最大长度（字符）
+const MAX_PROFILE_NAME_CHARS: usize = 32;
+
+/// 档案中的文件
+const PROFILE_FILES: [&str; 2] = ["config.json", "focus_mochi.db"];
+
+/// 档案操作错误
+#[derive(Debug)]
+pub enum ProfileError {
+    /// 名称不合法
+    InvalidName(String),
+    /// 档案不存在
+    NotFound(String),
+    /// 同名档案已存在
+    AlreadyExists(String),
+    /// 档案正在使用中
+    Active(String),
+    /// 文件读写错误
+    Io(io::Error),
+}
+
+impl From<io::Error> for ProfileError {
+    fn from(err: io::Error) -> Self {
+        ProfileError::Io(err)
+    }
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::InvalidName(name) => write!(f, "Invalid profile name: {:?}", name),
+            ProfileError::NotFound(name) => write!(f, "Profile not found: {}", name),
+            ProfileError::AlreadyExists(name) => write!(f, "Profile already exists: {}", name),
+            ProfileError::Active(name) => write!(f, "Profile is in use: {}", name),
+            ProfileError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// 校验档案名称：1-32 个字符，只允许字母、数字、`-` 和 `_`（可以是中文），不能是默认档案名
+pub fn validate_profile_name(name: &str) -> Result<(), ProfileError> {
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_PROFILE_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        && !name.eq_ignore_ascii_case(DEFAULT_PROFILE);
+    if valid {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidName(name.to_string()))
+    }
+}
+
+/// 档案存储
+#[derive(Debug, Clone)]
+pub struct ProfileStore {
+    /// 档案根目录（`<数据目录>/profiles`）
+    root: PathBuf,
+}
+
+impl ProfileStore {
+    /// 数据目录下的档案存储
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            root: data_dir.join(PROFILES_DIR),
+        }
+    }
+
+    /// 档案目录
+    fn profile_dir(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    /// 已保存的档案名称（按名称排序）
+    pub fn list(&self) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                if let Some(name) = entry.file_name().to_str() {
+                    if validate_profile_name(name).is_ok() {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// 档案是否存在
+    pub fn exists(&self, name: &str) -> bool {
+        self.profile_dir(name).is_dir()
+    }
+
+    /// 档案的数据目录（默认档案为数据目录根部），档案不存在时返回错误
+    pub fn data_dir(&self, name: &str) -> Result<PathBuf, ProfileError> {
+        if name == DEFAULT_PROFILE {
+            return Ok(self.root.parent().unwrap_or(&self.root).to_path_buf());
+        }
+        validate_profile_name(name)?;
+        if !self.exists(name) {
+            return Err(ProfileError::NotFound(name.to_string()));
+        }
+        Ok(self.profile_dir(name))
+    }
+
+    /// 创建空档案，切换到该档案时写入默认配置并创建数据库
+    pub fn create(&self, name: &str) -> Result<(), ProfileError> {
+        validate_profile_name(name)?;
+        if self.list()?.iter().any(|existing| existing.eq_ignore_ascii_case(name)) {
+            return Err(ProfileError::AlreadyExists(name.to_string()));
+        }
+        fs::create_dir_all(self.profile_dir(name))?;
+        Ok(())
+    }
+
+    /// 重命名档案（连同其中的配置和数据库）
+    pub fn rename(&self, old: &str, new: &str, active: &str) -> Result<(), ProfileError> {
+        validate_profile_name(old)?;
+        validate_profile_name(new)?;
+        if old == active {
+            return Err(ProfileError::Active(old.to_string()));
+        }
+        if !self.exists(old) {
+            return Err(ProfileError::NotFound(old.to_string()));
+        }
+        // 大小写不敏感的文件系统上只改大小写时目标目录“已存在”，这里按目录名精确比较
+        if self.list()?.iter().any(|name| name == new) {
+            return Err(ProfileError::AlreadyExists(new.to_string()));
+        }
+
+        fs::rename(self.profile_dir(old), self.profile_dir(new))?;
+        Ok(())
+    }
+
+    /// 删除档案
+    ///
+    /// 先删除已知的档案文件，再删除目录；目录中有其他文件时保留目录，避免误删用户放入的文件
+    pub fn delete(&self, name: &str, active: &str) -> Result<(), ProfileError> {
+        validate_profile_name(name)?;
+        if name == active {
+            return Err(ProfileError::Active(name.to_string()));
+        }
+        if !self.exists(name) {
+            return Err(ProfileError::NotFound(name.to_string()));
+        }
+
+        let dir = self.profile_dir(name);
+        for file in PROFILE_FILES {
+            // SQLite 的日志文件也一并删除
+            for suffix in ["", "-wal", "-shm", "-journal"] {
+                match fs::remove_file(dir.join(format!("{}{}", file, suffix))) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+        if let Err(e) = fs::remove_dir(&dir) {
+            tracing::warn!("Profile {} directory left in place: {}", name, e);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+
+    fn temp_store(name: &str) -> (PathBuf, ProfileStore) {
+        let dir = test_temp_path(name);
+        let store = ProfileStore::new(&dir);
+        (dir, store)
+    }
+
+    fn create_profile(store: &ProfileStore, name: &str) {
+        let dir = store.profile_dir(name);
+        fs::create_dir_all(&dir).unwrap();
+        for file in PROFILE_FILES {
+            fs::write(dir.join(file), name).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_profile_names() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("学习_2").is_ok());
+        for name in ["", "../work", "a/b", ".hidden", "default", "with space"] {
+            assert!(validate_profile_name(name).is_err(), "{:?}", name);
+        }
+        assert!(validate_profile_name(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_create_profile() {
+        let (dir, store) = temp_store("profiles_create");
+        store.create("work").unwrap();
+        assert_eq!(store.list().unwrap(), vec!["work"]);
+        assert_eq!(store.data_dir("work").unwrap(), dir.join(PROFILES_DIR).join("work"));
+        assert_eq!(store.data_dir(DEFAULT_PROFILE).unwrap(), dir);
+
+        assert!(matches!(store.create("work"), Err(ProfileError::AlreadyExists(_))));
+        assert!(matches!(store.create("../escape"), Err(ProfileError::InvalidName(_))));
+        assert!(matches!(store.data_dir("missing"), Err(ProfileError::NotFound(_))));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_rename_profile() {
+        let (dir, store) = temp_store("profiles_rename");
+        create_profile(&store, "work");
+        create_profile(&store, "study");
+
+        store.rename("work", "office", DEFAULT_PROFILE).unwrap();
+        assert_eq!(store.list().unwrap(), vec!["office", "study"]);
+        let config = fs::read_to_string(store.profile_dir("office").join("config.json")).unwrap();
+        assert_eq!(config, "work");
+
+        // 名称冲突时拒绝，原档案保持不变
+        assert!(matches!(
+            store.rename("office", "study", DEFAULT_PROFILE),
+            Err(ProfileError::AlreadyExists(_))
+        ));
+        assert_eq!(store.list().unwrap(), vec!["office", "study"]);
+
+        assert!(matches!(
+            store.rename("missing", "other", DEFAULT_PROFILE),
+            Err(ProfileError::NotFound(_))
+        ));
+        assert!(matches!(
+            store.rename("office", "../escape", DEFAULT_PROFILE),
+            Err(ProfileError::InvalidName(_))
+        ));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_delete_profile() {
+        let (dir, store) = temp_store("profiles_delete");
+        create_profile(&store, "work");
+        create_profile(&store, "study");
+
+        // 使用中的档案不能删除
+        assert!(matches!(
+            store.delete("work", "work"),
+            Err(ProfileError::Active(_))
+        ));
+        assert!(store.exists("work"));
+
+        store.delete("study", "work").unwrap();
+        assert_eq!(store.list().unwrap(), vec!["work"]);
+        assert!(matches!(
+            store.delete("study", "work"),
+            Err(ProfileError::NotFound(_))
+        ));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
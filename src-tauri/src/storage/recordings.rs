@@ -0,0 +1,217 @@
+//! 会话录制
+//!
+//! 开启后把每个专注会话中的专注状态（每秒最多一条）保存到 `<数据目录>/recordings/<ID>.jsonl`，
+//! 第一行是录制信息，之后每行一个 `FocusState`，供之后回看。
+//! 最多保留 `max_recordings` 个录制，超出时自动删除最旧的录制，避免录制无限占用磁盘空间。
+
+use crate::vision::FocusState;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// 录制目录名
+const RECORDINGS_DIR: &str = "recordings";
+
+/// 录制文件扩展名
+const RECORDING_EXTENSION: &str = "jsonl";
+
+/// 两条录制状态之间的最小间隔（毫秒）
+pub const RECORDING_INTERVAL_MS: u64 = 1_000;
+
+/// 录制信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingInfo {
+    /// 录制 ID（开始时间的毫秒时间戳）
+    pub id: String,
+    /// 录制日期 (YYYY-MM-DD)
+    pub date: String,
+    /// 开始时间 (Unix 时间戳，毫秒)
+    pub start_ms: i64,
+    /// 时长（毫秒）
+    pub duration_ms: i64,
+    /// 平均专注分数
+    pub avg_focus_score: f32,
+    /// 录制的状态数
+    pub samples: usize,
+}
+
+/// 录制存储
+#[derive(Debug, Clone)]
+pub struct RecordingStore {
+    /// 录制目录（`<数据目录>/recordings`）
+    dir: PathBuf,
+    /// 最多保留的录制数
+    max_recordings: usize,
+}
+
+impl RecordingStore {
+    /// 数据目录下的录制存储
+    pub fn new(data_dir: &Path, max_recordings: usize) -> Self {
+        Self {
+            dir: data_dir.join(RECORDINGS_DIR),
+            max_recordings,
+        }
+    }
+
+    /// 录制文件路径（ID 只能由数字组成，避免指向录制目录之外）
+    fn recording_path(&self, id: &str) -> Option<PathBuf> {
+        let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+        valid.then(|| self.dir.join(format!("{}.{}", id, RECORDING_EXTENSION)))
+    }
+
+    /// 保存一次会话的录制，并删除超出保留数量的最旧录制
+    ///
+    /// 没有状态时不保存，返回 None
+    pub fn save(&self, states: &[FocusState]) -> io::Result<Option<RecordingInfo>> {
+        let (Some(first), Some(last)) = (states.first(), states.last()) else {
+            return Ok(None);
+        };
+        let start_ms = first.timestamp_ms as i64;
+        let date = super::day_key_ms(start_ms)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid recording start time"))?;
+        let info = RecordingInfo {
+            id: start_ms.to_string(),
+            date,
+            start_ms,
+            duration_ms: last.timestamp_ms as i64 - start_ms,
+            avg_focus_score: states.iter().map(|s| s.focus_score).sum::<f32>() / states.len() as f32,
+            samples: states.len(),
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.recording_path(&info.id).ok_or_else(|| not_found(&info.id))?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, &info)?;
+        writer.write_all(b"\n")?;
+        for state in states {
+            serde_json::to_writer(&mut writer, state)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        self.prune()?;
+        Ok(Some(info))
+    }
+
+    /// 已保存的录制（从新到旧）
+    pub fn list(&self) -> io::Result<Vec<RecordingInfo>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut recordings = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(RECORDING_EXTENSION) {
+                continue;
+            }
+            let mut header = String::new();
+            BufReader::new(File::open(&path)?).read_line(&mut header)?;
+            match serde_json::from_str::<RecordingInfo>(&header) {
+                Ok(info) => recordings.push(info),
+                Err(e) => tracing::warn!("Skipping invalid recording {}: {}", path.display(), e),
+            }
+        }
+        recordings.sort_by_key(|info| std::cmp::Reverse(info.start_ms));
+        Ok(recordings)
+    }
+
+    /// 读取录制中的专注状态（按时间升序）
+    pub fn load(&self, id: &str) -> io::Result<Vec<FocusState>> {
+        let path = self.recording_path(id).ok_or_else(|| not_found(id))?;
+        let file = File::open(path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => not_found(id),
+            _ => e,
+        })?;
+
+        let mut states = Vec::new();
+        for line in BufReader::new(file).lines().skip(1) {
+            let line = line?;
+            if !line.trim().is_empty() {
+                states.push(FocusState::from_versioned_json(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+            }
+        }
+        Ok(states)
+    }
+
+    /// 删除录制
+    pub fn delete(&self, id: &str) -> io::Result<()> {
+        let path = self.recording_path(id).ok_or_else(|| not_found(id))?;
+        fs::remove_file(path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => not_found(id),
+            _ => e,
+        })
+    }
+
+    /// 删除超出保留数量的最旧录制
+    fn prune(&self) -> io::Result<()> {
+        for info in self.list()?.into_iter().skip(self.max_recordings) {
+            self.delete(&info.id)?;
+            tracing::debug!("Recording pruned: {}", info.id);
+        }
+        Ok(())
+    }
+}
+
+fn not_found(id: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("Recording not found: {}", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = test_temp_path(name);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 从 `start_ms` 开始、每秒一条的录制状态
+    fn session(start_ms: u64, seconds: u64, focus_score: f32) -> Vec<FocusState> {
+        (0..seconds)
+            .map(|i| FocusState {
+                face_present: true,
+                focus_score,
+                timestamp_ms: start_ms + i * RECORDING_INTERVAL_MS,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recordings_evict_oldest() {
+        let dir = temp_dir("recordings");
+        let store = RecordingStore::new(&dir, 2);
+        assert!(store.list().unwrap().is_empty());
+        assert_eq!(store.save(&[]).unwrap(), None);
+
+        let hour_ms = 3_600_000;
+        let first = store.save(&session(1_760_000_000_000, 60, 0.5)).unwrap().unwrap();
+        assert_eq!(first.duration_ms, 59_000);
+        assert_eq!(first.samples, 60);
+        assert!((first.avg_focus_score - 0.5).abs() < 1e-6);
+        let second = store.save(&session(1_760_000_000_000 + hour_ms, 30, 0.7)).unwrap().unwrap();
+
+        // 第 3 个录制挤掉最旧的录制
+        let third = store.save(&session(1_760_000_000_000 + 2 * hour_ms, 10, 0.9)).unwrap().unwrap();
+        let ids: Vec<String> = store.list().unwrap().into_iter().map(|info| info.id).collect();
+        assert_eq!(ids, [third.id.clone(), second.id.clone()]);
+        assert!(matches!(store.load(&first.id), Err(e) if e.kind() == io::ErrorKind::NotFound));
+
+        let states = store.load(&second.id).unwrap();
+        assert_eq!(states.len(), 30);
+        assert_eq!(states[0].focus_score, 0.7);
+
+        store.delete(&third.id).unwrap();
+        assert_eq!(store.list().unwrap(), [second]);
+        assert_eq!(store.delete(&third.id).unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(store.delete("../config").unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,192 @@
+//! 匿名统计报告
+//!
+//! 供用户自愿分享给研究项目的本地报告，只在用户主动导出时写入文件，不会自动发送。
+//!
+//! 报告包含的全部内容：
+//! - `schema_version`：报告格式版本
+//! - 每天一条记录（按时间顺序）：
+//!   - `day_index`：相对第一天的天数（不含具体日期）
+//!   - `weekday`：星期（0 = 周一，6 = 周日）
+//!   - `focus_minutes` / `distracted_minutes`：专注与分心时长，按 5 分钟取整
+//!   - `session_count`：会话数量
+//!   - `session_lengths`：会话专注时长分布（<15 / 15-30 / 30-60 / ≥60 分钟）
+//!   - `sessions_by_part_of_day`：会话开始时段分布（夜间 0-6 / 上午 6-12 / 下午 12-18 / 晚上 18-24）
+//!
+//! 不包含：具体日期、会话时间戳、会话 ID、专注分数采样、配置和任何设备信息
+
+use super::{Database, FocusSession};
+use chrono::{NaiveDate, TimeZone, Timelike};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 报告格式版本
+pub const ANON_REPORT_VERSION: u32 = 1;
+
+/// 时长取整粒度（分钟）
+const MINUTES_BUCKET: i64 = 5;
+
+/// 匿名报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnonReport {
+    /// 报告格式版本
+    pub schema_version: u32,
+    /// 每天的专注分布
+    pub days: Vec<AnonDay>,
+}
+
+/// 单日匿名统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnonDay {
+    /// 相对第一天的天数
+    pub day_index: u32,
+    /// 星期（0 = 周一，6 = 周日）
+    pub weekday: u32,
+    /// 专注时长（分钟，按 5 分钟取整）
+    pub focus_minutes: i64,
+    /// 分心时长（分钟，按 5 分钟取整）
+    pub distracted_minutes: i64,
+    /// 会话数量
+    pub session_count: i32,
+    /// 会话专注时长分布：<15 / 15-30 / 30-60 / ≥60 分钟
+    pub session_lengths: [u32; 4],
+    /// 会话开始时段分布：夜间 / 上午 / 下午 / 晚上
+    pub sessions_by_part_of_day: [u32; 4],
+}
+
+/// 毫秒转换为按粒度取整的分钟数
+fn coarse_minutes(ms: i64) -> i64 {
+    let minutes = ms / 60_000;
+    (minutes + MINUTES_BUCKET / 2) / MINUTES_BUCKET * MINUTES_BUCKET
+}
+
+/// 会话时长分桶
+fn length_bucket(focus_ms: i64) -> usize {
+    match focus_ms / 60_000 {
+        m if m < 15 => 0,
+        m if m < 30 => 1,
+        m if m < 60 => 2,
+        _ => 3,
+    }
+}
+
+impl Database {
+    /// 生成匿名统计报告
+    pub fn build_anonymized_report(&self) -> SqliteResult<AnonReport> {
+        let mut stats = self.get_recent_stats(u32::MAX)?;
+        stats.sort_by(|a, b| a.date.cmp(&b.date));
+
+        let sessions = self.get_recent_sessions(u32::MAX)?;
+        let first_day = stats
+            .first()
+            .and_then(|s| NaiveDate::parse_from_str(&s.date, "%Y-%m-%d").ok());
+
+        let mut days = Vec::with_capacity(stats.len());
+        for day_stats in &stats {
+            let Ok(date) = NaiveDate::parse_from_str(&day_stats.date, "%Y-%m-%d") else {
+                continue;
+            };
+            let day_index = first_day
+                .map(|first| (date - first).num_days().max(0) as u32)
+                .unwrap_or(0);
+
+            let mut session_lengths = [0u32; 4];
+            let mut sessions_by_part_of_day = [0u32; 4];
+            for session in sessions.iter().filter(|s| session_date(s) == Some(date)) {
+                session_lengths[length_bucket(session.focus_duration_ms)] += 1;
+                if let Some(hour) = session_hour(session) {
+                    sessions_by_part_of_day[(hour / 6) as usize] += 1;
+                }
+            }
+
+            days.push(AnonDay {
+                day_index,
+                weekday: super::weekday_of(&day_stats.date).unwrap_or(0) as u32,
+                focus_minutes: coarse_minutes(day_stats.total_focus_ms),
+                distracted_minutes: coarse_minutes(day_stats.total_distracted_ms),
+                session_count: day_stats.session_count,
+                session_lengths,
+                sessions_by_part_of_day,
+            });
+        }
+
+        Ok(AnonReport {
+            schema_version: ANON_REPORT_VERSION,
+            days,
+        })
+    }
+}
+
+/// 会话开始时的本地时间
+fn session_start(session: &FocusSession) -> Option<chrono::DateTime<chrono::Local>> {
+    chrono::Local.timestamp_millis_opt(session.start_time).single()
+}
+
+/// 会话开始的本地日期
+pub(super) fn session_date(session: &FocusSession) -> Option<NaiveDate> {
+    session_start(session).map(|t| t.date_naive())
+}
+
+/// 会话开始的本地小时
+pub(super) fn session_hour(session: &FocusSession) -> Option<u32> {
+    session_start(session).map(|t| t.hour())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_ms(date: &str, hour: u32) -> i64 {
+        let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        chrono::Local
+            .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    fn session(date: &str, hour: u32, focus_minutes: i64) -> FocusSession {
+        crate::storage::test_session(local_ms(date, hour), focus_minutes * 60_000)
+    }
+
+    #[test]
+    fn test_anonymized_report() {
+        let db = Database::in_memory().unwrap();
+        db.add_daily_stats("2026-10-12", 47 * 60_000, 6 * 60_000, 2, 47 * 60_000).unwrap();
+        db.add_daily_stats("2026-10-14", 90 * 60_000, 0, 1, 90 * 60_000).unwrap();
+        db.insert_session(&session("2026-10-12", 9, 10)).unwrap();
+        db.insert_session(&session("2026-10-12", 14, 37)).unwrap();
+        db.insert_session(&session("2026-10-14", 20, 90)).unwrap();
+
+        let report = db.build_anonymized_report().unwrap();
+        assert_eq!(report.days.len(), 2);
+
+        let first = &report.days[0];
+        assert_eq!(first.day_index, 0);
+        assert_eq!(first.weekday, 0); // 2026-10-12 是周一
+        assert_eq!(first.focus_minutes, 45);
+        assert_eq!(first.distracted_minutes, 5);
+        assert_eq!(first.session_lengths, [1, 0, 1, 0]);
+        assert_eq!(first.sessions_by_part_of_day, [0, 1, 1, 0]);
+
+        let second = &report.days[1];
+        assert_eq!(second.day_index, 2);
+        assert_eq!(second.focus_minutes, 90);
+        assert_eq!(second.session_lengths, [0, 0, 0, 1]);
+        assert_eq!(second.sessions_by_part_of_day, [0, 0, 0, 1]);
+
+        // 不包含日期和原始时间戳
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("2026"));
+        let earliest = local_ms("2026-10-12", 0);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        fn max_number(value: &serde_json::Value) -> f64 {
+            match value {
+                serde_json::Value::Number(n) => n.as_f64().unwrap_or(0.0),
+                serde_json::Value::Array(items) => items.iter().map(max_number).fold(0.0, f64::max),
+                serde_json::Value::Object(map) => map.values().map(max_number).fold(0.0, f64::max),
+                _ => 0.0,
+            }
+        }
+        assert!(max_number(&value) < (earliest / 1000) as f64);
+    }
+}
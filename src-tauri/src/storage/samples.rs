@@ -0,0 +1,166 @@
+//! 专注分数采样
+//!
+//! 视觉检测运行时按固定间隔保存专注分数，用于分析一天内专注是否平稳。
+//! 与活动事件一样先在内存中缓冲，由后台任务批量写入。
+
+use super::{day_bounds_ms, Database};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 计算一致性所需的最少采样数
+pub const MIN_CONSISTENCY_SAMPLES: usize = 10;
+
+/// 专注分数采样
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FocusSample {
+    /// 时间戳 (Unix 时间戳，毫秒)
+    pub timestamp_ms: i64,
+    /// 专注分数 (0.0 - 1.0)
+    pub focus_score: f32,
+}
+
+/// 一天内专注分数的波动情况，变异系数越低说明专注越平稳
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusConsistency {
+    /// 日期 (YYYY-MM-DD)
+    pub date: String,
+    /// 采样数
+    pub samples: u32,
+    /// 平均分数
+    pub mean: f32,
+    /// 标准差
+    pub std_dev: f32,
+    /// 变异系数（标准差 / 平均分数，平均分数为 0 时为 0）
+    pub coefficient_of_variation: f32,
+}
+
+impl FocusConsistency {
+    /// 由分数序列计算，采样不足时返回 None
+    pub fn from_scores(date: &str, scores: &[f32]) -> Option<Self> {
+        if scores.len() < MIN_CONSISTENCY_SAMPLES {
+            return None;
+        }
+
+        let n = scores.len() as f64;
+        let mean = scores.iter().map(|&s| s as f64).sum::<f64>() / n;
+        let variance = scores
+            .iter()
+            .map(|&s| (s as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        let std_dev = variance.sqrt();
+        let coefficient_of_variation = if mean > f64::EPSILON {
+            std_dev / mean
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            date: date.to_string(),
+            samples: scores.len() as u32,
+            mean: mean as f32,
+            std_dev: std_dev as f32,
+            coefficient_of_variation: coefficient_of_variation as f32,
+        })
+    }
+}
+
+impl Database {
+    /// 批量写入专注分数采样（单个事务）
+    pub fn insert_focus_samples(&self, samples: &[FocusSample]) -> SqliteResult<()> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx
+                .prepare("INSERT INTO focus_samples (timestamp_ms, focus_score) VALUES (?1, ?2)")?;
+            for sample in samples {
+                stmt.execute((sample.timestamp_ms, sample.focus_score))?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// 查询时间范围 [start_ms, end_ms) 内的采样（按时间升序）
+    pub fn get_focus_samples(&self, start_ms: i64, end_ms: i64) -> SqliteResult<Vec<FocusSample>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT timestamp_ms, focus_score
+            FROM focus_samples
+            WHERE timestamp_ms >= ?1 AND timestamp_ms < ?2
+            ORDER BY timestamp_ms ASC
+            "#,
+        )?;
+
+        let rows = stmt.query_map([start_ms, end_ms], |row| {
+            Ok(FocusSample {
+                timestamp_ms: row.get(0)?,
+                focus_score: row.get(1)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 指定日期的专注一致性，采样不足时返回 None
+    pub fn get_focus_consistency(&self, date: &str) -> SqliteResult<Option<FocusConsistency>> {
+        let Some((start_ms, end_ms)) = day_bounds_ms(date) else {
+            return Ok(None);
+        };
+        let scores: Vec<f32> = self
+            .get_focus_samples(start_ms, end_ms)?
+            .into_iter()
+            .map(|s| s.focus_score)
+            .collect();
+        Ok(FocusConsistency::from_scores(date, &scores))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(db: &Database, date: &str, scores: impl Iterator<Item = f32>) {
+        let (start_ms, _) = day_bounds_ms(date).unwrap();
+        let samples: Vec<FocusSample> = scores
+            .enumerate()
+            .map(|(i, focus_score)| FocusSample {
+                timestamp_ms: start_ms + 9 * 3_600_000 + i as i64 * 5_000,
+                focus_score,
+            })
+            .collect();
+        db.insert_focus_samples(&samples).unwrap();
+    }
+
+    #[test]
+    fn test_focus_consistency() {
+        let db = Database::in_memory().unwrap();
+        seed(&db, "2026-10-15", std::iter::repeat_n(0.7, 20));
+        seed(
+            &db,
+            "2026-10-16",
+            (0..20).map(|i| if i % 2 == 0 { 0.2 } else { 1.0 }),
+        );
+        seed(
+            &db,
+            "2026-10-17",
+            std::iter::repeat_n(0.7, MIN_CONSISTENCY_SAMPLES - 1),
+        );
+
+        let steady = db.get_focus_consistency("2026-10-15").unwrap().unwrap();
+        assert_eq!(steady.samples, 20);
+        assert!((steady.mean - 0.7).abs() < 1e-5);
+        assert!(steady.std_dev < 1e-5);
+        assert!(steady.coefficient_of_variation < 1e-5);
+
+        let spiky = db.get_focus_consistency("2026-10-16").unwrap().unwrap();
+        assert!((spiky.mean - 0.6).abs() < 1e-5);
+        assert!((spiky.std_dev - 0.4).abs() < 1e-5);
+        assert!(spiky.coefficient_of_variation > steady.coefficient_of_variation + 0.5);
+
+        // 采样不足 / 没有采样
+        assert!(db.get_focus_consistency("2026-10-17").unwrap().is_none());
+        assert!(db.get_focus_consistency("2026-10-18").unwrap().is_none());
+    }
+}
@@ -0,0 +1,233 @@
+//! 连续专注保护（冻结日）
+//!
+//! 偶尔漏掉一天不应该让连续天数清零。用户可以消耗一个“冻结”名额保护某个没有专注的日子，
+//! 计算连续天数时跳过被冻结的日子（不计入天数，也不打断连续）。
+//! 名额按周期恢复：每次冻结在冻结日之后 `period_days` 天内占用一个名额。
+
+use super::Database;
+use chrono::NaiveDate;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+/// 冻结名额的恢复规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreakFreezePolicy {
+    /// 每个周期内可用的冻结次数
+    pub tokens_per_period: u32,
+    /// 周期长度（天）
+    pub period_days: u32,
+}
+
+impl StreakFreezePolicy {
+    /// 截至 `today` 的当前周期的第一天（只能冻结这一天及之后的日子）
+    pub fn period_start(&self, today: NaiveDate) -> NaiveDate {
+        today - chrono::Duration::days(self.period_days.max(1) as i64 - 1)
+    }
+}
+
+impl Default for StreakFreezePolicy {
+    fn default() -> Self {
+        Self {
+            tokens_per_period: 2,
+            period_days: 30,
+        }
+    }
+}
+
+/// 一段历史连续专注
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Streak {
+    /// 第一天 (YYYY-MM-DD 格式)
+    pub start_date: String,
+    /// 最后一天 (YYYY-MM-DD 格式)
+    pub end_date: String,
+    /// 天数
+    pub length_days: u32,
+}
+
+/// 冻结名额状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreakFreezeStatus {
+    /// 当前可用的冻结次数
+    pub available: u32,
+    /// 当前周期内已冻结的日期（升序）
+    pub frozen_dates: Vec<String>,
+    /// 当前连续专注天数
+    pub streak: u32,
+}
+
+impl Database {
+    /// 冻结指定日期（已冻结时不做任何事）
+    pub fn insert_streak_freeze(&self, date: &str, used_at_ms: i64) -> SqliteResult<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO streak_freezes (date, used_at_ms) VALUES (?1, ?2)",
+            (date, used_at_ms),
+        )?;
+        Ok(())
+    }
+
+    /// 指定日期是否被冻结
+    pub fn is_day_frozen(&self, date: &str) -> SqliteResult<bool> {
+        let found = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM streak_freezes WHERE date = ?1",
+                [date],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// 截至 `today` 的当前周期内已冻结的日期（升序）
+    fn frozen_dates_in_period(
+        &self,
+        today: NaiveDate,
+        policy: StreakFreezePolicy,
+    ) -> SqliteResult<Vec<String>> {
+        let since = policy.period_start(today);
+        let mut stmt = self.conn.prepare(
+            "SELECT date FROM streak_freezes WHERE date >= ?1 AND date <= ?2 ORDER BY date",
+        )?;
+        let rows = stmt.query_map(
+            (
+                since.format("%Y-%m-%d").to_string(),
+                today.format("%Y-%m-%d").to_string(),
+            ),
+            |row| row.get::<_, String>(0),
+        )?;
+        rows.collect()
+    }
+
+    /// 冻结名额状态
+    pub fn streak_freeze_status(
+        &self,
+        today: NaiveDate,
+        policy: StreakFreezePolicy,
+    ) -> SqliteResult<StreakFreezeStatus> {
+        let frozen_dates = self.frozen_dates_in_period(today, policy)?;
+        let available = policy
+            .tokens_per_period
+            .saturating_sub(frozen_dates.len() as u32);
+        Ok(StreakFreezeStatus {
+            available,
+            frozen_dates,
+            streak: self.focus_streak(today)?,
+        })
+    }
+
+    /// 全部历史连续专注（最近的在前）
+    ///
+    /// 专注时长达到 `min_minutes` 分钟（为 0 时只要有专注）的日子算作达标，连续的达标日组成一段。
+    /// 示例数据不计入
+    pub fn list_streaks(&self, min_minutes: u32) -> SqliteResult<Vec<Streak>> {
+        let min_ms = (min_minutes as i64 * 60_000).max(1);
+        let mut stmt = self
+            .conn
+            .prepare("SELECT date FROM daily_stats WHERE total_focus_ms >= ?1 AND demo = 0 ORDER BY date")?;
+        let dates = stmt
+            .query_map([min_ms], |row| row.get::<_, String>(0))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+
+        let mut streaks: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+        for date in dates.iter().filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+            match streaks.last_mut() {
+                Some((_, end)) if *end + chrono::Duration::days(1) == date => *end = date,
+                _ => streaks.push((date, date)),
+            }
+        }
+
+        Ok(streaks
+            .into_iter()
+            .rev()
+            .map(|(start, end)| Streak {
+                start_date: start.format("%Y-%m-%d").to_string(),
+                end_date: end.format("%Y-%m-%d").to_string(),
+                length_days: (end - start).num_days() as u32 + 1,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_frozen_gap_keeps_streak() {
+        let db = Database::in_memory().unwrap();
+        for date in ["2026-10-12", "2026-10-13", "2026-10-15", "2026-10-16"] {
+            db.add_daily_stats(date, 20 * 60_000, 0, 1, 20 * 60_000).unwrap();
+        }
+
+        // 没有冻结时，10-14 的空缺打断连续
+        assert_eq!(db.focus_streak(day("2026-10-16")).unwrap(), 2);
+
+        // 冻结后跳过空缺，冻结日本身不计入天数
+        db.insert_streak_freeze("2026-10-14", 0).unwrap();
+        assert_eq!(db.focus_streak(day("2026-10-16")).unwrap(), 4);
+
+        // 另一个未冻结的空缺仍然打断连续
+        db.add_daily_stats("2026-10-10", 20 * 60_000, 0, 1, 20 * 60_000).unwrap();
+        assert_eq!(db.focus_streak(day("2026-10-16")).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_freeze_tokens_replenish() {
+        let db = Database::in_memory().unwrap();
+        let policy = StreakFreezePolicy {
+            tokens_per_period: 2,
+            period_days: 7,
+        };
+
+        let status = db.streak_freeze_status(day("2026-10-16"), policy).unwrap();
+        assert_eq!(status.available, 2);
+
+        db.insert_streak_freeze("2026-10-10", 0).unwrap();
+        db.insert_streak_freeze("2026-10-14", 0).unwrap();
+        // 重复冻结同一天不额外消耗
+        db.insert_streak_freeze("2026-10-14", 0).unwrap();
+        let status = db.streak_freeze_status(day("2026-10-16"), policy).unwrap();
+        assert_eq!(status.available, 0);
+        assert_eq!(status.frozen_dates, vec!["2026-10-10", "2026-10-14"]);
+
+        // 10-10 的冻结在 7 天后恢复
+        let status = db.streak_freeze_status(day("2026-10-17"), policy).unwrap();
+        assert_eq!(status.available, 1);
+    }
+
+    #[test]
+    fn test_list_streaks() {
+        let db = Database::in_memory().unwrap();
+        for date in ["2026-09-28", "2026-09-29", "2026-09-30", "2026-10-01"] {
+            db.add_daily_stats(date, 30 * 60_000, 0, 1, 30 * 60_000).unwrap();
+        }
+        // 不达标的一天打断连续
+        db.add_daily_stats("2026-10-02", 5 * 60_000, 0, 1, 5 * 60_000).unwrap();
+        db.add_daily_stats("2026-10-03", 30 * 60_000, 0, 1, 30 * 60_000).unwrap();
+        db.add_daily_stats("2026-10-04", 30 * 60_000, 0, 1, 30 * 60_000).unwrap();
+        // 单日连续
+        db.add_daily_stats("2026-10-10", 30 * 60_000, 0, 1, 30 * 60_000).unwrap();
+
+        let streaks = db.list_streaks(20).unwrap();
+        let summary: Vec<_> = streaks
+            .iter()
+            .map(|s| (s.start_date.as_str(), s.end_date.as_str(), s.length_days))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("2026-10-10", "2026-10-10", 1),
+                ("2026-10-03", "2026-10-04", 2),
+                ("2026-09-28", "2026-10-01", 4),
+            ]
+        );
+
+        // 阈值为 0 时只要有专注就算达标
+        assert_eq!(db.list_streaks(0).unwrap()[1].length_days, 7);
+    }
+}
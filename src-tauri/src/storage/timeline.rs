@@ -0,0 +1,328 @@
+//! 专注时间线
+//!
+//! 把一天划分为专注、分心、离开三种片段：视觉检测会话覆盖的时间默认为专注，
+//! 其中的分心记录按触发原因标为分心（专注分数下降）或离开（人脸丢失），会话之外的时间都视为离开。
+//! 时间线可以绘制成横向色条 PNG，方便嵌入报告或分享。
+//! 从时间线还可以找出当天最长的连续专注块（“今天最长的深度工作”），比按会话统计的最长专注更细。
+
+use super::card::encode_png;
+use super::distractions::{DistractionEvent, DistractionTrigger};
+use super::Database;
+use image::{Rgb, RgbImage};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+/// 时间线图片宽度（像素，每像素约 1 分钟）
+pub const TIMELINE_WIDTH: u32 = 1440;
+
+/// 时间线图片高度（像素）
+pub const TIMELINE_HEIGHT: u32 = 32;
+
+/// 连续专注块中允许夹杂的短暂分心或离开（毫秒），不超过该时长的间断不打断专注块
+pub const FOCUS_BLOCK_GRACE_MS: i64 = 60_000;
+
+/// 专注颜色
+const FOCUSED: Rgb<u8> = Rgb([76, 175, 80]);
+
+/// 分心颜色
+const DISTRACTED: Rgb<u8> = Rgb([255, 193, 7]);
+
+/// 离开颜色（也是图片底色）
+const AWAY: Rgb<u8> = Rgb([189, 189, 189]);
+
+/// 时间线片段状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineState {
+    /// 专注
+    Focused,
+    /// 分心
+    Distracted,
+    /// 离开
+    Away,
+}
+
+impl TimelineState {
+    /// 绘制时使用的颜色
+    fn color(&self) -> Rgb<u8> {
+        match self {
+            Self::Focused => FOCUSED,
+            Self::Distracted => DISTRACTED,
+            Self::Away => AWAY,
+        }
+    }
+}
+
+/// 时间线片段 `[start_ms, end_ms)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineSegment {
+    /// 开始时间 (Unix 时间戳，毫秒)
+    pub start_ms: i64,
+    /// 结束时间 (Unix 时间戳，毫秒)
+    pub end_ms: i64,
+    /// 状态
+    pub state: TimelineState,
+}
+
+/// 一段连续专注 `[start_ms, end_ms)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusBlock {
+    /// 开始时间 (Unix 时间戳，毫秒)
+    pub start_ms: i64,
+    /// 结束时间 (Unix 时间戳，毫秒)
+    pub end_ms: i64,
+    /// 时长（毫秒，包含其中的短暂间断）
+    pub duration_ms: i64,
+    /// 期间的平均专注分数（没有采样时为 None）
+    pub avg_score: Option<f32>,
+}
+
+impl Database {
+    /// 指定日期最长的连续专注块，不超过 `FOCUS_BLOCK_GRACE_MS` 的分心或离开不打断专注块；没有专注时返回 None
+    pub fn get_longest_focus_block(&self, date: &str) -> SqliteResult<Option<FocusBlock>> {
+        let segments = self.get_day_timeline(date)?;
+        let Some((start_ms, end_ms)) = longest_focus_span(&segments, FOCUS_BLOCK_GRACE_MS) else {
+            return Ok(None);
+        };
+
+        let samples = self.get_focus_samples(start_ms, end_ms)?;
+        let avg_score = (!samples.is_empty())
+            .then(|| samples.iter().map(|s| s.focus_score).sum::<f32>() / samples.len() as f32);
+        Ok(Some(FocusBlock {
+            start_ms,
+            end_ms,
+            duration_ms: end_ms - start_ms,
+            avg_score,
+        }))
+    }
+
+    /// 指定日期的时间线片段（按时间升序，只包含会话覆盖的时间，未覆盖的时间视为离开）
+    pub fn get_day_timeline(&self, date: &str) -> SqliteResult<Vec<TimelineSegment>> {
+        let Some((day_start, day_end)) = super::day_bounds_ms(date) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT start_time, end_time
+            FROM sessions
+            WHERE end_time > ?1 AND start_time < ?2
+            ORDER BY start_time
+            "#,
+        )?;
+        let sessions = stmt
+            .query_map([day_start, day_end], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        let distractions = self.get_distractions_between(day_start, day_end)?;
+
+        Ok(build_timeline(day_start, day_end, &sessions, &distractions))
+    }
+
+    /// 绘制指定日期的时间线色条，返回 PNG 数据
+    ///
+    /// 横轴为当天 0 点到 24 点，绿色为专注、黄色为分心、灰色为离开；没有记录的日期为整条灰色
+    pub fn render_timeline_image(&self, date: &str) -> SqliteResult<Vec<u8>> {
+        let segments = self.get_day_timeline(date)?;
+        let image = match super::day_bounds_ms(date) {
+            Some((day_start, day_end)) => draw_timeline(day_start, day_end, &segments),
+            None => RgbImage::from_pixel(TIMELINE_WIDTH, TIMELINE_HEIGHT, AWAY),
+        };
+        Ok(encode_png(&image))
+    }
+}
+
+/// 由会话和分心记录生成时间线片段（都截取到 `[day_start, day_end)` 之内）
+fn build_timeline(
+    day_start: i64,
+    day_end: i64,
+    sessions: &[(i64, i64)],
+    distractions: &[DistractionEvent],
+) -> Vec<TimelineSegment> {
+    let mut segments = Vec::new();
+    let mut push = |start_ms: i64, end_ms: i64, state: TimelineState| {
+        if end_ms > start_ms {
+            segments.push(TimelineSegment {
+                start_ms,
+                end_ms,
+                state,
+            });
+        }
+    };
+
+    let mut covered_until = day_start;
+    for &(start, end) in sessions {
+        let end = end.min(day_end);
+        // 会话之间有重叠时只绘制未覆盖的部分
+        let mut cursor = start.max(covered_until);
+        if cursor >= end {
+            continue;
+        }
+
+        for event in distractions {
+            let event_start = event.start_ms.max(cursor);
+            let event_end = (event.start_ms + event.duration_ms).min(end);
+            if event_end <= event_start {
+                continue;
+            }
+            let state = match event.trigger {
+                DistractionTrigger::LowFocus => TimelineState::Distracted,
+                DistractionTrigger::FaceLost => TimelineState::Away,
+            };
+            push(cursor, event_start, TimelineState::Focused);
+            push(event_start, event_end, state);
+            cursor = event_end;
+        }
+        push(cursor, end, TimelineState::Focused);
+        covered_until = end;
+    }
+
+    segments
+}
+
+/// 合并间隔不超过 `grace_ms` 的专注片段，返回最长的一段（时长相同时取较早的）
+fn longest_focus_span(segments: &[TimelineSegment], grace_ms: i64) -> Option<(i64, i64)> {
+    let mut longest: Option<(i64, i64)> = None;
+    let mut current: Option<(i64, i64)> = None;
+    let focused = segments.iter().filter(|s| s.state == TimelineState::Focused);
+
+    for segment in focused {
+        current = match current {
+            Some((start, end)) if segment.start_ms - end <= grace_ms => Some((start, segment.end_ms.max(end))),
+            _ => Some((segment.start_ms, segment.end_ms)),
+        };
+        if let Some((start, end)) = current {
+            if longest.is_none_or(|(s, e)| end - start > e - s) {
+                longest = Some((start, end));
+            }
+        }
+    }
+
+    longest
+}
+
+/// 绘制时间线色条
+fn draw_timeline(day_start: i64, day_end: i64, segments: &[TimelineSegment]) -> RgbImage {
+    let mut image = RgbImage::from_pixel(TIMELINE_WIDTH, TIMELINE_HEIGHT, AWAY);
+    let day_length = (day_end - day_start).max(1);
+    let to_x = |ms: i64| ((ms - day_start).clamp(0, day_length) * TIMELINE_WIDTH as i64 / day_length) as u32;
+
+    for segment in segments {
+        let x0 = to_x(segment.start_ms);
+        // 很短的片段也至少占 1 像素
+        let x1 = to_x(segment.end_ms).max(x0 + 1).min(TIMELINE_WIDTH);
+        for x in x0..x1 {
+            for y in 0..TIMELINE_HEIGHT {
+                image.put_pixel(x, y, segment.state.color());
+            }
+        }
+    }
+
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::samples::FocusSample;
+    use crate::storage::{test_session, FocusSession};
+    use image::ImageFormat;
+
+    fn session(start_time: i64, end_time: i64) -> FocusSession {
+        test_session(start_time, end_time - start_time)
+    }
+
+    /// 解码 PNG，返回各颜色的像素数 (专注, 分心, 离开)
+    fn color_counts(png: &[u8]) -> (usize, usize, usize) {
+        let image = image::load_from_memory_with_format(png, ImageFormat::Png)
+            .unwrap()
+            .to_rgb8();
+        assert_eq!((image.width(), image.height()), (TIMELINE_WIDTH, TIMELINE_HEIGHT));
+        let count = |color: Rgb<u8>| image.pixels().filter(|&&p| p == color).count();
+        (count(FOCUSED), count(DISTRACTED), count(AWAY))
+    }
+
+    #[test]
+    fn test_render_timeline_image() {
+        let db = Database::in_memory().unwrap();
+        let (day_start, day_end) = crate::storage::day_bounds_ms("2026-10-16").unwrap();
+        let total = (TIMELINE_WIDTH * TIMELINE_HEIGHT) as usize;
+
+        // 没有记录：整条灰色
+        assert_eq!(color_counts(&db.render_timeline_image("2026-10-16").unwrap()), (0, 0, total));
+
+        // 全天专注，中间有 10 分钟分心、5 分钟离开
+        db.insert_session(&session(day_start - 3_600_000, day_end)).unwrap();
+        for (offset_min, minutes, trigger) in [
+            (600, 10, DistractionTrigger::LowFocus),
+            (900, 5, DistractionTrigger::FaceLost),
+        ] {
+            db.insert_distraction(&DistractionEvent {
+                id: 0,
+                start_ms: day_start + offset_min * 60_000,
+                duration_ms: minutes * 60_000,
+                trigger,
+            })
+            .unwrap();
+        }
+
+        let timeline = db.get_day_timeline("2026-10-16").unwrap();
+        let states: Vec<TimelineState> = timeline.iter().map(|s| s.state).collect();
+        assert_eq!(
+            states,
+            [
+                TimelineState::Focused,
+                TimelineState::Distracted,
+                TimelineState::Focused,
+                TimelineState::Away,
+                TimelineState::Focused
+            ]
+        );
+        assert_eq!(timeline[0].start_ms, day_start);
+        assert_eq!(timeline[4].end_ms, day_end);
+
+        let (focused, distracted, away) = color_counts(&db.render_timeline_image("2026-10-16").unwrap());
+        assert!(focused > total * 9 / 10, "{} / {}", focused, total);
+        assert_eq!(distracted, 10 * TIMELINE_HEIGHT as usize);
+        assert_eq!(away, 5 * TIMELINE_HEIGHT as usize);
+    }
+
+    #[test]
+    fn test_longest_focus_block() {
+        let db = Database::in_memory().unwrap();
+        let (day_start, _) = crate::storage::day_bounds_ms("2026-10-16").unwrap();
+        let at = |minutes: i64| day_start + minutes * 60_000;
+        assert_eq!(db.get_longest_focus_block("2026-10-16").unwrap(), None);
+
+        // 9:00-10:00 专注一小时；13:00-15:00 中间有 30 秒分心（不打断）和 20 分钟分心（打断）；
+        // 16:00-17:10 与 17:10:40 开始的会话之间只隔 40 秒
+        for (start, end) in [(540, 600), (780, 900), (960, 1030)] {
+            db.insert_session(&session(at(start), at(end))).unwrap();
+        }
+        db.insert_session(&session(at(1030) + 40_000, at(1090))).unwrap();
+        for (start_ms, duration_ms) in [(at(800), 30_000), (at(850), 20 * 60_000)] {
+            db.insert_distraction(&DistractionEvent {
+                id: 0,
+                start_ms,
+                duration_ms,
+                trigger: DistractionTrigger::LowFocus,
+            })
+            .unwrap();
+        }
+        let samples: Vec<FocusSample> = [(at(560), 0.5), (at(970), 0.8), (at(1040), 0.9), (at(1100), 0.1)]
+            .into_iter()
+            .map(|(timestamp_ms, focus_score)| FocusSample {
+                timestamp_ms,
+                focus_score,
+            })
+            .collect();
+        db.insert_focus_samples(&samples).unwrap();
+
+        // 13:00-14:10 为 70 分钟，16:00-18:10 合并为 130 分钟
+        let block = db.get_longest_focus_block("2026-10-16").unwrap().unwrap();
+        assert_eq!((block.start_ms, block.end_ms), (at(960), at(1090)));
+        assert_eq!(block.duration_ms, 130 * 60_000);
+        assert!((block.avg_score.unwrap() - 0.85).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,83 @@
+//! 预览画面匿名化
+//!
+//! 只作用于发送到前端的预览图，检测器始终使用原始帧。
+//! 适合直播或共享屏幕时不希望露脸的用户。
+
+use image::{imageops, RgbImage};
+use serde::{Deserialize, Serialize};
+
+/// 最小处理强度
+pub const MIN_BLUR_STRENGTH: f32 = 1.0;
+
+/// 最大处理强度
+pub const MAX_BLUR_STRENGTH: f32 = 32.0;
+
+/// 预览模糊方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewBlur {
+    /// 不处理
+    #[default]
+    Off,
+    /// 高斯模糊，强度为 sigma（像素）
+    Gaussian,
+    /// 马赛克，强度为色块边长（像素）
+    Pixelate,
+}
+
+impl PreviewBlur {
+    /// 对预览图做匿名化处理，`strength` 会被限制在 [1, 32]
+    pub fn apply(self, image: RgbImage, strength: f32) -> RgbImage {
+        let strength = strength.clamp(MIN_BLUR_STRENGTH, MAX_BLUR_STRENGTH);
+        match self {
+            Self::Off => image,
+            Self::Gaussian => imageops::blur(&image, strength),
+            Self::Pixelate => pixelate(&image, strength.round() as u32),
+        }
+    }
+}
+
+/// 按色块缩小再放大（最近邻），得到马赛克效果
+fn pixelate(image: &RgbImage, block: u32) -> RgbImage {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 || block <= 1 {
+        return image.clone();
+    }
+
+    let small_w = width.div_ceil(block);
+    let small_h = height.div_ceil(block);
+    let small = imageops::resize(image, small_w, small_h, imageops::FilterType::Triangle);
+    imageops::resize(&small, width, height, imageops::FilterType::Nearest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard(width: u32, height: u32) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            if (x + y) % 2 == 0 {
+                image::Rgb([255, 255, 255])
+            } else {
+                image::Rgb([0, 0, 0])
+            }
+        })
+    }
+
+    #[test]
+    fn test_preview_blur_modes() {
+        let original = checkerboard(32, 24);
+
+        assert_eq!(PreviewBlur::Off.apply(original.clone(), 8.0), original);
+
+        for mode in [PreviewBlur::Gaussian, PreviewBlur::Pixelate] {
+            let blurred = mode.apply(original.clone(), 4.0);
+            assert_eq!(blurred.dimensions(), original.dimensions());
+            assert_ne!(blurred, original, "{:?} should change the image", mode);
+        }
+
+        // 马赛克：同一色块内颜色一致
+        let pixelated = PreviewBlur::Pixelate.apply(original, 4.0);
+        assert_eq!(pixelated.get_pixel(0, 0), pixelated.get_pixel(3, 3));
+    }
+}
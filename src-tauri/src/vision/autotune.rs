@@ -0,0 +1,257 @@
+//! 摄像头参数自动调优
+//!
+//! 在几组帧率 / 分辨率组合下各采集少量帧并运行人脸检测，
+//! 测量检测耗时和置信度，推荐兼顾 CPU 占用和检测效果的设置。
+//! 模拟模式下不打开摄像头，直接返回预设的测量结果。
+
+use serde::{Deserialize, Serialize};
+
+/// 参与测量的组合：(帧率, 宽, 高)
+pub const TUNE_CANDIDATES: [(u32, u32, u32); 4] = [
+    (10, 320, 240),
+    (15, 320, 240),
+    (15, 640, 480),
+    (30, 640, 480),
+];
+
+/// 每个组合采集的帧数
+pub const FRAMES_PER_CANDIDATE: usize = 15;
+
+/// 检测耗时占用单核时间的上限（耗时 × 帧率）
+const MAX_DETECTION_LOAD: f32 = 0.25;
+
+/// 检测质量与最佳组合相差不超过该值时，视为效果相当，选更省 CPU 的组合
+const QUALITY_TOLERANCE: f32 = 0.05;
+
+/// 单个组合的测量结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub fps: u32,
+    pub width: u32,
+    pub height: u32,
+    /// 平均单帧检测耗时（毫秒）
+    pub avg_latency_ms: f32,
+    /// 检测到人脸的帧中，最高置信度的平均值
+    pub avg_confidence: f32,
+    /// 参与测量的帧数
+    pub frames: u32,
+    /// 检测到人脸的帧数
+    pub detected_frames: u32,
+}
+
+impl BenchmarkResult {
+    /// 检测占用单核时间的比例
+    pub fn load(&self) -> f32 {
+        self.avg_latency_ms * self.fps as f32 / 1000.0
+    }
+
+    /// 检测质量：平均置信度 × 检出率
+    pub fn quality(&self) -> f32 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.avg_confidence * self.detected_frames as f32 / self.frames as f32
+    }
+}
+
+/// 推荐的摄像头设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecommendedCameraSettings {
+    pub fps: u32,
+    pub width: u32,
+    pub height: u32,
+    /// 推荐组合的平均检测耗时（毫秒）
+    pub avg_latency_ms: f32,
+    /// 推荐组合的平均置信度
+    pub avg_confidence: f32,
+    /// 是否已应用到当前设置
+    pub applied: bool,
+    /// 全部测量结果
+    pub results: Vec<BenchmarkResult>,
+}
+
+/// 根据测量结果选择推荐设置
+///
+/// 在 CPU 占用不超过上限的组合中，取检测质量接近最佳的最省 CPU 组合；
+/// 都超出上限时退而选占用最低的组合。没有有效结果时返回 `None`
+pub fn recommend(results: &[BenchmarkResult]) -> Option<RecommendedCameraSettings> {
+    let measured: Vec<&BenchmarkResult> = results.iter().filter(|r| r.frames > 0).collect();
+    let by_load = |a: &&BenchmarkResult, b: &&BenchmarkResult| a.load().total_cmp(&b.load());
+
+    let feasible: Vec<&BenchmarkResult> = measured
+        .iter()
+        .copied()
+        .filter(|r| r.load() <= MAX_DETECTION_LOAD)
+        .collect();
+
+    let chosen = if feasible.is_empty() {
+        measured.into_iter().min_by(by_load)?
+    } else {
+        let best_quality = feasible.iter().map(|r| r.quality()).fold(0.0, f32::max);
+        feasible
+            .into_iter()
+            .filter(|r| r.quality() >= best_quality - QUALITY_TOLERANCE)
+            .min_by(by_load)?
+    };
+
+    Some(RecommendedCameraSettings {
+        fps: chosen.fps,
+        width: chosen.width,
+        height: chosen.height,
+        avg_latency_ms: chosen.avg_latency_ms,
+        avg_confidence: chosen.avg_confidence,
+        applied: false,
+        results: results.to_vec(),
+    })
+}
+
+/// 依次测量每个组合，结束后摄像头已释放
+#[cfg(feature = "vision")]
+pub fn run_benchmarks(
+    device_index: u32,
+    detector: &mut super::BlazeFaceDetector,
+) -> Result<Vec<BenchmarkResult>, String> {
+    use super::{CameraCapture, CameraConfig};
+    use std::time::Instant;
+
+    let mut results = Vec::with_capacity(TUNE_CANDIDATES.len());
+    for (fps, width, height) in TUNE_CANDIDATES {
+        let config = CameraConfig {
+            device_index,
+            target_fps: fps,
+            width,
+            height,
+            ..Default::default()
+        };
+        let frames = CameraCapture::grab_frames(&config, FRAMES_PER_CANDIDATE)?;
+
+        let mut total_latency_ms = 0.0;
+        let mut total_confidence = 0.0;
+        let mut detected_frames = 0;
+        for frame in &frames {
+            let started = Instant::now();
+            let detections = detector
+                .detect(&frame.data, frame.width, frame.height)
+                .map_err(|e| e.to_string())?;
+            total_latency_ms += started.elapsed().as_secs_f32() * 1000.0;
+
+            if let Some(best) = detections.first() {
+                total_confidence += best.confidence;
+                detected_frames += 1;
+            }
+        }
+
+        let count = frames.len().max(1) as f32;
+        results.push(BenchmarkResult {
+            fps,
+            width,
+            height,
+            avg_latency_ms: total_latency_ms / count,
+            avg_confidence: if detected_frames > 0 {
+                total_confidence / detected_frames as f32
+            } else {
+                0.0
+            },
+            frames: frames.len() as u32,
+            detected_frames,
+        });
+    }
+
+    Ok(results)
+}
+
+/// 模拟模式：返回预设的测量结果
+#[cfg(not(feature = "vision"))]
+pub fn run_benchmarks(
+    _device_index: u32,
+    _detector: &mut super::BlazeFaceDetector,
+) -> Result<Vec<BenchmarkResult>, String> {
+    Ok(TUNE_CANDIDATES
+        .iter()
+        .map(|&(fps, width, height)| {
+            let large = width >= 640;
+            BenchmarkResult {
+                fps,
+                width,
+                height,
+                avg_latency_ms: if large { 12.0 } else { 8.0 },
+                avg_confidence: if large { 0.9 } else { 0.88 },
+                frames: FRAMES_PER_CANDIDATE as u32,
+                detected_frames: FRAMES_PER_CANDIDATE as u32,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(
+        fps: u32,
+        width: u32,
+        latency: f32,
+        confidence: f32,
+        detected: u32,
+    ) -> BenchmarkResult {
+        BenchmarkResult {
+            fps,
+            width,
+            height: width * 3 / 4,
+            avg_latency_ms: latency,
+            avg_confidence: confidence,
+            frames: 10,
+            detected_frames: detected,
+        }
+    }
+
+    #[test]
+    fn test_recommend_camera_settings() {
+        assert!(recommend(&[]).is_none());
+
+        // 效果相当时选更省 CPU 的组合
+        let results = vec![
+            result(10, 320, 8.0, 0.88, 10),
+            result(15, 320, 8.0, 0.88, 10),
+            result(15, 640, 12.0, 0.90, 10),
+        ];
+        let rec = recommend(&results).unwrap();
+        assert_eq!((rec.fps, rec.width), (10, 320));
+        assert!(!rec.applied);
+        assert_eq!(rec.results.len(), 3);
+
+        // 低分辨率检出率明显更差时选高分辨率
+        let results = vec![
+            result(10, 320, 8.0, 0.8, 5),
+            result(15, 640, 12.0, 0.9, 10),
+            result(30, 640, 12.0, 0.9, 10),
+        ];
+        let rec = recommend(&results).unwrap();
+        assert_eq!((rec.fps, rec.width), (15, 640));
+
+        // 超出 CPU 上限的组合不参与比较（30 × 12ms = 0.36）
+        let results = vec![
+            result(10, 320, 8.0, 0.5, 10),
+            result(30, 640, 12.0, 0.95, 10),
+        ];
+        assert_eq!(recommend(&results).unwrap().fps, 10);
+
+        // 全部超出上限时选占用最低的
+        let results = vec![
+            result(30, 320, 20.0, 0.9, 10),
+            result(30, 640, 40.0, 0.9, 10),
+        ];
+        assert_eq!(recommend(&results).unwrap().width, 320);
+    }
+
+    #[cfg(not(feature = "vision"))]
+    #[test]
+    fn test_mock_auto_tune() {
+        let mut detector =
+            super::super::BlazeFaceDetector::new("models/blazeface.onnx", None).unwrap();
+        let results = run_benchmarks(0, &mut detector).unwrap();
+        assert_eq!(results.len(), TUNE_CANDIDATES.len());
+        let rec = recommend(&results).unwrap();
+        assert_eq!((rec.fps, rec.width, rec.height), (10, 320, 240));
+    }
+}
@@ -0,0 +1,85 @@
+//! 自适应检测频率
+//!
+//! 专注分数在进入 / 退出阈值附近徘徊时，状态随时可能切换，需要更频繁地检测；
+//! 明显专注或明显离开时状态稳定，可以隔更多帧再检测，把 CPU 花在需要的地方。
+
+/// 检测频率配置
+#[derive(Debug, Clone)]
+pub struct DetectionCadenceConfig {
+    /// 最小检测间隔（帧），分数接近阈值时使用
+    pub min_interval: u32,
+    /// 最大检测间隔（帧），分数远离阈值时使用
+    pub max_interval: u32,
+    /// 与阈值的距离在该范围内视为“临界”，使用最小间隔
+    pub borderline_margin: f32,
+    /// 进入专注状态的阈值
+    pub enter_threshold: f32,
+    /// 退出专注状态的阈值
+    pub exit_threshold: f32,
+}
+
+impl Default for DetectionCadenceConfig {
+    fn default() -> Self {
+        Self {
+            min_interval: 1,
+            max_interval: 3,
+            borderline_margin: 0.1,
+            enter_threshold: 0.75,
+            exit_threshold: 0.35,
+        }
+    }
+}
+
+impl DetectionCadenceConfig {
+    /// 根据最近一次的专注分数决定下一次检测前间隔的帧数
+    ///
+    /// 临界范围内使用最小间隔；超出后随距离线性增加，距离达到两倍临界范围时为最大间隔
+    pub fn interval_for(&self, focus_score: f32) -> u32 {
+        let min = self.min_interval.max(1);
+        let max = self.max_interval.max(min);
+        let margin = self.borderline_margin.max(f32::EPSILON);
+
+        let distance = (focus_score - self.enter_threshold)
+            .abs()
+            .min((focus_score - self.exit_threshold).abs());
+        let t = ((distance - margin) / margin).clamp(0.0, 1.0);
+
+        min + ((max - min) as f32 * t).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borderline_scores_detect_more_often() {
+        let config = DetectionCadenceConfig::default();
+
+        // 阈值附近：每帧检测
+        assert_eq!(config.interval_for(0.74), 1);
+        assert_eq!(config.interval_for(0.38), 1);
+
+        // 明显专注 / 明显离开：间隔更大
+        let focused = config.interval_for(0.98);
+        let away = config.interval_for(0.0);
+        assert!(focused > config.interval_for(0.74));
+        assert_eq!(focused, config.max_interval);
+        assert_eq!(away, config.max_interval);
+
+        // 上下限
+        let fixed = DetectionCadenceConfig {
+            min_interval: 2,
+            max_interval: 2,
+            ..Default::default()
+        };
+        assert_eq!(fixed.interval_for(0.74), 2);
+        assert_eq!(fixed.interval_for(0.0), 2);
+        let inverted = DetectionCadenceConfig {
+            min_interval: 0,
+            max_interval: 0,
+            ..Default::default()
+        };
+        assert_eq!(inverted.interval_for(0.0), 1);
+    }
+}
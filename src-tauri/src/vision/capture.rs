@@ -2,10 +2,90 @@
 //! 负责从摄像头捕获视频帧，支持真实摄像头和模拟模式
 
 use image::RgbImage;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::watch;
 
+/// 允许的最低帧率
+pub const MIN_FPS: u32 = 1;
+/// 允许的最高帧率
+pub const MAX_FPS: u32 = 60;
+
+/// 把帧率限制在允许范围内
+pub fn clamp_fps(fps: u32) -> u32 {
+    fps.clamp(MIN_FPS, MAX_FPS)
+}
+
+/// 帧率对应的帧间隔（超出范围的帧率会先被限制）
+pub fn frame_interval_for_fps(fps: u32) -> std::time::Duration {
+    std::time::Duration::from_millis(1000 / clamp_fps(fps) as u64)
+}
+
+/// 像素格式偏好
+///
+/// 请求 RGB 时由驱动/nokhwa 做颜色转换，部分只输出 MJPEG/YUYV 的摄像头会很慢甚至不支持，
+/// 此时可以请求摄像头原生格式，由我们自己转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelFormatPreference {
+    /// 优先原生格式（YUYV / MJPEG），失败时回退到 RGB
+    #[default]
+    Auto,
+    /// 请求 RGB
+    Rgb,
+    /// 优先 YUYV，失败时回退到 RGB
+    Yuyv,
+    /// 优先 MJPEG，失败时回退到 RGB
+    Mjpeg,
+}
+
+/// YUYV (YUV 4:2:2) 转换为 RGB（BT.601，整数运算）
+///
+/// # Returns
+/// 数据长度与尺寸不匹配时返回 None
+pub fn yuyv_to_rgb(data: &[u8], width: u32, height: u32) -> Option<Vec<u8>> {
+    let pixels = (width * height) as usize;
+    if width % 2 == 1 || data.len() < pixels * 2 {
+        return None;
+    }
+
+    let convert = |y: u8, u: i32, v: i32| -> [u8; 3] {
+        let c = y as i32 - 16;
+        let r = (298 * c + 409 * v + 128) >> 8;
+        let g = (298 * c - 100 * u - 208 * v + 128) >> 8;
+        let b = (298 * c + 516 * u + 128) >> 8;
+        [r.clamp(0, 255) as u8, g.clamp(0, 255) as u8, b.clamp(0, 255) as u8]
+    };
+
+    let mut rgb = Vec::with_capacity(pixels * 3);
+    for block in data[..pixels * 2].chunks_exact(4) {
+        let (y0, u, y1, v) = (block[0], block[1] as i32 - 128, block[2], block[3] as i32 - 128);
+        rgb.extend_from_slice(&convert(y0, u, v));
+        rgb.extend_from_slice(&convert(y1, u, v));
+    }
+
+    Some(rgb)
+}
+
+/// 摄像头被其他应用占用时各平台返回的错误信息片段（小写）
+const CAMERA_BUSY_PATTERNS: &[&str] = &[
+    // Linux V4L2: EBUSY
+    "device or resource busy",
+    "os error 16",
+    // Windows Media Foundation: 硬件无法启动视频流 / 设备被其他进程占用
+    "0xc00d3704",
+    "0x80070020",
+    "being used by another process",
+    // macOS AVFoundation: AVErrorDeviceInUseByAnotherApplication
+    "in use by another application",
+    "deviceinusebyanotherapplication",
+];
+
+/// 错误信息是否表示摄像头被其他应用占用
+pub fn is_camera_busy_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    CAMERA_BUSY_PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
 /// 摄像头配置
 #[derive(Debug, Clone)]
 pub struct CameraConfig {
@@ -17,6 +97,8 @@ pub struct CameraConfig {
     pub width: u32,
     /// 采集高度
     pub height: u32,
+    /// 像素格式偏好
+    pub pixel_format: PixelFormatPreference,
 }
 
 impl Default for CameraConfig {
@@ -26,6 +108,7 @@ impl Default for CameraConfig {
             target_fps: 10, // 降低帧率以减少 CPU 占用
             width: 320,     // 使用较低分辨率
             height: 240,
+            pixel_format: PixelFormatPreference::default(),
         }
     }
 }
@@ -41,6 +124,8 @@ pub struct CapturedFrame {
     pub data: Vec<u8>,
     /// 时间戳（毫秒）
     pub timestamp_ms: u64,
+    /// 帧编号（由视觉处理器分配，用于匹配预览帧和叠加层数据）
+    pub frame_id: u64,
 }
 
 impl CapturedFrame {
@@ -51,6 +136,7 @@ impl CapturedFrame {
             height: 0,
             data: Vec::new(),
             timestamp_ms: 0,
+            frame_id: 0,
         }
     }
 
@@ -59,6 +145,13 @@ impl CapturedFrame {
         self.data.is_empty()
     }
 
+    /// 帧是否已过时（采集时间早于 `now_ms` 超过 `max_age_ms`）
+    ///
+    /// 处理跟不上采集时，watch 通道里取到的可能是较早的帧，检测它已经没有意义
+    pub fn is_stale(&self, now_ms: u64, max_age_ms: u64) -> bool {
+        now_ms.saturating_sub(self.timestamp_ms) > max_age_ms
+    }
+
     /// 转换为 image crate 的 RgbImage
     pub fn to_rgb_image(&self) -> Option<RgbImage> {
         if self.is_empty() {
@@ -91,20 +184,37 @@ pub struct CameraCapture {
     frame_tx: watch::Sender<CapturedFrame>,
     /// 帧接收端（供外部订阅）
     frame_rx: watch::Receiver<CapturedFrame>,
+    /// 目标帧率（采集循环每帧读取，可在运行中修改）
+    target_fps: Arc<AtomicU32>,
 }
 
 impl CameraCapture {
     /// 创建新的摄像头采集器
     pub fn new(config: CameraConfig) -> Self {
         let (frame_tx, frame_rx) = watch::channel(CapturedFrame::empty());
+        let target_fps = Arc::new(AtomicU32::new(clamp_fps(config.target_fps)));
         Self {
             config,
             running: Arc::new(AtomicBool::new(false)),
             frame_tx,
             frame_rx,
+            target_fps,
         }
     }
 
+    /// 使用外部共享的帧率控制（用于在采集器创建之前就能调整帧率）
+    pub fn with_fps_control(mut self, target_fps: Arc<AtomicU32>) -> Self {
+        self.target_fps = target_fps;
+        self
+    }
+
+    /// 运行中调整目标帧率，返回限制后的实际帧率
+    pub fn set_target_fps(&self, fps: u32) -> u32 {
+        let fps = clamp_fps(fps);
+        self.target_fps.store(fps, Ordering::SeqCst);
+        fps
+    }
+
     /// 获取帧接收器的克隆（用于订阅最新帧）
     pub fn subscribe(&self) -> watch::Receiver<CapturedFrame> {
         self.frame_rx.clone()
@@ -121,6 +231,7 @@ impl CameraCapture {
         let running = self.running.clone();
         let config = self.config.clone();
         let frame_tx = self.frame_tx.clone();
+        let target_fps = self.target_fps.clone();
 
         running.store(true, Ordering::SeqCst);
 
@@ -130,10 +241,7 @@ impl CameraCapture {
             std::thread::spawn(move || {
                 tracing::info!("Camera capture starting with config: {:?}", config);
 
-                let frame_interval =
-                    std::time::Duration::from_millis(1000 / config.target_fps.max(1) as u64);
-
-                match Self::run_real_capture_sync(&config, &running, &frame_tx, frame_interval) {
+                match Self::run_real_capture_sync(&config, &running, &frame_tx, &target_fps) {
                     Ok(_) => tracing::info!("Camera capture stopped normally"),
                     Err(e) => tracing::error!("Camera capture error: {}", e),
                 }
@@ -149,10 +257,7 @@ impl CameraCapture {
             tokio::spawn(async move {
                 tracing::info!("Camera capture starting with config: {:?}", config);
 
-                let frame_interval =
-                    std::time::Duration::from_millis(1000 / config.target_fps.max(1) as u64);
-
-                Self::run_mock_capture(&config, &running, &frame_tx, frame_interval).await;
+                Self::run_mock_capture(&config, &running, &frame_tx, &target_fps).await;
 
                 running.store(false, Ordering::SeqCst);
                 tracing::info!("Camera capture thread exited");
@@ -179,7 +284,7 @@ impl CameraCapture {
         config: &CameraConfig,
         running: &Arc<AtomicBool>,
         frame_tx: &watch::Sender<CapturedFrame>,
-        frame_interval: std::time::Duration,
+        target_fps: &AtomicU32,
     ) {
         tracing::info!("Running in MOCK mode (no real camera)");
 
@@ -198,6 +303,7 @@ impl CameraCapture {
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_millis() as u64,
+                frame_id: 0,
             };
 
             // 发送帧（watch 会自动丢弃旧帧）
@@ -211,7 +317,7 @@ impl CameraCapture {
                 tracing::debug!("Mock capture: {} frames captured", frame_count);
             }
 
-            tokio::time::sleep(frame_interval).await;
+            tokio::time::sleep(frame_interval_for_fps(target_fps.load(Ordering::SeqCst))).await;
         }
     }
 
@@ -221,21 +327,15 @@ impl CameraCapture {
         config: &CameraConfig,
         running: &Arc<AtomicBool>,
         frame_tx: &watch::Sender<CapturedFrame>,
-        frame_interval: std::time::Duration,
+        target_fps: &AtomicU32,
     ) -> Result<(), String> {
-        use nokhwa::pixel_format::RgbFormat;
-        use nokhwa::utils::{CameraIndex, RequestedFormat, RequestedFormatType};
-        use nokhwa::Camera;
-
-        // 创建摄像头请求格式
-        let requested = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestResolution);
-
-        // 打开摄像头
-        let index = CameraIndex::Index(config.device_index);
-        let mut camera = Camera::new(index, requested)
-            .map_err(|e| format!("Failed to open camera: {}", e))?;
+        // 打开摄像头（按像素格式偏好协商格式）
+        let mut camera = Self::open_camera(config)?;
 
-        tracing::info!("Camera opened successfully");
+        tracing::info!(
+            "Camera opened successfully, negotiated format: {:?}",
+            camera.camera_format()
+        );
 
         // 获取实际分辨率
         let resolution = camera.resolution();
@@ -257,9 +357,7 @@ impl CameraCapture {
             match camera.frame() {
                 Ok(buffer) => {
                     // 解码为 RGB
-                    let decoded = buffer
-                        .decode_image::<RgbFormat>()
-                        .map_err(|e| format!("Failed to decode frame: {}", e))?;
+                    let decoded = Self::decode_buffer(&buffer)?;
 
                     // 调整大小到目标分辨率（如果需要）
                     let resized = if decoded.width() != config.width
@@ -283,6 +381,7 @@ impl CameraCapture {
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap()
                             .as_millis() as u64,
+                        frame_id: 0,
                     };
 
                     if frame_tx.send(frame).is_err() {
@@ -300,8 +399,8 @@ impl CameraCapture {
                 }
             }
 
-            // 使用标准库的 sleep（不是 tokio）
-            std::thread::sleep(frame_interval);
+            // 使用标准库的 sleep（不是 tokio），每帧重新读取帧率以便运行中调整
+            std::thread::sleep(frame_interval_for_fps(target_fps.load(Ordering::SeqCst)));
         }
 
         // 关闭摄像头
@@ -310,6 +409,130 @@ impl CameraCapture {
 
         Ok(())
     }
+
+    /// 试探性打开摄像头并立即释放，用于在启动前发现设备被占用等问题
+    #[cfg(feature = "vision")]
+    pub fn probe(config: &CameraConfig) -> Result<(), String> {
+        let mut camera = Self::open_camera(config)?;
+        camera
+            .open_stream()
+            .map_err(|e| format!("Failed to start camera stream: {}", e))?;
+        camera.stop_stream().ok();
+        Ok(())
+    }
+
+    /// 模拟模式无需探测
+    #[cfg(not(feature = "vision"))]
+    pub fn probe(_config: &CameraConfig) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 按配置的分辨率和帧率同步采集 `count` 帧，结束后立即释放摄像头
+    ///
+    /// 供自动调优等一次性测量使用，不经过 watch 通道
+    #[cfg(feature = "vision")]
+    pub fn grab_frames(config: &CameraConfig, count: usize) -> Result<Vec<CapturedFrame>, String> {
+        let mut camera = Self::open_camera(config)?;
+        camera
+            .open_stream()
+            .map_err(|e| format!("Failed to start camera stream: {}", e))?;
+
+        let mut frames = Vec::with_capacity(count);
+        while frames.len() < count {
+            let buffer = match camera.frame() {
+                Ok(buffer) => buffer,
+                Err(e) => {
+                    camera.stop_stream().ok();
+                    return Err(format!("Failed to capture frame: {}", e));
+                }
+            };
+            let decoded = match Self::decode_buffer(&buffer) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    camera.stop_stream().ok();
+                    return Err(e);
+                }
+            };
+            let resized = image::imageops::resize(
+                &decoded,
+                config.width,
+                config.height,
+                image::imageops::FilterType::Triangle,
+            );
+            frames.push(CapturedFrame {
+                width: config.width,
+                height: config.height,
+                data: resized.into_raw(),
+                timestamp_ms: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+                frame_id: frames.len() as u64,
+            });
+            std::thread::sleep(frame_interval_for_fps(config.target_fps));
+        }
+
+        camera.stop_stream().ok();
+        Ok(frames)
+    }
+
+    /// 按像素格式偏好打开摄像头，原生格式失败时回退到 RGB
+    #[cfg(feature = "vision")]
+    fn open_camera(config: &CameraConfig) -> Result<nokhwa::Camera, String> {
+        use nokhwa::pixel_format::RgbFormat;
+        use nokhwa::utils::{CameraIndex, FrameFormat, RequestedFormat, RequestedFormatType};
+        use nokhwa::Camera;
+
+        let native_formats: &[FrameFormat] = match config.pixel_format {
+            PixelFormatPreference::Auto => &[FrameFormat::YUYV, FrameFormat::MJPEG],
+            PixelFormatPreference::Yuyv => &[FrameFormat::YUYV],
+            PixelFormatPreference::Mjpeg => &[FrameFormat::MJPEG],
+            PixelFormatPreference::Rgb => &[],
+        };
+
+        let index = CameraIndex::Index(config.device_index);
+
+        if !native_formats.is_empty() {
+            let requested =
+                RequestedFormat::with_formats(RequestedFormatType::AbsoluteHighestResolution, native_formats);
+            match Camera::new(index.clone(), requested) {
+                Ok(camera) => return Ok(camera),
+                Err(e) => tracing::warn!(
+                    "Failed to open camera with native formats {:?}: {}, falling back to RGB",
+                    native_formats,
+                    e
+                ),
+            }
+        }
+
+        let requested = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestResolution);
+        Camera::new(index, requested).map_err(|e| format!("Failed to open camera: {}", e))
+    }
+
+    /// 把摄像头缓冲区解码为 RGB 图像
+    ///
+    /// YUYV 由我们自己转换，MJPEG 直接用 image crate 解码，其他格式交给 nokhwa
+    #[cfg(feature = "vision")]
+    fn decode_buffer(buffer: &nokhwa::Buffer) -> Result<RgbImage, String> {
+        use nokhwa::pixel_format::RgbFormat;
+        use nokhwa::utils::FrameFormat;
+
+        match buffer.source_frame_format() {
+            FrameFormat::YUYV => {
+                let resolution = buffer.resolution();
+                let (width, height) = (resolution.width(), resolution.height());
+                yuyv_to_rgb(buffer.buffer(), width, height)
+                    .and_then(|rgb| RgbImage::from_raw(width, height, rgb))
+                    .ok_or_else(|| "Failed to convert YUYV frame".to_string())
+            }
+            FrameFormat::MJPEG => image::load_from_memory_with_format(buffer.buffer(), image::ImageFormat::Jpeg)
+                .map(|img| img.to_rgb8())
+                .map_err(|e| format!("Failed to decode MJPEG frame: {}", e)),
+            _ => buffer
+                .decode_image::<RgbFormat>()
+                .map_err(|e| format!("Failed to decode frame: {}", e)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -325,10 +548,61 @@ mod tests {
         assert_eq!(config.height, 240);
     }
 
+    #[test]
+    fn test_yuyv_to_rgb() {
+        // 两个像素一组：Y0 U Y1 V
+        let red = [81, 90, 81, 240];
+        assert_eq!(yuyv_to_rgb(&red, 2, 1).unwrap(), vec![255, 0, 0, 255, 0, 0]);
+
+        let gray = [126, 128, 126, 128];
+        assert_eq!(yuyv_to_rgb(&gray, 2, 1).unwrap(), vec![128; 6]);
+
+        // 2x2 图像：第一行红色，第二行灰色
+        let block = [81, 90, 81, 240, 126, 128, 126, 128];
+        let rgb = yuyv_to_rgb(&block, 2, 2).unwrap();
+        assert_eq!(rgb.len(), 12);
+        assert_eq!(&rgb[..3], &[255, 0, 0]);
+        assert_eq!(&rgb[6..9], &[128, 128, 128]);
+
+        // 数据长度不足
+        assert!(yuyv_to_rgb(&red, 4, 1).is_none());
+    }
+
+    #[test]
+    fn test_frame_interval_for_fps() {
+        assert_eq!(frame_interval_for_fps(10).as_millis(), 100);
+        assert_eq!(frame_interval_for_fps(30).as_millis(), 33);
+        assert_eq!(frame_interval_for_fps(1).as_millis(), 1000);
+
+        // 超出范围时限制在 1..=60
+        assert_eq!(frame_interval_for_fps(0), frame_interval_for_fps(MIN_FPS));
+        assert_eq!(frame_interval_for_fps(240), frame_interval_for_fps(MAX_FPS));
+        assert_eq!(clamp_fps(0), 1);
+        assert_eq!(clamp_fps(120), 60);
+    }
+
     #[test]
     fn test_captured_frame_empty() {
         let frame = CapturedFrame::empty();
         assert!(frame.is_empty());
         assert!(frame.to_rgb_image().is_none());
     }
+
+    #[test]
+    fn test_stale_frame() {
+        let frame = CapturedFrame {
+            width: 2,
+            height: 1,
+            data: vec![0; 6],
+            timestamp_ms: 10_000,
+            frame_id: 0,
+        };
+
+        assert!(!frame.is_stale(10_000, 500));
+        assert!(!frame.is_stale(10_500, 500));
+        // 人为制造的旧帧被判定为过时
+        assert!(frame.is_stale(12_000, 500));
+        // 时钟回拨时不误判
+        assert!(!frame.is_stale(9_000, 500));
+    }
 }
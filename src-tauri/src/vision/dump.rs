@@ -0,0 +1,179 @@
+//! 检测结果转储
+//!
+//! 调整专注评分公式时需要真实画面下的检测数据。转储期间，处理循环把每次检测选出的主人脸和
+//! 计算出的专注状态逐行写入 JSONL 文件，用户可以把一小段录制发给维护者回放和调整权重。
+//! 每行都可以直接解析为 [`ReplayFrame`](super::ReplayFrame)（时间戳相对转储开始）。
+//! 超过最长时长后停止写入，避免忘记停止时文件无限增长。
+//! 转储文件只写入数据目录下的 `dumps` 目录，且不覆盖已有文件。
+
+use super::{FaceDetection, FocusState};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// 单次转储的最长时长（毫秒）
+pub const MAX_DUMP_DURATION_MS: u64 = 5 * 60_000;
+
+/// 转储目录名（位于数据目录下）
+const DUMPS_DIR: &str = "dumps";
+
+/// 数据目录下转储文件 `name` 的路径，名称不是单纯的文件名（包含目录、`..` 等）时返回 None
+pub fn dump_path(data_dir: &Path, name: &str) -> Option<PathBuf> {
+    let file_name = Path::new(name).file_name()?;
+    if file_name != name || name.starts_with('.') {
+        return None;
+    }
+    Some(data_dir.join(DUMPS_DIR).join(name))
+}
+
+/// 转储文件中的一行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpRecord {
+    /// 相对转储开始的时间（毫秒）
+    pub timestamp_ms: u64,
+    /// 跟踪选出的主人脸（None 表示未检测到人脸）
+    pub face: Option<FaceDetection>,
+    /// 计算出的专注状态
+    pub focus_state: FocusState,
+}
+
+/// 转储结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpSummary {
+    /// 文件路径
+    pub path: String,
+    /// 写入的帧数
+    pub frames: u64,
+    /// 第一帧到最后一帧的时长（毫秒）
+    pub duration_ms: u64,
+    /// 是否因超过最长时长而提前停止写入
+    pub truncated: bool,
+}
+
+/// 进行中的检测结果转储
+pub struct DetectionDump {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    max_duration_ms: u64,
+    /// 第一帧的时间戳
+    first_ms: Option<u64>,
+    /// 最后写入的帧的相对时间
+    last_offset_ms: u64,
+    frames: u64,
+    truncated: bool,
+}
+
+impl DetectionDump {
+    /// 创建转储文件（连同所在目录），文件已存在时返回错误，不覆盖
+    pub fn create(path: &Path, max_duration_ms: u64) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(File::create_new(path)?),
+            max_duration_ms,
+            first_ms: None,
+            last_offset_ms: 0,
+            frames: 0,
+            truncated: false,
+        })
+    }
+
+    /// 写入一帧，超过最长时长后不再写入
+    pub fn append(&mut self, timestamp_ms: u64, face: Option<&FaceDetection>, focus_state: &FocusState) -> io::Result<()> {
+        if self.truncated {
+            return Ok(());
+        }
+        let offset_ms = timestamp_ms.saturating_sub(*self.first_ms.get_or_insert(timestamp_ms));
+        if offset_ms > self.max_duration_ms {
+            self.truncated = true;
+            self.writer.flush()?;
+            tracing::info!("Detection dump reached {} ms, no longer writing", self.max_duration_ms);
+            return Ok(());
+        }
+
+        let record = DumpRecord {
+            timestamp_ms: offset_ms,
+            face: face.cloned(),
+            focus_state: focus_state.clone(),
+        };
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        self.last_offset_ms = offset_ms;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// 结束转储，把缓冲写入磁盘
+    pub fn finish(mut self) -> io::Result<DumpSummary> {
+        self.writer.flush()?;
+        Ok(DumpSummary {
+            path: self.path.display().to_string(),
+            frames: self.frames,
+            duration_ms: self.last_offset_ms,
+            truncated: self.truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+    use crate::vision::ReplayFrame;
+
+    #[test]
+    fn test_detection_dump_lines() {
+        let path = test_temp_path("dump").with_extension("jsonl");
+        let mut dump = DetectionDump::create(&path, 1_000).unwrap();
+        for i in 0..20u64 {
+            let timestamp_ms = 1_700_000_000_000 + i * 100;
+            let present = i % 5 != 4;
+            let state = if present {
+                FocusState::from_detection(Some(&FaceDetection::frontal()), 0.8)
+            } else {
+                FocusState::default()
+            };
+            dump.append(timestamp_ms, present.then(FaceDetection::frontal).as_ref(), &state).unwrap();
+        }
+        let summary = dump.finish().unwrap();
+
+        // 超过最长时长（1 秒）的帧不写入
+        assert_eq!(summary.frames, 11);
+        assert_eq!(summary.duration_ms, 1_000);
+        assert!(summary.truncated);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 11);
+        for (i, line) in lines.iter().enumerate() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["timestamp_ms"], i as u64 * 100);
+            assert!(value["focus_state"]["focus_score"].is_number());
+            assert_eq!(value["face"].is_null(), i % 5 == 4);
+            // 每行都可以直接作为回放输入
+            let frame: ReplayFrame = serde_json::from_str(line).unwrap();
+            assert_eq!(frame.face.is_some(), i % 5 != 4);
+        }
+
+        // 已有的文件不覆盖
+        let existing = DetectionDump::create(&path, 1_000).err().unwrap();
+        assert_eq!(existing.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_dump_path_stays_in_data_dir() {
+        let data_dir = Path::new("/data/focus_mochi");
+        assert_eq!(
+            dump_path(data_dir, "run1.jsonl"),
+            Some(data_dir.join("dumps").join("run1.jsonl"))
+        );
+        for name in ["", ".", "..", "../escape.jsonl", "a/b.jsonl", "/etc/passwd", ".hidden"] {
+            assert_eq!(dump_path(data_dir, name), None, "{:?}", name);
+        }
+    }
+}
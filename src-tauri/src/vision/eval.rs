@@ -0,0 +1,258 @@
+//! 检测精度评估
+//!
+//! 对一个标注过的测试图片目录运行人脸检测并计算精度指标，
+//! 用于回归验证模型和锚框的改动。
+//!
+//! 每张图片（png / jpg / jpeg）旁边需要有同名的 JSON 标注文件，
+//! 记录归一化坐标的人脸边界框，没有人脸时为空列表：
+//!
+//! ```json
+//! { "faces": [[0.25, 0.15, 0.75, 0.85]] }
+//! ```
+//!
+//! 缺少或无法解析标注的图片会被跳过并计入 `skipped`。
+
+use super::{BlazeFaceDetector, FaceDetection, FaceDetectorError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 判定检测框命中标注框的最小 IoU
+pub const EVAL_IOU_THRESHOLD: f32 = 0.5;
+
+/// 支持的图片扩展名
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+/// 评估错误
+#[derive(Debug)]
+pub enum EvalError {
+    /// 读取目录失败
+    Io(std::io::Error),
+    /// 检测器运行失败
+    Detector(FaceDetectorError),
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::Io(e) => write!(f, "{}", e),
+            EvalError::Detector(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl From<std::io::Error> for EvalError {
+    fn from(err: std::io::Error) -> Self {
+        EvalError::Io(err)
+    }
+}
+
+/// 图片的标注文件
+#[derive(Debug, Clone, Deserialize)]
+struct FrameLabel {
+    /// 人脸边界框 (x1, y1, x2, y2)，归一化坐标
+    faces: Vec<(f32, f32, f32, f32)>,
+}
+
+/// 评估结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EvalResult {
+    /// 精确率：命中数 / 检测数
+    pub precision: f32,
+    /// 召回率：命中数 / 标注数
+    pub recall: f32,
+    /// 命中检测的平均 IoU
+    pub avg_iou: f32,
+    /// 参与评估的图片数
+    pub images: u32,
+    /// 因缺少标注或无法读取而跳过的图片数
+    pub skipped: u32,
+    /// 命中数
+    pub true_positives: u32,
+    /// 误检数
+    pub false_positives: u32,
+    /// 漏检数
+    pub false_negatives: u32,
+}
+
+impl EvalResult {
+    /// 累计一张图片的检测结果
+    ///
+    /// 检测按置信度从高到低贪心匹配 IoU 最大的未匹配标注框
+    fn add_frame(
+        &mut self,
+        detections: &[FaceDetection],
+        labels: &[(f32, f32, f32, f32)],
+        iou_sum: &mut f32,
+    ) {
+        let mut sorted: Vec<&FaceDetection> = detections.iter().collect();
+        sorted.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+        let mut matched = vec![false; labels.len()];
+        for detection in sorted {
+            let best = labels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !matched[*i])
+                .map(|(i, label)| (i, BlazeFaceDetector::calculate_iou(&detection.bbox, label)))
+                .filter(|(_, iou)| *iou >= EVAL_IOU_THRESHOLD)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+            match best {
+                Some((i, iou)) => {
+                    matched[i] = true;
+                    self.true_positives += 1;
+                    *iou_sum += iou;
+                }
+                None => self.false_positives += 1,
+            }
+        }
+
+        self.false_negatives += matched.iter().filter(|m| !**m).count() as u32;
+        self.images += 1;
+    }
+
+    /// 根据计数计算比率指标
+    fn finalize(&mut self, iou_sum: f32) {
+        let tp = self.true_positives as f32;
+        let detected = tp + self.false_positives as f32;
+        let labeled = tp + self.false_negatives as f32;
+
+        // 没有检测 / 没有标注时对应指标视为满分
+        self.precision = if detected > 0.0 { tp / detected } else { 1.0 };
+        self.recall = if labeled > 0.0 { tp / labeled } else { 1.0 };
+        self.avg_iou = if self.true_positives > 0 {
+            iou_sum / tp
+        } else {
+            0.0
+        };
+    }
+}
+
+/// 评估目录中的所有标注图片
+///
+/// `detect` 接收 RGB 数据和宽高，返回检测结果
+pub fn evaluate_dir<F>(dir: &Path, mut detect: F) -> Result<EvalResult, EvalError>
+where
+    F: FnMut(&[u8], u32, u32) -> Result<Vec<FaceDetection>, FaceDetectorError>,
+{
+    let mut images: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .collect();
+    images.sort();
+
+    let mut result = EvalResult::default();
+    let mut iou_sum = 0.0;
+
+    for path in images {
+        let label_path = path.with_extension("json");
+        let label = match fs::read(&label_path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| serde_json::from_slice::<FrameLabel>(&data).map_err(|e| e.to_string()))
+        {
+            Ok(label) => label,
+            Err(e) => {
+                tracing::warn!("Skipping {}: no usable label ({})", path.display(), e);
+                result.skipped += 1;
+                continue;
+            }
+        };
+
+        let image = match image::open(&path) {
+            Ok(image) => image.to_rgb8(),
+            Err(e) => {
+                tracing::warn!("Skipping {}: {}", path.display(), e);
+                result.skipped += 1;
+                continue;
+            }
+        };
+
+        let detections = detect(image.as_raw(), image.width(), image.height()).map_err(EvalError::Detector)?;
+        result.add_frame(&detections, &label.faces, &mut iou_sum);
+    }
+
+    result.finalize(iou_sum);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = test_temp_path(name);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_image(path: &Path, value: u8) {
+        image::RgbImage::from_pixel(4, 4, image::Rgb([value, value, value]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_evaluate_labeled_frames() {
+        let dir = temp_dir("eval");
+
+        // 有人脸的图片（亮）和没有人脸的图片（暗），以及一张缺少标注的图片
+        write_image(&dir.join("face.png"), 255);
+        fs::write(
+            dir.join("face.json"),
+            r#"{ "faces": [[0.2, 0.2, 0.6, 0.6]] }"#,
+        )
+        .unwrap();
+        write_image(&dir.join("empty.png"), 0);
+        fs::write(dir.join("empty.json"), r#"{ "faces": [] }"#).unwrap();
+        write_image(&dir.join("unlabeled.png"), 255);
+
+        // 模拟检测器：亮图返回略有偏移的人脸，暗图返回一个误检
+        let result = evaluate_dir(&dir, |data, _, _| {
+            let bbox = if data[0] > 0 {
+                (0.2, 0.2, 0.6, 0.5)
+            } else {
+                (0.7, 0.7, 0.9, 0.9)
+            };
+            Ok(vec![FaceDetection {
+                confidence: 0.9,
+                bbox,
+                landmarks: [(0.0, 0.0); 6],
+                landmark_quality: [1.0; 6],
+            }])
+        })
+        .unwrap();
+
+        assert_eq!(result.images, 2);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.true_positives, 1);
+        assert_eq!(result.false_positives, 1);
+        assert_eq!(result.false_negatives, 0);
+        assert!((result.precision - 0.5).abs() < 1e-6);
+        assert!((result.recall - 1.0).abs() < 1e-6);
+        assert!((result.avg_iou - 0.75).abs() < 1e-4);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_detector_failure_is_not_io_error() {
+        let dir = temp_dir("eval_fail");
+        write_image(&dir.join("face.png"), 255);
+        fs::write(dir.join("face.json"), r#"{ "faces": [] }"#).unwrap();
+
+        let err = evaluate_dir(&dir, |_, _, _| Err(FaceDetectorError::InferenceError("boom".to_string()))).unwrap_err();
+        assert!(matches!(err, EvalError::Detector(FaceDetectorError::InferenceError(_))));
+        assert!(matches!(evaluate_dir(&dir.join("missing"), |_, _, _| Ok(vec![])), Err(EvalError::Io(_))));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
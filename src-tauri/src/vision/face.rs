@@ -5,10 +5,181 @@
 //! 输出：人脸边界框 + 6个关键点（眼睛、耳朵、鼻子、嘴巴）
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
 
 /// BlazeFace 模型期望的输入尺寸
 pub const BLAZEFACE_INPUT_SIZE: u32 = 128;
 
+/// BlazeFace 锚框数量
+pub const BLAZEFACE_NUM_ANCHORS: usize = 896;
+
+/// 已加载模型的信息（用于排查模型文件是否损坏）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// 模型文件路径
+    pub path: String,
+    /// 模型文件 SHA-256（文件不可读时为 None）
+    pub sha256: Option<String>,
+    /// 锚框数量
+    pub num_anchors: usize,
+    /// 模型输入尺寸
+    pub input_size: u32,
+    /// 模型是否成功加载
+    pub loaded_ok: bool,
+    /// 是否为模拟模式
+    pub mock: bool,
+}
+
+impl ModelInfo {
+    /// 仅检查模型文件（不加载模型）
+    pub fn inspect(model_path: &str) -> Self {
+        Self {
+            path: model_path.to_string(),
+            sha256: file_sha256(model_path).ok(),
+            num_anchors: BLAZEFACE_NUM_ANCHORS,
+            input_size: BLAZEFACE_INPUT_SIZE,
+            loaded_ok: false,
+            mock: cfg!(not(feature = "vision")),
+        }
+    }
+}
+
+/// 计算文件的 SHA-256（十六进制小写）
+pub fn file_sha256(path: &str) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 张量签名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TensorSignature {
+    /// 张量名称
+    pub name: String,
+    /// 形状（-1 表示动态维度）
+    pub shape: Vec<i64>,
+}
+
+impl TensorSignature {
+    fn new(name: &str, shape: &[i64]) -> Self {
+        Self {
+            name: name.to_string(),
+            shape: shape.to_vec(),
+        }
+    }
+
+    /// 形状是否与预期一致（动态维度视为一致）
+    fn matches(&self, expected: &TensorSignature) -> bool {
+        self.shape.len() == expected.shape.len()
+            && self.shape.iter().zip(&expected.shape).all(|(&actual, &expected)| actual < 0 || actual == expected)
+    }
+}
+
+/// 模型的输入输出签名，以及与解码逻辑预期的对比
+///
+/// 替换模型后输出形状不符时，解码会在索引时出错；这里提前给出可读的诊断
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelIo {
+    /// 模型的输入（模拟模式下为预期的输入）
+    pub inputs: Vec<TensorSignature>,
+    /// 模型的输出（按解码使用的顺序）
+    pub outputs: Vec<TensorSignature>,
+    /// 解码逻辑预期的输入
+    pub expected_inputs: Vec<TensorSignature>,
+    /// 解码逻辑预期的输出
+    pub expected_outputs: Vec<TensorSignature>,
+    /// 与预期不符之处（为空表示一致）
+    pub mismatches: Vec<String>,
+    /// 是否为模拟模式
+    pub mock: bool,
+}
+
+impl ModelIo {
+    /// 解码逻辑预期的输入（NCHW 图像）和输出（回归值、分类值）
+    pub fn expected() -> (Vec<TensorSignature>, Vec<TensorSignature>) {
+        let size = BLAZEFACE_INPUT_SIZE as i64;
+        let anchors = BLAZEFACE_NUM_ANCHORS as i64;
+        (
+            vec![TensorSignature::new("input", &[1, 3, size, size])],
+            vec![
+                TensorSignature::new("regressors", &[1, anchors, 16]),
+                TensorSignature::new("classificators", &[1, anchors, 1]),
+            ],
+        )
+    }
+
+    /// 按位置对比实际签名和预期（解码按位置而不是名称读取输出）
+    pub fn compare(inputs: Vec<TensorSignature>, outputs: Vec<TensorSignature>, mock: bool) -> Self {
+        let (expected_inputs, expected_outputs) = Self::expected();
+        let mut mismatches = Vec::new();
+        for (kind, actual, expected) in [("input", &inputs, &expected_inputs), ("output", &outputs, &expected_outputs)] {
+            if actual.len() != expected.len() {
+                mismatches.push(format!("Expected {} {}s, model has {}", expected.len(), kind, actual.len()));
+            }
+            for (index, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+                if !actual.matches(expected) {
+                    mismatches.push(format!(
+                        "{} {} ({}): expected shape {:?}, model has {:?}",
+                        kind, index, actual.name, expected.shape, actual.shape
+                    ));
+                }
+            }
+        }
+
+        Self {
+            inputs,
+            outputs,
+            expected_inputs,
+            expected_outputs,
+            mismatches,
+            mock,
+        }
+    }
+}
+
+/// 锚框解码缩放配置
+///
+/// 回归输出相对锚框的换算方式：
+/// `cx = anchor_x + reg_x / x_scale * anchor_w`，宽高同理。
+/// 使用固定锚框尺寸时 `anchor_w = anchor_h = 1`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorScaleConfig {
+    /// x 方向回归值缩放
+    pub x_scale: f32,
+    /// y 方向回归值缩放
+    pub y_scale: f32,
+    /// 宽度回归值缩放
+    pub w_scale: f32,
+    /// 高度回归值缩放
+    pub h_scale: f32,
+    /// 是否使用固定锚框尺寸（忽略锚框自身的宽高）
+    pub fixed_anchor_size: bool,
+}
+
+impl Default for AnchorScaleConfig {
+    fn default() -> Self {
+        // BlazeFace 前置摄像头模型的默认值
+        Self {
+            x_scale: 128.0,
+            y_scale: 128.0,
+            w_scale: 128.0,
+            h_scale: 128.0,
+            fixed_anchor_size: true,
+        }
+    }
+}
+
 /// 人脸检测结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FaceDetection {
@@ -19,9 +190,62 @@ pub struct FaceDetection {
     /// 6个关键点坐标 [(x, y), ...] - 归一化坐标 [0, 1]
     /// 顺序：右眼、左眼、鼻子、嘴巴、右耳、左耳
     pub landmarks: [(f32, f32); 6],
+    /// 每个关键点的质量 (0.0 - 1.0)，顺序同 `landmarks`
+    /// 由检测置信度和关键点位置是否合理共同决定，姿态估计依赖的关键点质量低时相应降低其权重
+    #[serde(default = "full_landmark_quality")]
+    pub landmark_quality: [f32; 6],
+}
+
+/// 关键点质量缺省值（没有质量信息时视为全部可靠）
+fn full_landmark_quality() -> [f32; 6] {
+    [1.0; 6]
+}
+
+/// 关键点在人脸框外的距离（相对框的宽 / 高）不超过该值时不扣分
+const LANDMARK_BBOX_TOLERANCE: f32 = 0.1;
+
+/// 超出容许距离后质量线性下降，再超出该距离时降为 0
+const LANDMARK_BBOX_FALLOFF: f32 = 0.4;
+
+/// 根据检测置信度和几何合理性估算每个关键点的质量
+///
+/// `landmarks` 为限制到 [0, 1] 之前的原始坐标：超出画面的关键点质量为 0，
+/// 落在人脸框外的关键点按距离线性降低质量
+pub fn estimate_landmark_quality(
+    score: f32,
+    bbox: (f32, f32, f32, f32),
+    landmarks: &[(f32, f32); 6],
+) -> [f32; 6] {
+    let (x1, y1, x2, y2) = bbox;
+    let (w, h) = (x2 - x1, y2 - y1);
+    if w <= 0.0 || h <= 0.0 {
+        return [0.0; 6];
+    }
+    let score = score.clamp(0.0, 1.0);
+
+    landmarks.map(|(x, y)| {
+        if !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+            return 0.0;
+        }
+        let dx = (x1 - x).max(x - x2).max(0.0) / w;
+        let dy = (y1 - y).max(y - y2).max(0.0) / h;
+        let excess = dx.max(dy) - LANDMARK_BBOX_TOLERANCE;
+        let plausibility = 1.0 - (excess / LANDMARK_BBOX_FALLOFF).clamp(0.0, 1.0);
+        score * plausibility
+    })
 }
 
 impl FaceDetection {
+    /// 正对屏幕、大小适中的标准人脸（基准测试和测试共用）
+    pub(crate) fn frontal() -> Self {
+        Self {
+            confidence: 0.95,
+            bbox: (0.3, 0.2, 0.7, 0.8),
+            landmarks: [(0.42, 0.40), (0.58, 0.40), (0.50, 0.52), (0.50, 0.65), (0.32, 0.45), (0.68, 0.45)],
+            landmark_quality: [1.0; 6],
+        }
+    }
+
     /// 计算人脸中心点
     pub fn center(&self) -> (f32, f32) {
         let (x1, y1, x2, y2) = self.bbox;
@@ -34,6 +258,28 @@ impl FaceDetection {
         (x2 - x1) * (y2 - y1)
     }
 
+    /// 转换为像素坐标（按给定图像尺寸缩放，并限制在图像范围内）
+    pub fn to_pixels(&self, width: u32, height: u32) -> PixelFace {
+        let (w, h) = (width as f32, height as f32);
+        let px = |x: f32| (x * w).clamp(0.0, w);
+        let py = |y: f32| (y * h).clamp(0.0, h);
+        let (x1, y1, x2, y2) = self.bbox;
+
+        PixelFace {
+            confidence: self.confidence,
+            bbox: (px(x1), py(y1), px(x2), py(y2)),
+            landmarks: self.landmarks.map(|(x, y)| (px(x), py(y))),
+        }
+    }
+
+    /// 给定关键点中最低的质量（姿态估计的可靠程度取决于它依赖的最差的关键点）
+    pub fn landmark_reliability(&self, indices: &[usize]) -> f32 {
+        indices
+            .iter()
+            .map(|&i| self.landmark_quality[i])
+            .fold(1.0, f32::min)
+    }
+
     /// 估算头部偏航角（左右转头）
     /// 基于眼睛中心与人脸中心的偏移
     pub fn estimate_yaw(&self) -> f32 {
@@ -82,6 +328,17 @@ impl FaceDetection {
     }
 }
 
+/// 像素坐标下的人脸（用于在预览图上绘制）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PixelFace {
+    /// 人脸置信度 (0.0 - 1.0)
+    pub confidence: f32,
+    /// 人脸边界框 (x_min, y_min, x_max, y_max) - 像素坐标
+    pub bbox: (f32, f32, f32, f32),
+    /// 6个关键点坐标 - 像素坐标，顺序同 `FaceDetection::landmarks`
+    pub landmarks: [(f32, f32); 6],
+}
+
 /// 人脸检测器错误
 #[derive(Debug)]
 pub enum FaceDetectorError {
@@ -105,21 +362,97 @@ impl std::fmt::Display for FaceDetectorError {
 
 impl std::error::Error for FaceDetectorError {}
 
+/// 默认 NMS IoU 阈值
+pub const DEFAULT_NMS_THRESHOLD: f32 = 0.3;
+
+/// 模拟检测默认的随机种子
+pub const DEFAULT_MOCK_SEED: u64 = 42;
+
+/// 模拟检测配置（无 vision feature 时使用）
+///
+/// 模拟检测在固定的专注人脸上叠加小幅抖动并偶尔丢失人脸，使模拟模式下的场景更接近真实摄像头；
+/// 随机数由种子决定，相同种子总是得到相同的检测序列，便于复现问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockDetectionConfig {
+    /// 随机种子
+    pub seed: u64,
+    /// 人脸框和关键点的最大抖动（归一化坐标）
+    pub jitter: f32,
+    /// 每帧丢失人脸的概率 (0.0 - 1.0)
+    pub face_loss_probability: f32,
+}
+
+impl Default for MockDetectionConfig {
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_MOCK_SEED,
+            jitter: 0.01,
+            face_loss_probability: 0.02,
+        }
+    }
+}
+
+/// 调试时保留的最高分类分数个数
+pub const RAW_OUTPUT_TOP_SCORES: usize = 16;
+
+/// 最近一次推理的原始输出摘要（调试模型时使用）
+///
+/// 只保留分数最高的几个锚框的分类值和最高分锚框的回归值，避免每次检测复制整个输出张量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawModelOutput {
+    /// 最高的分类值（sigmoid 之前，降序）
+    pub top_scores: Vec<f32>,
+    /// 最高分锚框的 16 个回归值（bbox 4 个 + 6 个关键点）
+    pub sample_regressors: Vec<f32>,
+}
+
+impl RawModelOutput {
+    /// 从扁平的 classificators [896] 和 regressors [896 * 16] 中提取摘要
+    pub fn capture(classificators: &[f32], regressors: &[f32]) -> Self {
+        let mut ranked: Vec<usize> = (0..classificators.len()).collect();
+        ranked.sort_by(|&a, &b| classificators[b].total_cmp(&classificators[a]));
+        ranked.truncate(RAW_OUTPUT_TOP_SCORES);
+
+        let sample_regressors = ranked
+            .first()
+            .and_then(|&best| regressors.get(best * 16..best * 16 + 16))
+            .map(<[f32]>::to_vec)
+            .unwrap_or_default();
+        Self {
+            top_scores: ranked.iter().map(|&i| classificators[i]).collect(),
+            sample_regressors,
+        }
+    }
+}
+
 /// BlazeFace 人脸检测器
 ///
 /// 模拟实现（无 vision feature）或真实 ONNX 推理（有 vision feature）
 pub struct BlazeFaceDetector {
     /// 检测置信度阈值
     confidence_threshold: f32,
-    /// NMS IoU 阈值
-    #[allow(dead_code)]
+    /// 锚框解码缩放配置
+    anchor_scale: AnchorScaleConfig,
+    /// 模型信息（加载时计算并缓存）
+    model_info: ModelInfo,
+    /// NMS IoU 阈值：重叠度超过该值的低置信度检测框被合并
     nms_threshold: f32,
+    /// 是否保留每次推理的原始输出摘要
+    debug_raw_output: bool,
+    /// 最近一次推理的原始输出摘要（仅在 `debug_raw_output` 开启时记录）
+    last_raw_output: Option<RawModelOutput>,
     /// ONNX 会话（仅在 vision feature 启用时使用）
     #[cfg(feature = "vision")]
     session: ort::session::Session,
-    /// 锚框数据
+    /// 锚框数据 [896, 4]：x 中心、y 中心、宽、高
     #[cfg(feature = "vision")]
     anchors: ndarray::Array2<f32>,
+    /// 模拟检测配置（仅在无 vision feature 时使用）
+    #[cfg(not(feature = "vision"))]
+    mock: MockDetectionConfig,
+    /// 模拟检测的随机数发生器，由 `mock.seed` 初始化
+    #[cfg(not(feature = "vision"))]
+    mock_rng: rand::rngs::StdRng,
 }
 
 impl BlazeFaceDetector {
@@ -127,9 +460,23 @@ impl BlazeFaceDetector {
     ///
     /// # Arguments
     /// * `model_path` - ONNX 模型文件路径
-    /// * `anchors_path` - 锚框 npy 文件路径（可选，会尝试自动生成）
-    #[cfg(feature = "vision")]
+    /// * `anchors_path` - 锚框 npy 文件路径（可选，未提供时自动生成）
+    ///
+    /// 锚框文件解析失败时返回错误，不会回退到生成的锚框
     pub fn new(model_path: &str, anchors_path: Option<&str>) -> Result<Self, FaceDetectorError> {
+        Self::with_options(model_path, anchors_path, false)
+    }
+
+    /// 创建检测器，可选择在锚框文件解析失败时回退到生成的锚框
+    ///
+    /// # Arguments
+    /// * `allow_anchor_fallback` - 为 false 时锚框文件解析失败直接返回 `ModelLoadError`
+    #[cfg(feature = "vision")]
+    pub fn with_options(
+        model_path: &str,
+        anchors_path: Option<&str>,
+        allow_anchor_fallback: bool,
+    ) -> Result<Self, FaceDetectorError> {
         use ort::session::{Session, builder::GraphOptimizationLevel};
 
         // 加载 ONNX 模型
@@ -144,14 +491,24 @@ impl BlazeFaceDetector {
 
         // 加载或生成锚框
         let anchors = if let Some(path) = anchors_path {
-            Self::load_anchors(path)?
+            Self::load_anchors(path, allow_anchor_fallback)?
         } else {
             Self::generate_anchors()
         };
 
+        let model_info = ModelInfo {
+            num_anchors: anchors.nrows(),
+            loaded_ok: true,
+            ..ModelInfo::inspect(model_path)
+        };
+
         Ok(Self {
             confidence_threshold: 0.5,
-            nms_threshold: 0.3,
+            anchor_scale: AnchorScaleConfig::default(),
+            model_info,
+            nms_threshold: DEFAULT_NMS_THRESHOLD,
+            debug_raw_output: false,
+            last_raw_output: None,
             session,
             anchors,
         })
@@ -159,19 +516,149 @@ impl BlazeFaceDetector {
 
     /// 模拟模式创建（无真实模型）
     #[cfg(not(feature = "vision"))]
-    pub fn new(_model_path: &str, _anchors_path: Option<&str>) -> Result<Self, FaceDetectorError> {
+    pub fn with_options(
+        model_path: &str,
+        _anchors_path: Option<&str>,
+        _allow_anchor_fallback: bool,
+    ) -> Result<Self, FaceDetectorError> {
+        use rand::SeedableRng;
+
         tracing::info!("BlazeFace detector created in MOCK mode");
+        let mock = MockDetectionConfig::default();
         Ok(Self {
             confidence_threshold: 0.5,
-            nms_threshold: 0.3,
+            anchor_scale: AnchorScaleConfig::default(),
+            model_info: ModelInfo::inspect(model_path),
+            nms_threshold: DEFAULT_NMS_THRESHOLD,
+            debug_raw_output: false,
+            last_raw_output: None,
+            mock_rng: rand::rngs::StdRng::seed_from_u64(mock.seed),
+            mock,
         })
     }
 
+    /// 设置模拟检测配置，并用其中的种子重新初始化随机数发生器
+    #[cfg(not(feature = "vision"))]
+    pub fn set_mock_detection(&mut self, config: MockDetectionConfig) {
+        use rand::SeedableRng;
+
+        tracing::info!("Mock detection seed: {}", config.seed);
+        self.mock_rng = rand::rngs::StdRng::seed_from_u64(config.seed);
+        self.mock = config;
+    }
+
+    /// 获取模型信息
+    pub fn model_info(&self) -> &ModelInfo {
+        &self.model_info
+    }
+
+    /// 已加载模型的输入输出签名（与解码逻辑的预期对比）
+    #[cfg(feature = "vision")]
+    pub fn model_io(&self) -> ModelIo {
+        let signature = |name: &str, ty: &ort::value::ValueType| TensorSignature {
+            name: name.to_string(),
+            shape: ty.tensor_shape().map(|shape| shape.to_vec()).unwrap_or_default(),
+        };
+        ModelIo::compare(
+            self.session.inputs.iter().map(|i| signature(&i.name, &i.input_type)).collect(),
+            self.session.outputs.iter().map(|o| signature(&o.name, &o.output_type)).collect(),
+            false,
+        )
+    }
+
+    /// 模拟模式：返回预期的输入输出签名
+    #[cfg(not(feature = "vision"))]
+    pub fn model_io(&self) -> ModelIo {
+        let (inputs, outputs) = ModelIo::expected();
+        ModelIo::compare(inputs, outputs, true)
+    }
+
     /// 设置置信度阈值
     pub fn set_confidence_threshold(&mut self, threshold: f32) {
         self.confidence_threshold = threshold.clamp(0.0, 1.0);
     }
 
+    /// 设置 NMS IoU 阈值（限制到 0-1）
+    ///
+    /// 阈值越高保留的重叠人脸越多，适合多人画面；越低越容易合并重复框
+    pub fn set_nms_threshold(&mut self, threshold: f32) {
+        self.nms_threshold = threshold.clamp(0.0, 1.0);
+    }
+
+    /// 获取 NMS IoU 阈值
+    pub fn nms_threshold(&self) -> f32 {
+        self.nms_threshold
+    }
+
+    /// 开启或关闭原始输出记录，关闭时清除已记录的输出
+    pub fn set_debug_raw_output(&mut self, enabled: bool) {
+        self.debug_raw_output = enabled;
+        if !enabled {
+            self.last_raw_output = None;
+        }
+    }
+
+    /// 最近一次推理的原始输出摘要（未开启记录或模拟模式下为 None）
+    pub fn last_raw_output(&self) -> Option<&RawModelOutput> {
+        self.last_raw_output.as_ref()
+    }
+
+    /// 设置锚框解码缩放配置
+    pub fn set_anchor_scale(&mut self, anchor_scale: AnchorScaleConfig) {
+        self.anchor_scale = anchor_scale;
+    }
+
+    /// 获取锚框解码缩放配置
+    pub fn anchor_scale(&self) -> &AnchorScaleConfig {
+        &self.anchor_scale
+    }
+
+    /// 根据锚框解码单个检测结果
+    ///
+    /// # Arguments
+    /// * `score` - 置信度（已经过 sigmoid）
+    /// * `regressors` - 该锚框的 16 个回归值（bbox 4 个 + 6 个关键点）
+    /// * `anchor` - 锚框 [x 中心, y 中心, 宽, 高]
+    /// * `scale` - 锚框解码缩放配置
+    pub fn decode_detection(
+        score: f32,
+        regressors: &[f32],
+        anchor: [f32; 4],
+        scale: &AnchorScaleConfig,
+    ) -> FaceDetection {
+        let [anchor_x, anchor_y, anchor_w, anchor_h] = if scale.fixed_anchor_size {
+            [anchor[0], anchor[1], 1.0, 1.0]
+        } else {
+            anchor
+        };
+
+        let cx = anchor_x + regressors[0] / scale.x_scale * anchor_w;
+        let cy = anchor_y + regressors[1] / scale.y_scale * anchor_h;
+        let w = regressors[2] / scale.w_scale * anchor_w;
+        let h = regressors[3] / scale.h_scale * anchor_h;
+
+        let x1 = (cx - w / 2.0).clamp(0.0, 1.0);
+        let y1 = (cy - h / 2.0).clamp(0.0, 1.0);
+        let x2 = (cx + w / 2.0).clamp(0.0, 1.0);
+        let y2 = (cy + h / 2.0).clamp(0.0, 1.0);
+
+        // 解码 6 个关键点
+        let mut raw_landmarks = [(0.0f32, 0.0f32); 6];
+        for (j, landmark) in raw_landmarks.iter_mut().enumerate() {
+            let lx = anchor_x + regressors[4 + j * 2] / scale.x_scale * anchor_w;
+            let ly = anchor_y + regressors[4 + j * 2 + 1] / scale.y_scale * anchor_h;
+            *landmark = (lx, ly);
+        }
+        let landmark_quality = estimate_landmark_quality(score, (x1, y1, x2, y2), &raw_landmarks);
+
+        FaceDetection {
+            confidence: score,
+            bbox: (x1, y1, x2, y2),
+            landmarks: raw_landmarks.map(|(x, y)| (x.clamp(0.0, 1.0), y.clamp(0.0, 1.0))),
+            landmark_quality,
+        }
+    }
+
     /// 检测人脸
     ///
     /// # Arguments
@@ -235,6 +722,9 @@ impl BlazeFaceDetector {
             let (_, classificators_data) = outputs[1]
                 .try_extract_tensor::<f32>()
                 .map_err(|e| FaceDetectorError::InferenceError(format!("Extract classificators error: {}", e)))?;
+            if self.debug_raw_output {
+                self.last_raw_output = Some(RawModelOutput::capture(classificators_data, regressors_data));
+            }
 
             // 6. 解码检测结果
             // 输出形状: regressors [1, 896, 16] -> 扁平为 [896 * 16]
@@ -248,34 +738,21 @@ impl BlazeFaceDetector {
 
                 if score > self.confidence_threshold {
                     // 解码边界框（相对于锚框）
-                    let anchor_x = self.anchors[[i, 0]];
-                    let anchor_y = self.anchors[[i, 1]];
+                    let anchor = [
+                        self.anchors[[i, 0]],
+                        self.anchors[[i, 1]],
+                        self.anchors[[i, 2]],
+                        self.anchors[[i, 3]],
+                    ];
 
                     // regressors 是 [1, 896, 16] 扁平后，第 i 个检测框从 i * 16 开始
                     let reg_offset = i * 16;
-                    let cx = anchor_x + regressors_data[reg_offset] / 128.0;
-                    let cy = anchor_y + regressors_data[reg_offset + 1] / 128.0;
-                    let w = regressors_data[reg_offset + 2] / 128.0;
-                    let h = regressors_data[reg_offset + 3] / 128.0;
-
-                    let x1 = (cx - w / 2.0).clamp(0.0, 1.0);
-                    let y1 = (cy - h / 2.0).clamp(0.0, 1.0);
-                    let x2 = (cx + w / 2.0).clamp(0.0, 1.0);
-                    let y2 = (cy + h / 2.0).clamp(0.0, 1.0);
-
-                    // 解码 6 个关键点
-                    let mut landmarks = [(0.0f32, 0.0f32); 6];
-                    for j in 0..6 {
-                        let lx = anchor_x + regressors_data[reg_offset + 4 + j * 2] / 128.0;
-                        let ly = anchor_y + regressors_data[reg_offset + 4 + j * 2 + 1] / 128.0;
-                        landmarks[j] = (lx.clamp(0.0, 1.0), ly.clamp(0.0, 1.0));
-                    }
-
-                    detections.push(FaceDetection {
-                        confidence: score,
-                        bbox: (x1, y1, x2, y2),
-                        landmarks,
-                    });
+                    detections.push(Self::decode_detection(
+                        score,
+                        &regressors_data[reg_offset..reg_offset + 16],
+                        anchor,
+                        &self.anchor_scale,
+                    ));
                 }
             }
 
@@ -290,6 +767,8 @@ impl BlazeFaceDetector {
     }
 
     /// 模拟检测（无 vision feature）
+    ///
+    /// 模拟一个正在专注的用户：人脸框和关键点带有小幅抖动，偶尔丢失人脸
     #[cfg(not(feature = "vision"))]
     pub fn detect(
         &mut self,
@@ -297,25 +776,60 @@ impl BlazeFaceDetector {
         _width: u32,
         _height: u32,
     ) -> Result<Vec<FaceDetection>, FaceDetectorError> {
-        // 返回模拟的人脸检测结果
-        // 模拟一个正在专注的用户
+        use rand::Rng;
+
+        let rng = &mut self.mock_rng;
+        let loss_probability = self.mock.face_loss_probability.clamp(0.0, 1.0) as f64;
+        if rng.gen_bool(loss_probability) {
+            return Ok(Vec::new());
+        }
+
+        // 整体平移模拟头部的轻微晃动，关键点再各自叠加一半幅度的抖动
+        let jitter = self.mock.jitter.abs();
+        let dx = rng.gen_range(-jitter..=jitter);
+        let dy = rng.gen_range(-jitter..=jitter);
+        let landmarks = [
+            (0.35, 0.35), // 右眼
+            (0.65, 0.35), // 左眼
+            (0.50, 0.55), // 鼻子
+            (0.50, 0.75), // 嘴巴
+            (0.20, 0.40), // 右耳
+            (0.80, 0.40), // 左耳
+        ]
+        .map(|(x, y): (f32, f32)| {
+            (
+                x + dx + rng.gen_range(-jitter / 2.0..=jitter / 2.0),
+                y + dy + rng.gen_range(-jitter / 2.0..=jitter / 2.0),
+            )
+        });
+
         Ok(vec![FaceDetection {
             confidence: 0.95,
-            bbox: (0.25, 0.15, 0.75, 0.85),
-            landmarks: [
-                (0.35, 0.35), // 右眼
-                (0.65, 0.35), // 左眼
-                (0.50, 0.55), // 鼻子
-                (0.50, 0.75), // 嘴巴
-                (0.20, 0.40), // 右耳
-                (0.80, 0.40), // 左耳
-            ],
+            bbox: (0.25 + dx, 0.15 + dy, 0.75 + dx, 0.85 + dy),
+            landmarks,
+            landmark_quality: [1.0; 6],
         }])
     }
 
+    /// 批量检测多帧（离线评估、回放等批量处理）
+    ///
+    /// 结果与输入一一对应，单帧失败不影响其他帧。所有帧共享同一个会话和锚框，省去重复创建检测器的开销；
+    /// BlazeFace 模型的批大小固定为 1，因此逐帧推理而不合并为一次运行。实时处理仍使用 [`Self::detect`]
+    pub fn detect_batch(&mut self, frames: &[(&[u8], u32, u32)]) -> Vec<Result<Vec<FaceDetection>, FaceDetectorError>> {
+        frames
+            .iter()
+            .map(|&(image_data, width, height)| self.detect(image_data, width, height))
+            .collect()
+    }
+
     /// 非极大值抑制
     #[allow(dead_code)]
     fn nms(&self, detections: Vec<FaceDetection>) -> Vec<FaceDetection> {
+        Self::non_max_suppression(detections, self.nms_threshold)
+    }
+
+    /// 按指定 IoU 阈值做非极大值抑制（输入需按置信度降序排列）
+    pub(crate) fn non_max_suppression(detections: Vec<FaceDetection>, nms_threshold: f32) -> Vec<FaceDetection> {
         if detections.is_empty() {
             return detections;
         }
@@ -336,7 +850,7 @@ impl BlazeFaceDetector {
                 }
 
                 let iou = Self::calculate_iou(&detections[i].bbox, &detections[j].bbox);
-                if iou > self.nms_threshold {
+                if iou > nms_threshold {
                     suppressed[j] = true;
                 }
             }
@@ -346,7 +860,7 @@ impl BlazeFaceDetector {
     }
 
     /// 计算 IoU
-    fn calculate_iou(
+    pub(crate) fn calculate_iou(
         box1: &(f32, f32, f32, f32),
         box2: &(f32, f32, f32, f32),
     ) -> f32 {
@@ -371,8 +885,11 @@ impl BlazeFaceDetector {
     /// 生成 BlazeFace 锚框
     #[cfg(feature = "vision")]
     fn generate_anchors() -> ndarray::Array2<f32> {
-        use ndarray::Array2;
+        ndarray::Array2::from_shape_vec((BLAZEFACE_NUM_ANCHORS, 4), Self::generate_anchor_data()).unwrap()
+    }
 
+    /// 生成 BlazeFace 锚框数据，[896, 4] 展平（x 中心, y 中心, 宽, 高）
+    fn generate_anchor_data() -> Vec<f32> {
         // BlazeFace 使用的锚框配置
         // 两个特征图层级：16x16 和 8x8
         let strides = [8, 16];
@@ -387,20 +904,52 @@ impl BlazeFaceDetector {
                     for _ in 0..count {
                         let anchor_x = (x as f32 + 0.5) / grid_size as f32;
                         let anchor_y = (y as f32 + 0.5) / grid_size as f32;
-                        anchors.push([anchor_x, anchor_y]);
+                        // BlazeFace 使用固定锚框尺寸
+                        anchors.push([anchor_x, anchor_y, 1.0, 1.0]);
                     }
                 }
             }
         }
 
-        Array2::from_shape_vec((896, 2), anchors.into_iter().flatten().collect()).unwrap()
+        anchors.into_iter().flatten().collect()
+    }
+
+    /// 把生成的锚框写成 npy 文件（float32，shape [896, 4]）
+    ///
+    /// 随应用打包的锚框文件丢失或损坏时，可用它重新生成一份正确的文件
+    pub fn write_anchors<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, encode_npy_f32(&Self::generate_anchor_data(), (BLAZEFACE_NUM_ANCHORS, 4)))
+    }
+
+    /// 锚框文件是否存在且能被正确解析
+    pub fn anchors_file_valid<P: AsRef<std::path::Path>>(path: P) -> bool {
+        std::fs::read(path)
+            .map(|buffer| Self::parse_anchor_floats(&buffer).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// 确保锚框文件可用：文件缺失或损坏时重新生成
+    ///
+    /// # Returns
+    /// 是否重新生成了文件
+    pub fn ensure_anchors_file<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<bool> {
+        let path = path.as_ref();
+        if Self::anchors_file_valid(path) {
+            return Ok(false);
+        }
+        tracing::warn!("Anchors file {} is missing or invalid, regenerating", path.display());
+        Self::write_anchors(path)?;
+        Ok(true)
     }
 
     /// 从 npy 文件加载锚框
     #[cfg(feature = "vision")]
-    fn load_anchors(path: &str) -> Result<ndarray::Array2<f32>, FaceDetectorError> {
+    fn load_anchors(path: &str, allow_fallback: bool) -> Result<ndarray::Array2<f32>, FaceDetectorError> {
         use std::fs::File;
-        use std::io::Read;
 
         let mut file = File::open(path)
             .map_err(|e| FaceDetectorError::ModelLoadError(format!("Open anchors file error: {}", e)))?;
@@ -409,45 +958,107 @@ impl BlazeFaceDetector {
         file.read_to_end(&mut buffer)
             .map_err(|e| FaceDetectorError::ModelLoadError(format!("Read anchors file error: {}", e)))?;
 
-        // 简单解析 npy 格式（假设是 float32，shape [896, 2]）
-        // npy header 通常是 ~100 字节
-        // 这里使用简化解析，实际应用中可以使用 ndarray-npy crate
-        let header_len = buffer.iter().position(|&b| b == b'\n').unwrap_or(80) + 1;
-        let data_start = if buffer[header_len..].starts_with(&[0x0A]) {
-            header_len + 1
-        } else {
-            header_len
+        match Self::resolve_anchor_data(Self::parse_anchor_floats(&buffer), allow_fallback)? {
+            Some(float_data) => ndarray::Array2::from_shape_vec((BLAZEFACE_NUM_ANCHORS, 4), float_data)
+                .map_err(|e| FaceDetectorError::ModelLoadError(format!("Create anchors array error: {}", e))),
+            None => Ok(Self::generate_anchors()),
+        }
+    }
+
+    /// 根据解析结果和回退策略决定锚框数据
+    ///
+    /// # Returns
+    /// - `Ok(Some(data))`：使用解析出的锚框
+    /// - `Ok(None)`：解析失败但允许回退，使用生成的锚框
+    /// - `Err`：解析失败且不允许回退
+    #[cfg_attr(not(feature = "vision"), allow(dead_code))]
+    fn resolve_anchor_data(
+        parsed: Result<Vec<f32>, String>,
+        allow_fallback: bool,
+    ) -> Result<Option<Vec<f32>>, FaceDetectorError> {
+        match parsed {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if allow_fallback => {
+                tracing::warn!("!!! Anchors file is invalid ({}), FALLING BACK to generated anchors", e);
+                Ok(None)
+            }
+            Err(e) => Err(FaceDetectorError::ModelLoadError(format!("Invalid anchors file: {}", e))),
+        }
+    }
+
+    /// 解析 npy 锚框数据（float32，shape [896, 2] 或 [896, 4]），返回 [896, 4] 展平后的数据
+    fn parse_anchor_floats(buffer: &[u8]) -> Result<Vec<f32>, String> {
+        // npy 格式：魔数 + 版本 + 头部长度 + 头部字典（以换行结尾）+ 数据
+        if !buffer.starts_with(NPY_MAGIC) || buffer.len() < 10 {
+            return Err("not an npy file".to_string());
+        }
+        let (header_len, header_start) = match buffer[6] {
+            1 => (u16::from_le_bytes([buffer[8], buffer[9]]) as usize, 10),
+            2 | 3 if buffer.len() >= 12 => (
+                u32::from_le_bytes([buffer[8], buffer[9], buffer[10], buffer[11]]) as usize,
+                12,
+            ),
+            version => return Err(format!("unsupported npy version {}", version)),
         };
+        let data_start = header_start + header_len;
+        if data_start > buffer.len() {
+            return Err(format!("file too short ({} bytes)", buffer.len()));
+        }
 
-        // 跳过第二个换行符（如果存在）
-        let data_start = buffer[data_start..]
-            .iter()
-            .position(|&b| b != 0x0A && b != 0x20)
-            .map(|p| data_start + p)
-            .unwrap_or(data_start);
+        let header = String::from_utf8_lossy(&buffer[header_start..data_start]);
+        if !header.contains("'descr': '<f4'") {
+            return Err(format!("unsupported dtype in header {}", header.trim()));
+        }
+        if header.contains("'fortran_order': True") {
+            return Err("fortran order is not supported".to_string());
+        }
 
         let float_data: Vec<f32> = buffer[data_start..]
             .chunks_exact(4)
             .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
             .collect();
 
-        if float_data.len() != 896 * 2 {
-            // 如果解析失败，使用生成的锚框
-            tracing::warn!(
-                "Anchors file parsing failed (got {} floats, expected 1792), using generated anchors",
-                float_data.len()
-            );
-            return Ok(Self::generate_anchors());
+        match float_data.len() {
+            // 只有中心点，补充固定尺寸 1.0
+            n if n == BLAZEFACE_NUM_ANCHORS * 2 => Ok(float_data
+                .chunks_exact(2)
+                .flat_map(|c| [c[0], c[1], 1.0, 1.0])
+                .collect()),
+            n if n == BLAZEFACE_NUM_ANCHORS * 4 => Ok(float_data),
+            n => Err(format!("got {} floats, expected 1792 or 3584", n)),
         }
+    }
+}
 
-        ndarray::Array2::from_shape_vec((896, 2), float_data)
-            .map_err(|e| FaceDetectorError::ModelLoadError(format!("Create anchors array error: {}", e)))
+/// npy 文件魔数
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// 编码为 npy 1.0 格式（float32，小端，C 顺序）
+fn encode_npy_f32(data: &[f32], shape: (usize, usize)) -> Vec<u8> {
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        shape.0, shape.1
+    );
+    // 魔数(6) + 版本(2) + 长度(2) + 头部（含结尾换行）按 64 字节对齐
+    let total = 10 + header.len() + 1;
+    header.push_str(&" ".repeat(total.next_multiple_of(64) - total));
+    header.push('\n');
+
+    let mut buffer = Vec::with_capacity(10 + header.len() + data.len() * 4);
+    buffer.extend_from_slice(NPY_MAGIC);
+    buffer.extend_from_slice(&[1, 0]);
+    buffer.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(header.as_bytes());
+    for value in data {
+        buffer.extend_from_slice(&value.to_le_bytes());
     }
+    buffer
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::test_temp_path;
 
     #[test]
     fn test_face_detection_center() {
@@ -455,23 +1066,250 @@ mod tests {
             confidence: 0.9,
             bbox: (0.2, 0.1, 0.8, 0.9),
             landmarks: [(0.0, 0.0); 6],
+            landmark_quality: [1.0; 6],
         };
         let (cx, cy) = detection.center();
         assert!((cx - 0.5).abs() < 0.001);
         assert!((cy - 0.5).abs() < 0.001);
     }
 
+    #[test]
+    fn test_nms_threshold() {
+        let make = |x: f32, confidence: f32| FaceDetection {
+            confidence,
+            bbox: (x, 0.2, x + 0.4, 0.6),
+            landmarks: [(0.0, 0.0); 6],
+            landmark_quality: [1.0; 6],
+        };
+        // 两个框部分重叠，IoU = 0.2 / 0.6 ≈ 0.33
+        let detections = vec![make(0.1, 0.9), make(0.3, 0.8)];
+
+        assert_eq!(BlazeFaceDetector::non_max_suppression(detections.clone(), 0.3).len(), 1);
+        assert_eq!(BlazeFaceDetector::non_max_suppression(detections, 0.5).len(), 2);
+    }
+
     #[test]
     fn test_face_detection_size() {
         let detection = FaceDetection {
             confidence: 0.9,
             bbox: (0.2, 0.2, 0.8, 0.8),
             landmarks: [(0.0, 0.0); 6],
+            landmark_quality: [1.0; 6],
         };
         let size = detection.size();
         assert!((size - 0.36).abs() < 0.001);
     }
 
+    #[test]
+    fn test_model_info_fixture() {
+        let path = test_temp_path("model").with_extension("bin");
+        std::fs::write(&path, b"abc").unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let info = ModelInfo::inspect(&path);
+        assert_eq!(info.num_anchors, 896);
+        assert_eq!(info.input_size, 128);
+        assert_eq!(
+            info.sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert!(!info.loaded_ok);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_decode_fixed_anchor_size() {
+        // 锚框中心 (0.5, 0.5)，回归值以 128 像素为单位
+        let mut regressors = [0.0f32; 16];
+        regressors[0] = 12.8; // cx 偏移 0.1
+        regressors[1] = -6.4; // cy 偏移 -0.05
+        regressors[2] = 64.0; // 宽 0.5
+        regressors[3] = 32.0; // 高 0.25
+        regressors[4] = 12.8; // 第一个关键点 x 偏移 0.1
+
+        let scale = AnchorScaleConfig::default();
+        // 固定尺寸时忽略锚框宽高
+        let detection = BlazeFaceDetector::decode_detection(0.9, &regressors, [0.5, 0.5, 2.0, 2.0], &scale);
+
+        let (x1, y1, x2, y2) = detection.bbox;
+        assert!((x1 - 0.35).abs() < 1e-5);
+        assert!((y1 - 0.325).abs() < 1e-5);
+        assert!((x2 - 0.85).abs() < 1e-5);
+        assert!((y2 - 0.575).abs() < 1e-5);
+        assert!((detection.landmarks[0].0 - 0.6).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_decode_with_anchor_scale() {
+        let mut regressors = [0.0f32; 16];
+        regressors[0] = 12.8;
+        regressors[2] = 64.0;
+        regressors[3] = 64.0;
+
+        let scale = AnchorScaleConfig {
+            fixed_anchor_size: false,
+            ..Default::default()
+        };
+        // 锚框宽高 0.5，偏移和尺寸都按锚框缩放
+        let detection = BlazeFaceDetector::decode_detection(0.9, &regressors, [0.5, 0.5, 0.5, 0.5], &scale);
+
+        let (x1, y1, x2, y2) = detection.bbox;
+        // cx = 0.5 + 0.1 * 0.5 = 0.55，w = 0.5 * 0.5 = 0.25
+        assert!((x1 - 0.425).abs() < 1e-5);
+        assert!((x2 - 0.675).abs() < 1e-5);
+        assert!((y1 - 0.375).abs() < 1e-5);
+        assert!((y2 - 0.625).abs() < 1e-5);
+    }
+
+    /// 构造 npy 格式的锚框文件内容
+    fn make_npy(floats: usize) -> Vec<u8> {
+        encode_npy_f32(&vec![0.5; floats], (floats / 2, 2))
+    }
+
+    #[test]
+    fn test_parse_anchor_floats() {
+        let data = BlazeFaceDetector::parse_anchor_floats(&make_npy(BLAZEFACE_NUM_ANCHORS * 2)).unwrap();
+        assert_eq!(data.len(), BLAZEFACE_NUM_ANCHORS * 4);
+        assert_eq!(&data[..4], &[0.5, 0.5, 1.0, 1.0]);
+
+        assert!(BlazeFaceDetector::parse_anchor_floats(&make_npy(100)).is_err());
+    }
+
+    #[test]
+    fn test_write_anchors_round_trip() {
+        let path = test_temp_path("anchors").with_extension("npy");
+
+        BlazeFaceDetector::write_anchors(&path).unwrap();
+        let buffer = std::fs::read(&path).unwrap();
+        assert!(buffer.starts_with(NPY_MAGIC));
+        // 头部按 64 字节对齐
+        let header_len = u16::from_le_bytes([buffer[8], buffer[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+
+        let reloaded = BlazeFaceDetector::parse_anchor_floats(&buffer).unwrap();
+        assert_eq!(reloaded, BlazeFaceDetector::generate_anchor_data());
+
+        // 损坏的文件会被重新生成
+        std::fs::write(&path, b"garbage").unwrap();
+        assert!(!BlazeFaceDetector::anchors_file_valid(&path));
+        assert!(BlazeFaceDetector::ensure_anchors_file(&path).unwrap());
+        assert!(!BlazeFaceDetector::ensure_anchors_file(&path).unwrap());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_anchor_fallback_strict() {
+        let parsed = BlazeFaceDetector::parse_anchor_floats(&make_npy(100));
+        let result = BlazeFaceDetector::resolve_anchor_data(parsed, false);
+        assert!(matches!(result, Err(FaceDetectorError::ModelLoadError(_))));
+    }
+
+    #[test]
+    fn test_anchor_fallback_permissive() {
+        let parsed = BlazeFaceDetector::parse_anchor_floats(&make_npy(100));
+        let result = BlazeFaceDetector::resolve_anchor_data(parsed, true);
+        // None 表示使用生成的锚框
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[cfg(not(feature = "vision"))]
+    #[test]
+    fn test_detect_batch_matches_detect() {
+        let mut batch_detector = BlazeFaceDetector::new("models/blazeface.onnx", None).unwrap();
+        let mut detector = BlazeFaceDetector::new("models/blazeface.onnx", None).unwrap();
+        let small = vec![0u8; 4 * 4 * 3];
+        let large = vec![128u8; 8 * 6 * 3];
+        let frames: Vec<(&[u8], u32, u32)> = vec![(&small, 4, 4), (&large, 8, 6), (&small, 4, 4)];
+
+        // 两个检测器种子相同，逐帧检测与批量检测得到相同的序列
+        let batch = batch_detector.detect_batch(&frames);
+        assert_eq!(batch.len(), frames.len());
+        for (&(data, width, height), batched) in frames.iter().zip(&batch) {
+            let single = detector.detect(data, width, height).unwrap();
+            assert_eq!(format!("{:?}", batched.as_ref().unwrap()), format!("{:?}", single));
+        }
+        assert!(batch_detector.detect_batch(&[]).is_empty());
+    }
+
+    #[cfg(not(feature = "vision"))]
+    #[test]
+    fn test_mock_detection_seed_reproducible() {
+        let sequence = |seed: u64| {
+            let mut detector = BlazeFaceDetector::new("models/blazeface.onnx", None).unwrap();
+            detector.set_mock_detection(MockDetectionConfig {
+                seed,
+                face_loss_probability: 0.1,
+                ..Default::default()
+            });
+            (0..200)
+                .map(|_| format!("{:?}", detector.detect(&[], 0, 0).unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        let first = sequence(7);
+        assert_eq!(first, sequence(7));
+        assert_ne!(first, sequence(8));
+
+        // 有抖动（不是每帧都相同），也会偶尔丢失人脸
+        assert!(first.iter().any(|frame| frame != &first[0]));
+        assert!(first.iter().any(|frame| frame == "[]"));
+        assert!(first.iter().filter(|frame| *frame != "[]").count() > 150);
+    }
+
+    #[cfg(feature = "vision")]
+    #[test]
+    fn test_raw_output_captured_when_debugging() {
+        let mut detector = BlazeFaceDetector::new(
+            "resources/models/blazeface.onnx",
+            Some("resources/models/anchors.npy"),
+        )
+        .unwrap();
+        let frame = vec![128u8; 320 * 240 * 3];
+
+        // 未开启时不记录
+        detector.detect(&frame, 320, 240).unwrap();
+        assert!(detector.last_raw_output().is_none());
+
+        detector.set_debug_raw_output(true);
+        detector.detect(&frame, 320, 240).unwrap();
+        let raw = detector.last_raw_output().unwrap();
+        assert_eq!(raw.top_scores.len(), RAW_OUTPUT_TOP_SCORES);
+        assert_eq!(raw.sample_regressors.len(), 16);
+        assert!(raw.top_scores.windows(2).all(|w| w[0] >= w[1]));
+
+        detector.set_debug_raw_output(false);
+        assert!(detector.last_raw_output().is_none());
+    }
+
+    #[cfg(feature = "vision")]
+    #[test]
+    fn test_model_io_matches_decoder() {
+        // 读取真实模型的输入输出签名
+        let detector = BlazeFaceDetector::new(
+            "resources/models/blazeface.onnx",
+            Some("resources/models/anchors.npy"),
+        )
+        .unwrap();
+        let io = detector.model_io();
+        assert!(!io.mock);
+        assert!(io.mismatches.is_empty(), "{:?}", io.mismatches);
+        assert_eq!(io.inputs.len(), 1);
+        assert!(io.inputs[0].matches(&io.expected_inputs[0]), "input {:?}", io.inputs[0].shape);
+        assert_eq!(io.expected_inputs[0].shape, [1, 3, 128, 128]);
+        assert_eq!(io.outputs.len(), 2);
+    }
+
+    #[test]
+    fn test_model_io_reports_mismatch() {
+        // 输出维度不符时给出可读的说明
+        let (inputs, mut outputs) = ModelIo::expected();
+        outputs[0].shape = vec![1, 2304, 16];
+        let swapped = ModelIo::compare(inputs, outputs, false);
+        assert_eq!(swapped.mismatches.len(), 1);
+        assert!(swapped.mismatches[0].contains("[1, 896, 16]"), "{}", swapped.mismatches[0]);
+    }
+
     #[test]
     fn test_iou_calculation() {
         // 完全重叠
@@ -8,6 +8,7 @@
 
 use super::face::FaceDetection;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 /// 专注度计算器配置
 #[derive(Debug, Clone)]
@@ -26,14 +27,89 @@ pub struct FocusCalculatorConfig {
     pub max_pitch: f32,
     /// 翻滚角最大值（度）
     pub max_roll: f32,
-    /// 最小人脸置信度阈值
+    /// 翻滚角死区（度）：思考时常会歪头，小于该角度不扣分，超出后按平滑曲线扣分，到 `max_roll` 时扣满。
+    /// 为 0 时关闭（默认），按线性扣分
+    pub roll_dead_zone: f32,
+    /// 最小人脸置信度阈值（未检测到人脸时，达到该值才视为人脸出现）
     pub min_face_confidence: f32,
+    /// 已检测到人脸时，置信度降到该值以下才视为人脸离开，避免置信度在阈值附近抖动时反复切换。
+    /// 默认等于 `min_face_confidence`（不启用滞后，检测器阈值保持不变）
+    pub presence_exit_confidence: f32,
     /// 人脸大小权重（用于判断是否靠近屏幕）
     pub face_size_weight: f32,
     /// 理想人脸大小比例（相对于画面）
     pub ideal_face_size: f32,
+    /// 检测到有效人脸时的最低专注分数，避免转头时各分量同时下降导致分数接近 0
+    pub min_present_score: f32,
+    /// 关键点质量低于该值时按比例降低依赖它的姿态分量的权重，降低的权重按比例分给其余分量
+    pub min_landmark_quality: f32,
+    /// 副屏所在的偏航角区间：偏航角落在区间内时视为看向副屏，不按转头扣分（None 表示单屏）
+    pub secondary_screen: Option<YawZone>,
 }
 
+/// 偏航角区间（度，正值表示向右转头）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct YawZone {
+    /// 区间下限
+    pub min_yaw: f32,
+    /// 区间上限
+    pub max_yaw: f32,
+}
+
+/// 校准副屏区间所需的最少偏航角采样数
+const MIN_CALIBRATION_SAMPLES: usize = 5;
+
+/// 校准时偏航角平均值与正前方的最小距离（度），太接近正前方时无法与主屏区分
+const MIN_CALIBRATION_YAW: f32 = 10.0;
+
+impl YawZone {
+    /// 偏航角是否在区间内
+    pub fn contains(&self, yaw: f32) -> bool {
+        (self.min_yaw..=self.max_yaw).contains(&yaw)
+    }
+
+    /// 根据看向副屏时采集的偏航角校准区间：以平均值为中心、左右各 `half_width` 度
+    ///
+    /// 采样不足或平均值太接近正前方时返回 None
+    pub fn calibrate(yaws: &[f32], half_width: f32) -> Option<Self> {
+        if yaws.len() < MIN_CALIBRATION_SAMPLES {
+            return None;
+        }
+        let mean = yaws.iter().sum::<f32>() / yaws.len() as f32;
+        if mean.abs() < MIN_CALIBRATION_YAW {
+            return None;
+        }
+        let half_width = half_width.abs();
+        Some(Self {
+            min_yaw: mean - half_width,
+            max_yaw: mean + half_width,
+        })
+    }
+}
+
+/// 用正常坐姿时采集的人脸大小校准理想人脸大小：取中位数，避免偶尔前倾、后仰影响结果
+///
+/// 忽略未检测到人脸的采样，有效采样不足时返回 None
+pub fn calibrated_face_size(sizes: &[f32]) -> Option<f32> {
+    let mut sizes: Vec<f32> = sizes.iter().copied().filter(|&size| size > 0.0).collect();
+    if sizes.len() < MIN_CALIBRATION_SAMPLES {
+        return None;
+    }
+    sizes.sort_by(f32::total_cmp);
+    let mid = sizes.len() / 2;
+    Some(if sizes.len().is_multiple_of(2) {
+        (sizes[mid - 1] + sizes[mid]) / 2.0
+    } else {
+        sizes[mid]
+    })
+}
+
+/// 偏航角、翻滚角依赖的关键点（双眼）
+const EYE_LANDMARKS: [usize; 2] = [0, 1];
+
+/// 俯仰角依赖的关键点（双眼和鼻子）
+const EYE_NOSE_LANDMARKS: [usize; 3] = [0, 1, 2];
+
 impl Default for FocusCalculatorConfig {
     fn default() -> Self {
         Self {
@@ -45,23 +121,119 @@ impl Default for FocusCalculatorConfig {
             max_yaw: 30.0,
             max_pitch: 25.0,
             max_roll: 20.0,
+            roll_dead_zone: 0.0,
             min_face_confidence: 0.5,
+            presence_exit_confidence: 0.5,
             ideal_face_size: 0.15, // 人脸占画面 15% 左右为理想
+            min_present_score: 0.2,
+            min_landmark_quality: 0.5,
+            secondary_screen: None,
+        }
+    }
+}
+
+/// 专注评分档位
+///
+/// 预设档位一键切换计算器参数和专注阈值，`Custom` 保留用户手动调整的参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusProfile {
+    /// 严格：轻微转头即视为分心
+    Strict,
+    /// 标准
+    #[default]
+    Normal,
+    /// 宽松：允许较大的头部转动
+    Relaxed,
+    /// 自定义
+    Custom,
+}
+
+/// 预设档位对应的参数
+#[derive(Debug, Clone)]
+pub struct FocusPreset {
+    /// 专注度计算器配置
+    pub calculator: FocusCalculatorConfig,
+    /// 进入专注状态的阈值
+    pub enter_threshold: f32,
+    /// 退出专注状态的阈值
+    pub exit_threshold: f32,
+}
+
+impl FocusProfile {
+    /// 从字符串解析档位
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "normal" => Some(Self::Normal),
+            "relaxed" => Some(Self::Relaxed),
+            "custom" => Some(Self::Custom),
+            _ => None,
+        }
+    }
+
+    /// 档位对应的预设参数，`Custom` 返回 None
+    pub fn preset(&self) -> Option<FocusPreset> {
+        let base = FocusCalculatorConfig::default();
+
+        match self {
+            Self::Strict => Some(FocusPreset {
+                calculator: FocusCalculatorConfig {
+                    max_yaw: 20.0,
+                    max_pitch: 15.0,
+                    max_roll: 15.0,
+                    ..base
+                },
+                enter_threshold: 0.8,
+                exit_threshold: 0.5,
+            }),
+            Self::Normal => Some(FocusPreset {
+                calculator: base,
+                enter_threshold: 0.75,
+                exit_threshold: 0.35,
+            }),
+            Self::Relaxed => Some(FocusPreset {
+                calculator: FocusCalculatorConfig {
+                    max_yaw: 45.0,
+                    max_pitch: 35.0,
+                    max_roll: 30.0,
+                    ..base
+                },
+                enter_threshold: 0.65,
+                exit_threshold: 0.25,
+            }),
+            Self::Custom => None,
         }
     }
 }
 
+/// 专注评分基准测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalculatorBenchmark {
+    /// 计算次数
+    pub iterations: u32,
+    /// 总耗时（纳秒）
+    pub total_ns: u64,
+    /// 平均每次计算的耗时（纳秒）
+    pub ns_per_call: f64,
+}
+
 /// 专注度计算器
 ///
 /// 根据人脸检测结果计算用户的专注程度
 pub struct FocusCalculator {
     config: FocusCalculatorConfig,
+    /// 上一次 `update` 是否检测到有效人脸（用于在场滞后）
+    face_present: bool,
 }
 
 impl FocusCalculator {
     /// 创建新的计算器
     pub fn new(config: FocusCalculatorConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            face_present: false,
+        }
     }
 
     /// 使用默认配置创建
@@ -69,6 +241,11 @@ impl FocusCalculator {
         Self::new(FocusCalculatorConfig::default())
     }
 
+    /// 替换计算器配置
+    pub fn set_config(&mut self, config: FocusCalculatorConfig) {
+        self.config = config;
+    }
+
     /// 计算专注分数
     ///
     /// # Arguments
@@ -78,22 +255,79 @@ impl FocusCalculator {
     /// 返回 (专注分数, 是否检测到人脸)
     /// 专注分数范围 0.0 - 1.0，越高表示越专注
     pub fn calculate(&self, detection: Option<&FaceDetection>) -> (f32, bool) {
+        self.calculate_with_confidence(detection, self.config.min_face_confidence, false)
+    }
+
+    /// 带在场滞后地计算专注分数（处理循环逐帧调用）
+    ///
+    /// 上一帧没有有效人脸时，置信度需要达到 `min_face_confidence`；
+    /// 上一帧有有效人脸时，只要不低于 `presence_exit_confidence` 就仍视为在场
+    pub fn update(&mut self, detection: Option<&FaceDetection>) -> (f32, bool) {
+        self.update_tolerating_yaw(detection, false)
+    }
+
+    /// 同 [`update`](Self::update)，`tolerate_yaw` 为 true 时不按转头扣分（转头宽限内）
+    pub fn update_tolerating_yaw(&mut self, detection: Option<&FaceDetection>, tolerate_yaw: bool) -> (f32, bool) {
+        let min_confidence = if self.face_present {
+            self.config.presence_exit_confidence.min(self.config.min_face_confidence)
+        } else {
+            self.config.min_face_confidence
+        };
+        let result = self.calculate_with_confidence(detection, min_confidence, tolerate_yaw);
+        self.face_present = result.1;
+        result
+    }
+
+    /// 对固定的人脸检测结果重复计算 `iterations` 次，测量专注评分本身的 CPU 耗时（不含采集和推理）
+    pub fn benchmark(&self, iterations: u32) -> CalculatorBenchmark {
+        let iterations = iterations.max(1);
+        // 正对屏幕、大小适中的人脸会走完所有评分步骤
+        let face = FaceDetection::frontal();
+        let started = std::time::Instant::now();
+        for _ in 0..iterations {
+            std::hint::black_box(self.calculate(std::hint::black_box(Some(&face))));
+        }
+        let total_ns = (started.elapsed().as_nanos() as u64).max(1);
+
+        CalculatorBenchmark {
+            iterations,
+            total_ns,
+            ns_per_call: total_ns as f64 / iterations as f64,
+        }
+    }
+
+    /// 人脸检测器需要保留的最低置信度（低于该值的人脸不会影响在场判断）
+    pub fn detection_confidence_threshold(&self) -> f32 {
+        self.config.presence_exit_confidence.min(self.config.min_face_confidence)
+    }
+
+    /// 按给定的最低人脸置信度计算专注分数，`tolerate_yaw` 为 true 时偏航角分量按正对屏幕计分
+    fn calculate_with_confidence(
+        &self,
+        detection: Option<&FaceDetection>,
+        min_confidence: f32,
+        tolerate_yaw: bool,
+    ) -> (f32, bool) {
         let Some(face) = detection else {
             return (0.0, false);
         };
 
         // 检查人脸置信度是否足够
-        if face.confidence < self.config.min_face_confidence {
+        if face.confidence < min_confidence {
             return (0.0, false);
         }
 
         // 1. 人脸置信度分量
         let conf_score = face.confidence;
 
-        // 2. 偏航角分量（左右转头）
+        // 2. 偏航角分量（左右转头），看向副屏或在转头宽限内时按正对屏幕计分
         let yaw = face.estimate_yaw();
-        let yaw_normalized = (yaw.abs() / self.config.max_yaw).min(1.0);
-        let yaw_score = 1.0 - yaw_normalized;
+        let on_secondary_screen = self.config.secondary_screen.is_some_and(|zone| zone.contains(yaw));
+        let yaw_score = if on_secondary_screen || tolerate_yaw {
+            1.0
+        } else {
+            1.0 - (yaw.abs() / self.config.max_yaw).min(1.0)
+        };
 
         // 3. 俯仰角分量（上下点头）
         let pitch = face.estimate_pitch();
@@ -102,23 +336,43 @@ impl FocusCalculator {
 
         // 4. 翻滚角分量（歪头）
         let roll = face.estimate_roll();
-        let roll_normalized = (roll.abs() / self.config.max_roll).min(1.0);
-        let roll_score = 1.0 - roll_normalized;
+        let roll_score = self.roll_score(roll);
 
         // 5. 人脸大小分量（判断距离是否合适）
         let face_size = face.size();
         let size_diff = (face_size - self.config.ideal_face_size).abs();
         let size_score = (1.0 - size_diff / self.config.ideal_face_size).max(0.0);
 
-        // 综合计算专注分数
-        let focus_score = self.config.face_confidence_weight * conf_score
-            + self.config.yaw_weight * yaw_score
-            + self.config.pitch_weight * pitch_score
-            + self.config.roll_weight * roll_score
+        // 关键点质量不足时降低对应姿态分量的权重
+        let yaw_weight = self.config.yaw_weight * self.pose_confidence(face, &EYE_LANDMARKS);
+        let pitch_weight =
+            self.config.pitch_weight * self.pose_confidence(face, &EYE_NOSE_LANDMARKS);
+        let roll_weight = self.config.roll_weight * self.pose_confidence(face, &EYE_LANDMARKS);
+
+        // 综合计算专注分数，总权重保持不变
+        let nominal_weight = self.config.face_confidence_weight
+            + self.config.yaw_weight
+            + self.config.pitch_weight
+            + self.config.roll_weight
+            + self.config.face_size_weight;
+        let effective_weight = self.config.face_confidence_weight
+            + yaw_weight
+            + pitch_weight
+            + roll_weight
+            + self.config.face_size_weight;
+        let weighted = self.config.face_confidence_weight * conf_score
+            + yaw_weight * yaw_score
+            + pitch_weight * pitch_score
+            + roll_weight * roll_score
             + self.config.face_size_weight * size_score;
+        let focus_score = if effective_weight > 0.0 {
+            weighted * nominal_weight / effective_weight
+        } else {
+            0.0
+        };
 
-        // 确保分数在 0-1 范围内
-        let focus_score = focus_score.clamp(0.0, 1.0);
+        // 人脸有效时分数不低于下限，并确保在 0-1 范围内
+        let focus_score = focus_score.max(self.config.min_present_score).clamp(0.0, 1.0);
 
         tracing::trace!(
             "Focus calculation: conf={:.2}, yaw={:.1}({:.2}), pitch={:.1}({:.2}), roll={:.1}({:.2}), size={:.3}({:.2}) => {:.2}",
@@ -127,13 +381,54 @@ impl FocusCalculator {
 
         (focus_score, true)
     }
+
+    /// 翻滚角分量 (0.0 - 1.0)
+    ///
+    /// 未设置死区时按线性扣分；设置后死区内为 1，超出后按二次曲线下降，开始时扣分平缓
+    fn roll_score(&self, roll: f32) -> f32 {
+        let dead_zone = self.config.roll_dead_zone;
+        if dead_zone <= 0.0 {
+            return 1.0 - (roll.abs() / self.config.max_roll).min(1.0);
+        }
+        let span = (self.config.max_roll - dead_zone).max(f32::EPSILON);
+        let t = ((roll.abs() - dead_zone) / span).clamp(0.0, 1.0);
+        1.0 - t * t
+    }
+
+    /// 姿态分量的权重系数 (0.0 - 1.0)：依赖的关键点质量达到 `min_landmark_quality` 时为 1
+    fn pose_confidence(&self, face: &FaceDetection, landmarks: &[usize]) -> f32 {
+        if self.config.min_landmark_quality <= 0.0 {
+            return 1.0;
+        }
+        (face.landmark_reliability(landmarks) / self.config.min_landmark_quality).clamp(0.0, 1.0)
+    }
+}
+
+/// `FocusState` 当前的序列化版本
+///
+/// - 1：初始版本（没有 `motion_wake`、`rolling_focus_30s`，也没有版本字段）
+/// - 2：增加 `schema_version`、`motion_wake`、`rolling_focus_30s`
+/// - 3：增加 `heat`
+/// - 4：增加 `detected_at_ms`
+/// - 5：增加 `face_size`
+/// - 6：增加 `passivity`
+///
+/// 增删字段时递增版本，并在 `FocusState::migrate` 中补上从上一版本的迁移
+pub const FOCUS_STATE_SCHEMA_VERSION: u32 = 6;
+
+/// 缺少版本字段的数据视为第 1 版
+fn legacy_schema_version() -> u32 {
+    1
 }
 
 /// 专注状态快照
 ///
-/// 用于通过 watch 通道在线程间传递
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 用于通过 watch 通道在线程间传递，也会发送到前端和写入采样日志
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FocusState {
+    /// 序列化版本，见 [`FOCUS_STATE_SCHEMA_VERSION`]
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     /// 是否检测到人脸
     pub face_present: bool,
     /// 人脸置信度
@@ -148,11 +443,30 @@ pub struct FocusState {
     pub roll: f32,
     /// 时间戳（毫秒）
     pub timestamp_ms: u64,
+    /// 静止后检测到明显运动（用于提前唤醒宠物）
+    pub motion_wake: bool,
+    /// 最近一段时间（默认 30 秒）内专注分数的滑动平均
+    #[serde(default)]
+    pub rolling_focus_30s: f32,
+    /// 专注热度 (0.0 - 1.0)：持续专注时缓慢升高，分心时逐渐冷却，用于氛围可视化
+    #[serde(default)]
+    pub heat: f32,
+    /// 产生这组数值的检测时间（毫秒）
+    /// 不检测的帧复用上一次的结果时只更新 `timestamp_ms`，这里保持原检测时间；0 表示与 `timestamp_ms` 相同
+    #[serde(default)]
+    pub detected_at_ms: u64,
+    /// 人脸面积占画面的比例（未检测到人脸时为 0）
+    #[serde(default)]
+    pub face_size: f32,
+    /// 被动程度 (0.0 - 1.0)：头部长时间几乎不动（如看视频）时升高，未开启被动观看估计时为 0
+    #[serde(default)]
+    pub passivity: f32,
 }
 
 impl Default for FocusState {
     fn default() -> Self {
         Self {
+            schema_version: FOCUS_STATE_SCHEMA_VERSION,
             face_present: false,
             face_confidence: 0.0,
             focus_score: 0.0,
@@ -160,6 +474,12 @@ impl Default for FocusState {
             pitch: 0.0,
             roll: 0.0,
             timestamp_ms: 0,
+            motion_wake: false,
+            rolling_focus_30s: 0.0,
+            heat: 0.0,
+            detected_at_ms: 0,
+            face_size: 0.0,
+            passivity: 0.0,
         }
     }
 }
@@ -174,6 +494,7 @@ impl FocusState {
 
         match detection {
             Some(face) => Self {
+                schema_version: FOCUS_STATE_SCHEMA_VERSION,
                 face_present: true,
                 face_confidence: face.confidence,
                 focus_score,
@@ -181,8 +502,15 @@ impl FocusState {
                 pitch: face.estimate_pitch(),
                 roll: face.estimate_roll(),
                 timestamp_ms,
+                motion_wake: false,
+                rolling_focus_30s: 0.0,
+                heat: 0.0,
+                detected_at_ms: timestamp_ms,
+                face_size: face.size(),
+                passivity: 0.0,
             },
             None => Self {
+                schema_version: FOCUS_STATE_SCHEMA_VERSION,
                 face_present: false,
                 face_confidence: 0.0,
                 focus_score: 0.0,
@@ -190,14 +518,216 @@ impl FocusState {
                 pitch: 0.0,
                 roll: 0.0,
                 timestamp_ms,
+                motion_wake: false,
+                rolling_focus_30s: 0.0,
+                heat: 0.0,
+                detected_at_ms: timestamp_ms,
+                face_size: 0.0,
+                passivity: 0.0,
             },
         }
     }
+
+    /// 数值对应的检测时间（毫秒）
+    pub fn detected_at(&self) -> u64 {
+        if self.detected_at_ms == 0 {
+            self.timestamp_ms
+        } else {
+            self.detected_at_ms
+        }
+    }
+
+    /// 是否复用了之前的检测结果（不检测的帧）
+    pub fn is_reused(&self) -> bool {
+        self.detected_at() < self.timestamp_ms
+    }
+
+    /// 截至 `now_ms`，数值是否已过时：距离产生这组数值的检测超过 `max_age_ms`
+    ///
+    /// 复用的状态按原检测时间计算，时间戳被刷新也不会显得新鲜
+    pub fn is_stale_at(&self, now_ms: u64, max_age_ms: u64) -> bool {
+        now_ms.saturating_sub(self.detected_at()) > max_age_ms
+    }
+
+    /// 从任意已知版本的 JSON 解析（例如旧版采样日志），逐版本迁移到当前结构
+    ///
+    /// 版本高于当前程序支持的版本时返回错误
+    pub fn from_versioned_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        let version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or_else(legacy_schema_version);
+
+        if version > FOCUS_STATE_SCHEMA_VERSION {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported FocusState schema version {} (max {})",
+                version, FOCUS_STATE_SCHEMA_VERSION
+            )));
+        }
+
+        Self::migrate(&mut value, version);
+        serde_json::from_value(value)
+    }
+
+    /// 把 `version` 版本的 JSON 原地迁移到当前版本
+    fn migrate(value: &mut serde_json::Value, version: u32) {
+        let Some(object) = value.as_object_mut() else {
+            return;
+        };
+
+        if version < 2 {
+            object.entry("motion_wake").or_insert(false.into());
+            object.entry("rolling_focus_30s").or_insert(0.0.into());
+        }
+        if version < 3 {
+            object.entry("heat").or_insert(0.0.into());
+        }
+        if version < 4 {
+            object.entry("detected_at_ms").or_insert(0.into());
+        }
+        if version < 5 {
+            object.entry("face_size").or_insert(0.0.into());
+        }
+        if version < 6 {
+            object.entry("passivity").or_insert(0.0.into());
+        }
+
+        object.insert("schema_version".to_string(), FOCUS_STATE_SCHEMA_VERSION.into());
+    }
+}
+
+/// 固定时间窗口的专注分数滑动平均
+///
+/// 与 EMA 不同，窗口外的采样完全不再影响结果，更适合“最近专注度”这类直观指标
+#[derive(Debug, Clone)]
+pub struct RollingFocus {
+    /// 窗口长度（毫秒）
+    window_ms: u64,
+    /// 窗口内的采样 (时间戳, 分数)，按时间递增
+    samples: VecDeque<(u64, f32)>,
+    /// 窗口内分数之和
+    sum: f64,
+}
+
+impl RollingFocus {
+    /// 创建指定窗口长度的滑动平均
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            samples: VecDeque::new(),
+            sum: 0.0,
+        }
+    }
+
+    /// 加入一个采样并返回最新的平均值
+    pub fn push(&mut self, timestamp_ms: u64, score: f32) -> f32 {
+        self.samples.push_back((timestamp_ms, score));
+        self.sum += score as f64;
+        self.expire(timestamp_ms);
+        self.average()
+    }
+
+    /// 丢弃早于窗口起点的采样
+    pub fn expire(&mut self, now_ms: u64) {
+        while let Some(&(timestamp, score)) = self.samples.front() {
+            if now_ms.saturating_sub(timestamp) < self.window_ms {
+                break;
+            }
+            self.samples.pop_front();
+            self.sum -= score as f64;
+        }
+        // 窗口清空时归零，避免浮点误差累积
+        if self.samples.is_empty() {
+            self.sum = 0.0;
+        }
+    }
+
+    /// 窗口内的平均分数（没有采样时为 0）
+    pub fn average(&self) -> f32 {
+        if self.samples.is_empty() {
+            0.0
+        } else {
+            (self.sum / self.samples.len() as f64) as f32
+        }
+    }
+}
+
+/// 专注热度配置
+#[derive(Debug, Clone)]
+pub struct FocusHeatConfig {
+    /// 专注时每分钟升高的热度（按分数加权，满分时为该值）
+    pub rise_per_minute: f32,
+    /// 分心或离开时每分钟降低的热度
+    pub decay_per_minute: f32,
+    /// 分数不低于该值时视为专注
+    pub focus_threshold: f32,
+}
+
+impl Default for FocusHeatConfig {
+    fn default() -> Self {
+        Self {
+            rise_per_minute: 0.1,
+            decay_per_minute: 0.2,
+            focus_threshold: 0.5,
+        }
+    }
+}
+
+/// 单次更新计入的最长时间（毫秒），避免长时间没有采样（如系统睡眠）后热度突变
+const MAX_HEAT_STEP_MS: u64 = 5_000;
+
+/// 专注热度累积器
+///
+/// 与瞬时分数不同，热度变化很慢：需要持续专注几分钟才能“热”起来，短暂分心只会让它稍微冷却
+#[derive(Debug, Clone)]
+pub struct FocusHeat {
+    config: FocusHeatConfig,
+    /// 当前热度 (0.0 - 1.0)
+    heat: f32,
+    /// 上一次更新的时间戳（毫秒）
+    last_ms: Option<u64>,
+}
+
+impl FocusHeat {
+    /// 创建累积器，热度从 0 开始
+    pub fn new(config: FocusHeatConfig) -> Self {
+        Self {
+            config,
+            heat: 0.0,
+            last_ms: None,
+        }
+    }
+
+    /// 加入一个采样并返回最新的热度
+    pub fn update(&mut self, timestamp_ms: u64, score: f32) -> f32 {
+        let elapsed_ms = match self.last_ms {
+            Some(last) => timestamp_ms.saturating_sub(last).min(MAX_HEAT_STEP_MS),
+            None => 0,
+        };
+        self.last_ms = Some(timestamp_ms);
+
+        let minutes = elapsed_ms as f32 / 60_000.0;
+        if score >= self.config.focus_threshold {
+            self.heat += self.config.rise_per_minute * score.clamp(0.0, 1.0) * minutes;
+        } else {
+            self.heat -= self.config.decay_per_minute * minutes;
+        }
+        self.heat = self.heat.clamp(0.0, 1.0);
+        self.heat
+    }
+
+    /// 当前热度
+    pub fn heat(&self) -> f32 {
+        self.heat
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vision::face::estimate_landmark_quality;
 
     fn make_focused_face() -> FaceDetection {
         FaceDetection {
@@ -211,6 +741,7 @@ mod tests {
                 (0.20, 0.40), // 右耳
                 (0.80, 0.40), // 左耳
             ],
+            landmark_quality: [1.0; 6],
         }
     }
 
@@ -226,6 +757,7 @@ mod tests {
                 (0.05, 0.30), // 右耳
                 (0.40, 0.25), // 左耳
             ],
+            landmark_quality: [1.0; 6],
         }
     }
 
@@ -250,6 +782,134 @@ mod tests {
         assert!(score < 0.8, "Expected lower focus score, got {}", score);
     }
 
+    #[test]
+    fn test_focus_profiles_distinguishable() {
+        // 轻微转头的人脸
+        let detection = FaceDetection {
+            confidence: 0.9,
+            bbox: (0.3, 0.2, 0.7, 0.8),
+            landmarks: [
+                (0.52, 0.35), // 右眼
+                (0.72, 0.35), // 左眼
+                (0.55, 0.50), // 鼻子
+                (0.55, 0.65), // 嘴巴
+                (0.30, 0.40), // 右耳
+                (0.75, 0.40), // 左耳
+            ],
+            landmark_quality: [1.0; 6],
+        };
+
+        let score = |profile: FocusProfile| {
+            let preset = profile.preset().unwrap();
+            FocusCalculator::new(preset.calculator).calculate(Some(&detection)).0
+        };
+        let strict = score(FocusProfile::Strict);
+        let normal = score(FocusProfile::Normal);
+        let relaxed = score(FocusProfile::Relaxed);
+
+        assert!(strict + 0.01 < normal, "strict {} normal {}", strict, normal);
+        assert!(normal + 0.01 < relaxed, "normal {} relaxed {}", normal, relaxed);
+        assert!(FocusProfile::Custom.preset().is_none());
+    }
+
+    #[test]
+    fn test_min_present_score_floor() {
+        // 大幅转头、低头并歪头，离屏幕也很远，但人脸置信度仍高于阈值
+        let detection = FaceDetection {
+            confidence: 0.55,
+            bbox: (0.40, 0.40, 0.42, 0.42),
+            landmarks: [
+                (0.90, 0.10), // 右眼
+                (0.95, 0.20), // 左眼
+                (0.92, 0.90), // 鼻子
+                (0.92, 0.95), // 嘴巴
+                (0.85, 0.15), // 右耳
+                (0.99, 0.15), // 左耳
+            ],
+            landmark_quality: [1.0; 6],
+        };
+
+        let unfloored = FocusCalculator::new(FocusCalculatorConfig {
+            min_present_score: 0.0,
+            ..Default::default()
+        })
+        .calculate(Some(&detection));
+        let floored = FocusCalculator::with_defaults().calculate(Some(&detection));
+
+        assert!(unfloored.0 < 0.2, "expected a low raw score, got {}", unfloored.0);
+        assert!(floored.1);
+        assert!(floored.0 >= FocusCalculatorConfig::default().min_present_score);
+        assert!(floored.0 >= unfloored.0);
+
+        // 没有人脸时不受下限影响
+        assert_eq!(FocusCalculator::with_defaults().calculate(None).0, 0.0);
+    }
+
+    #[test]
+    fn test_low_quality_landmarks_down_weighted() {
+        let calculator = FocusCalculator::with_defaults();
+        let good = make_focused_face();
+
+        // 耳朵关键点退化（贴在画面角落、质量为 0）：偏航角只依赖双眼，不受影响
+        let mut raw_landmarks = good.landmarks;
+        raw_landmarks[4] = (-0.2, -0.1);
+        raw_landmarks[5] = (-0.3, -0.1);
+        let mut degenerate_ears = good.clone();
+        degenerate_ears.landmarks[4] = (0.0, 0.0);
+        degenerate_ears.landmarks[5] = (0.0, 0.0);
+        degenerate_ears.landmark_quality =
+            estimate_landmark_quality(good.confidence, good.bbox, &raw_landmarks);
+        assert!(degenerate_ears.landmark_quality[..4].iter().all(|&q| q > 0.9));
+        assert_eq!(degenerate_ears.landmark_quality[4], 0.0);
+        assert_eq!(degenerate_ears.landmark_quality[5], 0.0);
+        assert_eq!(degenerate_ears.estimate_yaw(), good.estimate_yaw());
+        assert_eq!(
+            calculator.calculate(Some(&degenerate_ears)),
+            calculator.calculate(Some(&good))
+        );
+
+        // 眼睛关键点不可靠时，明显转头的偏航角不再把分数拉低
+        let mut turned = good.clone();
+        turned.landmarks[0] = (0.60, 0.35);
+        turned.landmarks[1] = (0.74, 0.35);
+        let trusted = calculator.calculate(Some(&turned)).0;
+        turned.landmark_quality[0] = 0.1;
+        let distrusted = calculator.calculate(Some(&turned)).0;
+        assert!(distrusted > trusted, "trusted {} distrusted {}", trusted, distrusted);
+        assert!(distrusted <= 1.0);
+    }
+
+    #[test]
+    fn test_secondary_screen_yaw_zone() {
+        // 向右转头约 27 度看副屏：开启副屏区间后与正对主屏得分相同
+        let mut detection = make_focused_face();
+        detection.landmarks[0].0 += 0.3;
+        detection.landmarks[1].0 += 0.3;
+        let yaw = detection.estimate_yaw();
+        assert!((yaw - 27.0).abs() < 0.1, "yaw {}", yaw);
+
+        let single = FocusCalculator::with_defaults().calculate(Some(&detection)).0;
+        let zone = YawZone::calibrate(&[25.0, 28.0, 27.0, 26.0, 29.0], 10.0).unwrap();
+        let dual = FocusCalculator::new(FocusCalculatorConfig {
+            secondary_screen: Some(zone),
+            ..Default::default()
+        });
+        let (score, _) = dual.calculate(Some(&detection));
+        let facing = FocusCalculator::with_defaults().calculate(Some(&make_focused_face())).0;
+        assert!((score - facing).abs() < 1e-5, "facing {} secondary {}", facing, score);
+        assert!(score > single + 0.1, "single {} dual {}", single, score);
+
+        // 转向另一侧不在区间内，照常扣分
+        let mut other_side = make_focused_face();
+        other_side.landmarks[0].0 -= 0.3;
+        other_side.landmarks[1].0 -= 0.3;
+        assert!(dual.calculate(Some(&other_side)).0 < score);
+
+        // 校准：采样不足或看向正前方时拒绝
+        assert!(YawZone::calibrate(&[25.0, 26.0], 10.0).is_none());
+        assert!(YawZone::calibrate(&[2.0, -1.0, 0.0, 3.0, 1.0], 10.0).is_none());
+    }
+
     #[test]
     fn test_focus_calculation_no_face() {
         let calculator = FocusCalculator::with_defaults();
@@ -265,6 +925,7 @@ mod tests {
             confidence: 0.3, // 低于阈值
             bbox: (0.25, 0.15, 0.75, 0.85),
             landmarks: [(0.5, 0.5); 6],
+            landmark_quality: [1.0; 6],
         };
 
         let (score, detected) = calculator.calculate(Some(&detection));
@@ -281,4 +942,225 @@ mod tests {
         assert!((state.face_confidence - 0.95).abs() < 0.01);
         assert!((state.focus_score - 0.85).abs() < 0.01);
     }
+
+    #[test]
+    fn test_rolling_focus_window() {
+        let mut rolling = RollingFocus::new(30_000);
+
+        assert!((rolling.push(0, 1.0) - 1.0).abs() < 1e-6);
+        assert!((rolling.push(10_000, 0.5) - 0.75).abs() < 1e-6);
+        assert!((rolling.push(20_000, 0.0) - 0.5).abs() < 1e-6);
+
+        // 30 秒时第一个采样过期：(0.5 + 0.0 + 0.4) / 3
+        assert!((rolling.push(30_000, 0.4) - 0.3).abs() < 1e-6);
+
+        // 55 秒时只剩 30 秒和 55 秒的采样：(0.4 + 0.8) / 2
+        assert!((rolling.push(55_000, 0.8) - 0.6).abs() < 1e-6);
+
+        // 长时间没有采样后窗口清空
+        rolling.expire(200_000);
+        assert_eq!(rolling.average(), 0.0);
+    }
+
+    #[test]
+    fn test_focus_heat_rises_and_cools() {
+        let mut heat = FocusHeat::new(FocusHeatConfig::default());
+        let mut ts = 0;
+        let mut previous = heat.update(ts, 0.9);
+        assert_eq!(previous, 0.0);
+
+        // 持续专注 3 分钟：热度单调升高
+        for _ in 0..180 {
+            ts += 1_000;
+            let current = heat.update(ts, 0.9);
+            assert!(current > previous, "{} should exceed {}", current, previous);
+            previous = current;
+        }
+        let peak = previous;
+        assert!((peak - 0.27).abs() < 1e-3, "peak {}", peak);
+
+        // 分心 30 秒：热度冷却，但不会立即归零
+        for _ in 0..30 {
+            ts += 1_000;
+            let current = heat.update(ts, 0.2);
+            assert!(current < previous);
+            previous = current;
+        }
+        assert!(previous > 0.0 && previous < peak);
+
+        // 长时间没有采样只计入有限时间
+        let after_gap = heat.update(ts + 3_600_000, 0.9);
+        assert!(after_gap - previous < 0.01);
+    }
+
+    #[test]
+    fn test_focus_state_v1_migration() {
+        let v1 = r#"{
+            "face_present": true,
+            "face_confidence": 0.9,
+            "focus_score": 0.8,
+            "yaw": 1.0,
+            "pitch": -2.0,
+            "roll": 0.5,
+            "timestamp_ms": 1234
+        }"#;
+
+        let state = FocusState::from_versioned_json(v1).unwrap();
+        assert_eq!(state.schema_version, FOCUS_STATE_SCHEMA_VERSION);
+        assert!(state.face_present);
+        assert_eq!(state.focus_score, 0.8);
+        assert_eq!(state.timestamp_ms, 1234);
+        assert!(!state.motion_wake);
+        assert_eq!(state.rolling_focus_30s, 0.0);
+        assert_eq!(state.heat, 0.0);
+
+        let future = format!(r#"{{ "schema_version": {} }}"#, FOCUS_STATE_SCHEMA_VERSION + 1);
+        assert!(FocusState::from_versioned_json(&future).is_err());
+    }
+
+    #[test]
+    fn test_focus_state_v3_migration() {
+        let v3 = r#"{
+            "schema_version": 3,
+            "face_present": true,
+            "face_confidence": 0.9,
+            "focus_score": 0.8,
+            "yaw": 1.0,
+            "pitch": -2.0,
+            "roll": 0.5,
+            "timestamp_ms": 5678,
+            "motion_wake": false,
+            "rolling_focus_30s": 0.7,
+            "heat": 0.4
+        }"#;
+
+        let state = FocusState::from_versioned_json(v3).unwrap();
+        assert_eq!(state.schema_version, FOCUS_STATE_SCHEMA_VERSION);
+        assert_eq!(state.heat, 0.4);
+        assert_eq!(state.detected_at_ms, 0);
+        assert_eq!(state.detected_at(), 5678);
+        assert!(!state.is_reused());
+        assert_eq!(state.face_size, 0.0);
+        assert_eq!(state.passivity, 0.0);
+    }
+
+    #[test]
+    fn test_focus_state_round_trip() {
+        let mut state = FocusState::from_detection(Some(&make_focused_face()), 0.7);
+        state.motion_wake = true;
+        state.rolling_focus_30s = 0.65;
+
+        let json = serde_json::to_string(&state).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], FOCUS_STATE_SCHEMA_VERSION);
+
+        assert_eq!(FocusState::from_versioned_json(&json).unwrap(), state);
+        assert_eq!(serde_json::from_str::<FocusState>(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn test_roll_dead_zone() {
+        // 默认不开启死区，保持线性扣分
+        let linear = FocusCalculator::with_defaults();
+        assert_eq!(linear.roll_score(0.0), 1.0);
+        assert!((linear.roll_score(5.0) - 0.75).abs() < 1e-6);
+        assert_eq!(linear.roll_score(25.0), 0.0);
+
+        let calculator = FocusCalculator::new(FocusCalculatorConfig {
+            roll_dead_zone: 8.0,
+            ..Default::default()
+        });
+
+        // 轻微歪头不扣分
+        assert_eq!(calculator.roll_score(0.0), 1.0);
+        assert_eq!(calculator.roll_score(5.0), 1.0);
+        assert_eq!(calculator.roll_score(-8.0), 1.0);
+
+        // 超出死区后平缓下降，比线性扣分宽容；大幅歪头明显扣分
+        let moderate = calculator.roll_score(12.0);
+        assert!(moderate < 1.0 && moderate > linear.roll_score(12.0), "{}", moderate);
+        assert!(calculator.roll_score(18.0) < 0.5);
+        assert_eq!(calculator.roll_score(25.0), 0.0);
+    }
+
+    #[test]
+    fn test_presence_hysteresis() {
+        let face = |confidence| FaceDetection {
+            confidence,
+            ..make_focused_face()
+        };
+        // 默认不启用滞后，检测器阈值保持为最低人脸置信度
+        let mut calculator = FocusCalculator::with_defaults();
+        assert_eq!(calculator.detection_confidence_threshold(), 0.5);
+        assert!(calculator.update(Some(&face(0.55))).1);
+        assert!(!calculator.update(Some(&face(0.47))).1);
+
+        let mut calculator = FocusCalculator::new(FocusCalculatorConfig {
+            presence_exit_confidence: 0.4,
+            ..Default::default()
+        });
+
+        // 置信度在 0.5 上下抖动：不带滞后时反复切换，带滞后时保持在场
+        let jitter = [0.55, 0.47, 0.53, 0.46, 0.52, 0.48, 0.51];
+        let toggles = jitter
+            .windows(2)
+            .filter(|pair| calculator.calculate(Some(&face(pair[0]))).1 != calculator.calculate(Some(&face(pair[1]))).1)
+            .count();
+        assert_eq!(toggles, 6);
+        for &confidence in &jitter {
+            let (score, present) = calculator.update(Some(&face(confidence)));
+            assert!(present && score > 0.0, "{}", confidence);
+        }
+
+        // 明显降低才离开，离开后需要重新达到进入阈值
+        assert!(!calculator.update(Some(&face(0.3))).1);
+        assert!(!calculator.update(Some(&face(0.45))).1);
+        assert!(calculator.update(Some(&face(0.5))).1);
+        assert!(!calculator.update(None).1);
+
+        // 退出阈值不高于进入阈值，检测器按较低的阈值保留人脸
+        assert_eq!(calculator.detection_confidence_threshold(), 0.4);
+    }
+
+    #[test]
+    fn test_calculator_benchmark() {
+        let calculator = FocusCalculator::with_defaults();
+        assert!(calculator.calculate(Some(&FaceDetection::frontal())).1);
+
+        let started = std::time::Instant::now();
+        let result = calculator.benchmark(1_000);
+        assert_eq!(result.iterations, 1_000);
+        assert!(result.ns_per_call > 0.0);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert_eq!(calculator.benchmark(0).iterations, 1);
+    }
+
+    #[test]
+    fn test_focus_state_staleness() {
+        let state = FocusState {
+            timestamp_ms: 10_000,
+            detected_at_ms: 10_000,
+            ..Default::default()
+        };
+        assert!(!state.is_reused());
+        assert!(!state.is_stale_at(11_000, 2_000));
+        assert!(state.is_stale_at(12_001, 2_000));
+
+        // 复用的状态即使时间戳刚刷新，也按原检测时间判断
+        let reused = FocusState {
+            timestamp_ms: 14_000,
+            ..state.clone()
+        };
+        assert!(reused.is_reused());
+        assert!(reused.is_stale_at(14_000, 2_000));
+
+        // 旧版数据没有检测时间，按时间戳判断
+        let legacy = FocusState {
+            timestamp_ms: 10_000,
+            ..Default::default()
+        };
+        assert!(!legacy.is_reused());
+        assert!(!legacy.is_stale_at(11_000, 2_000));
+        assert!(legacy.is_stale_at(13_000, 2_000));
+    }
 }
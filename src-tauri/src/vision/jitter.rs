@@ -0,0 +1,152 @@
+//! 检测稳定性测量
+//!
+//! 用户保持不动时连续检测一小段时间，统计人脸框中心和置信度的波动以及漏检比例。
+//! 波动大通常说明光线不足或摄像头画质差，界面据此提示用户改善拍摄条件。
+//! 模拟模式下不打开摄像头，直接返回低波动的结果。
+
+use super::FaceDetection;
+use serde::{Deserialize, Serialize};
+
+/// 测量时长的上限（秒）
+pub const MAX_JITTER_SECS: u32 = 10;
+
+/// 检测稳定性指标
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JitterMetrics {
+    /// 人脸框中心位置的标准差（归一化坐标，x、y 方向合并）
+    pub bbox_center_std: f32,
+    /// 置信度的标准差
+    pub confidence_std: f32,
+    /// 未检测到人脸的帧比例 (0.0 - 1.0)
+    pub drop_rate: f32,
+    /// 参与测量的帧数
+    pub frames: u32,
+    /// 检测到人脸的帧数
+    pub detected_frames: u32,
+}
+
+impl JitterMetrics {
+    /// 根据逐帧的检测结果（每帧置信度最高的人脸）计算稳定性指标
+    ///
+    /// 没有检测到人脸的帧只计入漏检比例
+    pub fn compute(detections: &[Option<FaceDetection>]) -> Self {
+        let faces: Vec<&FaceDetection> = detections.iter().flatten().collect();
+        let centers: Vec<(f32, f32)> = faces
+            .iter()
+            .map(|face| ((face.bbox.0 + face.bbox.2) / 2.0, (face.bbox.1 + face.bbox.3) / 2.0))
+            .collect();
+        let xs: Vec<f32> = centers.iter().map(|c| c.0).collect();
+        let ys: Vec<f32> = centers.iter().map(|c| c.1).collect();
+        let confidences: Vec<f32> = faces.iter().map(|face| face.confidence).collect();
+
+        let frames = detections.len() as u32;
+        let detected_frames = faces.len() as u32;
+        Self {
+            bbox_center_std: (variance(&xs) + variance(&ys)).sqrt(),
+            confidence_std: variance(&confidences).sqrt(),
+            drop_rate: if frames == 0 {
+                0.0
+            } else {
+                (frames - detected_frames) as f32 / frames as f32
+            },
+            frames,
+            detected_frames,
+        }
+    }
+}
+
+/// 总体方差，少于两个值时为 0
+fn variance(values: &[f32]) -> f32 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n
+}
+
+/// 以默认摄像头设置连续采集 `duration_secs` 秒并检测，结束后摄像头已释放
+#[cfg(feature = "vision")]
+pub fn measure_jitter(
+    device_index: u32,
+    duration_secs: u32,
+    detector: &mut super::BlazeFaceDetector,
+) -> Result<JitterMetrics, String> {
+    use super::{CameraCapture, CameraConfig};
+
+    let config = CameraConfig {
+        device_index,
+        ..Default::default()
+    };
+    let count = (duration_secs.min(MAX_JITTER_SECS) * config.target_fps).max(1) as usize;
+    let frames = CameraCapture::grab_frames(&config, count)?;
+
+    let mut detections = Vec::with_capacity(frames.len());
+    for frame in &frames {
+        let faces = detector
+            .detect(&frame.data, frame.width, frame.height)
+            .map_err(|e| e.to_string())?;
+        detections.push(faces.into_iter().next());
+    }
+    Ok(JitterMetrics::compute(&detections))
+}
+
+/// 模拟模式：返回低波动的测量结果
+#[cfg(not(feature = "vision"))]
+pub fn measure_jitter(
+    _device_index: u32,
+    duration_secs: u32,
+    _detector: &mut super::BlazeFaceDetector,
+) -> Result<JitterMetrics, String> {
+    let frames = (duration_secs.min(MAX_JITTER_SECS) * super::CameraConfig::default().target_fps).max(1);
+    Ok(JitterMetrics {
+        bbox_center_std: 0.002,
+        confidence_std: 0.01,
+        drop_rate: 0.0,
+        frames,
+        detected_frames: frames,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 中心在 (cx, cy)、置信度为 confidence 的人脸
+    fn face(cx: f32, cy: f32, confidence: f32) -> Option<FaceDetection> {
+        Some(FaceDetection {
+            confidence,
+            bbox: (cx - 0.2, cy - 0.3, cx + 0.2, cy + 0.3),
+            landmarks: [(cx, cy); 6],
+            landmark_quality: [1.0; 6],
+        })
+    }
+
+    #[test]
+    fn test_jitter_metrics() {
+        // 中心在 x 方向 ±0.03、y 方向 ±0.04 交替，置信度 0.8 / 0.9 交替，每 4 帧漏检 1 帧
+        let detections: Vec<Option<FaceDetection>> = (0..40)
+            .map(|i| match (i % 4, i % 2) {
+                (3, _) => None,
+                (_, 0) => face(0.53, 0.54, 0.9),
+                _ => face(0.47, 0.46, 0.8),
+            })
+            .collect();
+        let metrics = JitterMetrics::compute(&detections);
+        assert_eq!(metrics.frames, 40);
+        assert_eq!(metrics.detected_frames, 30);
+        assert!((metrics.drop_rate - 0.25).abs() < 1e-6);
+
+        // 检测到的 30 帧中两种位置各占 20 / 10 帧
+        let (p, q) = (2.0 / 3.0, 1.0 / 3.0);
+        let expected_center = ((0.06f32 * 0.06 + 0.08 * 0.08) * p * q).sqrt();
+        assert!((metrics.bbox_center_std - expected_center).abs() < 1e-4, "{}", metrics.bbox_center_std);
+        let expected_confidence = (0.1f32 * 0.1 * p * q).sqrt();
+        assert!((metrics.confidence_std - expected_confidence).abs() < 1e-4);
+
+        // 完全稳定、没有帧
+        let still = JitterMetrics::compute(&vec![face(0.5, 0.5, 0.9); 10]);
+        assert!(still.bbox_center_std < 1e-6 && still.confidence_std < 1e-6 && still.drop_rate == 0.0);
+        assert_eq!(JitterMetrics::compute(&[]).drop_rate, 0.0);
+    }
+}
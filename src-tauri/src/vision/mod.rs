@@ -4,9 +4,18 @@
 //!
 //! ## 模块结构
 //!
+//! - `anonymize`: 预览画面匿名化（模糊 / 马赛克），不影响检测
+//! - `autotune`: 摄像头帧率 / 分辨率自动调优
+//! - `cadence`: 自适应检测频率，分数接近阈值时检测更频繁
 //! - `capture`: 摄像头采集，支持真实摄像头和模拟模式
+//! - `eval`: 基于标注图片的检测精度评估
 //! - `face`: BlazeFace 人脸检测，使用 ONNX Runtime
 //! - `focus`: 专注度计算，基于人脸姿态估计
+//! - `motion`: 帧差运动检测，用于在人脸锁定前提前唤醒宠物
+//! - `overlay`: 预览叠加层，把检测结果与预览帧对应
+//! - `passivity`: 被动观看估计，头部过于静止时轻微下调专注分数
+//! - `source`: 专注状态来源（摄像头 / 外部推送）
+//! - `tracker`: 主人脸跟踪，避免在多张人脸之间来回切换
 //!
 //! ## 使用方式
 //!
@@ -32,13 +41,50 @@
 //! }
 //! ```
 
+pub mod anonymize;
+pub mod autotune;
+pub mod cadence;
 pub mod capture;
+pub mod dump;
+pub mod eval;
 pub mod face;
 pub mod focus;
+pub mod jitter;
+pub mod motion;
+pub mod overlay;
+pub mod passivity;
 pub mod processor;
+pub mod replay;
+pub mod resources;
+pub mod source;
+pub mod tracker;
+pub mod turn_grace;
 
 // 重新导出主要类型
-pub use capture::{CameraCapture, CameraConfig, CapturedFrame};
-pub use face::{BlazeFaceDetector, FaceDetection, FaceDetectorError, BLAZEFACE_INPUT_SIZE};
-pub use focus::{FocusCalculator, FocusCalculatorConfig, FocusState};
-pub use processor::{VisionProcessor, VisionProcessorConfig, create_default_processor};
+pub use anonymize::PreviewBlur;
+pub use cadence::DetectionCadenceConfig;
+pub use capture::{
+    clamp_fps, is_camera_busy_error, frame_interval_for_fps, yuyv_to_rgb, CameraCapture, CameraConfig, CapturedFrame, PixelFormatPreference,
+    MAX_FPS, MIN_FPS,
+};
+pub use dump::{dump_path, DetectionDump, DumpRecord, DumpSummary, MAX_DUMP_DURATION_MS};
+pub use eval::{evaluate_dir, EvalError, EvalResult};
+pub use face::{
+    AnchorScaleConfig, BlazeFaceDetector, FaceDetection, FaceDetectorError, MockDetectionConfig, ModelInfo,
+    ModelIo, PixelFace, RawModelOutput, TensorSignature, BLAZEFACE_INPUT_SIZE, BLAZEFACE_NUM_ANCHORS, DEFAULT_MOCK_SEED, DEFAULT_NMS_THRESHOLD,
+    RAW_OUTPUT_TOP_SCORES,
+};
+pub use focus::{
+    FOCUS_STATE_SCHEMA_VERSION, CalculatorBenchmark, FocusCalculator, FocusCalculatorConfig, FocusHeat, FocusHeatConfig, FocusPreset, FocusProfile, FocusState,
+    RollingFocus, YawZone, calibrated_face_size,
+};
+pub use jitter::JitterMetrics;
+pub use motion::{MotionDetector, MotionDetectorConfig};
+pub use overlay::{FrameOverlay, OverlayData};
+pub use passivity::{PassivityConfig, PassivityEstimator};
+pub use processor::{VisionProcessor, VisionProcessorConfig, create_default_processor, DEFAULT_PREVIEW_FPS};
+pub use replay::{compare_replay, replay, ReplayFrame, ReplayStep};
+pub use resources::{verify_resources, ResourceEntry, ResourceReport, ResourceStatus};
+pub use source::FocusSource;
+pub use tracker::{FaceTieBreak, FaceTracker, FaceTrackerConfig};
+pub use turn_grace::{TurnGrace, TurnGraceConfig};
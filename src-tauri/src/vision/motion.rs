@@ -0,0 +1,146 @@
+//! 运动检测模块
+//!
+//! 基于相邻帧差分的轻量运动检测。
+//! 用户坐下时人脸检测需要一段时间才能稳定锁定，
+//! 在一段静止期之后出现明显运动，即可提前唤醒宠物。
+
+use super::capture::CapturedFrame;
+
+/// 运动检测器配置
+#[derive(Debug, Clone)]
+pub struct MotionDetectorConfig {
+    /// 判定为明显运动的平均帧差阈值（0-255 灰度）
+    pub motion_threshold: f32,
+    /// 判定为静止的平均帧差阈值（0-255 灰度）
+    pub quiet_threshold: f32,
+    /// 触发唤醒前需要连续静止的帧数
+    pub quiet_frames: u32,
+    /// 采样步长（每隔多少个像素采样一次，降低计算量）
+    pub sample_step: usize,
+}
+
+impl Default for MotionDetectorConfig {
+    fn default() -> Self {
+        Self {
+            motion_threshold: 12.0,
+            quiet_threshold: 3.0,
+            quiet_frames: 20, // 约 2 秒（10fps）
+            sample_step: 4,
+        }
+    }
+}
+
+/// 运动检测器
+///
+/// 只保存上一帧的降采样灰度值，每帧计算一次平均绝对差
+pub struct MotionDetector {
+    config: MotionDetectorConfig,
+    /// 上一帧的降采样灰度值
+    prev_samples: Option<Vec<u8>>,
+    /// 连续静止的帧数
+    quiet_count: u32,
+}
+
+impl MotionDetector {
+    /// 创建运动检测器
+    pub fn new(config: MotionDetectorConfig) -> Self {
+        Self {
+            config,
+            prev_samples: None,
+            quiet_count: 0,
+        }
+    }
+
+    /// 输入新帧
+    ///
+    /// # Returns
+    /// 如果在足够长的静止期之后检测到明显运动，返回 true
+    pub fn update(&mut self, frame: &CapturedFrame) -> bool {
+        if frame.is_empty() {
+            return false;
+        }
+
+        let samples = Self::luma_samples(frame, self.config.sample_step.max(1));
+
+        let delta = match self.prev_samples.as_ref() {
+            Some(prev) if prev.len() == samples.len() && !samples.is_empty() => {
+                let sum: u64 = prev
+                    .iter()
+                    .zip(samples.iter())
+                    .map(|(a, b)| a.abs_diff(*b) as u64)
+                    .sum();
+                Some(sum as f32 / samples.len() as f32)
+            }
+            _ => None,
+        };
+
+        self.prev_samples = Some(samples);
+
+        let Some(delta) = delta else {
+            return false;
+        };
+
+        if delta < self.config.quiet_threshold {
+            self.quiet_count = self.quiet_count.saturating_add(1);
+            return false;
+        }
+
+        let woke = delta >= self.config.motion_threshold && self.quiet_count >= self.config.quiet_frames;
+        self.quiet_count = 0;
+
+        if woke {
+            tracing::debug!("Motion wake: frame delta {:.1} after stillness", delta);
+        }
+
+        woke
+    }
+
+    /// 降采样并转换为灰度
+    fn luma_samples(frame: &CapturedFrame, step: usize) -> Vec<u8> {
+        frame
+            .data
+            .chunks_exact(3)
+            .step_by(step)
+            .map(|px| ((px[0] as u32 * 299 + px[1] as u32 * 587 + px[2] as u32 * 114) / 1000) as u8)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_frame(brightness: u8) -> CapturedFrame {
+        CapturedFrame {
+            width: 16,
+            height: 16,
+            data: vec![brightness; 16 * 16 * 3],
+            timestamp_ms: 0,
+            frame_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_motion_after_stillness() {
+        let mut detector = MotionDetector::new(MotionDetectorConfig::default());
+
+        // 静止画面
+        for _ in 0..30 {
+            assert!(!detector.update(&make_frame(100)));
+        }
+
+        // 明显运动
+        assert!(detector.update(&make_frame(180)));
+    }
+
+    #[test]
+    fn test_motion_without_stillness() {
+        let mut detector = MotionDetector::new(MotionDetectorConfig::default());
+
+        // 持续运动不会触发唤醒（没有静止期）
+        for i in 0..30 {
+            let brightness = if i % 2 == 0 { 50 } else { 200 };
+            assert!(!detector.update(&make_frame(brightness)));
+        }
+    }
+}
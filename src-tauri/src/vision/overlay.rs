@@ -0,0 +1,76 @@
+//! 预览叠加层模块
+//!
+//! 预览帧和检测结果通过不同的通道发布，用帧编号把两者对应起来，
+//! 前端据此在预览图上绘制人脸框和关键点。
+
+use super::{FaceDetection, PixelFace};
+use serde::{Deserialize, Serialize};
+
+/// 预览帧对应的检测结果（归一化坐标）
+#[derive(Debug, Clone, Default)]
+pub struct FrameOverlay {
+    /// 对应的帧编号
+    pub frame_id: u64,
+    /// 主人脸
+    pub face: Option<FaceDetection>,
+    /// 专注分数
+    pub focus_score: f32,
+}
+
+impl FrameOverlay {
+    /// 按预览图尺寸转换为像素坐标
+    pub fn to_pixels(&self, width: u32, height: u32) -> OverlayData {
+        OverlayData {
+            frame_id: self.frame_id,
+            width,
+            height,
+            face: self.face.as_ref().map(|face| face.to_pixels(width, height)),
+            focus_score: self.focus_score,
+        }
+    }
+}
+
+/// 叠加层数据（发送到前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayData {
+    /// 对应的预览帧编号
+    pub frame_id: u64,
+    /// 预览图宽度
+    pub width: u32,
+    /// 预览图高度
+    pub height: u32,
+    /// 主人脸（像素坐标）
+    pub face: Option<PixelFace>,
+    /// 专注分数
+    pub focus_score: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_matches_frame_size() {
+        let overlay = FrameOverlay {
+            frame_id: 7,
+            face: Some(FaceDetection {
+                confidence: 0.9,
+                bbox: (0.25, 0.25, 0.75, 1.2),
+                landmarks: [(0.5, 0.5); 6],
+                landmark_quality: [1.0; 6],
+            }),
+            focus_score: 0.8,
+        };
+
+        let data = overlay.to_pixels(160, 120);
+        assert_eq!(data.frame_id, 7);
+        assert_eq!((data.width, data.height), (160, 120));
+
+        let face = data.face.unwrap();
+        assert_eq!(face.bbox, (40.0, 30.0, 120.0, 120.0));
+        assert_eq!(face.landmarks[0], (80.0, 60.0));
+        for (x, y) in face.landmarks {
+            assert!((0.0..=160.0).contains(&x) && (0.0..=120.0).contains(&y));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! 被动观看估计
+//!
+//! 看视频课程时人脸一直居中、几乎不动，专注分数会一直很高，但这不一定是主动的工作。
+//! 这里根据最近一段时间头部的微小移动估计“被动程度”：几乎完全静止时接近 1，
+//! 正常工作时的自然晃动接近 0。BlazeFace 的 6 个关键点不包含眼睑信息，无法检测眨眼，
+//! 所以只使用微动信号。默认关闭；开启后按被动程度轻微下调专注分数。
+
+use super::FaceDetection;
+use std::collections::VecDeque;
+
+/// 参与估计的时间窗口（毫秒）
+const PASSIVITY_WINDOW_MS: u64 = 20_000;
+
+/// 估计所需的最少采样数
+const MIN_PASSIVITY_SAMPLES: usize = 20;
+
+/// 头部位置标准差（以人脸宽度为单位）低于该值视为完全静止
+const STILL_MOTION: f32 = 0.004;
+
+/// 头部位置标准差达到该值视为正常的自然晃动
+const NATURAL_MOTION: f32 = 0.02;
+
+/// 被动观看估计配置
+#[derive(Debug, Clone)]
+pub struct PassivityConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 完全被动时专注分数下调的比例 (0.0 - 1.0)
+    pub discount: f32,
+}
+
+impl Default for PassivityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            discount: 0.15,
+        }
+    }
+}
+
+impl PassivityConfig {
+    /// 按被动程度下调专注分数
+    pub fn apply(&self, focus_score: f32, passivity: f32) -> f32 {
+        focus_score * (1.0 - self.discount.clamp(0.0, 1.0) * passivity)
+    }
+}
+
+/// 被动观看估计器
+#[derive(Debug, Clone)]
+pub struct PassivityEstimator {
+    config: PassivityConfig,
+    /// 窗口内的 (时间戳, 鼻尖位置 / 人脸宽度)
+    samples: VecDeque<(u64, (f32, f32))>,
+}
+
+impl PassivityEstimator {
+    /// 创建估计器
+    pub fn new(config: PassivityConfig) -> Self {
+        Self {
+            config,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// 加入一次检测结果，返回当前的被动程度 (0.0 - 1.0)
+    ///
+    /// 未启用、没有人脸或采样不足时返回 0；人脸离开时清空窗口
+    pub fn update(&mut self, timestamp_ms: u64, face: Option<&FaceDetection>) -> f32 {
+        if !self.config.enabled {
+            return 0.0;
+        }
+        let Some(face) = face else {
+            self.samples.clear();
+            return 0.0;
+        };
+
+        let width = (face.bbox.2 - face.bbox.0).max(f32::EPSILON);
+        let (nose_x, nose_y) = face.landmarks[2];
+        self.samples.push_back((timestamp_ms, (nose_x / width, nose_y / width)));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(t, _)| timestamp_ms.saturating_sub(*t) > PASSIVITY_WINDOW_MS)
+        {
+            self.samples.pop_front();
+        }
+        if self.samples.len() < MIN_PASSIVITY_SAMPLES {
+            return 0.0;
+        }
+
+        let n = self.samples.len() as f32;
+        let (sum_x, sum_y) = self.samples.iter().fold((0.0, 0.0), |(sx, sy), (_, (x, y))| (sx + x, sy + y));
+        let (mean_x, mean_y) = (sum_x / n, sum_y / n);
+        let variance = self
+            .samples
+            .iter()
+            .map(|(_, (x, y))| (x - mean_x).powi(2) + (y - mean_y).powi(2))
+            .sum::<f32>()
+            / n;
+
+        (1.0 - (variance.sqrt() - STILL_MOTION) / (NATURAL_MOTION - STILL_MOTION)).clamp(0.0, 1.0)
+    }
+
+    /// 按配置下调专注分数
+    pub fn apply(&self, focus_score: f32, passivity: f32) -> f32 {
+        self.config.apply(focus_score, passivity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 鼻尖偏移 (dx, dy) 的人脸
+    fn face(dx: f32, dy: f32) -> FaceDetection {
+        let mut face = FaceDetection::frontal();
+        let (x1, y1, x2, y2) = face.bbox;
+        face.bbox = (x1 + dx, y1 + dy, x2 + dx, y2 + dy);
+        for (x, y) in &mut face.landmarks {
+            *x += dx;
+            *y += dy;
+        }
+        face
+    }
+
+    #[test]
+    fn test_passivity_static_vs_micro_motion() {
+        let enabled = PassivityConfig {
+            enabled: true,
+            ..Default::default()
+        };
+
+        // 完全静止
+        let mut estimator = PassivityEstimator::new(enabled.clone());
+        let still = (0..100).map(|i| estimator.update(i * 100, Some(&face(0.0, 0.0)))).last().unwrap();
+        assert!(still > 0.9, "static passivity {}", still);
+        assert!(estimator.apply(0.9, still) < 0.8);
+
+        // 自然的微小晃动（约 1% 画面宽度）
+        let mut estimator = PassivityEstimator::new(enabled.clone());
+        let moving = (0..100)
+            .map(|i| {
+                let t = i as f32 * 0.7;
+                estimator.update(i * 100, Some(&face(0.012 * t.sin(), 0.008 * (t * 1.3).cos())))
+            })
+            .last()
+            .unwrap();
+        assert!(moving < 0.2, "micro-motion passivity {}", moving);
+
+        // 人脸离开后重新积累；关闭时始终为 0
+        assert_eq!(estimator.update(20_000, None), 0.0);
+        assert_eq!(estimator.update(20_100, Some(&face(0.0, 0.0))), 0.0);
+        let mut disabled = PassivityEstimator::new(PassivityConfig::default());
+        assert!((0..100).all(|i| disabled.update(i * 100, Some(&face(0.0, 0.0))) == 0.0));
+        assert_eq!(disabled.apply(0.9, 0.0), 0.9);
+    }
+}
@@ -4,9 +4,14 @@
 //! 提供统一的视觉处理循环
 
 use super::{
-    BlazeFaceDetector, CameraCapture, CameraConfig, FocusCalculator, FocusState,
+    clamp_fps, AnchorScaleConfig, BlazeFaceDetector, CameraCapture, CameraConfig, DetectionCadenceConfig, DetectionDump, DumpSummary, FaceDetection, FaceDetectorError, FaceTracker, FaceTrackerConfig,
+    FocusCalculator, FocusCalculatorConfig, FocusHeat, FocusHeatConfig, FocusState, RollingFocus, FrameOverlay, MockDetectionConfig, MotionDetector, MotionDetectorConfig, ModelInfo,
+    PassivityConfig, PassivityEstimator, RawModelOutput, TurnGrace, TurnGraceConfig,
+    DEFAULT_NMS_THRESHOLD,
 };
-use std::sync::atomic::{AtomicBool, Ordering};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::watch;
 
@@ -19,8 +24,42 @@ pub struct VisionProcessorConfig {
     pub model_path: String,
     /// 锚框路径（可选）
     pub anchors_path: Option<String>,
-    /// 是否每帧都进行检测（false 则隔帧检测以降低 CPU）
+    /// 是否每帧都进行检测（false 则按专注分数自适应调整检测间隔以降低 CPU）
     pub detect_every_frame: bool,
+    /// 自适应检测频率配置
+    pub cadence: DetectionCadenceConfig,
+    /// 运动检测配置
+    pub motion: MotionDetectorConfig,
+    /// 锚框文件解析失败时是否允许回退到生成的锚框（默认不允许，直接报错）
+    pub allow_anchor_fallback: bool,
+    /// 锚框解码缩放配置
+    pub anchor_scale: AnchorScaleConfig,
+    /// 主人脸跟踪配置
+    pub face_tracker: FaceTrackerConfig,
+    /// 专注度计算器配置
+    pub focus: FocusCalculatorConfig,
+    /// 专注分数滑动平均的窗口长度（毫秒）
+    pub rolling_window_ms: u64,
+    /// 专注热度配置
+    pub heat: FocusHeatConfig,
+    /// 被动观看估计配置
+    pub passivity: PassivityConfig,
+    /// 转头宽限配置
+    pub turn_grace: TurnGraceConfig,
+    /// 人脸检测 NMS IoU 阈值
+    pub nms_threshold: f32,
+    /// 帧的最大允许延迟（毫秒），更旧的帧直接跳过
+    pub max_frame_age_ms: u64,
+    /// 连续收到多少个空帧后视为摄像头卡住，开始发布未检测到人脸的状态
+    pub max_empty_frames: u32,
+    /// 预览帧的目标帧率（按帧时间戳限流，与摄像头帧率无关）
+    pub preview_fps: u32,
+    /// 看门狗超时（毫秒）：超过该时间没有新帧时视为采集任务已失效，重新创建摄像头和检测器（0 表示关闭）
+    pub frame_timeout_ms: u64,
+    /// 模拟检测配置（仅在无 vision feature 时生效）
+    pub mock_detection: MockDetectionConfig,
+    /// 记录每次推理的原始输出摘要（调试模型用，默认关闭）
+    pub debug_raw_output: bool,
 }
 
 impl Default for VisionProcessorConfig {
@@ -29,11 +68,164 @@ impl Default for VisionProcessorConfig {
             camera: CameraConfig::default(),
             model_path: "resources/models/blazeface.onnx".to_string(),
             anchors_path: Some("resources/models/anchors.npy".to_string()),
-            detect_every_frame: false, // 默认隔帧检测
+            detect_every_frame: false, // 默认自适应检测间隔
+            cadence: DetectionCadenceConfig::default(),
+            motion: MotionDetectorConfig::default(),
+            allow_anchor_fallback: false,
+            anchor_scale: AnchorScaleConfig::default(),
+            face_tracker: FaceTrackerConfig::default(),
+            focus: FocusCalculatorConfig::default(),
+            rolling_window_ms: 30_000,
+            heat: FocusHeatConfig::default(),
+            passivity: PassivityConfig::default(),
+            turn_grace: TurnGraceConfig::default(),
+            nms_threshold: DEFAULT_NMS_THRESHOLD,
+            max_frame_age_ms: 500,
+            max_empty_frames: 30, // 约 2 秒（15fps）
+            preview_fps: DEFAULT_PREVIEW_FPS,
+            frame_timeout_ms: 5_000,
+            mock_detection: MockDetectionConfig::default(),
+            debug_raw_output: false,
         }
     }
 }
 
+/// 默认的预览帧率
+pub const DEFAULT_PREVIEW_FPS: u32 = 4;
+
+/// 预览帧限流
+///
+/// 按帧时间戳把转发给预览的帧限制在目标帧率，调整摄像头帧率时预览帧率保持不变
+#[derive(Debug, Clone)]
+struct PreviewThrottle {
+    /// 两个预览帧之间的间隔（毫秒）
+    interval_ms: u64,
+    /// 下一个预览帧的最早时间戳
+    next_due_ms: Option<u64>,
+}
+
+impl PreviewThrottle {
+    fn new(fps: u32) -> Self {
+        Self {
+            interval_ms: 1000 / fps.clamp(1, 1000) as u64,
+            next_due_ms: None,
+        }
+    }
+
+    /// 时间戳为 `timestamp_ms` 的帧是否转发给预览（第一帧总是转发）
+    fn should_forward(&mut self, timestamp_ms: u64) -> bool {
+        match self.next_due_ms {
+            Some(due) if timestamp_ms < due => false,
+            // 按固定节拍推进，落后超过一个间隔（处理暂停等）时从当前帧重新计时
+            Some(due) if timestamp_ms < due + self.interval_ms => {
+                self.next_due_ms = Some(due + self.interval_ms);
+                true
+            }
+            _ => {
+                self.next_due_ms = Some(timestamp_ms + self.interval_ms);
+                true
+            }
+        }
+    }
+}
+
+/// 连续空帧计数
+///
+/// 摄像头卡住时只会收到空帧，如果直接跳过，状态机永远看不到“没有人脸”，宠物会一直停在开心状态。
+/// 连续空帧超过阈值后，每个空帧都发布一次未检测到人脸的状态，让离开判断正常生效
+#[derive(Debug, Clone)]
+struct EmptyFrameRun {
+    /// 阈值（帧）
+    threshold: u32,
+    /// 当前连续空帧数
+    count: u32,
+}
+
+impl EmptyFrameRun {
+    fn new(threshold: u32) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            count: 0,
+        }
+    }
+
+    /// 收到一个空帧，超过阈值后返回未检测到人脸的状态
+    fn on_empty(&mut self) -> Option<FocusState> {
+        self.count = self.count.saturating_add(1);
+        if self.count < self.threshold {
+            return None;
+        }
+        if self.count == self.threshold {
+            tracing::warn!("{} empty frames in a row, treating camera as stalled", self.count);
+        }
+        Some(FocusState::from_detection(None, 0.0))
+    }
+
+    /// 收到正常帧
+    fn reset(&mut self) {
+        if self.count >= self.threshold {
+            tracing::info!("Camera recovered after {} empty frames", self.count);
+        }
+        self.count = 0;
+    }
+}
+
+/// 处理循环使用的人脸检测器
+///
+/// 正常运行时是 [`BlazeFaceDetector`]，测试中可以替换为按脚本返回结果的检测器
+pub trait FrameDetector: Send {
+    /// 检测一帧中的人脸
+    fn detect(&mut self, image_data: &[u8], width: u32, height: u32) -> Result<Vec<FaceDetection>, FaceDetectorError>;
+
+    /// 设置 NMS IoU 阈值（每次检测前调用）
+    fn set_nms_threshold(&mut self, _threshold: f32) {}
+
+    /// 设置人脸置信度阈值（每次检测前调用）
+    fn set_confidence_threshold(&mut self, _threshold: f32) {}
+
+    /// 最近一次推理的原始输出摘要（调试用）
+    fn last_raw_output(&self) -> Option<RawModelOutput> {
+        None
+    }
+}
+
+impl FrameDetector for BlazeFaceDetector {
+    fn detect(&mut self, image_data: &[u8], width: u32, height: u32) -> Result<Vec<FaceDetection>, FaceDetectorError> {
+        BlazeFaceDetector::detect(self, image_data, width, height)
+    }
+
+    fn set_nms_threshold(&mut self, threshold: f32) {
+        BlazeFaceDetector::set_nms_threshold(self, threshold);
+    }
+
+    fn set_confidence_threshold(&mut self, threshold: f32) {
+        BlazeFaceDetector::set_confidence_threshold(self, threshold);
+    }
+
+    fn last_raw_output(&self) -> Option<RawModelOutput> {
+        BlazeFaceDetector::last_raw_output(self).cloned()
+    }
+}
+
+/// 处理器与处理循环之间共享的控制句柄
+#[derive(Clone)]
+struct SharedControls {
+    /// 已加载模型的信息（检测器创建后可用）
+    model_info: Arc<Mutex<Option<ModelInfo>>>,
+    /// 待应用的专注度计算器配置（运行中切换档位时设置，处理循环取走后生效）
+    pending_focus_config: Arc<Mutex<Option<FocusCalculatorConfig>>>,
+    /// 摄像头目标帧率（采集循环每帧读取）
+    camera_fps: Arc<AtomicU32>,
+    /// NMS IoU 阈值（每次检测前应用到检测器）
+    nms_threshold: Arc<Mutex<f32>>,
+    /// 专注进入 / 退出阈值（用于自适应检测间隔）
+    focus_thresholds: Arc<Mutex<(f32, f32)>>,
+    /// 最近一次推理的原始输出摘要（开启 `debug_raw_output` 时每次检测后更新）
+    last_raw_output: Arc<Mutex<Option<RawModelOutput>>>,
+    /// 进行中的检测结果转储（每次检测后写入一行）
+    detection_dump: Arc<Mutex<Option<DetectionDump>>>,
+}
+
 /// 视觉处理器
 ///
 /// 管理完整的视觉处理流程：
@@ -52,6 +244,16 @@ pub struct VisionProcessor {
     frame_tx: watch::Sender<super::CapturedFrame>,
     /// 原始帧接收端（供外部订阅预览）
     frame_rx: watch::Receiver<super::CapturedFrame>,
+    /// 预览帧叠加层发送端（与预览帧同步发送，帧编号一致）
+    overlay_tx: watch::Sender<FrameOverlay>,
+    /// 预览帧叠加层接收端
+    overlay_rx: watch::Receiver<FrameOverlay>,
+    /// 看门狗重启后恢复出帧的累计次数发送端
+    recovered_tx: watch::Sender<u32>,
+    /// 恢复次数接收端（供外部订阅）
+    recovered_rx: watch::Receiver<u32>,
+    /// 共享控制句柄
+    shared: SharedControls,
 }
 
 impl VisionProcessor {
@@ -59,6 +261,17 @@ impl VisionProcessor {
     pub fn new(config: VisionProcessorConfig) -> Self {
         let (state_tx, state_rx) = watch::channel(FocusState::default());
         let (frame_tx, frame_rx) = watch::channel(super::CapturedFrame::empty());
+        let (overlay_tx, overlay_rx) = watch::channel(FrameOverlay::default());
+        let (recovered_tx, recovered_rx) = watch::channel(0);
+        let shared = SharedControls {
+            model_info: Arc::new(Mutex::new(None)),
+            pending_focus_config: Arc::new(Mutex::new(None)),
+            camera_fps: Arc::new(AtomicU32::new(clamp_fps(config.camera.target_fps))),
+            nms_threshold: Arc::new(Mutex::new(config.nms_threshold.clamp(0.0, 1.0))),
+            focus_thresholds: Arc::new(Mutex::new((config.cadence.enter_threshold, config.cadence.exit_threshold))),
+            last_raw_output: Arc::new(Mutex::new(None)),
+            detection_dump: Arc::new(Mutex::new(None)),
+        };
 
         Self {
             config,
@@ -67,7 +280,71 @@ impl VisionProcessor {
             state_rx,
             frame_tx,
             frame_rx,
+            overlay_tx,
+            overlay_rx,
+            recovered_tx,
+            recovered_rx,
+            shared,
+        }
+    }
+
+    /// 获取已加载模型的信息
+    pub fn model_info(&self) -> Option<ModelInfo> {
+        self.shared.model_info.lock().clone()
+    }
+
+    /// 最近一次推理的原始输出摘要（需开启 `debug_raw_output`）
+    pub fn last_raw_output(&self) -> Option<RawModelOutput> {
+        self.shared.last_raw_output.lock().clone()
+    }
+
+    /// 开始检测结果转储，已有进行中的转储时返回错误
+    pub fn start_detection_dump(&self, dump: DetectionDump) -> Result<(), String> {
+        let mut slot = self.shared.detection_dump.lock();
+        if slot.is_some() {
+            return Err("A detection dump is already running".to_string());
+        }
+        *slot = Some(dump);
+        Ok(())
+    }
+
+    /// 结束检测结果转储，没有进行中的转储时返回 None
+    pub fn stop_detection_dump(&self) -> Option<std::io::Result<DumpSummary>> {
+        self.shared.detection_dump.lock().take().map(DetectionDump::finish)
+    }
+
+    /// 在运行中更新专注度计算器配置（下一次检测时生效）
+    pub fn set_focus_config(&self, config: FocusCalculatorConfig) {
+        *self.shared.pending_focus_config.lock() = Some(config);
+    }
+
+    /// 在运行中调整摄像头帧率（1-2 帧内生效），返回限制后的实际帧率
+    pub fn set_camera_fps(&self, fps: u32) -> u32 {
+        let fps = clamp_fps(fps);
+        self.shared.camera_fps.store(fps, Ordering::SeqCst);
+        fps
+    }
+
+    /// 在运行中调整 NMS IoU 阈值（下一次检测时生效），返回限制后的实际阈值
+    ///
+    /// 非有限值（NaN、无穷大）会被忽略并返回 None，保持当前阈值
+    pub fn set_nms_threshold(&self, threshold: f32) -> Option<f32> {
+        if !threshold.is_finite() {
+            return None;
         }
+        let threshold = threshold.clamp(0.0, 1.0);
+        *self.shared.nms_threshold.lock() = threshold;
+        Some(threshold)
+    }
+
+    /// 在运行中更新专注进入 / 退出阈值（用于自适应检测间隔，下一次检测后生效）
+    pub fn set_focus_thresholds(&self, enter: f32, exit: f32) {
+        *self.shared.focus_thresholds.lock() = (enter, exit);
+    }
+
+    /// 当前 NMS IoU 阈值
+    pub fn nms_threshold(&self) -> f32 {
+        *self.shared.nms_threshold.lock()
     }
 
     /// 获取专注状态订阅器
@@ -80,6 +357,16 @@ impl VisionProcessor {
         self.frame_rx.clone()
     }
 
+    /// 获取预览叠加层订阅器
+    pub fn subscribe_overlay(&self) -> watch::Receiver<FrameOverlay> {
+        self.overlay_rx.clone()
+    }
+
+    /// 获取恢复次数订阅器：看门狗重启采集后重新收到帧时加一
+    pub fn subscribe_recoveries(&self) -> watch::Receiver<u32> {
+        self.recovered_rx.clone()
+    }
+
     /// 检查是否正在运行
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::SeqCst)
@@ -91,111 +378,319 @@ impl VisionProcessor {
             return Err("Vision processor is already running".to_string());
         }
 
-        let running = self.running.clone();
-        let config = self.config.clone();
-        let state_tx = self.state_tx.clone();
-        let frame_tx = self.frame_tx.clone();
-
-        running.store(true, Ordering::SeqCst);
+        let context = self.loop_context();
+        context.running.store(true, Ordering::SeqCst);
 
         tokio::spawn(async move {
             tracing::info!("Vision processor starting...");
 
-            if let Err(e) = Self::run_processing_loop(&config, &running, &state_tx, &frame_tx).await {
+            if let Err(e) = context.supervise(|| context.run()).await {
                 tracing::error!("Vision processing error: {}", e);
             }
 
-            running.store(false, Ordering::SeqCst);
+            context.running.store(false, Ordering::SeqCst);
             tracing::info!("Vision processor stopped");
         });
 
         Ok(())
     }
 
+    /// 处理循环使用的句柄
+    fn loop_context(&self) -> LoopContext {
+        LoopContext {
+            config: self.config.clone(),
+            running: self.running.clone(),
+            state_tx: self.state_tx.clone(),
+            frame_tx: self.frame_tx.clone(),
+            overlay_tx: self.overlay_tx.clone(),
+            recovered_tx: self.recovered_tx.clone(),
+            recovering: AtomicBool::new(false),
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// 使用给定的帧来源和检测器在后台运行处理循环（不打开摄像头、不加载模型）
+    ///
+    /// 用于端到端测试：关闭帧来源的发送端后循环结束，任务返回
+    #[cfg(test)]
+    fn spawn_with<D: FrameDetector + 'static>(
+        &self,
+        frame_rx: watch::Receiver<super::CapturedFrame>,
+        mut detector: D,
+    ) -> tokio::task::JoinHandle<Result<(), String>> {
+        let context = self.loop_context();
+        context.running.store(true, Ordering::SeqCst);
+        tokio::spawn(async move {
+            let result = context.process_frames(frame_rx, &mut detector).await;
+            context.running.store(false, Ordering::SeqCst);
+            result.map(|_| ())
+        })
+    }
+
     /// 停止视觉处理
     pub fn stop(&self) {
         tracing::info!("Stopping vision processor...");
         self.running.store(false, Ordering::SeqCst);
     }
+}
+
+/// 处理循环使用的句柄（从处理器克隆后移入后台任务）
+struct LoopContext {
+    config: VisionProcessorConfig,
+    running: Arc<AtomicBool>,
+    state_tx: watch::Sender<FocusState>,
+    frame_tx: watch::Sender<super::CapturedFrame>,
+    overlay_tx: watch::Sender<FrameOverlay>,
+    recovered_tx: watch::Sender<u32>,
+    /// 看门狗已重启采集、等待第一帧
+    recovering: AtomicBool,
+    shared: SharedControls,
+}
+
+/// 处理循环结束的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopExit {
+    /// 处理器停止或帧来源关闭
+    Stopped,
+    /// 超过看门狗超时仍没有新帧
+    Stalled,
+}
+
+impl LoopContext {
+    /// 运行处理循环，帧来源卡住时由看门狗重新运行（重新创建摄像头和检测器），直到停止或出错
+    async fn supervise<F, Fut>(&self, mut run_once: F) -> Result<(), String>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<LoopExit, String>>,
+    {
+        let mut restarts = 0u32;
+        loop {
+            match run_once().await? {
+                LoopExit::Stalled if self.running.load(Ordering::SeqCst) => {
+                    restarts += 1;
+                    self.recovering.store(true, Ordering::SeqCst);
+                    tracing::warn!("Restarting capture and detection (attempt {})", restarts);
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// 运行一次处理循环：打开摄像头、加载检测器，然后处理采集到的帧直到停止或帧来源卡住
+    async fn run(&self) -> Result<LoopExit, String> {
+        let config = &self.config;
+        let shared = &self.shared;
 
-    /// 运行处理循环
-    async fn run_processing_loop(
-        config: &VisionProcessorConfig,
-        running: &Arc<AtomicBool>,
-        state_tx: &watch::Sender<FocusState>,
-        frame_tx: &watch::Sender<super::CapturedFrame>,
-    ) -> Result<(), String> {
         // 1. 创建摄像头采集器
-        let camera = CameraCapture::new(config.camera.clone());
-        let mut frame_rx = camera.subscribe();
+        let camera = CameraCapture::new(config.camera.clone()).with_fps_control(shared.camera_fps.clone());
+        let frame_rx = camera.subscribe();
 
         // 2. 创建人脸检测器
-        let mut detector = BlazeFaceDetector::new(
+        let mut detector = BlazeFaceDetector::with_options(
             &config.model_path,
             config.anchors_path.as_deref(),
+            config.allow_anchor_fallback,
         )
         .map_err(|e| format!("Failed to create face detector: {}", e))?;
+        detector.set_anchor_scale(config.anchor_scale.clone());
+        detector.set_debug_raw_output(config.debug_raw_output);
+        #[cfg(not(feature = "vision"))]
+        detector.set_mock_detection(config.mock_detection.clone());
+        *shared.model_info.lock() = Some(detector.model_info().clone());
 
-        // 3. 创建专注度计算器
-        let calculator = FocusCalculator::with_defaults();
-
-        // 4. 启动摄像头
+        // 3. 启动摄像头
         camera.start().map_err(|e| format!("Failed to start camera: {}", e))?;
 
+        let result = self.process_frames(frame_rx, &mut detector).await;
+
+        // 停止摄像头
+        camera.stop();
+
+        result
+    }
+
+    /// 处理帧直到停止、帧来源关闭或超过看门狗超时没有新帧
+    async fn process_frames(
+        &self,
+        mut frame_rx: watch::Receiver<super::CapturedFrame>,
+        detector: &mut dyn FrameDetector,
+    ) -> Result<LoopExit, String> {
+        let config = &self.config;
+        let running = &self.running;
+        let state_tx = &self.state_tx;
+        let frame_tx = &self.frame_tx;
+        let overlay_tx = &self.overlay_tx;
+        let shared = &self.shared;
+
+        // 创建专注度计算器、运动检测器和主人脸跟踪器
+        let mut calculator = FocusCalculator::new(config.focus.clone());
+        let mut motion_detector = MotionDetector::new(config.motion.clone());
+        let mut face_tracker = FaceTracker::new(config.face_tracker.clone());
+        let mut rolling_focus = RollingFocus::new(config.rolling_window_ms);
+        let mut focus_heat = FocusHeat::new(config.heat.clone());
+        let mut passivity_estimator = PassivityEstimator::new(config.passivity.clone());
+        let mut turn_grace = TurnGrace::new(config.turn_grace.clone());
+
         tracing::info!("Vision processing loop started");
 
         let mut frame_count = 0u64;
+        let mut stale_frames = 0u64;
         let mut last_focus_state = FocusState::default();
+        let mut last_primary_face = None;
+        let mut cadence = config.cadence.clone();
+        let mut detect_interval = cadence.min_interval.max(1);
+        let mut frames_since_detect = 0u32;
+        let mut empty_run = EmptyFrameRun::new(config.max_empty_frames);
+        let mut preview_throttle = PreviewThrottle::new(config.preview_fps);
+        let frame_timeout = (config.frame_timeout_ms > 0).then(|| std::time::Duration::from_millis(config.frame_timeout_ms));
 
         // 5. 处理循环
         while running.load(Ordering::SeqCst) {
-            // 等待新帧
-            if frame_rx.changed().await.is_err() {
+            // 等待新帧（采集任务失效时通道不会再更新，超时后交给看门狗重启）
+            let changed = match frame_timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, frame_rx.changed()).await {
+                    Ok(changed) => changed,
+                    Err(_) if running.load(Ordering::SeqCst) => {
+                        tracing::error!("No frames for {}ms, capture appears to be stalled", config.frame_timeout_ms);
+                        return Ok(LoopExit::Stalled);
+                    }
+                    Err(_) => break,
+                },
+                None => frame_rx.changed().await,
+            };
+            if changed.is_err() {
                 tracing::warn!("Frame channel closed");
                 break;
             }
+            if self.recovering.swap(false, Ordering::SeqCst) {
+                tracing::info!("Frames resumed after restart");
+                self.recovered_tx.send_modify(|count| *count += 1);
+            }
 
-            let frame = frame_rx.borrow().clone();
+            let mut frame = frame_rx.borrow().clone();
 
-            // 跳过空帧
+            // 跳过空帧；持续空帧时发布未检测到人脸的状态
             if frame.is_empty() {
+                if let Some(mut absent) = empty_run.on_empty() {
+                    absent.rolling_focus_30s = rolling_focus.push(absent.timestamp_ms, 0.0);
+                    absent.heat = focus_heat.update(absent.timestamp_ms, 0.0);
+                    last_focus_state = absent.clone();
+                    last_primary_face = None;
+                    if state_tx.send(absent).is_err() {
+                        break;
+                    }
+                }
+                continue;
+            }
+            empty_run.reset();
+
+            // 跳过过时的帧（处理跟不上时），等待下一帧
+            let now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64;
+            if frame.is_stale(now_ms, config.max_frame_age_ms) {
+                stale_frames += 1;
+                if stale_frames % 50 == 1 {
+                    tracing::debug!(
+                        "Skipping stale frame ({}ms old), {} skipped so far",
+                        now_ms.saturating_sub(frame.timestamp_ms),
+                        stale_frames
+                    );
+                }
                 continue;
             }
 
             frame_count += 1;
+            frame.frame_id = frame_count;
+
+            // 每帧一个 span，检测结果在处理过程中补记；未启用对应级别的订阅者时几乎没有开销
+            let span = tracing::debug_span!(
+                "vision_frame",
+                frame_count,
+                detected = tracing::field::Empty,
+                focus_score = tracing::field::Empty,
+                detect_latency_us = tracing::field::Empty,
+            );
+            let _entered = span.enter();
 
             if frame_count == 1 {
                 tracing::info!("First frame captured: {}x{}", frame.width, frame.height);
             }
 
-            // 转发帧用于预览（每 3 帧转发一次，约 3-4 fps，包含第一帧）
-            if frame_count % 3 == 1 {
-                let _ = frame_tx.send(frame.clone());
-            }
+            // 运动检测（每帧都做，开销很小）
+            let motion_wake = motion_detector.update(&frame);
 
-            // 是否进行检测（隔帧检测以降低 CPU）
-            let should_detect = config.detect_every_frame || (frame_count % 2 == 0);
+            // 是否进行检测（按上一次的专注分数调整间隔以降低 CPU）
+            frames_since_detect += 1;
+            let should_detect = config.detect_every_frame || frames_since_detect >= detect_interval;
+            // 本帧是否有新的检测结果，只有这样的帧才发送叠加层
+            let mut detected_this_frame = false;
 
             if should_detect {
+                frames_since_detect = 0;
+                if let Some(focus_config) = shared.pending_focus_config.lock().take() {
+                    calculator.set_config(focus_config);
+                }
+                detector.set_nms_threshold(*shared.nms_threshold.lock());
+                // 保留置信度略低的人脸，由计算器按在场滞后判断
+                detector.set_confidence_threshold(calculator.detection_confidence_threshold());
+
                 // 运行人脸检测
-                match detector.detect(&frame.data, frame.width, frame.height) {
+                let detect_started = std::time::Instant::now();
+                let detect_result = detector.detect(&frame.data, frame.width, frame.height);
+                span.record("detect_latency_us", detect_started.elapsed().as_micros() as u64);
+                if config.debug_raw_output {
+                    *shared.last_raw_output.lock() = detector.last_raw_output();
+                }
+                match detect_result {
                     Ok(detections) => {
-                        // 获取最大置信度的人脸
-                        let primary_face = detections.first();
+                        // 选出主人脸（带时间平滑，避免在相近人脸之间跳动）
+                        let primary_face = face_tracker.select(&detections);
+                        let primary_face = primary_face.as_ref();
+
+                        // 计算专注分数（置信度不足时视为未检测到人脸，转头宽限内不按转头扣分）
+                        let tolerate_yaw = turn_grace.update(frame.timestamp_ms, primary_face);
+                        let (focus_score, face_detected) = calculator.update_tolerating_yaw(primary_face, tolerate_yaw);
+                        let tracked_face = primary_face;
+                        let primary_face = primary_face.filter(|_| face_detected);
 
-                        // 计算专注分数
-                        let (focus_score, face_detected) = calculator.calculate(primary_face);
+                        // 头部过于静止（可能在被动观看）时轻微下调分数
+                        let passivity = passivity_estimator.update(frame.timestamp_ms, primary_face);
+                        let focus_score = passivity_estimator.apply(focus_score, passivity);
+                        span.record("detected", face_detected);
+                        span.record("focus_score", focus_score);
+
+                        // 分数接近阈值时下一次更早检测
+                        (cadence.enter_threshold, cadence.exit_threshold) = *shared.focus_thresholds.lock();
+                        detect_interval = cadence.interval_for(focus_score);
 
                         // 创建专注状态
-                        let focus_state = FocusState::from_detection(primary_face, focus_score);
+                        let mut focus_state = FocusState::from_detection(primary_face, focus_score);
+                        focus_state.passivity = passivity;
+                        focus_state.rolling_focus_30s = rolling_focus.push(focus_state.timestamp_ms, focus_score);
+                        focus_state.heat = focus_heat.update(focus_state.timestamp_ms, focus_score);
+
+                        // 转储检测结果（写入失败时停止转储）
+                        {
+                            let mut dump = shared.detection_dump.lock();
+                            if let Some(Err(e)) = dump.as_mut().map(|d| d.append(frame.timestamp_ms, tracked_face, &focus_state)) {
+                                tracing::warn!("Failed to write detection dump, stopping: {}", e);
+                                *dump = None;
+                            }
+                        }
 
                         // 发布状态
-                        if state_tx.send(focus_state.clone()).is_err() {
+                        let mut published = focus_state.clone();
+                        published.motion_wake = motion_wake;
+                        if state_tx.send(published).is_err() {
                             tracing::warn!("All state receivers dropped");
                             break;
                         }
 
                         last_focus_state = focus_state;
+                        last_primary_face = primary_face.cloned();
+                        detected_this_frame = true;
 
                         if frame_count % 50 == 0 {
                             tracing::debug!(
@@ -211,23 +706,38 @@ impl VisionProcessor {
                     }
                 }
             } else {
-                // 不检测时发送上一次的状态（更新时间戳）
+                // 不检测时发送上一次的状态（更新时间戳，保留原检测时间）
                 let mut state = last_focus_state.clone();
+                state.detected_at_ms = last_focus_state.detected_at();
                 state.timestamp_ms = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_millis() as u64;
+                state.motion_wake = motion_wake;
+                span.record("detected", state.face_present);
+                span.record("focus_score", state.focus_score);
 
                 if state_tx.send(state).is_err() {
                     break;
                 }
             }
-        }
 
-        // 停止摄像头
-        camera.stop();
+            // 按目标预览帧率转发帧用于预览（包含第一帧）
+            // 先发送叠加层，预览任务收到帧时即可按帧编号取到对应的检测结果；
+            // 未检测的帧不发送，避免把旧的人脸框标成这一帧的结果
+            if preview_throttle.should_forward(frame.timestamp_ms) {
+                if detected_this_frame {
+                    let _ = overlay_tx.send(FrameOverlay {
+                        frame_id: frame.frame_id,
+                        face: last_primary_face.clone(),
+                        focus_score: last_focus_state.focus_score,
+                    });
+                }
+                let _ = frame_tx.send(frame);
+            }
+        }
 
-        Ok(())
+        Ok(LoopExit::Stopped)
     }
 }
 
@@ -247,9 +757,365 @@ mod tests {
         assert!(config.model_path.contains("blazeface"));
     }
 
+    #[test]
+    fn test_empty_frames_publish_face_absent() {
+        let mut run = EmptyFrameRun::new(5);
+
+        // 偶尔的空帧不影响
+        for _ in 0..4 {
+            assert!(run.on_empty().is_none());
+        }
+        run.reset();
+
+        // 连续空帧超过阈值后持续发布未检测到人脸的状态
+        let published: Vec<FocusState> = (0..10).filter_map(|_| run.on_empty()).collect();
+        assert_eq!(published.len(), 6);
+        assert!(published.iter().all(|s| !s.face_present && s.focus_score == 0.0));
+
+        run.reset();
+        assert!(run.on_empty().is_none());
+    }
+
+    #[test]
+    fn test_nms_threshold_rejects_non_finite() {
+        let processor = VisionProcessor::new(VisionProcessorConfig::default());
+        assert_eq!(processor.set_nms_threshold(1.5), Some(1.0));
+        assert_eq!(processor.set_nms_threshold(f32::NAN), None);
+        assert_eq!(processor.set_nms_threshold(f32::INFINITY), None);
+        assert_eq!(processor.nms_threshold(), 1.0);
+    }
+
     #[test]
     fn test_vision_processor_creation() {
         let processor = VisionProcessor::new(VisionProcessorConfig::default());
         assert!(!processor.is_running());
     }
+
+    /// 返回测试设定的场景（当前帧中的人脸）的检测器
+    struct ScriptedDetector {
+        scene: Arc<Mutex<Vec<FaceDetection>>>,
+    }
+
+    impl FrameDetector for ScriptedDetector {
+        fn detect(&mut self, _image_data: &[u8], _width: u32, _height: u32) -> Result<Vec<FaceDetection>, FaceDetectorError> {
+            Ok(self.scene.lock().clone())
+        }
+    }
+
+    fn make_frame() -> super::super::CapturedFrame {
+        super::super::CapturedFrame {
+            width: 8,
+            height: 8,
+            data: vec![128; 8 * 8 * 3],
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            frame_id: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_processing_loop_end_to_end() {
+        use crate::state::{FocusLevel, PetMood, PetStateConfig, PetStateMachine};
+        use std::time::{Duration, Instant};
+
+        // 正对屏幕、大小合适的人脸
+        let focused_face = FaceDetection {
+            confidence: 0.95,
+            bbox: (0.35, 0.25, 0.65, 0.75),
+            landmarks: [
+                (0.45, 0.40), // 右眼
+                (0.55, 0.40), // 左眼
+                (0.50, 0.50), // 鼻子
+                (0.50, 0.60), // 嘴巴
+                (0.36, 0.45), // 右耳
+                (0.64, 0.45), // 左耳
+            ],
+            landmark_quality: [1.0; 6],
+        };
+
+        let processor = VisionProcessor::new(VisionProcessorConfig {
+            detect_every_frame: true,
+            ..Default::default()
+        });
+        let mut state_rx = processor.subscribe();
+        let scene = Arc::new(Mutex::new(vec![focused_face]));
+        let (frame_tx, frame_rx) = watch::channel(super::super::CapturedFrame::empty());
+        let handle = processor.spawn_with(frame_rx, ScriptedDetector { scene: scene.clone() });
+        assert!(processor.is_running());
+
+        // 逐帧发送，等到对应的状态发布后再发送下一帧；状态机使用模拟时钟，每帧 100ms
+        let mut machine = PetStateMachine::new(PetStateConfig::default());
+        let start = Instant::now();
+        let mut frames = 0u64;
+        let mut step = |machine: &mut PetStateMachine, state: FocusState| {
+            frames += 1;
+            let now = start + Duration::from_millis(frames * 100);
+            machine.update_at(now, state.focus_score, state.face_present);
+            state
+        };
+
+        let mut published = Vec::new();
+        for _ in 0..60 {
+            frame_tx.send(make_frame()).unwrap();
+            state_rx.changed().await.unwrap();
+            let state = state_rx.borrow_and_update().clone();
+            published.push(step(&mut machine, state));
+        }
+        assert!(published.iter().all(|s| s.face_present && s.focus_score > 0.8));
+        assert!(published.last().unwrap().rolling_focus_30s > 0.8);
+        assert_eq!(machine.focus_level, FocusLevel::Focused);
+        assert_eq!(machine.mood, PetMood::Happy);
+
+        // 用户离开：超过离开超时后宠物睡觉
+        scene.lock().clear();
+        for _ in 0..80 {
+            frame_tx.send(make_frame()).unwrap();
+            state_rx.changed().await.unwrap();
+            let state = state_rx.borrow_and_update().clone();
+            assert!(!state.face_present);
+            step(&mut machine, state);
+        }
+        assert_eq!(machine.focus_level, FocusLevel::Away);
+        assert_eq!(machine.mood, PetMood::Sleepy);
+
+        // 关闭帧来源后循环结束
+        processor.stop();
+        drop(frame_tx);
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("processing loop did not stop")
+            .unwrap()
+            .unwrap();
+        assert!(!processor.is_running());
+    }
+
+    /// 记录检测次数的检测器
+    struct CountingDetector {
+        calls: Arc<Mutex<u32>>,
+    }
+
+    impl FrameDetector for CountingDetector {
+        fn detect(&mut self, _image_data: &[u8], _width: u32, _height: u32) -> Result<Vec<FaceDetection>, FaceDetectorError> {
+            *self.calls.lock() += 1;
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlay_only_for_detected_frames() {
+        use std::time::Duration;
+
+        let processor = VisionProcessor::new(VisionProcessorConfig {
+            cadence: DetectionCadenceConfig {
+                min_interval: 3,
+                max_interval: 3,
+                ..Default::default()
+            },
+            preview_fps: 10,
+            ..Default::default()
+        });
+        let mut preview_rx = processor.subscribe_frames();
+        let overlay_rx = processor.subscribe_overlay();
+        let calls = Arc::new(Mutex::new(0));
+        let (frame_tx, frame_rx) = watch::channel(super::super::CapturedFrame::empty());
+        let handle = processor.spawn_with(frame_rx, CountingDetector { calls: calls.clone() });
+
+        // 每帧间隔 100ms，全部转发给预览，每 3 帧检测一次
+        let start_ms = make_frame().timestamp_ms;
+        let mut overlays = 0;
+        for i in 0..12u64 {
+            let before = *calls.lock();
+            frame_tx
+                .send(super::super::CapturedFrame {
+                    timestamp_ms: start_ms + i * 100,
+                    ..make_frame()
+                })
+                .unwrap();
+            preview_rx.changed().await.unwrap();
+            let preview_id = preview_rx.borrow_and_update().frame_id;
+            let detected = *calls.lock() > before;
+
+            // 叠加层的帧编号只会是真正做过检测的帧
+            let overlay = overlay_rx.borrow().clone();
+            assert_eq!(overlay.frame_id == preview_id, detected, "frame {}", preview_id);
+            if detected {
+                overlays += 1;
+            }
+        }
+        assert_eq!(overlays, 4);
+
+        processor.stop();
+        drop(frame_tx);
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("processing loop did not stop")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_preview_throttle_independent_of_capture_fps() {
+        // 10 秒内的预览帧数只取决于目标帧率
+        for capture_fps in [5, 15, 30, 60] {
+            let mut throttle = PreviewThrottle::new(DEFAULT_PREVIEW_FPS);
+            let frames = capture_fps * 10;
+            let forwarded = (0..frames)
+                .filter(|i| throttle.should_forward(1_000_000 + i * 1000 / capture_fps))
+                .count() as u64;
+            let expected = DEFAULT_PREVIEW_FPS as u64 * 10;
+            assert!(forwarded.abs_diff(expected) <= 1, "{} fps capture: {} previews", capture_fps, forwarded);
+        }
+
+        // 暂停后从当前帧重新计时，不会连续补发
+        let mut throttle = PreviewThrottle::new(4);
+        assert!(throttle.should_forward(0));
+        assert!(throttle.should_forward(5_000));
+        assert!(!throttle.should_forward(5_100));
+        assert!(throttle.should_forward(5_250));
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_restarts_stalled_source() {
+        use std::collections::VecDeque;
+        use std::time::Duration;
+
+        let processor = VisionProcessor::new(VisionProcessorConfig {
+            detect_every_frame: true,
+            frame_timeout_ms: 100,
+            ..Default::default()
+        });
+        let mut state_rx = processor.subscribe();
+        let mut recovered_rx = processor.subscribe_recoveries();
+        let scene = Arc::new(Mutex::new(Vec::new()));
+
+        // 第一个帧来源一直不出帧（发送端保持打开），第二个来源已有新帧
+        let (stalled_tx, stalled_rx) = watch::channel(super::super::CapturedFrame::empty());
+        let (live_tx, live_rx) = watch::channel(super::super::CapturedFrame::empty());
+        live_tx.send(make_frame()).unwrap();
+        let sources = Mutex::new(VecDeque::from([stalled_rx, live_rx]));
+        let starts = Arc::new(AtomicU32::new(0));
+
+        let context = processor.loop_context();
+        context.running.store(true, Ordering::SeqCst);
+        let loop_starts = starts.clone();
+        let handle = tokio::spawn(async move {
+            let context = &context;
+            let result = context
+                .supervise(|| {
+                    loop_starts.fetch_add(1, Ordering::SeqCst);
+                    let source = sources.lock().pop_front();
+                    let mut detector = ScriptedDetector { scene: scene.clone() };
+                    async move {
+                        match source {
+                            Some(frame_rx) => context.process_frames(frame_rx, &mut detector).await,
+                            None => Ok(LoopExit::Stopped),
+                        }
+                    }
+                })
+                .await;
+            context.running.store(false, Ordering::SeqCst);
+            result
+        });
+
+        // 看门狗超时后换用第二个来源，收到帧后报告恢复
+        tokio::time::timeout(Duration::from_secs(5), recovered_rx.changed())
+            .await
+            .expect("watchdog did not restart the stalled source")
+            .unwrap();
+        assert_eq!(*recovered_rx.borrow(), 1);
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+        tokio::time::timeout(Duration::from_secs(5), state_rx.changed()).await.unwrap().unwrap();
+        assert!(!state_rx.borrow().face_present);
+
+        processor.stop();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("processing loop did not stop")
+            .unwrap()
+            .unwrap();
+        drop((stalled_tx, live_tx));
+    }
+
+    /// 收集已关闭的 `vision_frame` span 及其字段
+    #[derive(Clone, Default)]
+    struct FrameSpanCapture {
+        open: Arc<Mutex<std::collections::HashMap<tracing::span::Id, std::collections::HashMap<String, String>>>>,
+        closed: Arc<Mutex<Vec<std::collections::HashMap<String, String>>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut std::collections::HashMap<String, String>);
+
+    impl tracing::field::Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for FrameSpanCapture
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, id: &tracing::span::Id, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            if attrs.metadata().name() == "vision_frame" {
+                let mut fields = std::collections::HashMap::new();
+                attrs.record(&mut FieldVisitor(&mut fields));
+                self.open.lock().insert(id.clone(), fields);
+            }
+        }
+
+        fn on_record(&self, id: &tracing::span::Id, values: &tracing::span::Record<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            if let Some(fields) = self.open.lock().get_mut(id) {
+                values.record(&mut FieldVisitor(fields));
+            }
+        }
+
+        fn on_close(&self, id: tracing::span::Id, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            if let Some(fields) = self.open.lock().remove(&id) {
+                self.closed.lock().push(fields);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_processing_loop_emits_frame_spans() {
+        use std::time::Duration;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        // 单线程运行时：处理任务和测试在同一线程，线程内的默认订阅者对两者都生效
+        let capture = FrameSpanCapture::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+        let processor = VisionProcessor::new(VisionProcessorConfig {
+            detect_every_frame: true,
+            ..Default::default()
+        });
+        let mut state_rx = processor.subscribe();
+        let scene = Arc::new(Mutex::new(Vec::new()));
+        let (frame_tx, frame_rx) = watch::channel(super::super::CapturedFrame::empty());
+        let handle = processor.spawn_with(frame_rx, ScriptedDetector { scene });
+
+        for _ in 0..3 {
+            frame_tx.send(make_frame()).unwrap();
+            state_rx.changed().await.unwrap();
+            state_rx.borrow_and_update();
+        }
+        processor.stop();
+        drop(frame_tx);
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("processing loop did not stop")
+            .unwrap()
+            .unwrap();
+
+        let spans = capture.closed.lock().clone();
+        assert_eq!(spans.len(), 3);
+        for (i, fields) in spans.iter().enumerate() {
+            assert_eq!(fields.get("frame_count"), Some(&(i + 1).to_string()));
+            assert_eq!(fields.get("detected").map(String::as_str), Some("false"));
+            assert_eq!(fields.get("focus_score").map(String::as_str), Some("0.0"));
+            assert!(fields.contains_key("detect_latency_us"), "{:?}", fields);
+        }
+    }
 }
@@ -0,0 +1,116 @@
+//! 专注评分回放
+//!
+//! 把一段固定的人脸检测序列依次送入专注度计算器和宠物状态机（使用模拟时钟），
+//! 得到每一帧的专注分数和情绪。测试中与提交在仓库中的基准结果逐帧比较，用于发现评分逻辑的意外变化。
+//!
+//! 有意调整评分时需要同时更新基准文件：设置环境变量 `FOCUS_MOCHI_UPDATE_GOLDEN=1` 运行测试即可重新生成
+//! `testdata/replay_golden.json`，提交前检查差异是否符合预期。
+
+use super::{FaceDetection, FocusCalculator, FocusCalculatorConfig};
+use crate::state::{FocusLevel, PetMood, PetStateConfig, PetStateMachine};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// 回放输入中的一帧
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    /// 相对回放开始的时间（毫秒）
+    pub timestamp_ms: u64,
+    /// 检测到的主人脸（None 表示未检测到人脸）
+    pub face: Option<FaceDetection>,
+}
+
+/// 回放中一帧的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    /// 相对回放开始的时间（毫秒）
+    pub timestamp_ms: u64,
+    /// 专注分数（保留 4 位小数，避免不同平台浮点误差导致比较失败）
+    pub focus_score: f32,
+    /// 是否检测到有效人脸
+    pub face_detected: bool,
+    /// 专注等级
+    pub focus_level: FocusLevel,
+    /// 宠物情绪
+    pub mood: PetMood,
+}
+
+/// 回放一段人脸检测序列，返回每一帧的结果
+pub fn replay(frames: &[ReplayFrame], calculator: FocusCalculatorConfig, pet: PetStateConfig) -> Vec<ReplayStep> {
+    let mut calculator = FocusCalculator::new(calculator);
+    let mut machine = PetStateMachine::new(pet);
+    let start = Instant::now();
+
+    frames
+        .iter()
+        .map(|frame| {
+            let (focus_score, face_detected) = calculator.update(frame.face.as_ref());
+            let confidence = frame.face.as_ref().filter(|_| face_detected).map(|face| face.confidence);
+            let now = start + Duration::from_millis(frame.timestamp_ms);
+            machine.update_with_confidence_at(now, focus_score, face_detected, confidence);
+
+            ReplayStep {
+                timestamp_ms: frame.timestamp_ms,
+                focus_score: (focus_score * 10_000.0).round() / 10_000.0,
+                face_detected,
+                focus_level: machine.focus_level,
+                mood: machine.mood,
+            }
+        })
+        .collect()
+}
+
+/// 逐帧比较回放结果和基准结果，返回第一处差异的说明
+pub fn compare_replay(actual: &[ReplayStep], expected: &[ReplayStep]) -> Result<(), String> {
+    if let Some((index, (actual, expected))) =
+        actual.iter().zip(expected).enumerate().find(|(_, (actual, expected))| actual != expected)
+    {
+        return Err(format!(
+            "Replay differs at frame {} ({} ms): expected {:?}, got {:?}",
+            index, expected.timestamp_ms, expected, actual
+        ));
+    }
+    if actual.len() != expected.len() {
+        return Err(format!("Replay has {} frames, expected {}", actual.len(), expected.len()));
+    }
+    Ok(())
+}
+
+/// 序列化为每帧一行的 JSON 数组，便于查看基准文件的差异
+pub fn to_json_lines<T: Serialize>(items: &[T]) -> serde_json::Result<String> {
+    let lines = items.iter().map(serde_json::to_string).collect::<serde_json::Result<Vec<_>>>()?;
+    Ok(format!("[\n{}\n]\n", lines.join(",\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// 固定的输入序列：专注、转头、离开、低置信度、专注、低头、靠近屏幕（每 500ms 一帧，共 3 分钟）
+    const REPLAY_FRAMES: &str = include_str!("testdata/replay_frames.json");
+
+    /// 基准结果
+    const REPLAY_GOLDEN: &str = include_str!("testdata/replay_golden.json");
+
+    #[test]
+    fn test_replay_matches_golden() {
+        let frames: Vec<ReplayFrame> = serde_json::from_str(REPLAY_FRAMES).unwrap();
+        let actual = replay(&frames, FocusCalculatorConfig::default(), PetStateConfig::default());
+        assert_eq!(actual.len(), frames.len());
+
+        if std::env::var_os("FOCUS_MOCHI_UPDATE_GOLDEN").is_some() {
+            let path = Path::new(file!()).with_file_name("testdata/replay_golden.json");
+            std::fs::write(&path, to_json_lines(&actual).unwrap()).unwrap();
+            return;
+        }
+
+        let expected: Vec<ReplayStep> = serde_json::from_str(REPLAY_GOLDEN).unwrap();
+        if let Err(e) = compare_replay(&actual, &expected) {
+            panic!("{}\nIf the scoring change is intended, rerun with FOCUS_MOCHI_UPDATE_GOLDEN=1", e);
+        }
+
+        // 回放是确定的
+        assert_eq!(replay(&frames, FocusCalculatorConfig::default(), PetStateConfig::default()), actual);
+    }
+}
@@ -0,0 +1,153 @@
+//! 内置资源校验
+//!
+//! 安装包损坏或被杀毒软件截断时，模型加载可能报出难以理解的错误，甚至加载成功但结果异常。
+//! 这里逐个检查随应用发布的资源是否存在、大小，以及模型和锚框的校验和是否和发布时一致。
+
+use super::face::file_sha256;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 随应用发布的资源（相对资源目录的路径，发布时的 SHA-256）
+const BUNDLED_RESOURCES: &[(&str, Option<&str>)] = &[
+    (
+        "models/blazeface.onnx",
+        Some("3b047586414fb835b6d288a2f728cc2b661af437f0f59f468c0116125b666d7e"),
+    ),
+    (
+        "models/anchors.npy",
+        Some("a10bb2fb93ab54ca426d6c750bfc3aad685028a16dcf231357d03694f261fd95"),
+    ),
+];
+
+/// 单个资源的校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceStatus {
+    /// 存在且校验和一致（或没有已知校验和）
+    Ok,
+    /// 文件不存在
+    Missing,
+    /// 文件存在但无法读取
+    Unreadable,
+    /// 校验和和发布时不一致
+    ChecksumMismatch,
+}
+
+/// 单个资源的检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceEntry {
+    /// 相对资源目录的路径
+    pub name: String,
+    /// 完整路径
+    pub path: String,
+    /// 文件是否存在
+    pub exists: bool,
+    /// 文件大小（字节，不存在时为 None）
+    pub size_bytes: Option<u64>,
+    /// 文件的 SHA-256（有已知校验和且文件可读时计算）
+    pub sha256: Option<String>,
+    /// 发布时的 SHA-256
+    pub expected_sha256: Option<String>,
+    /// 校验结果
+    pub status: ResourceStatus,
+}
+
+/// 资源校验报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceReport {
+    /// 资源目录
+    pub root: String,
+    /// 每个资源的检查结果
+    pub entries: Vec<ResourceEntry>,
+    /// 是否全部正常
+    pub all_ok: bool,
+}
+
+/// 检查 `root` 下随应用发布的资源
+pub fn verify_resources(root: &Path) -> ResourceReport {
+    verify_resource_list(root, BUNDLED_RESOURCES)
+}
+
+/// 检查 `root` 下的指定资源，单个资源出错不影响其他资源的检查
+fn verify_resource_list(root: &Path, resources: &[(&str, Option<&str>)]) -> ResourceReport {
+    let entries: Vec<ResourceEntry> = resources
+        .iter()
+        .map(|&(name, expected)| {
+            let path = root.join(name);
+            let size_bytes = std::fs::metadata(&path).ok().filter(|m| m.is_file()).map(|m| m.len());
+            let sha256 = match (size_bytes, expected) {
+                (Some(_), Some(_)) => file_sha256(&path.to_string_lossy()).ok(),
+                _ => None,
+            };
+            let status = match (size_bytes, expected, &sha256) {
+                (None, _, _) => ResourceStatus::Missing,
+                (Some(_), None, _) => ResourceStatus::Ok,
+                (Some(_), Some(_), None) => ResourceStatus::Unreadable,
+                (Some(_), Some(expected), Some(actual)) if actual == expected => ResourceStatus::Ok,
+                (Some(_), Some(_), Some(_)) => ResourceStatus::ChecksumMismatch,
+            };
+            if status != ResourceStatus::Ok {
+                tracing::warn!("Resource {} is {:?}", path.display(), status);
+            }
+
+            ResourceEntry {
+                name: name.to_string(),
+                path: path.to_string_lossy().into_owned(),
+                exists: size_bytes.is_some(),
+                size_bytes,
+                sha256,
+                expected_sha256: expected.map(str::to_string),
+                status,
+            }
+        })
+        .collect();
+
+    ResourceReport {
+        root: root.to_string_lossy().into_owned(),
+        all_ok: entries.iter().all(|entry| entry.status == ResourceStatus::Ok),
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_temp_path;
+
+    #[test]
+    fn test_verify_resources_per_entry_status() {
+        let root = test_temp_path("resources");
+        std::fs::create_dir_all(root.join("models")).unwrap();
+        std::fs::write(root.join("models/good.bin"), b"hello").unwrap();
+        std::fs::write(root.join("models/bad.bin"), b"truncated").unwrap();
+        std::fs::write(root.join("skin.png"), b"png").unwrap();
+
+        // "hello" 的 SHA-256
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let report = verify_resource_list(
+            &root,
+            &[
+                ("models/good.bin", Some(hello)),
+                ("models/bad.bin", Some(hello)),
+                ("models/missing.bin", Some(hello)),
+                ("skin.png", None),
+            ],
+        );
+        let statuses: Vec<_> = report.entries.iter().map(|e| e.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                ResourceStatus::Ok,
+                ResourceStatus::ChecksumMismatch,
+                ResourceStatus::Missing,
+                ResourceStatus::Ok,
+            ]
+        );
+        assert_eq!(report.entries[0].size_bytes, Some(5));
+        assert!(!report.entries[2].exists);
+        assert_eq!(report.entries[3].sha256, None);
+        assert!(!report.all_ok);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,28 @@
+//! 专注状态来源
+//!
+//! 专注状态既可以来自本机摄像头的视觉检测，也可以由外部程序（例如其他设备上的检测器）推送，
+//! 同一时间只有一个来源处于活动状态。
+
+use serde::{Deserialize, Serialize};
+
+/// 专注状态来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusSource {
+    /// 本机摄像头
+    #[default]
+    Camera,
+    /// 外部程序推送
+    External,
+}
+
+impl FocusSource {
+    /// 从字符串解析来源
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "camera" => Some(Self::Camera),
+            "external" => Some(Self::External),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! 主人脸跟踪模块
+//!
+//! 画面中有多张人脸时，逐帧取最大置信度会在两张相近的人脸之间来回跳动。
+//! 跟踪器会记住上一帧的主人脸，只有当另一张人脸持续明显更强时才切换。
+//!
+//! 比较人脸时依次看置信度、面积，两者都相同时按 [`FaceTieBreak`] 决定，
+//! 保证同样的检测结果总是选出同一张人脸，不依赖 NMS 输出的顺序。
+
+use super::{BlazeFaceDetector, FaceDetection};
+use std::cmp::Ordering;
+
+/// 置信度和面积都相同时如何选择主人脸
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FaceTieBreak {
+    /// 离画面中心最近的人脸，距离相同时取左上方的
+    #[default]
+    Centermost,
+    /// 左上方的人脸（先比较上边缘，再比较左边缘）
+    TopLeft,
+}
+
+impl FaceTieBreak {
+    /// 比较两张人脸，`Greater` 表示 `a` 更优先
+    fn compare(&self, a: &FaceDetection, b: &FaceDetection) -> Ordering {
+        let top_left = || {
+            b.bbox
+                .1
+                .total_cmp(&a.bbox.1)
+                .then_with(|| b.bbox.0.total_cmp(&a.bbox.0))
+        };
+        match self {
+            Self::Centermost => {
+                let distance = |face: &FaceDetection| {
+                    let (x, y) = face.center();
+                    (x - 0.5).powi(2) + (y - 0.5).powi(2)
+                };
+                distance(b).total_cmp(&distance(a)).then_with(top_left)
+            }
+            Self::TopLeft => top_left(),
+        }
+    }
+}
+
+/// 主人脸跟踪配置
+#[derive(Debug, Clone)]
+pub struct FaceTrackerConfig {
+    /// 判定为同一张人脸的最小 IoU
+    pub match_iou: f32,
+    /// 当前主人脸的置信度加成，其他人脸需要超过加成后的置信度才算更强
+    pub stickiness: f32,
+    /// 其他人脸需要连续更强的帧数才会切换
+    pub switch_frames: u32,
+    /// 置信度和面积都相同时的选择规则
+    pub tie_break: FaceTieBreak,
+}
+
+impl Default for FaceTrackerConfig {
+    fn default() -> Self {
+        Self {
+            match_iou: 0.3,
+            stickiness: 0.1,
+            switch_frames: 5,
+            tie_break: FaceTieBreak::default(),
+        }
+    }
+}
+
+/// 主人脸跟踪器
+pub struct FaceTracker {
+    config: FaceTrackerConfig,
+    /// 当前主人脸的边界框
+    primary_bbox: Option<(f32, f32, f32, f32)>,
+    /// 其他人脸连续更强的帧数
+    challenger_frames: u32,
+}
+
+impl FaceTracker {
+    /// 创建跟踪器
+    pub fn new(config: FaceTrackerConfig) -> Self {
+        Self {
+            config,
+            primary_bbox: None,
+            challenger_frames: 0,
+        }
+    }
+
+    /// 比较两张人脸：先比置信度，再比面积，最后按平局规则，`Greater` 表示 `a` 更优先
+    fn rank(&self, a: &FaceDetection, b: &FaceDetection) -> Ordering {
+        a.confidence
+            .total_cmp(&b.confidence)
+            .then_with(|| a.size().total_cmp(&b.size()))
+            .then_with(|| self.config.tie_break.compare(a, b))
+    }
+
+    /// 从本帧检测结果中选出主人脸
+    pub fn select(&mut self, detections: &[FaceDetection]) -> Option<FaceDetection> {
+        let strongest = detections
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| self.rank(a, b))
+            .map(|(i, _)| i);
+
+        let Some(strongest) = strongest else {
+            self.reset();
+            return None;
+        };
+
+        // 在本帧中找到上一帧的主人脸
+        let current = self.primary_bbox.and_then(|bbox| {
+            detections
+                .iter()
+                .enumerate()
+                .map(|(i, d)| (i, BlazeFaceDetector::calculate_iou(&bbox, &d.bbox)))
+                .filter(|(_, iou)| *iou >= self.config.match_iou)
+                .max_by(|(i, a), (j, b)| a.total_cmp(b).then_with(|| self.rank(&detections[*i], &detections[*j])))
+                .map(|(i, _)| i)
+        });
+
+        let selected = match current {
+            // 主人脸丢失或首次选择：直接取最大置信度
+            None => {
+                self.challenger_frames = 0;
+                strongest
+            }
+            Some(current) if current == strongest => {
+                self.challenger_frames = 0;
+                current
+            }
+            Some(current) => {
+                let weighted = detections[current].confidence + self.config.stickiness;
+                if detections[strongest].confidence > weighted {
+                    self.challenger_frames += 1;
+                } else {
+                    self.challenger_frames = 0;
+                }
+
+                if self.challenger_frames >= self.config.switch_frames {
+                    tracing::debug!("Primary face switched");
+                    self.challenger_frames = 0;
+                    strongest
+                } else {
+                    current
+                }
+            }
+        };
+
+        let face = detections[selected].clone();
+        self.primary_bbox = Some(face.bbox);
+        Some(face)
+    }
+
+    /// 清除跟踪状态
+    pub fn reset(&mut self) {
+        self.primary_bbox = None;
+        self.challenger_frames = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_face(x: f32, confidence: f32) -> FaceDetection {
+        FaceDetection {
+            confidence,
+            bbox: (x, 0.3, x + 0.2, 0.6),
+            landmarks: [(x + 0.1, 0.4); 6],
+            landmark_quality: [1.0; 6],
+        }
+    }
+
+    #[test]
+    fn test_primary_does_not_oscillate() {
+        let mut tracker = FaceTracker::new(FaceTrackerConfig::default());
+        let mut primaries = Vec::new();
+
+        // 两张相近的人脸，置信度每帧交替领先
+        for i in 0..20 {
+            let (a, b) = if i % 2 == 0 { (0.82, 0.80) } else { (0.80, 0.82) };
+            let detections = vec![make_face(0.1, a), make_face(0.6, b)];
+            primaries.push(tracker.select(&detections).unwrap().bbox.0);
+        }
+
+        assert!(primaries.iter().all(|&x| x == primaries[0]));
+    }
+
+    #[test]
+    fn test_switch_to_persistently_stronger_face() {
+        let mut tracker = FaceTracker::new(FaceTrackerConfig::default());
+        tracker.select(&[make_face(0.1, 0.9)]);
+
+        // 另一张人脸持续明显更强，数帧后切换
+        let detections = vec![make_face(0.1, 0.6), make_face(0.6, 0.95)];
+        let mut switched_at = None;
+        for i in 0..10 {
+            if tracker.select(&detections).unwrap().bbox.0 == 0.6 {
+                switched_at = Some(i);
+                break;
+            }
+        }
+
+        assert_eq!(switched_at, Some(FaceTrackerConfig::default().switch_frames - 1));
+    }
+
+    #[test]
+    fn test_tie_break_is_deterministic() {
+        // 置信度和面积相同的两张人脸，不论检测顺序都选同一张
+        let left = make_face(0.1, 0.9);
+        let center = make_face(0.4, 0.9);
+        for detections in [vec![left.clone(), center.clone()], vec![center.clone(), left.clone()]] {
+            let mut tracker = FaceTracker::new(FaceTrackerConfig::default());
+            assert_eq!(tracker.select(&detections).unwrap().bbox.0, 0.4);
+        }
+
+        // 左上优先
+        let config = FaceTrackerConfig {
+            tie_break: FaceTieBreak::TopLeft,
+            ..Default::default()
+        };
+        for detections in [vec![left.clone(), center.clone()], vec![center.clone(), left.clone()]] {
+            let mut tracker = FaceTracker::new(config.clone());
+            assert_eq!(tracker.select(&detections).unwrap().bbox.0, 0.1);
+        }
+
+        // 置信度相同时面积大的优先
+        let mut large = make_face(0.6, 0.9);
+        large.bbox.2 += 0.1;
+        let mut tracker = FaceTracker::new(FaceTrackerConfig::default());
+        assert_eq!(tracker.select(&[center, large]).unwrap().bbox.0, 0.6);
+    }
+}
@@ -0,0 +1,166 @@
+//! 转头宽限
+//!
+//! 协作场景中转头和同事讨论、看白板是正常工作，不应立即判为分心。开启后，适度的转头
+//! （偏航角在 `TURN_MIN_YAW` 和 `max_yaw` 之间）在时间预算内不按转头扣分；预算按
+//! `budget` 的比例随时间恢复，转头时消耗，最多积累 `budget × window_ms`。
+//! 预算用完、单次转头持续太久或转头角度过大时照常扣分。
+//!
+//! 与副屏偏航角区间不同，宽限不限定方向，而是限定转头的时间占比。
+
+use super::FaceDetection;
+
+/// 偏航角（绝对值，度）达到该值才视为转头，更小的偏转本身扣分很少
+const TURN_MIN_YAW: f32 = 12.0;
+
+/// 转头宽限配置
+#[derive(Debug, Clone)]
+pub struct TurnGraceConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 允许转头的时间占比 (0.0 - 1.0)
+    pub budget: f32,
+    /// 预算的时间窗口（毫秒）：最多可连续使用 `budget × window_ms` 的转头时间
+    pub window_ms: u64,
+    /// 单次转头的最长宽限时间（毫秒，0 表示不限）
+    pub max_turn_ms: u64,
+    /// 宽限的最大偏航角（绝对值，度），更大的转头照常扣分
+    pub max_yaw: f32,
+}
+
+impl Default for TurnGraceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget: 0.25,
+            window_ms: 10 * 60 * 1000,
+            max_turn_ms: 60_000,
+            max_yaw: 60.0,
+        }
+    }
+}
+
+/// 转头宽限跟踪器
+#[derive(Debug, Clone)]
+pub struct TurnGrace {
+    config: TurnGraceConfig,
+    /// 剩余的转头预算（毫秒）
+    balance_ms: f64,
+    /// 上一次检测的时间戳和当时是否在转头
+    last: Option<(u64, bool)>,
+    /// 当前这次转头的开始时间
+    turn_started_ms: Option<u64>,
+}
+
+impl TurnGrace {
+    /// 创建跟踪器，初始预算为满
+    pub fn new(config: TurnGraceConfig) -> Self {
+        let balance_ms = Self::capacity_ms(&config);
+        Self {
+            config,
+            balance_ms,
+            last: None,
+            turn_started_ms: None,
+        }
+    }
+
+    /// 预算上限（毫秒）
+    fn capacity_ms(config: &TurnGraceConfig) -> f64 {
+        config.window_ms as f64 * config.budget.clamp(0.0, 1.0) as f64
+    }
+
+    /// 加入一次检测结果，返回这次转头是否在宽限内（在宽限内时不按转头扣分）
+    ///
+    /// 未启用、没有人脸或没有转头时返回 false
+    pub fn update(&mut self, timestamp_ms: u64, face: Option<&FaceDetection>) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+
+        // 按上一次检测以来的时间恢复和消耗预算
+        if let Some((last_ms, was_turning)) = self.last {
+            let elapsed = timestamp_ms.saturating_sub(last_ms) as f64;
+            let mut balance = self.balance_ms + elapsed * self.config.budget.clamp(0.0, 1.0) as f64;
+            if was_turning {
+                balance -= elapsed;
+            }
+            self.balance_ms = balance.clamp(0.0, Self::capacity_ms(&self.config));
+        }
+
+        let yaw = face.map(|face| face.estimate_yaw().abs());
+        let turning = yaw.is_some_and(|yaw| yaw >= TURN_MIN_YAW);
+        self.last = Some((timestamp_ms, turning));
+        if !turning {
+            self.turn_started_ms = None;
+            return false;
+        }
+
+        let started = *self.turn_started_ms.get_or_insert(timestamp_ms);
+        let within_turn_limit =
+            self.config.max_turn_ms == 0 || timestamp_ms.saturating_sub(started) <= self.config.max_turn_ms;
+        yaw.is_some_and(|yaw| yaw <= self.config.max_yaw) && within_turn_limit && self.balance_ms > 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::FocusCalculator;
+
+    /// 向右转头约 `yaw` 度的人脸
+    fn face(yaw: f32) -> FaceDetection {
+        let dx = yaw / 90.0;
+        let mut face = FaceDetection::frontal();
+        face.landmarks[0].0 += dx;
+        face.landmarks[1].0 += dx;
+        face
+    }
+
+    /// 每秒检测一次，`turning(秒)` 为 true 时转头 27 度，返回每次检测的专注分数
+    fn run(config: &TurnGraceConfig, secs: u64, turning: impl Fn(u64) -> bool) -> Vec<(bool, f32)> {
+        let mut grace = TurnGrace::new(config.clone());
+        let mut calculator = FocusCalculator::with_defaults();
+        (0..secs)
+            .map(|s| {
+                let face = face(if turning(s) { 27.0 } else { 0.0 });
+                let tolerated = grace.update(s * 1000, Some(&face));
+                (turning(s), calculator.update_tolerating_yaw(Some(&face), tolerated).0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_turn_grace_budget() {
+        let enabled = TurnGraceConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let facing = FocusCalculator::with_defaults().calculate(Some(&face(0.0))).0;
+        let turned = FocusCalculator::with_defaults().calculate(Some(&face(27.0))).0;
+        assert!(turned < facing - 0.1, "facing {} turned {}", facing, turned);
+
+        // 每分钟转头 10 秒（低于 25% 的预算）：半小时内都不扣分
+        let occasional = run(&enabled, 30 * 60, |s| s % 60 < 10);
+        assert!(occasional.iter().all(|&(_, score)| (score - facing).abs() < 1e-5));
+
+        // 一直转头：超过单次转头上限后扣分
+        let constant = run(&enabled, 120, |_| true);
+        assert!((constant[30].1 - facing).abs() < 1e-5);
+        assert!((constant[90].1 - turned).abs() < 1e-5);
+
+        // 不限单次时长时，预算（150 秒，每秒恢复 0.25 秒）约 200 秒后用完
+        let unlimited = TurnGraceConfig {
+            max_turn_ms: 0,
+            ..enabled.clone()
+        };
+        let constant = run(&unlimited, 300, |_| true);
+        assert!((constant[150].1 - facing).abs() < 1e-5);
+        assert!((constant[250].1 - turned).abs() < 1e-5);
+
+        // 转头角度过大时不宽限；关闭时始终不宽限
+        let mut grace = TurnGrace::new(enabled);
+        assert!(!grace.update(0, Some(&face(72.0))));
+        assert!(!grace.update(1000, None));
+        let mut disabled = TurnGrace::new(TurnGraceConfig::default());
+        assert!(!disabled.update(0, Some(&face(27.0))));
+    }
+}
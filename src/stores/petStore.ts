@@ -5,7 +5,22 @@
 
 import { useCallback, useEffect, useReducer } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import type { PetMood, PetStateResponse, FocusStats, GestureType, FocusState } from '../types';
+import type { PetMood, PetStateResponse, FocusStats, GestureType, GestureReaction, FocusState, CommandError } from '../types';
+
+/** 把命令错误转换为提示文案 */
+function describeError(err: unknown): string {
+  if (typeof err === 'object' && err !== null && 'kind' in err) {
+    const { kind, message } = err as CommandError;
+    if (kind === 'camera_busy') {
+      return '摄像头正被其他应用占用，请关闭后重试';
+    }
+    if (kind === 'timeout') {
+      return '操作超时，请稍后重试';
+    }
+    return message;
+  }
+  return String(err);
+}
 
 /** Store 状态 */
 interface PetState {
@@ -94,7 +109,7 @@ export function usePetStore() {
       await invoke('start_vision');
       dispatch({ type: 'SET_VISION_ACTIVE', payload: true });
     } catch (err) {
-      dispatch({ type: 'SET_ERROR', payload: String(err) });
+      dispatch({ type: 'SET_ERROR', payload: describeError(err) });
     }
   }, []);
 
@@ -111,8 +126,8 @@ export function usePetStore() {
   /** 触发手势 */
   const triggerGesture = useCallback(async (gesture: GestureType) => {
     try {
-      const newMood = await invoke<PetMood>('trigger_gesture', { gesture });
-      dispatch({ type: 'SET_MOOD', payload: newMood });
+      const reaction = await invoke<GestureReaction>('trigger_gesture', { gesture });
+      dispatch({ type: 'SET_MOOD', payload: reaction.mood });
     } catch (err) {
       dispatch({ type: 'SET_ERROR', payload: String(err) });
     }
@@ -24,10 +24,33 @@ export type GestureType =
   | 'ok'        // OK 手势
   | 'thumbsup'; // 竖大拇指
 
+/** 手势反应方式（由互动前的情绪决定） */
+export type ReactionCue =
+  | 'cheer_up'   // 伤心时被安慰
+  | 'wake_up'    // 睡觉时被叫醒
+  | 'greet'      // 待机时打招呼
+  | 'celebrate'; // 本来就开心
+
+/** 一次手势互动的反应 */
+export interface GestureReaction {
+  /** 触发的手势 */
+  gesture: GestureType;
+  /** 互动后的情绪 */
+  mood: PetMood;
+  /** 互动前的情绪 */
+  from_mood: PetMood;
+  /** 反应方式 */
+  cue: ReactionCue;
+  /** 反应强度 (0-1) */
+  intensity: number;
+}
+
 /** 宠物状态响应 */
 export interface PetStateResponse {
   /** 当前情绪 */
   mood: PetMood;
+  /** 情绪强度 (0-1) */
+  intensity: number;
   /** 当前专注分数 (0-1) */
   focus_score: number;
   /** 今日累计专注时间（分钟） */
@@ -36,10 +59,28 @@ export interface PetStateResponse {
   is_vision_active: boolean;
   /** 是否检测到人脸 */
   face_detected: boolean;
+  /** 是否处于手动休息 */
+  on_break: boolean;
+  /** 人脸短暂丢失，宠物正在寻找用户 */
+  looking_for_user: boolean;
+  /** 专注分数已过时（一段时间没有新的检测结果） */
+  is_stale: boolean;
+  /** 连续专注已达到深度专注时间 */
+  deep_focus: boolean;
+}
+
+/** 情绪变化事件（pet_mood_changed） */
+export interface MoodChangedPayload {
+  /** 新的情绪 */
+  mood: PetMood;
+  /** 情绪强度 (0-1) */
+  intensity: number;
 }
 
 /** 专注状态（来自视觉检测） */
 export interface FocusState {
+  /** 序列化版本 */
+  schema_version: number;
   /** 是否检测到人脸 */
   face_present: boolean;
   /** 人脸置信度 */
@@ -54,6 +95,18 @@ export interface FocusState {
   roll: number;
   /** 时间戳（毫秒） */
   timestamp_ms: number;
+  /** 静止后检测到明显运动（用于提前唤醒宠物） */
+  motion_wake: boolean;
+  /** 最近一段时间（默认 30 秒）内专注分数的滑动平均 */
+  rolling_focus_30s: number;
+  /** 专注热度 (0-1)：持续专注时缓慢升高，分心时逐渐冷却 */
+  heat: number;
+  /** 产生这组数值的检测时间（毫秒，0 表示与 timestamp_ms 相同） */
+  detected_at_ms: number;
+  /** 人脸面积占画面的比例（未检测到人脸时为 0） */
+  face_size: number;
+  /** 被动程度 (0-1)：头部长时间几乎不动（如看视频）时升高，未开启时为 0 */
+  passivity: number;
 }
 
 /** 视觉检测状态响应 */
@@ -62,6 +115,12 @@ export interface VisionStatusResponse {
   is_running: boolean;
   /** 当前专注状态 */
   focus_state: FocusState | null;
+  /** 当前专注状态是否已过时 */
+  is_stale: boolean;
+  /** 应用已运行的时长（秒） */
+  uptime_secs: number;
+  /** 本次运行中启动视觉检测的次数 */
+  vision_sessions: number;
 }
 
 /** 摄像头预览帧 */
@@ -74,6 +133,598 @@ export interface PreviewFrame {
   height: number;
   /** 时间戳（毫秒） */
   timestamp_ms: number;
+  /** 帧编号（与 overlay_data 事件对应） */
+  frame_id: number;
+}
+
+/** 像素坐标下的人脸 */
+export interface PixelFace {
+  /** 人脸置信度 */
+  confidence: number;
+  /** 边界框 [x_min, y_min, x_max, y_max]（像素） */
+  bbox: [number, number, number, number];
+  /** 6 个关键点 [x, y]（像素）：右眼、左眼、鼻子、嘴巴、右耳、左耳 */
+  landmarks: [number, number][];
+}
+
+/** 预览叠加层数据 */
+export interface OverlayData {
+  /** 对应的预览帧编号 */
+  frame_id: number;
+  /** 预览图宽度 */
+  width: number;
+  /** 预览图高度 */
+  height: number;
+  /** 主人脸（未检测到时为 null） */
+  face: PixelFace | null;
+  /** 专注分数 (0-1) */
+  focus_score: number;
+}
+
+/** 下一个情绪里程碑 */
+export interface Milestone {
+  /** 将要进入的情绪 */
+  target_mood: PetMood;
+  /** 剩余时间（秒） */
+  seconds_remaining: number;
+  /** 是否为深度专注里程碑（情绪保持兴奋） */
+  deep_focus: boolean;
+}
+
+/** 专注教练建议 */
+export interface Tip {
+  /** 建议类型 ID */
+  id: string;
+  /** 展示文案 */
+  message: string;
+}
+
+/** 摄像头自动调优的单组测量结果 */
+export interface BenchmarkResult {
+  fps: number;
+  width: number;
+  height: number;
+  /** 平均单帧检测耗时（毫秒） */
+  avg_latency_ms: number;
+  /** 平均置信度 */
+  avg_confidence: number;
+  /** 参与测量的帧数 */
+  frames: number;
+  /** 检测到人脸的帧数 */
+  detected_frames: number;
+}
+
+/** 自动调优推荐的摄像头设置 */
+export interface RecommendedCameraSettings {
+  fps: number;
+  width: number;
+  height: number;
+  avg_latency_ms: number;
+  avg_confidence: number;
+  /** 是否已应用 */
+  applied: boolean;
+  /** 全部测量结果 */
+  results: BenchmarkResult[];
+}
+
+/** 一天内专注分数的波动情况（变异系数越低越平稳） */
+export interface FocusConsistency {
+  date: string;
+  /** 采样数 */
+  samples: number;
+  /** 平均分数 */
+  mean: number;
+  /** 标准差 */
+  std_dev: number;
+  /** 变异系数 */
+  coefficient_of_variation: number;
+}
+
+/** `goal_progress` 事件：累计专注跨过每日目标的一个进度档位 */
+export interface GoalProgressEvent {
+  /** 跨过的档位（百分比，最大 100） */
+  percent: number;
+  /** 已完成专注时长（毫秒） */
+  achieved_ms: number;
+  /** 目标专注时长（毫秒） */
+  target_ms: number;
+}
+
+/** 分心触发原因 */
+export type DistractionTrigger =
+  | 'low_focus'  // 人还在，但专注分数下降
+  | 'face_lost'; // 人脸丢失
+
+/** 一次分心 */
+export interface DistractionEvent {
+  id: number;
+  /** 开始时间（毫秒） */
+  start_ms: number;
+  /** 持续时长（毫秒） */
+  duration_ms: number;
+  /** 触发原因 */
+  trigger: DistractionTrigger;
+}
+
+/** 连续专注冻结名额状态 */
+export interface StreakFreezeStatus {
+  /** 当前可用的冻结次数 */
+  available: number;
+  /** 当前周期内已冻结的日期（升序） */
+  frozen_dates: string[];
+  /** 当前连续专注天数 */
+  streak: number;
+}
+
+/** 一段历史连续专注 */
+export interface Streak {
+  /** 第一天 (YYYY-MM-DD) */
+  start_date: string;
+  /** 最后一天 (YYYY-MM-DD) */
+  end_date: string;
+  /** 天数 */
+  length_days: number;
+}
+
+/** 应用数据文件路径 */
+export interface DataPaths {
+  /** 应用数据目录 */
+  data_dir: string;
+  /** 配置文件 */
+  config_path: string;
+  /** 数据库文件 */
+  database_path: string;
+  /** 采样日志目录 */
+  log_path: string;
+}
+
+/** 专注分数趋势方向 */
+export type Trend = 'rising' | 'falling' | 'steady';
+
+/** 最近几秒的专注分数趋势 */
+export interface FocusTrend {
+  /** 趋势方向（采样不足时为 steady） */
+  trend: Trend;
+  /** 拟合斜率（每秒分数变化） */
+  slope: number;
+  /** 参与计算的采样数 */
+  samples: number;
+}
+
+/** 专注会话记录 */
+export interface FocusSession {
+  /** 会话 ID */
+  id: number;
+  /** 开始时间（Unix 时间戳，毫秒） */
+  start_time: number;
+  /** 结束时间（Unix 时间戳，毫秒） */
+  end_time: number;
+  /** 专注时长（毫秒） */
+  focus_duration_ms: number;
+  /** 分心时长（毫秒） */
+  distracted_duration_ms: number;
+  /** 专注期间的平均专注分数 */
+  avg_focus_score: number;
+  /** 专注期间的最低专注分数 */
+  min_focus_score: number;
+  /** 专注期间的最高专注分数 */
+  max_focus_score: number;
+}
+
+/** 手动保存检查点的结果 */
+export interface CheckpointInfo {
+  /** 写入的统计日（YYYY-MM-DD） */
+  date: string;
+  /** 写入的会话记录（没有进行中的会话时为 null） */
+  session: FocusSession | null;
+  /** 本次新写入的专注时长（毫秒） */
+  focus_ms: number;
+  /** 本次新写入的分心时长（毫秒） */
+  distracted_ms: number;
+  /** 写入时间（Unix 时间戳，毫秒） */
+  checkpointed_at_ms: number;
+}
+
+/** 偏航角区间（度，正值表示向右转头） */
+export interface YawZone {
+  /** 区间下限 */
+  min_yaw: number;
+  /** 区间上限 */
+  max_yaw: number;
+}
+
+/** 建议休息的原因 */
+export type BreakReason = 'focus_declining';
+
+/** 休息建议（仅作提示） */
+export interface BreakSuggestion {
+  /** 原因 */
+  reason: BreakReason;
+  /** 建议的休息时长（分钟） */
+  recommended_minutes: number;
+}
+
+/** 宠物性格 */
+export type Personality = 'companion' | 'accountability' | 'custom';
+
+/** 宠物反应风格 */
+export type ReactionStyle = 'calm' | 'expressive' | 'stoic';
+
+/** 性格对应的设置组合 */
+export interface PersonalitySettings {
+  /** 反应风格 */
+  reaction_style: ReactionStyle;
+  /** 分心时宠物伤心 */
+  sad_on_distraction: boolean;
+  /** 专注回落时主动提醒休息 */
+  break_reminders: boolean;
+}
+
+/** 专注状态来源 */
+export type FocusSource = 'camera' | 'external';
+
+/** 今日目标是否有望完成 */
+export interface GoalOnTrack {
+  /** 按预计专注时长能否完成目标（没有目标时为 true） */
+  on_track: boolean;
+  /** 预计今天结束时的专注时长（毫秒） */
+  projected_ms: number;
+  /** 目标专注时长（毫秒，0 表示没有目标） */
+  target_ms: number;
+  /** 已完成的专注时长（毫秒） */
+  achieved_ms: number;
+  /** 参与估计的历史天数（0 表示没有历史数据） */
+  history_days: number;
+}
+
+/** 界面语言 */
+export type Locale = 'zh' | 'en';
+
+/** 界面文案表（键为文案 ID，如 `mood.happy`） */
+export type LocalizedStrings = Record<string, string>;
+
+/** 会话录制信息 */
+export interface RecordingInfo {
+  /** 录制 ID（开始时间的毫秒时间戳） */
+  id: string;
+  /** 录制日期 (YYYY-MM-DD) */
+  date: string;
+  /** 开始时间（毫秒） */
+  start_ms: number;
+  /** 时长（毫秒） */
+  duration_ms: number;
+  /** 平均专注分数 */
+  avg_focus_score: number;
+  /** 录制的状态数 */
+  samples: number;
+}
+
+/** 自安装以来的累计统计 */
+export interface LifetimeStats {
+  /** 累计专注时长（毫秒） */
+  total_focus_ms: number;
+  /** 有记录的天数 */
+  total_days: number;
+  /** 活跃天数（专注达到 5 分钟） */
+  active_days: number;
+  /** 活跃日的平均专注时长（毫秒） */
+  avg_daily_ms: number;
+  /** 最早有记录的日期 (YYYY-MM-DD) */
+  first_date: string | null;
+}
+
+/** 看手机信号（`phone_distraction` 事件） */
+export interface PhoneDistraction {
+  /** 开始低头的时间戳（毫秒） */
+  started_ms: number;
+  /** 已持续低头的时长（毫秒） */
+  duration_ms: number;
+  /** 当前俯仰角（度） */
+  pitch: number;
+}
+
+/** 需要重启的配置字段如何处理 */
+export type ApplyMode = 'apply_and_restart' | 'apply_live_only';
+
+/** 配置更新的应用结果 */
+export interface ApplyReport {
+  /** 已立即生效的字段（点分路径） */
+  live: string[];
+  /** 需要重启视觉检测才能生效的字段 */
+  needs_restart: string[];
+  /** 是否已重启视觉检测 */
+  restarted: boolean;
+}
+
+/** 会话中的最高专注分数 */
+export interface SessionPeak {
+  /** 最高专注分数 */
+  peak_score: number;
+  /** 首次达到最高分数的时间（毫秒时间戳） */
+  at_ms: number;
+}
+
+/** 会话结束时的回顾（`session_recap` 事件） */
+export interface SessionRecap {
+  /** 会话时长（毫秒） */
+  duration_ms: number;
+  /** 专注时长（毫秒） */
+  focus_ms: number;
+  /** 专注期间的平均专注分数 */
+  avg_focus_score: number;
+  /** 最高专注分数 */
+  peak_score: number;
+  /** 进入分心的次数 */
+  distraction_count: number;
+  /** 专注时长是否超过了之前所有会话 */
+  personal_best: boolean;
+}
+
+/** 专注评分基准测试结果 */
+export interface CalculatorBenchmark {
+  /** 计算次数 */
+  iterations: number;
+  /** 总耗时（纳秒） */
+  total_ns: number;
+  /** 平均每次计算的耗时（纳秒） */
+  ns_per_call: number;
+}
+
+/** 分数预览结果 */
+export interface ScoreClassification {
+  /** 专注等级 */
+  focus_level: FocusLevel;
+  /** 宠物会呈现的情绪 */
+  would_be_mood: PetMood;
+}
+
+/** 最近一次推理的原始输出摘要（调试模型用） */
+export interface RawModelOutput {
+  /** 最高的分类值（sigmoid 之前，降序） */
+  top_scores: number[];
+  /** 最高分锚框的 16 个回归值 */
+  sample_regressors: number[];
+}
+
+/** 按时段统计的粒度 */
+export type BucketGranularity = 'hour' | 'quarter_hour';
+
+/** 内置资源的校验结果 */
+export type ResourceStatus = 'ok' | 'missing' | 'unreadable' | 'checksum_mismatch';
+
+/** 单个资源的检查结果 */
+export interface ResourceEntry {
+  /** 相对资源目录的路径 */
+  name: string;
+  /** 完整路径 */
+  path: string;
+  /** 文件是否存在 */
+  exists: boolean;
+  /** 文件大小（字节） */
+  size_bytes: number | null;
+  /** 文件的 SHA-256 */
+  sha256: string | null;
+  /** 发布时的 SHA-256 */
+  expected_sha256: string | null;
+  /** 校验结果 */
+  status: ResourceStatus;
+}
+
+/** 资源校验报告 */
+export interface ResourceReport {
+  /** 资源目录 */
+  root: string;
+  /** 每个资源的检查结果 */
+  entries: ResourceEntry[];
+  /** 是否全部正常 */
+  all_ok: boolean;
+}
+
+/** 连续专注的里程碑（分钟） */
+export interface FocusMilestones {
+  /** 进入兴奋状态的连续专注时间 */
+  excited_minutes: number;
+  /** 进入深度专注的连续专注时间（null 表示不判断） */
+  deep_focus_minutes: number | null;
+}
+
+/** 张量签名 */
+export interface TensorSignature {
+  /** 张量名称 */
+  name: string;
+  /** 形状（-1 表示动态维度） */
+  shape: number[];
+}
+
+/** 模型的输入输出签名，以及与解码逻辑预期的对比 */
+export interface ModelIo {
+  /** 模型的输入（模拟模式下为预期的输入） */
+  inputs: TensorSignature[];
+  /** 模型的输出 */
+  outputs: TensorSignature[];
+  /** 解码逻辑预期的输入 */
+  expected_inputs: TensorSignature[];
+  /** 解码逻辑预期的输出 */
+  expected_outputs: TensorSignature[];
+  /** 与预期不符之处（为空表示一致） */
+  mismatches: string[];
+  /** 是否为模拟模式 */
+  mock: boolean;
+}
+
+/** 内存缓冲区的最大条目数 */
+export interface BufferLimits {
+  focus_history: number;
+  sparkline: number;
+  trend: number;
+  pending_samples: number;
+  pending_events: number;
+  session_recording: number;
+}
+
+/** 内存缓冲区的当前大小 */
+export interface BufferStats {
+  focus_history_len: number;
+  sparkline_len: number;
+  trend_len: number;
+  pending_samples_len: number;
+  pending_events_len: number;
+  session_recording_len: number;
+  /** 估算的内存占用（字节） */
+  est_bytes: number;
+  /** 当前的上限 */
+  limits: BufferLimits;
+}
+
+/** 专注挑战进度（challenge_progress / challenge_complete / challenge_failed 事件） */
+export interface ChallengeProgress {
+  /** 开始时间（毫秒时间戳） */
+  started_at_ms: number;
+  /** 目标专注时长（毫秒） */
+  target_ms: number;
+  /** 已累计的专注时长（毫秒） */
+  focused_ms: number;
+  /** 剩余的专注时长（毫秒） */
+  remaining_ms: number;
+  /** 倒计时是否暂停（分心或离开） */
+  paused: boolean;
+}
+
+/** 已完成的专注挑战 */
+export interface ChallengeRecord {
+  id: number;
+  started_at_ms: number;
+  completed_at_ms: number;
+  target_ms: number;
+}
+
+/** 检测稳定性指标 */
+export interface JitterMetrics {
+  /** 人脸框中心位置的标准差（归一化坐标） */
+  bbox_center_std: number;
+  /** 置信度的标准差 */
+  confidence_std: number;
+  /** 未检测到人脸的帧比例 */
+  drop_rate: number;
+  /** 参与测量的帧数 */
+  frames: number;
+  /** 检测到人脸的帧数 */
+  detected_frames: number;
+}
+
+/** 摄像头帧率过低提醒（low_fps_warning 事件） */
+export interface LowFpsWarning {
+  /** 最近一段时间的实际帧率 */
+  measured_fps: number;
+  /** 设置的目标帧率 */
+  target_fps: number;
+}
+
+/** 检测结果转储的结果 */
+export interface DumpSummary {
+  /** 文件路径 */
+  path: string;
+  /** 写入的帧数 */
+  frames: number;
+  /** 第一帧到最后一帧的时长（毫秒） */
+  duration_ms: number;
+  /** 是否因超过最长时长而提前停止写入 */
+  truncated: boolean;
+}
+
+/** `system_resumed` 事件：系统睡眠唤醒 */
+export interface ResumeReport {
+  /** 推测的睡眠时长（毫秒） */
+  slept_ms: number;
+  /** 睡眠前视觉检测是否在运行 */
+  was_running: boolean;
+  /** 重新检查摄像头时的错误 */
+  camera_error: string | null;
+  /** 是否将重新启动视觉检测 */
+  restart: boolean;
+}
+
+/** 通知类型 */
+export type NotificationKind =
+  | 'break_reminder'
+  | 'goal_progress'
+  | 'goal_reached'
+  | 'day_rollover'
+  | 'phone_distraction'
+  | 'challenge_complete';
+
+/** 通知偏好 */
+export interface NotificationPrefs {
+  break_reminder: boolean;
+  goal_progress: boolean;
+  goal_reached: boolean;
+  day_rollover: boolean;
+  phone_distraction: boolean;
+  challenge_complete: boolean;
+  /** 启用免打扰时段 */
+  quiet_hours_enabled: boolean;
+  /** 免打扰开始的小时 (0 - 23) */
+  quiet_start_hour: number;
+  /** 免打扰结束的小时 (0 - 23，不含)，小于开始时跨过午夜 */
+  quiet_end_hour: number;
+  /** 通知时播放提示音 */
+  sound: boolean;
+}
+
+/** `notification` 事件：需要弹出的系统通知 */
+export interface AppNotification {
+  kind: NotificationKind;
+  title: string;
+  body: string | null;
+  /** 是否播放提示音 */
+  sound: boolean;
+  /** 是否为预览通知 */
+  preview: boolean;
+}
+
+/** `accessible_state` 事件：无障碍模式下的状态描述 */
+export interface AccessibleState {
+  mood: PetMood;
+  focus_level: FocusLevel;
+  /** 本次连续专注的分钟数 */
+  focus_minutes: number;
+  /** 状态描述 */
+  description: string;
+  /** 需要朗读的文本（未开启朗读时为 null） */
+  speech: string | null;
+}
+
+/** 一天中最长的连续专注块 */
+export interface FocusBlock {
+  /** 开始时间 (Unix 时间戳，毫秒) */
+  start_ms: number;
+  /** 结束时间 (Unix 时间戳，毫秒) */
+  end_ms: number;
+  /** 时长（毫秒，包含其中的短暂间断） */
+  duration_ms: number;
+  /** 期间的平均专注分数（没有采样时为 null） */
+  avg_score: number | null;
+}
+
+/** 两天对比结论 */
+export type Verdict = 'more_focused' | 'less_focused' | 'about_the_same';
+
+/** 两天统计对比（差值为 date_a 减 date_b） */
+export interface DayComparison {
+  date_a: string;
+  date_b: string;
+  /** 专注时长差（毫秒） */
+  focus_delta_ms: number;
+  /** 专注占比差 */
+  ratio_delta: number;
+  /** 会话数差 */
+  session_delta: number;
+  /** 结论 */
+  verdict: Verdict;
+  /** date_a 的用户备注 */
+  note_a: string | null;
+  /** date_b 的用户备注 */
+  note_b: string | null;
 }
 
 /** 专注统计 */
@@ -88,6 +739,28 @@ export interface FocusStats {
   focus_score: number;
 }
 
+/** 单日统计 */
+export interface DailyStats {
+  /** 日期 (YYYY-MM-DD) */
+  date: string;
+  /** 总专注时长（毫秒） */
+  total_focus_ms: number;
+  /** 总分心时长（毫秒） */
+  total_distracted_ms: number;
+  /** 会话数量 */
+  session_count: number;
+  /** 最长单次专注时长（毫秒） */
+  longest_focus_ms: number;
+}
+
+/** 定期推送的统计更新（stats_update 事件） */
+export interface StatsUpdate {
+  /** 实时统计 */
+  live: FocusStats;
+  /** 今日统计：数据库记录加上尚未写入的专注时间 */
+  today: DailyStats;
+}
+
 /** 宠物动画帧配置 */
 export interface PetAnimationConfig {
   /** 动画帧图片 */
@@ -107,3 +780,59 @@ export interface PetAnimations {
   sleepy: PetAnimationConfig;
   interact: PetAnimationConfig;
 }
+
+/** 命令错误（结构化错误的命令返回） */
+export interface CommandError {
+  /** 错误类型 */
+  kind:
+    | 'window_not_found'
+    | 'window'
+    | 'invalid_argument'
+    | 'database'
+    | 'io'
+    | 'camera_busy'
+    | 'vision'
+    | 'timeout';
+  /** 错误信息 */
+  message: string;
+}
+
+/** 检测精度评估结果 */
+export interface EvalResult {
+  /** 精确率 */
+  precision: number;
+  /** 召回率 */
+  recall: number;
+  /** 命中检测的平均 IoU */
+  avg_iou: number;
+  /** 参与评估的图片数 */
+  images: number;
+  /** 跳过的图片数（缺少标注或无法读取） */
+  skipped: number;
+  /** 命中数 */
+  true_positives: number;
+  /** 误检数 */
+  false_positives: number;
+  /** 漏检数 */
+  false_negatives: number;
+}
+
+/** 活动事件类型 */
+export type EventKind =
+  | 'mood_changed'
+  | 'gesture'
+  | 'session_started'
+  | 'session_ended'
+  | 'goal_completed';
+
+/** 活动事件 */
+export interface ActivityEvent {
+  /** 事件 ID */
+  id: number;
+  /** 发生时间（Unix 时间戳，毫秒） */
+  timestamp_ms: number;
+  /** 事件类型 */
+  kind: EventKind;
+  /** 事件详情 */
+  payload: Record<string, unknown>;
+}